@@ -0,0 +1,187 @@
+//! Fit discrust discretizers on Arrow arrays, and columns of Parquet
+//! files, so large columnar datasets can be binned without first copying
+//! them into vectors. The methods are added to `Discretizer` by the
+//! `FitArrow` trait.
+use arrow::array::{Array, ArrayRef, Float64Array};
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use discrust_core::{Discretizer, DiscrustError, FitResult};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ProjectionMask;
+use std::borrow::Cow;
+use std::fs::File;
+use std::path::Path;
+
+pub trait FitArrow {
+    /// Fit the discretizer on Arrow arrays. The values are borrowed,
+    /// unless an array has nulls, which are read as missing (NaN), this
+    /// is only allowed in `x`. When no `w` is given, every record has a
+    /// weight of 1.
+    fn fit_arrow(
+        &mut self,
+        x: &Float64Array,
+        y: &Float64Array,
+        w: Option<&Float64Array>,
+        exception_values: Option<Vec<f64>>,
+    ) -> Result<FitResult, DiscrustError>;
+
+    /// Fit the discretizer on columns of a Parquet file, read in the same
+    /// way as `fit_arrow`. Integer, and other numeric, columns are cast
+    /// to `f64`. When no `w_col` is given, every record has a weight of 1.
+    fn fit_parquet<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        x_col: &str,
+        y_col: &str,
+        w_col: Option<&str>,
+        exception_values: Option<Vec<f64>>,
+    ) -> Result<FitResult, DiscrustError>;
+}
+
+impl FitArrow for Discretizer {
+    fn fit_arrow(
+        &mut self,
+        x: &Float64Array,
+        y: &Float64Array,
+        w: Option<&Float64Array>,
+        exception_values: Option<Vec<f64>>,
+    ) -> Result<FitResult, DiscrustError> {
+        let w = w.map(values);
+        self.fit(&values(x), &values(y), w.as_deref(), exception_values)
+    }
+
+    fn fit_parquet<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        x_col: &str,
+        y_col: &str,
+        w_col: Option<&str>,
+        exception_values: Option<Vec<f64>>,
+    ) -> Result<FitResult, DiscrustError> {
+        let mut columns = vec![x_col, y_col];
+        columns.extend(w_col);
+        let mut values = read_columns(path.as_ref(), &columns)?;
+        let w = w_col.map(|_| values.pop().unwrap());
+        let y = values.pop().unwrap();
+        self.fit(&values[0], &y, w.as_deref(), exception_values)
+    }
+}
+
+// The values of `array`, with nulls as NaN, borrowed if it has no nulls.
+fn values(array: &Float64Array) -> Cow<'_, [f64]> {
+    if array.null_count() == 0 {
+        Cow::Borrowed(&array.values()[..])
+    } else {
+        Cow::Owned(array.iter().map(|v| v.unwrap_or(f64::NAN)).collect())
+    }
+}
+
+// The named columns of a Parquet file, in the order of `columns`,
+// with the values of every record batch of each column.
+fn read_columns(path: &Path, columns: &[&str]) -> Result<Vec<Vec<f64>>, DiscrustError> {
+    let error = |e: String| DiscrustError::ReadParquet(path.display().to_string(), e);
+    let file = File::open(path).map_err(|e| error(e.to_string()))?;
+    let builder =
+        ParquetRecordBatchReaderBuilder::try_new(file).map_err(|e| error(e.to_string()))?;
+    let roots = columns
+        .iter()
+        .map(|c| {
+            builder
+                .schema()
+                .index_of(c)
+                .map_err(|_| error(format!("there is no column named {}", c)))
+        })
+        .collect::<Result<Vec<usize>, DiscrustError>>()?;
+    let mask = ProjectionMask::roots(builder.parquet_schema(), roots);
+    let reader = builder
+        .with_projection(mask)
+        .build()
+        .map_err(|e| error(e.to_string()))?;
+    let mut res = vec![Vec::new(); columns.len()];
+    for batch in reader {
+        let batch = batch.map_err(|e| error(e.to_string()))?;
+        for (col, name) in res.iter_mut().zip(columns) {
+            let array: &ArrayRef = batch.column_by_name(name).unwrap();
+            let array = cast(array, &DataType::Float64)
+                .map_err(|e| error(format!("the column {} is not numeric: {}", name, e)))?;
+            let array = array.as_any().downcast_ref::<Float64Array>().unwrap();
+            col.extend_from_slice(&values(array));
+        }
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use arrow::array::Int64Array;
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use std::env;
+    use std::fs;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_fit_arrow() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, f64::NAN, f64::NAN];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        let mut expected = disc.unfitted_copy();
+        let expected = expected.fit(&x_, &y_, None, None).unwrap();
+
+        // Nulls are read as missing values.
+        let x: Float64Array = x_
+            .iter()
+            .map(|v| if v.is_nan() { None } else { Some(*v) })
+            .collect();
+        let y = Float64Array::from(y_.clone());
+        assert_eq!(disc.fit_arrow(&x, &y, None, None).unwrap(), expected);
+        let w = Float64Array::from(vec![1.0; 10]);
+        assert_eq!(disc.fit_arrow(&x, &y, Some(&w), None).unwrap(), expected);
+        assert!(matches!(
+            disc.fit_arrow(&x, &x, None, None),
+            Err(DiscrustError::ContainsNaN(_))
+        ));
+        assert!(matches!(values(&y), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_fit_parquet() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0];
+        let y_ = vec![1, 1, 1, 0, 1, 0, 0, 0];
+        let w_ = vec![1.0, 2.0, 1.0, 1.0, 1.0, 1.0, 1.0, 3.0];
+        let batch = RecordBatch::try_from_iter(vec![
+            ("fare", Arc::new(Float64Array::from(x_.clone())) as ArrayRef),
+            (
+                "survived",
+                Arc::new(Int64Array::from(y_.clone())) as ArrayRef,
+            ),
+            (
+                "weight",
+                Arc::new(Float64Array::from(w_.clone())) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let path = env::temp_dir().join(format!("discrust_{}.parquet", std::process::id()));
+        let mut writer =
+            ArrowWriter::try_new(File::create(&path).unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        let res = disc
+            .fit_parquet(&path, "fare", "survived", Some("weight"), None)
+            .unwrap();
+        let y_: Vec<f64> = y_.iter().map(|v| *v as f64).collect();
+        let mut expected = disc.unfitted_copy();
+        assert_eq!(res, expected.fit(&x_, &y_, Some(&w_), None).unwrap());
+        assert!(matches!(
+            disc.fit_parquet(&path, "age", "survived", None, None),
+            Err(DiscrustError::ReadParquet(..))
+        ));
+        fs::remove_file(&path).unwrap();
+        assert!(disc
+            .fit_parquet(&path, "fare", "survived", None, None)
+            .is_err());
+    }
+}
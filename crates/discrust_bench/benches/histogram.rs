@@ -0,0 +1,31 @@
+//! Compare the exact fit against the histogram fit of `new_histogram`,
+//! on the Titanic fares scaled up, as the number of records grows.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use discrust_bench::titanic_data;
+use discrust_core::Discretizer;
+
+fn bench_fit(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fit");
+    group.sample_size(10);
+    for rows in [10_000, 100_000, 1_000_000] {
+        let (fare, survived) = titanic_data(rows, 42);
+        let fits = [
+            ("exact", Discretizer::default()),
+            ("histogram_64", Discretizer::new_histogram(64)),
+            ("histogram_256", Discretizer::new_histogram(256)),
+            ("histogram_1024", Discretizer::new_histogram(1_024)),
+        ];
+        for (name, disc) in fits {
+            group.bench_with_input(BenchmarkId::new(name, rows), &rows, |b, _| {
+                b.iter(|| {
+                    let mut disc = disc.unfitted_copy();
+                    disc.fit(&fare, &survived, None, None).unwrap()
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_fit);
+criterion_main!(benches);
@@ -0,0 +1,28 @@
+//! The data the benchmarks of discrust discretizers are run on, which
+//! are in `benches`, and run on stable with criterion.
+//!
+//!     cargo bench --manifest-path crates/discrust_bench/Cargo.toml
+
+/// The fares, and survival, of the Titanic passengers, repeated to fill
+/// `rows` records. Each repeat scales the fares by a small random factor,
+/// so the number of unique values grows with the rows, as in large data.
+pub fn titanic_data(rows: usize, seed: u64) -> (Vec<f64>, Vec<f64>) {
+    let records: Vec<(f64, f64)> = include_str!("../../discrust_core/resources/data.csv")
+        .lines()
+        .map(|l| {
+            let mut fields = l.split(',').map(|v| v.parse::<f64>().unwrap());
+            (fields.next().unwrap(), fields.next().unwrap())
+        })
+        .collect();
+    let mut state = seed.max(1);
+    (0..rows)
+        .map(|i| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let jitter = 1.0 + ((state >> 11) as f64 / (1u64 << 53) as f64 - 0.5) / 100.0;
+            let (fare, survived) = records[i % records.len()];
+            ((fare * jitter * 10_000.0).round() / 10_000.0, survived)
+        })
+        .unzip()
+}
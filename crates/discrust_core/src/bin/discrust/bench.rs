@@ -0,0 +1,180 @@
+use crate::flag_value;
+use discrust_core::{Discretizer, Strategy};
+use std::thread;
+use std::time::Instant;
+
+// Run the fit and predict benchmarks, for each strategy
+// and thread count, and print the throughput of each.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let rows = match flag_value(args, "--rows")? {
+        Some(v) => v
+            .parse::<usize>()
+            .map_err(|_| format!("--rows must be a positive integer, but {} was passed", v))?,
+        None => 100_000,
+    };
+    let threads = match flag_value(args, "--threads")? {
+        Some(v) => parse_threads(v)?,
+        None => {
+            let cores = thread::available_parallelism().map_or(1, |n| n.get());
+            if cores > 1 {
+                vec![1, cores]
+            } else {
+                vec![1]
+            }
+        }
+    };
+    if rows == 0 {
+        return Err(String::from("--rows must be a positive integer"));
+    }
+    let (x, y) = synthetic_data(rows, 42);
+    let w = vec![1.0; rows];
+    println!(
+        "{:<12} {:>8} {:>16} {:>16}",
+        "strategy", "threads", "fit rows/s", "predict rows/s"
+    );
+    for (name, strategy) in strategies(&x) {
+        for t in threads.iter() {
+            let (fit_rate, predict_rate) = bench_strategy(&strategy, &x, &y, &w, *t)?;
+            println!(
+                "{:<12} {:>8} {:>16.0} {:>16.0}",
+                name, t, fit_rate, predict_rate
+            );
+        }
+    }
+    println!();
+    println!(
+        "{:<12} {:>16} {:>16} {:>16} {:>16}",
+        "splits", "linear rows/s", "binary rows/s", "sorted rows/s", "presorted rows/s"
+    );
+    let mut x_sorted = x.to_vec();
+    x_sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    for n_splits in [10, 100, 1_000, 10_000] {
+        let (linear, binary, sorted) = bench_predict(n_splits, &x, &y, &w)?;
+        let (_, _, presorted) = bench_predict(n_splits, &x_sorted, &y, &w)?;
+        println!(
+            "{:<12} {:>16.0} {:>16.0} {:>16.0} {:>16.0}",
+            n_splits, linear, binary, sorted, presorted
+        );
+    }
+    Ok(())
+}
+
+// Compare the throughput of assigning bins with a linear scan of the
+// splits, the binary search of `predict_idx`, and the single sort of
+// `predict_idx_sorted`, with `n_splits` splits at quantiles of `x`.
+fn bench_predict(
+    n_splits: usize,
+    x: &[f64],
+    y: &[f64],
+    w: &[f64],
+) -> Result<(f64, f64, f64), String> {
+    let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), None);
+    disc.fit(x, y, Some(w), None).map_err(|e| e.to_string())?;
+    let mut sorted = x.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mut splits: Vec<f64> = (1..=n_splits)
+        .map(|i| sorted[i * (sorted.len() - 1) / (n_splits + 1)])
+        .collect();
+    splits.dedup();
+    disc.splits_ = [vec![f64::NEG_INFINITY], splits, vec![f64::INFINITY]].concat();
+
+    let start = Instant::now();
+    let linear: Vec<i64> = x
+        .iter()
+        .map(|v| disc.splits_[1..].iter().position(|s| s >= v).unwrap() as i64)
+        .collect();
+    let linear_secs = start.elapsed().as_secs_f64();
+    let start = Instant::now();
+    let binary = disc.predict_idx(x).map_err(|e| e.to_string())?;
+    let binary_secs = start.elapsed().as_secs_f64();
+    let start = Instant::now();
+    let sorted = disc.predict_idx_sorted(x).map_err(|e| e.to_string())?;
+    let sorted_secs = start.elapsed().as_secs_f64();
+    if (linear != binary) || (binary != sorted) {
+        return Err(String::from("the predict methods assigned different bins"));
+    }
+    let rows = x.len() as f64;
+    Ok((rows / linear_secs, rows / binary_secs, rows / sorted_secs))
+}
+
+fn parse_threads(v: &str) -> Result<Vec<usize>, String> {
+    v.split(',')
+        .map(|t| match t.trim().parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(format!(
+                "--threads must be a comma separated list of positive integers, but {} was passed",
+                v
+            )),
+        })
+        .collect()
+}
+
+fn strategies(x: &[f64]) -> Vec<(&'static str, Strategy)> {
+    let mut sorted = x.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let edges = (1..100)
+        .map(|i| sorted[i * (sorted.len() - 1) / 100])
+        .collect();
+    vec![
+        ("tree", Strategy::Tree),
+        ("merge-only", Strategy::MergeOnly { edges }),
+        ("quantile", Strategy::Quantile { n_bins: 10 }),
+    ]
+}
+
+// Each thread fits, and then predicts, its own copy of the data, so the
+// throughput is the total rows processed by all threads per second.
+fn bench_strategy(
+    strategy: &Strategy,
+    x: &[f64],
+    y: &[f64],
+    w: &[f64],
+    threads: usize,
+) -> Result<(f64, f64), String> {
+    let timings = thread::scope(|s| {
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut disc =
+                        Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), None);
+                    disc.strategy = strategy.clone();
+                    let start = Instant::now();
+                    disc.fit(x, y, Some(w), None).map_err(|e| e.to_string())?;
+                    let fit_secs = start.elapsed().as_secs_f64();
+                    let start = Instant::now();
+                    disc.predict_woe(x).map_err(|e| e.to_string())?;
+                    Ok((fit_secs, start.elapsed().as_secs_f64()))
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .collect::<Result<Vec<(f64, f64)>, String>>()
+    })?;
+    // The slowest thread determines the wall clock time.
+    let fit_secs = timings.iter().map(|t| t.0).fold(0.0, f64::max);
+    let predict_secs = timings.iter().map(|t| t.1).fold(0.0, f64::max);
+    let total_rows = (x.len() * threads) as f64;
+    Ok((total_rows / fit_secs, total_rows / predict_secs))
+}
+
+// A feature with a monotonic relationship to a binary target,
+// generated with a small xorshift random number generator.
+fn synthetic_data(rows: usize, seed: u64) -> (Vec<f64>, Vec<f64>) {
+    let mut state = seed.max(1);
+    let mut uniform = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state >> 11) as f64 / (1u64 << 53) as f64
+    };
+    (0..rows)
+        .map(|_| {
+            let x = (uniform() * 10_000.0).round() / 100.0;
+            let p = 1.0 / (1.0 + (2.0 - x / 25.0).exp());
+            let y = if uniform() < p { 1.0 } else { 0.0 };
+            (x, y)
+        })
+        .unzip()
+}
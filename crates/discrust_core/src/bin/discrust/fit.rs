@@ -0,0 +1,59 @@
+use crate::{flag_value, parse_flag, required_flag};
+use discrust_core::{Discretizer, DiscretizerSet};
+use std::fs::File;
+use std::io::BufWriter;
+
+// Fit a discretizer for each feature column of a CSV
+// file, and write the set of them to a JSON model file.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let input = required_flag(args, "--input")?;
+    let target = required_flag(args, "--target")?;
+    let output = required_flag(args, "--output")?;
+    let features: Vec<String> = required_flag(args, "--features")?
+        .split(',')
+        .map(|f| f.trim().to_string())
+        .collect();
+    let exceptions = match flag_value(args, "--exceptions")? {
+        Some(v) => Some(
+            v.split(',')
+                .map(|e| {
+                    e.trim().parse::<f64>().map_err(|_| {
+                        format!(
+                            "--exceptions must be a comma separated list of numbers, but {} was passed",
+                            v
+                        )
+                    })
+                })
+                .collect::<Result<Vec<f64>, String>>()?,
+        ),
+        None => None,
+    };
+    let template = Discretizer::new(
+        parse_flag(args, "--min-obs")?,
+        parse_flag(args, "--max-bins")?,
+        parse_flag(args, "--min-iv")?,
+        parse_flag(args, "--min-pos")?,
+        parse_flag(args, "--mono")?,
+    );
+    let mut set = DiscretizerSet::new(&template, features);
+    let results = set
+        .fit_csv(input, target, flag_value(args, "--weight")?, exceptions)
+        .map_err(|e| e.to_string())?;
+    println!(
+        "{:<24} {:>6} {:>10} {:>10}",
+        "feature", "bins", "iv", "warnings"
+    );
+    for ((name, disc), res) in set.names.iter().zip(&set.discretizers).zip(&results) {
+        let iv = disc.total_iv_().map_err(|e| e.to_string())?;
+        println!(
+            "{:<24} {:>6} {:>10.4} {:>10}",
+            name,
+            res.bins.len(),
+            iv,
+            res.warnings.len()
+        );
+    }
+    let file = File::create(output).map_err(|e| format!("Unable to create {}: {}", output, e))?;
+    serde_json::to_writer_pretty(BufWriter::new(file), &set)
+        .map_err(|e| format!("Unable to write {}: {}", output, e))
+}
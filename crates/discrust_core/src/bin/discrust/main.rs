@@ -0,0 +1,84 @@
+mod bench;
+#[cfg(feature = "cli")]
+mod fit;
+#[cfg(feature = "cli")]
+mod score;
+
+use std::env;
+use std::process;
+
+const USAGE: &str = "Usage: discrust <command> [options]
+
+Commands:
+    fit      Fit a discretizer for each feature column of a CSV file,
+             and write them to a JSON model file
+             --input PATH      CSV file, with a header row naming each column
+             --target NAME     Column of the binary performance
+             --features LIST   Comma separated feature columns
+             --output PATH     Model file to write
+             --weight NAME     Column of the sample weights (default 1 for each record)
+             --exceptions LIST Comma separated exception values
+             --min-obs N       Minimum weight in each bin (default 5)
+             --max-bins N      Maximum number of bins (default 10)
+             --min-iv N        Minimum information value of a split (default 0.001)
+             --min-pos N       Minimum weight of ones in each bin (default 5)
+             --mono N          Monotonic trend, -1, 0 or 1 (default none)
+    score    Write the weight of evidence of each feature of a CSV file
+             --model PATH      Model file written by fit
+             --input PATH      CSV file, with a column for each feature
+             --output PATH     CSV file to write (default stdout)
+    bench    Report fit and predict throughput on synthetic data, and
+             compare the ways of assigning bins as the splits grow
+             --rows N          Number of rows to generate (default 100000)
+             --threads LIST    Comma separated thread counts (default 1,<available cores>)";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let res = match args.first().map(|s| s.as_str()) {
+        Some("bench") => bench::run(&args[1..]),
+        #[cfg(feature = "cli")]
+        Some("fit") => fit::run(&args[1..]),
+        #[cfg(feature = "cli")]
+        Some("score") => score::run(&args[1..]),
+        #[cfg(not(feature = "cli"))]
+        Some("fit") | Some("score") => Err(String::from(
+            "The fit and score commands require the cli feature, build with --features cli",
+        )),
+        _ => Err(String::from(USAGE)),
+    };
+    if let Err(e) = res {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+// The value following a flag such as `--rows`, if the flag was passed.
+pub(crate) fn flag_value<'a>(args: &'a [String], flag: &str) -> Result<Option<&'a str>, String> {
+    match args.iter().position(|a| a == flag) {
+        None => Ok(None),
+        Some(i) => args
+            .get(i + 1)
+            .map(|v| Some(v.as_str()))
+            .ok_or_else(|| format!("{} requires a value", flag)),
+    }
+}
+
+// The value of a flag that must be passed.
+#[cfg(feature = "cli")]
+pub(crate) fn required_flag<'a>(args: &'a [String], flag: &str) -> Result<&'a str, String> {
+    flag_value(args, flag)?.ok_or_else(|| format!("{} is required", flag))
+}
+
+// The value of a flag parsed as `T`, if the flag was passed.
+#[cfg(feature = "cli")]
+pub(crate) fn parse_flag<T: std::str::FromStr>(
+    args: &[String],
+    flag: &str,
+) -> Result<Option<T>, String> {
+    flag_value(args, flag)?
+        .map(|v| {
+            v.parse::<T>()
+                .map_err(|_| format!("{} was passed the invalid value {}", flag, v))
+        })
+        .transpose()
+}
@@ -0,0 +1,31 @@
+use crate::{flag_value, required_flag};
+use discrust_core::DiscretizerSet;
+use std::fs::File;
+use std::io::{self, BufReader, Write};
+
+// Write the weight of evidence of each feature of a CSV file, with the
+// discretizers of a model written by `fit`, as a CSV file with a column
+// for each feature, and a row for each record in the same order.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let model = required_flag(args, "--model")?;
+    let input = required_flag(args, "--input")?;
+    let file = File::open(model).map_err(|e| format!("Unable to open {}: {}", model, e))?;
+    let set: DiscretizerSet = serde_json::from_reader(BufReader::new(file))
+        .map_err(|e| format!("Unable to read {}: {}", model, e))?;
+    let woe = set.predict_woe_csv(input).map_err(|e| e.to_string())?;
+    let out: Box<dyn Write> = match flag_value(args, "--output")? {
+        Some(p) => Box::new(File::create(p).map_err(|e| format!("Unable to create {}: {}", p, e))?),
+        None => Box::new(io::stdout()),
+    };
+    let mut writer = csv::Writer::from_writer(out);
+    let error = |e: csv::Error| format!("Unable to write the scores: {}", e);
+    writer.write_record(&set.names).map_err(error)?;
+    for row in woe {
+        writer
+            .write_record(row.iter().map(|v| v.to_string()))
+            .map_err(error)?;
+    }
+    writer
+        .flush()
+        .map_err(|e| format!("Unable to write the scores: {}", e))
+}
@@ -0,0 +1,269 @@
+use crate::datetime::FeatureType;
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::label::LabelFormat;
+use crate::spec::InputSpec;
+use crate::strategy::{
+    Closed, Growth, MonotonicTrend, OnMissing, SplitPoint, Strategy, TargetType, TieBreak,
+};
+use crate::transform::PreTransform;
+use std::collections::BTreeMap;
+
+/// Configure a discretizer one parameter at a time, starting from
+/// the defaults of `Discretizer::new`. The parameters are checked
+/// when the discretizer is built.
+///
+/// ```
+/// use discrust_core::prelude::*;
+///
+/// let disc = Discretizer::builder()
+///     .min_obs(5.0)
+///     .max_bins(10)
+///     .mono(MonotonicTrend::Ascending)
+///     .max_pvalue(0.05)
+///     .build()
+///     .unwrap();
+/// assert_eq!(disc.mono, MonotonicTrend::Ascending);
+/// ```
+#[derive(Default)]
+pub struct DiscretizerBuilder {
+    disc: Discretizer,
+}
+
+impl DiscretizerBuilder {
+    /// The smallest weight of records required in a bin.
+    pub fn min_obs(mut self, min_obs: f64) -> Self {
+        self.disc.min_obs = min_obs;
+        self
+    }
+
+    /// The largest number of bins to split the feature into.
+    pub fn max_bins(mut self, max_bins: i64) -> Self {
+        self.disc.max_bins = max_bins;
+        self
+    }
+
+    /// The smallest information value gain required for a split.
+    pub fn min_iv(mut self, min_iv: f64) -> Self {
+        self.disc.min_iv = min_iv;
+        self
+    }
+
+    /// The smallest weight of records with a value of one required in a bin.
+    pub fn min_pos(mut self, min_pos: f64) -> Self {
+        self.disc.min_pos = min_pos;
+        self
+    }
+
+    pub fn mono(mut self, mono: MonotonicTrend) -> Self {
+        self.disc.mono = mono;
+        self
+    }
+
+    pub fn min_effective_obs(mut self, min_effective_obs: impl Into<Option<f64>>) -> Self {
+        self.disc.min_effective_obs = min_effective_obs.into();
+        self
+    }
+
+    pub fn approx_splits(mut self, approx_splits: impl Into<Option<usize>>) -> Self {
+        self.disc.approx_splits = approx_splits.into();
+        self
+    }
+
+    pub fn max_candidates(mut self, max_candidates: impl Into<Option<usize>>) -> Self {
+        self.disc.max_candidates = max_candidates.into();
+        self
+    }
+
+    pub fn min_obs_rate(mut self, min_obs_rate: impl Into<Option<f64>>) -> Self {
+        self.disc.min_obs_rate = min_obs_rate.into();
+        self
+    }
+
+    pub fn min_pos_rate(mut self, min_pos_rate: impl Into<Option<f64>>) -> Self {
+        self.disc.min_pos_rate = min_pos_rate.into();
+        self
+    }
+
+    pub fn min_pos_auto(mut self, min_pos_auto: bool) -> Self {
+        self.disc.min_pos_auto = min_pos_auto;
+        self
+    }
+
+    pub fn max_pvalue(mut self, max_pvalue: impl Into<Option<f64>>) -> Self {
+        self.disc.max_pvalue = max_pvalue.into();
+        self
+    }
+
+    pub fn min_bin_width(mut self, min_bin_width: impl Into<Option<f64>>) -> Self {
+        self.disc.min_bin_width = min_bin_width.into();
+        self
+    }
+
+    pub fn max_bin_pct(mut self, max_bin_pct: impl Into<Option<f64>>) -> Self {
+        self.disc.max_bin_pct = max_bin_pct.into();
+        self
+    }
+
+    pub fn mdlp(mut self, mdlp: bool) -> Self {
+        self.disc.mdlp = mdlp;
+        self
+    }
+
+    pub fn min_iv_gain_ratio(mut self, min_iv_gain_ratio: impl Into<Option<f64>>) -> Self {
+        self.disc.min_iv_gain_ratio = min_iv_gain_ratio.into();
+        self
+    }
+
+    pub fn growth(mut self, growth: Growth) -> Self {
+        self.disc.growth = growth;
+        self
+    }
+
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.disc.verbose = verbose;
+        self
+    }
+
+    pub fn n_threads(mut self, n_threads: impl Into<Option<usize>>) -> Self {
+        self.disc.n_threads = n_threads.into();
+        self
+    }
+
+    pub fn feature_type(mut self, feature_type: FeatureType) -> Self {
+        self.disc.feature_type = feature_type;
+        self
+    }
+
+    pub fn pre_transform(mut self, pre_transform: impl Into<Option<PreTransform>>) -> Self {
+        self.disc.pre_transform = pre_transform.into();
+        self
+    }
+
+    pub fn clip_quantiles(mut self, clip_quantiles: impl Into<Option<(f64, f64)>>) -> Self {
+        self.disc.clip_quantiles = clip_quantiles.into();
+        self
+    }
+
+    pub fn enforce_monotonicity(mut self, enforce_monotonicity: bool) -> Self {
+        self.disc.enforce_monotonicity = enforce_monotonicity;
+        self
+    }
+
+    pub fn woe_regularization(mut self, woe_regularization: f64) -> Self {
+        self.disc.woe_regularization = woe_regularization;
+        self
+    }
+
+    pub fn special_missing(mut self, special_missing: bool) -> Self {
+        self.disc.special_missing = special_missing;
+        self
+    }
+
+    pub fn cv_folds(mut self, cv_folds: impl Into<Option<usize>>) -> Self {
+        self.disc.cv_folds = cv_folds.into();
+        self
+    }
+
+    pub fn cv_agreement(mut self, cv_agreement: f64) -> Self {
+        self.disc.cv_agreement = cv_agreement;
+        self
+    }
+
+    pub fn merge_exceptions(mut self, merge_exceptions: bool) -> Self {
+        self.disc.merge_exceptions = merge_exceptions;
+        self
+    }
+
+    pub fn target_type(mut self, target_type: TargetType) -> Self {
+        self.disc.target_type = target_type;
+        self
+    }
+
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.disc.strategy = strategy;
+        self
+    }
+
+    pub fn split_point(mut self, split_point: SplitPoint) -> Self {
+        self.disc.split_point = split_point;
+        self
+    }
+
+    pub fn closed(mut self, closed: Closed) -> Self {
+        self.disc.closed = closed;
+        self
+    }
+
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.disc.tie_break = tie_break;
+        self
+    }
+
+    pub fn on_missing(mut self, on_missing: OnMissing) -> Self {
+        self.disc.on_missing = on_missing;
+        self
+    }
+
+    pub fn label_format(mut self, label_format: LabelFormat) -> Self {
+        self.disc.label_format = label_format;
+        self
+    }
+
+    pub fn input_spec(mut self, input_spec: impl Into<Option<InputSpec>>) -> Self {
+        self.disc.input_spec = input_spec.into();
+        self
+    }
+
+    pub fn tags(mut self, tags: BTreeMap<String, String>) -> Self {
+        self.disc.tags = tags;
+        self
+    }
+
+    /// Build the discretizer, returning an error if
+    /// any of the parameters are invalid.
+    pub fn build(self) -> Result<Discretizer, DiscrustError> {
+        self.disc.validate_parameters()?;
+        Ok(self.disc)
+    }
+}
+
+impl Discretizer {
+    /// Start configuring a discretizer from the default parameters.
+    pub fn builder() -> DiscretizerBuilder {
+        DiscretizerBuilder::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_builder() {
+        let disc = Discretizer::builder()
+            .min_obs(1.0)
+            .max_bins(4)
+            .min_pos(2.0)
+            .mono(MonotonicTrend::Descending)
+            .max_pvalue(0.05)
+            .cv_folds(5)
+            .build()
+            .unwrap();
+        let new = Discretizer::new(Some(1.0), Some(4), None, Some(2.0), Some(-1));
+        let mut parameters = new.parameters();
+        parameters.insert(String::from("max_pvalue"), String::from("Some(0.05)"));
+        parameters.insert(String::from("cv_folds"), String::from("Some(5)"));
+        assert_eq!(disc.parameters(), parameters);
+        assert_eq!(
+            Discretizer::builder().build().unwrap().parameters(),
+            Discretizer::new(None, None, None, None, None).parameters()
+        );
+        assert!(Discretizer::builder().cv_agreement(2.0).build().is_err());
+        assert!(Discretizer::builder()
+            .target_type(TargetType::Continuous)
+            .cv_folds(5)
+            .build()
+            .is_err());
+    }
+}
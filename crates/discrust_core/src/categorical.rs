@@ -0,0 +1,172 @@
+use crate::datetime::FeatureType;
+use crate::discretize::Discretizer;
+use crate::errors::{Column, DiscrustError, NanValues};
+use crate::feature::iv_woe;
+use crate::strategy::{Strategy, TargetType};
+use crate::summary::FitResult;
+use crate::utils::nan_safe_compare;
+use std::collections::{BTreeMap, HashMap};
+
+impl Discretizer {
+    /// Fit the discretizer on a categorical feature. The levels are
+    /// ordered by their weight of evidence, and the ordered levels are
+    /// then binned like a numeric feature, so each bin is a set of levels
+    /// with similar weights of evidence. The ordered levels are stored in
+    /// `categories_`, and the splits are positions in this order.
+    pub fn fit_categorical<S: AsRef<str>>(
+        &mut self,
+        x: &[S],
+        y: &[f64],
+        w: &[f64],
+    ) -> Result<FitResult, DiscrustError> {
+        if (self.feature_type != FeatureType::Numeric)
+            | self.pre_transform.is_some()
+            | self.clip_quantiles.is_some()
+            | self.input_spec.is_some()
+            | (self.strategy != Strategy::Tree)
+            | (self.target_type != TargetType::Binary)
+        {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "feature_type, pre_transform, clip_quantiles, input_spec, strategy and a continuous target can not be used with a categorical feature",
+            )));
+        }
+        // The weighted (ones, zero) counts of each level.
+        let mut counts: BTreeMap<&str, (f64, f64)> = BTreeMap::new();
+        if let Some(nan) = NanValues::find(Column::Y, y) {
+            return Err(DiscrustError::ContainsNaN(nan));
+        }
+        for ((v, y_), w_) in x.iter().zip(y).zip(w) {
+            let ct = counts.entry(v.as_ref()).or_insert((0.0, 0.0));
+            if *y_ == 1.0 {
+                ct.0 += w_;
+            } else {
+                ct.1 += w_;
+            }
+        }
+        let total_ones: f64 = counts.values().map(|c| c.0).sum();
+        let total_zero: f64 = counts.values().map(|c| c.1).sum();
+        let mut levels: Vec<(f64, &str)> = counts
+            .iter()
+            .map(|(l, (ones, zero))| {
+                let (_, woe) = iv_woe(
+                    *ones,
+                    *zero,
+                    total_ones,
+                    total_zero,
+                    self.woe_regularization,
+                );
+                (woe, *l)
+            })
+            .collect();
+        // Levels with the same weight of evidence are ordered by name,
+        // so the order does not depend on the order of the records.
+        levels.sort_by(|a, b| nan_safe_compare(&a.0, &b.0).then(a.1.cmp(b.1)));
+        let rank: HashMap<&str, f64> = levels
+            .iter()
+            .enumerate()
+            .map(|(i, (_, l))| (*l, i as f64))
+            .collect();
+        let x_rank: Vec<f64> = x.iter().map(|v| rank[v.as_ref()]).collect();
+        let res = self.fit(&x_rank, y, Some(w), None)?;
+        self.categories_ = levels.iter().map(|(_, l)| l.to_string()).collect();
+        Ok(res)
+    }
+
+    /// The levels of a categorical feature that make up each bin,
+    /// in the same order as the bin indexes returned by `predict_categorical_idx`.
+    pub fn category_bins(&self) -> Result<Vec<Vec<String>>, DiscrustError> {
+        let ranks: Vec<f64> = (0..self.categories_.len()).map(|i| i as f64).collect();
+        let idx = self.predict_idx(&ranks)?;
+        let mut bins = vec![Vec::new(); self.splits_.len().saturating_sub(1)];
+        for (level, i) in self.categories_.iter().zip(idx) {
+            bins[i as usize].push(level.to_owned());
+        }
+        Ok(bins)
+    }
+
+    /// The weight of evidence of the bin each level falls in, the
+    /// discretizer must have been fit with `fit_categorical`.
+    pub fn predict_categorical_woe<S: AsRef<str>>(
+        &self,
+        x: &[S],
+    ) -> Result<Vec<f64>, DiscrustError> {
+        self.predict_woe(&self.category_ranks(x)?)
+    }
+
+    /// The index of the bin each level falls in, the discretizer
+    /// must have been fit with `fit_categorical`.
+    pub fn predict_categorical_idx<S: AsRef<str>>(
+        &self,
+        x: &[S],
+    ) -> Result<Vec<i64>, DiscrustError> {
+        self.predict_idx(&self.category_ranks(x)?)
+    }
+
+    fn category_ranks<S: AsRef<str>>(&self, x: &[S]) -> Result<Vec<f64>, DiscrustError> {
+        if self.categories_.is_empty() {
+            return Err(DiscrustError::NotFitted);
+        }
+        let rank: HashMap<&str, f64> = self
+            .categories_
+            .iter()
+            .enumerate()
+            .map(|(i, l)| (l.as_str(), i as f64))
+            .collect();
+        x.iter()
+            .map(|v| {
+                rank.get(v.as_ref())
+                    .cloned()
+                    .ok_or_else(|| DiscrustError::UnknownCategory(v.as_ref().to_string()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fit_categorical() {
+        let x_ = vec!["a", "a", "a", "b", "b", "b", "c", "c", "c", "d", "d", "d"];
+        let y_ = vec![1.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.predict_categorical_idx(&["a"]).is_err());
+        disc.fit_categorical(&x_, &y_, &w_).unwrap();
+        // b and d have a lower weight of evidence than a and c.
+        assert_eq!(disc.categories_, vec!["b", "d", "a", "c"]);
+        assert_eq!(
+            disc.category_bins().unwrap(),
+            vec![vec!["b", "d"], vec!["a", "c"]]
+        );
+        assert_eq!(
+            disc.predict_categorical_idx(&["a", "b", "c", "d"]).unwrap(),
+            vec![1, 0, 1, 0]
+        );
+        let woe = disc.predict_categorical_woe(&["a", "b"]).unwrap();
+        assert!(woe[0] > woe[1]);
+        match disc.predict_categorical_idx(&["e"]) {
+            Err(DiscrustError::UnknownCategory(l)) => assert_eq!(l, "e"),
+            r => panic!("Unexpected result {:?}", r),
+        }
+
+        // Fitting a numeric feature clears the categories.
+        disc.fit(
+            &[1.0, 2.0, 1.0, 2.0],
+            &[1.0, 0.0, 1.0, 0.0],
+            Some(&[1.0; 4]),
+            None,
+        )
+        .unwrap();
+        assert!(disc.categories_.is_empty());
+    }
+
+    #[test]
+    fn test_fit_categorical_invalid_parameters() {
+        let x_ = vec![String::from("a"), String::from("b")];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        disc.clip_quantiles = Some((0.01, 0.99));
+        assert!(disc.fit_categorical(&x_, &[1.0, 0.0], &[1.0, 1.0]).is_err());
+    }
+}
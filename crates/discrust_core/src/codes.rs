@@ -0,0 +1,121 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::strategy::OnMissing;
+use crate::utils::to_f64;
+use num::Float;
+
+/// The code of values that are missing, NaN or a special code of
+/// the input spec, that were not exception values when fitting,
+/// including the values of the missing bin.
+pub const MISSING_CODE: u16 = 0;
+
+/// Compact bin codes, along with the table to expand each
+/// code to its weight of evidence. Code 0 is reserved for missing
+/// values, codes 1 to m for the m exception values, in the order of
+/// `exception_values_`, and the following codes for the bins, in order.
+#[derive(Debug, PartialEq)]
+pub struct BinCodes {
+    pub codes: Vec<u16>,
+    /// The weight of evidence of each code, empty bins have a weight of
+    /// evidence of zero, and missing values the weight of evidence of the
+    /// missing bin, or if there is none, given by the `on_missing` policy.
+    pub woe: Vec<f64>,
+}
+
+impl BinCodes {
+    /// The weight of evidence of each code.
+    pub fn expand(&self) -> Vec<f64> {
+        self.codes.iter().map(|c| self.woe[*c as usize]).collect()
+    }
+}
+
+impl Discretizer {
+    /// The bin of each value as a `u16` code, which takes a quarter of the
+    /// memory of its weight of evidence, along with the lookup table to
+    /// expand the codes. An error is returned if there are more bins and
+    /// exception values than can be represented.
+    pub fn predict_code_u16<T: Float>(&self, x: &[T]) -> Result<BinCodes, DiscrustError> {
+        let woe = self.code_woe_table()?;
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        let n_exceptions = feature.exception_values_.vals_.len() as i64;
+        let codes = x
+            .iter()
+            .zip(self.predict_idx(x)?)
+            .map(|(v, idx)| {
+                let v = to_f64(*v);
+                if self.is_unseen_missing(&v, feature) || feature.in_missing_bin(&v) {
+                    MISSING_CODE
+                } else if idx < 0 {
+                    -idx as u16
+                } else {
+                    (idx + n_exceptions + 1) as u16
+                }
+            })
+            .collect();
+        Ok(BinCodes { codes, woe })
+    }
+
+    /// The weight of evidence of each code returned by `predict_code_u16`.
+    pub fn code_woe_table(&self) -> Result<Vec<f64>, DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        let exceptions = &feature.exception_values_;
+        let n_codes = 1 + exceptions.vals_.len() + self.splits_.len().saturating_sub(1);
+        if n_codes > (u16::MAX as usize + 1) {
+            return Err(DiscrustError::InvalidParameter(format!(
+                "{} codes are needed, but at most {} can be stored as u16",
+                n_codes,
+                u16::MAX as usize + 1
+            )));
+        }
+        let bin_woe = self.bin_woe(feature);
+        let missing_woe = match self.on_missing {
+            _ if feature.in_missing_bin(&f64::NAN) => feature.missing_.woe_[0],
+            OnMissing::Error => 0.0,
+            OnMissing::LowestBin => bin_woe[0],
+            OnMissing::HighestBin => bin_woe[bin_woe.len() - 1],
+            OnMissing::WoeValue(w) => w,
+        };
+        let mut woe = vec![missing_woe];
+        woe.extend(
+            exceptions
+                .woe_
+                .iter()
+                .zip(&exceptions.totals_ct_)
+                .map(|(w, ct)| if *ct == 0.0 { 0.0 } else { *w }),
+        );
+        woe.extend(bin_woe);
+        Ok(woe)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_predict_code_u16() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.predict_code_u16(&[1.0]).is_err());
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0, -2.0]))
+            .unwrap();
+        let coded = disc.predict_code_u16(&[1.0, 2.0, -1.0, -2.0]).unwrap();
+        // Missing, the two exception values, which are
+        // kept sorted, then the two bins.
+        assert_eq!(coded.woe.len(), 5);
+        assert_eq!(coded.codes, vec![3, 4, 2, 1]);
+        assert_eq!(
+            coded.expand(),
+            disc.predict_woe(&[1.0, 2.0, -1.0, -2.0]).unwrap()
+        );
+        let missing = disc.predict_code_u16(&[f64::NAN]).unwrap();
+        assert_eq!(missing.codes, vec![MISSING_CODE]);
+        assert_eq!(missing.expand(), disc.predict_woe(&[f64::NAN]).unwrap());
+        disc.on_missing = OnMissing::WoeValue(0.5);
+        let missing = disc.predict_code_u16(&[f64::NAN, 2.0]).unwrap();
+        assert_eq!(missing.codes, vec![MISSING_CODE, 4]);
+        assert_eq!(missing.expand()[0], 0.5);
+    }
+}
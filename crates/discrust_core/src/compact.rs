@@ -0,0 +1,94 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+
+impl Discretizer {
+    /// Collapse the fitted feature to the counts of each bin, rather than
+    /// of each unique value, so a discretizer fit on a feature with many
+    /// unique values is small to keep in memory, and serialize. Predicting,
+    /// the summaries, and the exports, are unchanged, and bins can still be
+    /// merged, but a bin can no longer be split, nor a value made an
+    /// exception value. The fitted tree, and any batches added with
+    /// `partial_fit`, are dropped. Refitting, or `recompute_stats`, builds
+    /// the full feature again.
+    pub fn compact(&mut self) -> Result<(), DiscrustError> {
+        let feature = self.feature.take().ok_or(DiscrustError::NotFitted)?;
+        let ranges = self.bin_ranges(&feature);
+        self.feature = Some(feature.compact(&ranges, self.closed));
+        self.root_node = None;
+        self.partial_ = None;
+        Ok(())
+    }
+
+    // An error if the feature was compacted, as `action` needs
+    // the counts of the values within each bin.
+    pub(crate) fn check_not_compacted(&self, action: &str) -> Result<(), DiscrustError> {
+        match &self.feature {
+            Some(feature) if feature.compacted_ => Err(DiscrustError::InvalidParameter(format!(
+                "the discretizer was compacted, so {}, refit it first",
+                action
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_compact() {
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        let (mut fare, survived): (Vec<f64>, Vec<f64>) = file
+            .lines()
+            .map(|l| {
+                let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+                (split[0], split[1])
+            })
+            .unzip();
+        fare[0] = -1.0;
+        fare[1] = f64::NAN;
+        let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.compact().is_err());
+        disc.fit(&fare, &survived, None, Some(vec![-1.0])).unwrap();
+        let woe = disc.predict_woe(&fare).unwrap();
+        let bins = disc.bin_summary().unwrap();
+        let exceptions = disc.exception_summary().unwrap();
+        let missing = disc.missing_summary().unwrap();
+        let n_bins = disc.splits_.len() - 1;
+
+        // Only a single value is kept for each bin.
+        disc.compact().unwrap();
+        assert_eq!(disc.feature.as_ref().unwrap().vals_.len(), n_bins);
+        assert!(disc.validate_splits().is_ok());
+        assert_eq!(disc.predict_woe(&fare).unwrap(), woe);
+        assert_eq!(disc.exception_summary().unwrap(), exceptions);
+        assert_eq!(
+            disc.missing_summary().unwrap().unwrap().totals_ct,
+            missing.unwrap().totals_ct
+        );
+        for (a, b) in disc.bin_summary().unwrap().iter().zip(&bins) {
+            assert_eq!(a.totals_ct, b.totals_ct);
+            assert!((a.woe - b.woe).abs() < 1e-9);
+        }
+        assert!(disc.tree().is_err());
+
+        // Bins can be merged, but not split.
+        assert!(matches!(
+            disc.split_bin_at(20.0),
+            Err(DiscrustError::InvalidParameter(_))
+        ));
+        assert!(disc.add_exception_value(0.0).is_err());
+        disc.merge_bins(0, 1).unwrap();
+        assert_eq!(
+            disc.bin_summary().unwrap()[0].totals_ct,
+            bins[0].totals_ct + bins[1].totals_ct
+        );
+
+        // Recomputing the statistics builds the full feature again.
+        disc.recompute_stats(&fare, &survived, None).unwrap();
+        assert!(disc.split_bin_at(20.0).is_ok());
+    }
+}
@@ -0,0 +1,188 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::set::DiscretizerSet;
+use crate::summary::FitResult;
+use std::path::Path;
+
+impl Discretizer {
+    /// Fit the discretizer on a column of a CSV file, which must have a
+    /// header row naming each column. Quoted values, and whitespace around
+    /// values, are allowed. Empty cells, and cells of NA, are read as
+    /// missing (NaN), this is only allowed in the `x_col` column. When
+    /// no `w_col` is given, every record has a weight of 1.
+    pub fn fit_csv<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        x_col: &str,
+        y_col: &str,
+        w_col: Option<&str>,
+        exception_values: Option<Vec<f64>>,
+    ) -> Result<FitResult, DiscrustError> {
+        let (mut x, y, w) = read_fit_columns(path.as_ref(), &[x_col], y_col, w_col)?;
+        self.fit(&x.remove(0), &y, Some(&w), exception_values)
+    }
+}
+
+impl DiscretizerSet {
+    /// Fit each discretizer on the column of a CSV file with the same
+    /// name, read in the same way as `Discretizer::fit_csv`. The results
+    /// are in the same order as `names`.
+    pub fn fit_csv<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        y_col: &str,
+        w_col: Option<&str>,
+        exception_values: Option<Vec<f64>>,
+    ) -> Result<Vec<FitResult>, DiscrustError> {
+        let names: Vec<&str> = self.names.iter().map(|n| n.as_str()).collect();
+        let (x, y, w) = read_fit_columns(path.as_ref(), &names, y_col, w_col)?;
+        let x: Vec<&[f64]> = x.iter().map(|c| c.as_slice()).collect();
+        self.fit(&x, &y, &w, exception_values)
+    }
+
+    /// The weight of evidence of each record of a CSV file, read in the
+    /// same way as `Discretizer::fit_csv`, returned as a row for each
+    /// record, with a value for each column in the order of `names`.
+    pub fn predict_woe_csv<P: AsRef<Path>>(&self, path: P) -> Result<Vec<Vec<f64>>, DiscrustError> {
+        let names: Vec<&str> = self.names.iter().map(|n| n.as_str()).collect();
+        let x = read_columns(path.as_ref(), &names)?;
+        let x: Vec<&[f64]> = x.iter().map(|c| c.as_slice()).collect();
+        self.predict_woe_matrix(&x)
+    }
+}
+
+// The feature columns, the performance column, and
+// the weight column, or a weight of 1 for each record.
+type FitColumns = (Vec<Vec<f64>>, Vec<f64>, Vec<f64>);
+
+fn read_fit_columns(
+    path: &Path,
+    x_cols: &[&str],
+    y_col: &str,
+    w_col: Option<&str>,
+) -> Result<FitColumns, DiscrustError> {
+    let mut columns: Vec<&str> = x_cols.to_vec();
+    columns.push(y_col);
+    columns.extend(w_col);
+    let mut values = read_columns(path, &columns)?;
+    let w = match w_col {
+        Some(_) => values.pop().unwrap(),
+        None => vec![1.0; values[0].len()],
+    };
+    let y = values.pop().unwrap();
+    Ok((values, y, w))
+}
+
+// The named columns of a CSV file, in the order of `columns`.
+fn read_columns(path: &Path, columns: &[&str]) -> Result<Vec<Vec<f64>>, DiscrustError> {
+    let error = |e: String| DiscrustError::ReadCsv(path.display().to_string(), e);
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .map_err(|e| error(e.to_string()))?;
+    let headers = reader.headers().map_err(|e| error(e.to_string()))?.clone();
+    let positions = columns
+        .iter()
+        .map(|c| {
+            headers
+                .iter()
+                .position(|h| h == *c)
+                .ok_or_else(|| error(format!("there is no column named {}", c)))
+        })
+        .collect::<Result<Vec<usize>, DiscrustError>>()?;
+    let mut values = vec![Vec::new(); columns.len()];
+    for (row, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| error(e.to_string()))?;
+        for ((col, pos), name) in values.iter_mut().zip(&positions).zip(columns) {
+            let cell = record.get(*pos).unwrap_or("");
+            let v = parse_cell(cell).ok_or_else(|| {
+                error(format!(
+                    "the value {:?} in column {} of row {} is not a number",
+                    cell,
+                    name,
+                    row + 1
+                ))
+            })?;
+            col.push(v);
+        }
+    }
+    Ok(values)
+}
+
+fn parse_cell(cell: &str) -> Option<f64> {
+    match cell {
+        "" | "NA" => Some(f64::NAN),
+        c => c.parse::<f64>().ok(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn write_csv(name: &str, contents: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("discrust_{}_{}.csv", name, std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_fit_csv() {
+        let path = write_csv(
+            "fit",
+            "\"name\",fare,age,survived,weight\n\
+             \"Braund, Mr. Owen\",1.0,22,1,1\n\
+             \"Cumings, Mrs. John\", 1.0 ,38,1,2\n\
+             Heikkinen,2.0,,0,1\n\
+             Futrelle,2.0,NA,1,1\n\
+             Allen,3.0,35,0,1\n\
+             Moran,3.0,35,0,1\n",
+        );
+        let x_ = vec![1.0, 1.0, 2.0, 2.0, 3.0, 3.0];
+        let y_ = vec![1.0, 1.0, 0.0, 1.0, 0.0, 0.0];
+        let w_ = vec![1.0, 2.0, 1.0, 1.0, 1.0, 1.0];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(0.0), None);
+        let res = disc
+            .fit_csv(&path, "fare", "survived", Some("weight"), None)
+            .unwrap();
+        let mut expected = disc.unfitted_copy();
+        assert_eq!(res, expected.fit(&x_, &y_, Some(&w_), None).unwrap());
+        // Without a weight column every record has a weight of 1.
+        let res = disc.fit_csv(&path, "fare", "survived", None, None).unwrap();
+        assert_eq!(res.bins.iter().map(|b| b.totals_ct).sum::<f64>(), 6.0);
+
+        let mut set = DiscretizerSet::new(&disc, vec![String::from("fare"), String::from("age")]);
+        let res = set.fit_csv(&path, "survived", None, None).unwrap();
+        assert_eq!(res.len(), 2);
+        // The empty, and NA, ages are missing.
+        assert_eq!(res[1].missing.as_ref().unwrap().totals_ct, 2.0);
+        let woe = set.predict_woe_csv(&path).unwrap();
+        assert_eq!(woe.len(), 6);
+        assert_eq!(woe[2][1], res[1].missing.as_ref().unwrap().woe);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_fit_csv_errors() {
+        let path = write_csv("errors", "fare,survived\n1.0,1\nlow,0\n2.0,\n");
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(0.0), None);
+        match disc.fit_csv(&path, "fare", "survived", None, None) {
+            Err(DiscrustError::ReadCsv(_, e)) => assert!(e.contains("row 2")),
+            r => panic!("Unexpected result {:?}", r),
+        }
+        assert!(matches!(
+            disc.fit_csv(&path, "age", "survived", None, None),
+            Err(DiscrustError::ReadCsv(..))
+        ));
+        fs::write(&path, "fare,survived\n1.0,1\n2.0,\n").unwrap();
+        assert!(matches!(
+            disc.fit_csv(&path, "fare", "survived", None, None),
+            Err(DiscrustError::ContainsNaN(_))
+        ));
+        fs::remove_file(&path).unwrap();
+        assert!(disc.fit_csv(&path, "fare", "survived", None, None).is_err());
+    }
+}
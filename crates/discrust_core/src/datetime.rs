@@ -0,0 +1,179 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The type of the feature being discretized, this determines
+/// how the bin edges are rounded and rendered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FeatureType {
+    Numeric,
+    /// A feature of epoch timestamps. Splits are still learned
+    /// numerically, but are rendered as dates, and will be rounded
+    /// to the end of the `snap` period.
+    Datetime {
+        unit: TimeUnit,
+        snap: DateSnap,
+    },
+}
+
+/// The unit of an epoch timestamp.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TimeUnit {
+    Seconds,
+    Days,
+}
+
+/// The boundary datetime splits should be rounded to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DateSnap {
+    None,
+    Day,
+    Month,
+}
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+impl FeatureType {
+    /// Round a value to the last instant of the snap period it falls
+    /// in. All values in the same period will share a value, so any
+    /// split learned on the snapped values will be on a period boundary.
+    pub fn snap(&self, v: f64) -> f64 {
+        let (unit, snap) = match self {
+            FeatureType::Datetime { unit, snap } => (unit, snap),
+            FeatureType::Numeric => return v,
+        };
+        if !v.is_finite() {
+            return v;
+        }
+        let days = to_days(v, unit);
+        let end_day = match snap {
+            DateSnap::None => return v,
+            DateSnap::Day => days,
+            DateSnap::Month => {
+                let (y, m, _) = civil_from_days(days);
+                days_from_civil(y, m, days_in_month(y, m))
+            }
+        };
+        match unit {
+            TimeUnit::Days => end_day as f64,
+            TimeUnit::Seconds => (end_day as f64 + 1.0) * SECONDS_PER_DAY - 1.0,
+        }
+    }
+
+    /// Render a value, such as a bin edge, as a string.
+    pub fn format_value(&self, v: f64) -> String {
+        if !v.is_finite() {
+            return format!("{}", v);
+        }
+        match self {
+            FeatureType::Numeric => format!("{}", v),
+            FeatureType::Datetime { unit, snap } => {
+                let days = to_days(v, unit);
+                let (y, m, d) = civil_from_days(days);
+                let date = format!("{:04}-{:02}-{:02}", y, m, d);
+                if (*unit == TimeUnit::Days) | (*snap != DateSnap::None) {
+                    return date;
+                }
+                let secs = (v - (days as f64) * SECONDS_PER_DAY).floor() as i64;
+                format!(
+                    "{}T{:02}:{:02}:{:02}",
+                    date,
+                    secs / 3600,
+                    (secs % 3600) / 60,
+                    secs % 60
+                )
+            }
+        }
+    }
+}
+
+fn to_days(v: f64, unit: &TimeUnit) -> i64 {
+    match unit {
+        TimeUnit::Days => v.floor() as i64,
+        TimeUnit::Seconds => (v / SECONDS_PER_DAY).floor() as i64,
+    }
+}
+
+fn is_leap_year(y: i64) -> bool {
+    (y % 4 == 0) && ((y % 100 != 0) || (y % 400 == 0))
+}
+
+fn days_in_month(y: i64, m: u32) -> u32 {
+    match m {
+        2 if is_leap_year(y) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Convert days since 1970-01-01 to a (year, month, day) date.
+// See http://howardhinnant.github.io/date_algorithms.html
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + (m <= 2) as i64;
+    (y, m, d)
+}
+
+// Convert a (year, month, day) date to days since 1970-01-01.
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let m = m as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn test_civil_days_round_trip() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(18321), (2020, 2, 29));
+        for d in -1000..1000 {
+            let (y, m, dd) = civil_from_days(d * 37);
+            assert_eq!(days_from_civil(y, m, dd), d * 37);
+        }
+    }
+
+    #[test]
+    fn test_snap_and_format() {
+        let ft = FeatureType::Datetime {
+            unit: TimeUnit::Seconds,
+            snap: DateSnap::Month,
+        };
+        // 2020-02-10T12:00:00
+        let v = 1581336000.0;
+        // 2020-02-29T23:59:59
+        assert_eq!(ft.snap(v), 1583020799.0);
+        assert_eq!(ft.format_value(ft.snap(v)), "2020-02-29");
+
+        let ft = FeatureType::Datetime {
+            unit: TimeUnit::Seconds,
+            snap: DateSnap::None,
+        };
+        assert_eq!(ft.snap(v), v);
+        assert_eq!(ft.format_value(v), "2020-02-10T12:00:00");
+
+        let ft = FeatureType::Datetime {
+            unit: TimeUnit::Days,
+            snap: DateSnap::Month,
+        };
+        assert_eq!(ft.format_value(ft.snap(18302.0)), "2020-02-29");
+        assert_eq!(ft.format_value(f64::INFINITY), "inf");
+        assert_eq!(FeatureType::Numeric.format_value(6.95), "6.95");
+    }
+}
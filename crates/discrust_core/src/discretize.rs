@@ -1,22 +1,221 @@
-use crate::errors::DiscrustError;
-use crate::feature::Feature;
-use crate::node::{Node, NodePtr};
-use crate::utils::nan_safe_compare;
+use crate::datetime::{DateSnap, FeatureType};
+use crate::errors::{DiscrustError, UnsatisfiableConstraint};
+use crate::feature::{check_records, check_weights, ExceptionValues, Feature, ValueCounts};
+use crate::label::LabelFormat;
+use crate::metadata::FitMetadata;
+use crate::node::{Node, NodePtr, SplitInfo, SplitResult};
+use crate::parallel::{SplitSearch, Threads};
+use crate::partial::PartialFit;
+use crate::report::{FitEvent, StopReason};
+use crate::segment::{check_segment, segment_indices, take_records, BinConsistency};
+use crate::spec::InputSpec;
+use crate::strategy::{
+    Closed, Growth, MonotonicTrend, OnMissing, SplitPoint, Strategy, TargetType, TieBreak,
+};
+use crate::summary::{BinSummary, ExceptionSummary, FitResult, FitWarning};
+use crate::transform::PreTransform;
+use crate::utils::{nan_safe_compare, to_f64, weighted_quantile};
+use num::Float;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::cmp::Ordering;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 
+// A fitted discretizer is shared by threads scoring concurrently, so it
+// must stay `Send` and `Sync` as fields are added.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Discretizer>();
+};
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Discretizer {
-    min_obs: f64,
-    max_bins: i64,
-    min_iv: f64,
-    min_pos: f64,
-    pub mono: Option<i8>,
-    root_node: NodePtr,
+    pub(crate) min_obs: f64,
+    pub(crate) max_bins: i64,
+    pub(crate) min_iv: f64,
+    pub(crate) min_pos: f64,
+    pub min_effective_obs: Option<f64>,
+    pub approx_splits: Option<usize>,
+    /// The largest number of candidate splits of the feature. If set, the
+    /// values are aggregated into this many buckets, of about the same
+    /// weight, as the records are counted, so only the bucket boundaries
+    /// can be split on. Unlike `approx_splits`, the buckets are found once,
+    /// and also reduce the number of unique values the feature holds.
+    pub max_candidates: Option<usize>,
+    /// The smallest share of the total weight required in a bin. If
+    /// `min_obs` is also set, the larger of the two thresholds is used.
+    pub min_obs_rate: Option<f64>,
+    /// The smallest share of the total weight, that must be records with
+    /// a value of one, in a bin. If `min_pos` is also set, the larger of
+    /// the two thresholds is used.
+    pub min_pos_rate: Option<f64>,
+    /// Scale the minimum count of ones in a bin to the imbalance of a
+    /// binary target, in place of `min_pos`. The minimum is the number of
+    /// ones a bin of `min_obs` records would hold at the overall event
+    /// rate, and at least one, so a rare event does not require bins far
+    /// larger than `min_obs`, and a common one is not under constrained.
+    pub min_pos_auto: bool,
+    /// The largest p-value of a two-proportion z-test, between the event
+    /// rates of the two halves of a split, for the split to be made.
+    pub max_pvalue: Option<f64>,
+    /// The narrowest a numeric bin may be, the distance between its lower
+    /// and upper split, so no split is made that would create a narrower
+    /// bin. If a `pre_transform` is set, the width is on the transformed
+    /// scale the splits are searched on.
+    pub min_bin_width: Option<f64>,
+    /// The largest share of the total weight a bin may hold. A bin holding
+    /// more is split further, even if the information value of the split
+    /// is below `min_iv`, while a split meeting the other constraints,
+    /// and `max_bins`, can be found.
+    pub max_bin_pct: Option<f64>,
+    /// Accept a split only if its fall in the entropy of the target passes
+    /// the minimum description length test of Fayyad and Irani, in place
+    /// of requiring an information value of at least `min_iv`. This only
+    /// applies to a binary or multiclass target.
+    pub mdlp: bool,
+    /// The smallest information value a split must gain over the node it
+    /// splits, `lhs_iv + rhs_iv - iv`, as a share of the node's information
+    /// value, so splits that barely improve on their node are not made.
+    /// The root node has no information value, so its split only needs
+    /// to satisfy `min_iv`.
+    pub min_iv_gain_ratio: Option<f64>,
+    /// The order the nodes of the tree are split in, which decides
+    /// which splits are made once `max_bins` is reached.
+    pub growth: Growth,
+    /// Record the search for the best split of each node of the tree
+    /// when fitting, returned by `fit_trace`.
+    pub verbose: bool,
+    /// The number of threads the records are sorted and counted with,
+    /// and the splits searched for with, when built with the `rayon`
+    /// feature. Zero uses one thread per core, and None the calling thread.
+    pub n_threads: Option<usize>,
+    pub mono: MonotonicTrend,
+    pub feature_type: FeatureType,
+    pub pre_transform: Option<PreTransform>,
+    pub clip_quantiles: Option<(f64, f64)>,
+    /// Merge adjacent bins after fitting, until the weight of evidence
+    /// of the bins follows `mono` across all of the bins, not only
+    /// between the halves of each split.
+    pub enforce_monotonicity: bool,
+    /// Added to the ones and zero counts of every bin, and exception
+    /// value, when calculating the weight of evidence, so bins with
+    /// only ones or only zeros have a finite weight of evidence, and
+    /// are not chosen as splits for their infinite information value.
+    pub woe_regularization: f64,
+    /// Collect missing (NaN) values in a bin of their own, with its own
+    /// counts and weight of evidence, unless NaN is passed as an exception
+    /// value. If false, NaN must be an exception value when fitting.
+    pub special_missing: bool,
+    /// The number of folds the records are split into, when each
+    /// candidate split is checked by cross validation. A split is only
+    /// made if its information value gain, on the records held out in
+    /// each fold, is positive on at least `cv_agreement` of the folds.
+    pub cv_folds: Option<usize>,
+    /// The share of the `cv_folds` a split must hold up on.
+    pub cv_agreement: f64,
+    /// Once the bins are found, merge each exception value with records
+    /// into the bin with the closest weight of evidence, or mean of a
+    /// continuous performance, adding its records to those of the bin. A
+    /// merged value is predicted as the bin it was merged into. The values
+    /// are merged one at a time, as each changes the statistics of its bin.
+    pub merge_exceptions: bool,
+    pub target_type: TargetType,
+    pub strategy: Strategy,
+    pub split_point: SplitPoint,
+    /// Which end of each bin is closed, so which bin a value equal
+    /// to a split falls in. The records on each side of a split are
+    /// the same either way, only the value of the split changes.
+    pub closed: Closed,
+    /// How a tie, between candidate splits with exactly the same
+    /// information value, is broken, so the split found does not
+    /// depend on the order the candidates are evaluated in.
+    pub tie_break: TieBreak,
+    pub on_missing: OnMissing,
+    pub label_format: LabelFormat,
+    pub input_spec: Option<InputSpec>,
+    /// User supplied tags, recorded in the metadata when fitting.
+    pub tags: BTreeMap<String, String>,
+    pub(crate) root_node: NodePtr,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float::vec"))]
     pub splits_: Vec<f64>,
     pub feature: Option<Feature>,
+    pub categories_: Vec<String>,
+    pub(crate) metadata_: Option<FitMetadata>,
+    // The records of the batches passed to `partial_fit`, which are
+    // not kept when the discretizer is serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) partial_: Option<PartialFit>,
+    // The trace of the last fit, when verbose, which is
+    // not kept when the discretizer is serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) fit_trace_: Vec<FitEvent>,
+}
+
+// The values prepared for fitting, and the (transformed, original)
+// value pairs of the pre transform.
+type PreparedValues<'a> = (Cow<'a, [f64]>, Vec<(f64, f64)>);
+
+// The (parameter, value) of the minimum counts used when fitting.
+struct Minimums {
+    min_obs: (&'static str, f64),
+    min_pos: (&'static str, f64),
+}
+
+impl Default for Discretizer {
+    fn default() -> Self {
+        Discretizer {
+            min_obs: 5.0,
+            max_bins: 10,
+            min_iv: 0.001,
+            min_pos: 5.0,
+            min_effective_obs: None,
+            approx_splits: None,
+            max_candidates: None,
+            min_obs_rate: None,
+            min_pos_rate: None,
+            min_pos_auto: false,
+            max_pvalue: None,
+            min_bin_width: None,
+            max_bin_pct: None,
+            mdlp: false,
+            min_iv_gain_ratio: None,
+            growth: Growth::BreadthFirst,
+            verbose: false,
+            n_threads: None,
+            mono: MonotonicTrend::Auto,
+            feature_type: FeatureType::Numeric,
+            pre_transform: None,
+            clip_quantiles: None,
+            enforce_monotonicity: false,
+            woe_regularization: 0.0,
+            special_missing: true,
+            cv_folds: None,
+            cv_agreement: 1.0,
+            merge_exceptions: false,
+            target_type: TargetType::Binary,
+            strategy: Strategy::Tree,
+            split_point: SplitPoint::Observed,
+            closed: Closed::Right,
+            tie_break: TieBreak::Lowest,
+            on_missing: OnMissing::LowestBin,
+            label_format: LabelFormat::default(),
+            input_spec: None,
+            tags: BTreeMap::new(),
+            root_node: None,
+            splits_: Vec::new(),
+            feature: None,
+            categories_: Vec::new(),
+            metadata_: None,
+            partial_: None,
+            fit_trace_: Vec::new(),
+        }
+    }
 }
 
 impl Discretizer {
+    /// A discretizer with the default parameters, other than those
+    /// passed. Use `Discretizer::builder` to set any of the others.
     pub fn new(
         min_obs: Option<f64>,
         max_bins: Option<i64>,
@@ -24,296 +223,3373 @@ impl Discretizer {
         min_pos: Option<f64>,
         mono: Option<i8>,
     ) -> Self {
-        let min_obs = min_obs.unwrap_or(5.0);
-        let max_bins = max_bins.unwrap_or(10);
-        let min_iv = min_iv.unwrap_or(0.001);
-        let min_pos = min_pos.unwrap_or(5.0);
+        let defaults = Discretizer::default();
         Discretizer {
-            min_obs,
-            max_bins,
-            min_iv,
-            min_pos,
-            mono,
-            root_node: None,
-            splits_: Vec::new(),
-            feature: None,
+            min_obs: min_obs.unwrap_or(defaults.min_obs),
+            max_bins: max_bins.unwrap_or(defaults.max_bins),
+            min_iv: min_iv.unwrap_or(defaults.min_iv),
+            min_pos: min_pos.unwrap_or(defaults.min_pos),
+            mono: MonotonicTrend::from(mono),
+            ..defaults
         }
     }
 
+    /// Create a discretizer that aggregates the values of the feature into
+    /// a histogram of at most `n_prebins` buckets, at approximate weighted
+    /// quantiles, such as 256 for a large sample. The records are counted
+    /// in a single pass, where only short runs of records are sorted, and
+    /// the splits are searched for among the bucket boundaries only, so
+    /// neither grows with the number of unique values. This is the same as
+    /// setting `max_candidates`, with the other parameters defaulted.
+    pub fn new_histogram(n_prebins: usize) -> Self {
+        Discretizer {
+            max_candidates: Some(n_prebins),
+            ..Discretizer::default()
+        }
+    }
+
+    /// Fit the discretizer, finding the splits of `x` against the
+    /// performance `y`. The records are weighted by `w`, if it is passed,
+    /// which must have a finite, non-negative weight for each record.
     pub fn fit(
         &mut self,
         x: &[f64],
         y: &[f64],
-        w: &[f64],
+        w: Option<&[f64]>,
+        exception_values: Option<Vec<f64>>,
+    ) -> Result<FitResult, DiscrustError> {
+        let threads = Threads::new(self.n_threads)?;
+        self.fit_on(x, y, w, exception_values, &threads)
+    }
+
+    // Fit the discretizer on the `threads`, which are built once, and
+    // shared by counting the records, and searching for the splits.
+    pub(crate) fn fit_on(
+        &mut self,
+        x: &[f64],
+        y: &[f64],
+        w: Option<&[f64]>,
         exception_values: Option<Vec<f64>>,
-    ) -> Result<Vec<f64>, DiscrustError> {
-        // Reset the splits
+        threads: &Threads,
+    ) -> Result<FitResult, DiscrustError> {
+        if x.is_empty() {
+            return Err(DiscrustError::EmptyInput);
+        }
+        self.check_target(y)?;
+        self.check_input(x)?;
+        // Reset the splits, and any batches from `partial_fit`.
         self.splits_ = Vec::new();
-        let e = match exception_values {
-            Some(v) => v,
-            None => Vec::new(),
+        self.categories_ = Vec::new();
+        self.metadata_ = None;
+        self.partial_ = None;
+        let e = self.with_manual_exceptions(exception_values);
+        let range = self.value_range(x, &e);
+        let (x, original_lookup) = self.prepare_values(x, w, &e)?;
+        self.validate_parameters()?;
+        let to_original =
+            |v: f64| match original_lookup.binary_search_by(|p| nan_safe_compare(&p.0, &v)) {
+                Ok(i) => original_lookup[i].1,
+                Err(_) => v,
+            };
+        let buckets = self.max_candidates.map(|n| (n, self.closed));
+        let mut feature = ValueCounts::new(&x, y, w, &e, self.special_missing, buckets, threads)?
+            .into_feature(self.target_type);
+        // The range of the values as passed, rather than as prepared.
+        (feature.min_, feature.max_) = range;
+        // The classes and folds are counted against the buckets.
+        let x = match buckets {
+            Some(_) if (self.target_type == TargetType::Multiclass) || self.cv_folds.is_some() => {
+                Cow::Owned(feature.bucket_values(&x, self.closed))
+            }
+            _ => x,
+        };
+        if self.target_type == TargetType::Multiclass {
+            feature.set_classes(&x, y, w)?;
+            // There is no single weight of evidence to follow a trend.
+            if self.mono == MonotonicTrend::Auto {
+                self.mono = MonotonicTrend::Unconstrained;
+            }
+        }
+        if let Some(n_folds) = self.cv_folds {
+            feature.set_folds(&x, y, w, n_folds, self.cv_agreement);
+        }
+        self.fit_feature(feature, to_original, y.len(), threads)
+    }
+
+    /// Create a discretizer with the given splits, such as cut points set
+    /// by a business rule, rather than searching for them. Infinite splits
+    /// are ignored, as the first and last bins are always unbounded. The
+    /// exception values are held out of the bins when it is fit, with
+    /// `fit_stats`, which only computes the statistics of each bin.
+    pub fn from_splits(
+        splits: &[f64],
+        exception_values: &[f64],
+    ) -> Result<Discretizer, DiscrustError> {
+        Discretizer::builder()
+            .strategy(Strategy::Manual {
+                splits: splits.to_vec(),
+                exception_values: exception_values.to_vec(),
+            })
+            .build()
+    }
+
+    /// Compute the statistics of the bins, exception values and missing
+    /// bin of a discretizer created with `from_splits`, after which it
+    /// can predict as any fitted discretizer.
+    pub fn fit_stats(
+        &mut self,
+        x: &[f64],
+        y: &[f64],
+        w: Option<&[f64]>,
+    ) -> Result<FitResult, DiscrustError> {
+        if !matches!(self.strategy, Strategy::Manual { .. }) {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "fit_stats can only be used with the manual strategy, see from_splits",
+            )));
+        }
+        self.fit(x, y, w, None)
+    }
+
+    // The exception values passed when fitting, along with
+    // those of the manual strategy.
+    pub(crate) fn with_manual_exceptions(&self, exception_values: Option<Vec<f64>>) -> Vec<f64> {
+        let mut e = exception_values.unwrap_or_default();
+        if let Strategy::Manual {
+            exception_values, ..
+        } = &self.strategy
+        {
+            e.extend(exception_values);
+        }
+        e
+    }
+
+    // Check that a binary performance only has values of 0 and 1, as
+    // any other value would be counted as a 0. NaN values are reported
+    // when the records are counted.
+    pub(crate) fn check_target(&self, y: &[f64]) -> Result<(), DiscrustError> {
+        if self.target_type != TargetType::Binary {
+            return Ok(());
+        }
+        match y.iter().find(|v| !(v.is_nan() || **v == 0.0 || **v == 1.0)) {
+            Some(v) => Err(DiscrustError::InvalidTarget(format!(
+                "the value {} of a binary performance is not 0 or 1",
+                v
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    // Apply the datetime snapping, clipping, pre-binning and pre transform
+    // to the values, before they are aggregated. The (transformed, original)
+    // value pairs of the pre transform are returned, sorted by the
+    // transformed value, and are empty if there is no pre transform.
+    pub(crate) fn prepare_values<'a>(
+        &self,
+        x: &'a [f64],
+        w: Option<&[f64]>,
+        e: &[f64],
+    ) -> Result<PreparedValues<'a>, DiscrustError> {
+        let is_exception = |v: &f64| {
+            e.iter()
+                .any(|ev| nan_safe_compare(ev, v) == Ordering::Equal)
+        };
+        let mut x = Cow::Borrowed(x);
+        // Datetime features are snapped to the end of their period, so
+        // that all splits fall on a period boundary. Exception values
+        // are left as is, so they can still be identified.
+        if let FeatureType::Datetime { .. } = self.feature_type {
+            x = Cow::Owned(
+                x.iter()
+                    .map(|v| {
+                        if is_exception(v) {
+                            *v
+                        } else {
+                            self.feature_type.snap(*v)
+                        }
+                    })
+                    .collect(),
+            );
+        }
+        // Winsorize the feature at the clip quantiles, so extreme values
+        // are not considered as possible splits. When predicting, values
+        // beyond these quantiles will fall in the first and last bins.
+        if let Some((lower_q, upper_q)) = self.clip_quantiles {
+            if !((0.0..=1.0).contains(&lower_q) && (0.0..=1.0).contains(&upper_q))
+                || (lower_q >= upper_q)
+            {
+                return Err(DiscrustError::InvalidParameter(String::from(
+                    "clip_quantiles must be increasing values between 0 and 1",
+                )));
+            }
+            if let Some(w) = w {
+                check_weights(w, x.len())?;
+            }
+            let (x_, w_): (Vec<f64>, Vec<f64>) = x
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| !is_exception(v))
+                .map(|(i, v)| (*v, w.map_or(1.0, |w| w[i])))
+                .unzip();
+            let lower = weighted_quantile(&x_, &w_, lower_q);
+            let upper = weighted_quantile(&x_, &w_, upper_q);
+            x = Cow::Owned(
+                x.iter()
+                    .map(|v| {
+                        if is_exception(v) || v.is_nan() {
+                            *v
+                        } else {
+                            v.clamp(lower, upper)
+                        }
+                    })
+                    .collect(),
+            );
+        }
+        // When only merging pre-bins, each value is replaced by the
+        // upper edge of its pre-bin, so only these edges can be split
+        // on. Values above the last edge all share the same value, which
+        // will be the largest, and so is never a candidate split. When
+        // bins are closed on the left, the pre-bins are too, and each
+        // value is replaced by the lower edge of its pre-bin instead.
+        if let Strategy::MergeOnly { edges } = &self.strategy {
+            if edges.iter().any(|e| e.is_nan()) {
+                return Err(DiscrustError::InvalidParameter(String::from(
+                    "merge only edges cannot contain NaN",
+                )));
+            }
+            let mut edges = edges.to_vec();
+            edges.sort_by(nan_safe_compare);
+            x = Cow::Owned(
+                x.iter()
+                    .map(|v| {
+                        if is_exception(v) || v.is_nan() {
+                            *v
+                        } else if self.closed == Closed::Left {
+                            match edges.partition_point(|e| e <= v) {
+                                0 => f64::NEG_INFINITY,
+                                i => edges[i - 1],
+                            }
+                        } else {
+                            *edges
+                                .get(edges.partition_point(|e| e < v))
+                                .unwrap_or(&f64::INFINITY)
+                        }
+                    })
+                    .collect(),
+            );
+        }
+        // If there is a pre transform, the splits are searched for on the
+        // transformed scale. The (transformed, original) value pairs are
+        // kept, so that the splits can be expressed on the original scale.
+        let mut original_lookup: Vec<(f64, f64)> = Vec::new();
+        // Values outside of the domain of the transform would otherwise
+        // become NaN, and be silently counted as missing.
+        if let Some(t) = self.pre_transform {
+            let x_transformed: Vec<f64> = x
+                .iter()
+                .map(|v| {
+                    if is_exception(v) {
+                        return Ok(*v);
+                    }
+                    let v_t = t.apply(*v);
+                    if v_t.is_nan() && !v.is_nan() {
+                        return Err(DiscrustError::InvalidParameter(format!(
+                            "{} is outside of the domain of the pre transform {:?}",
+                            v, t
+                        )));
+                    }
+                    Ok(v_t)
+                })
+                .collect::<Result<Vec<f64>, DiscrustError>>()?;
+            original_lookup = x_transformed
+                .iter()
+                .cloned()
+                .zip(x.iter().cloned())
+                .collect();
+            original_lookup.sort_by(|a, b| nan_safe_compare(&a.0, &b.0));
+            x = Cow::Owned(x_transformed);
+        }
+        Ok((x, original_lookup))
+    }
+
+    // Check the fitting parameters that are not checked
+    // when the values are prepared.
+    pub(crate) fn validate_parameters(&self) -> Result<(), DiscrustError> {
+        if !self.woe_regularization.is_finite() || (self.woe_regularization < 0.0) {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "woe_regularization must be a finite value of at least 0",
+            )));
+        }
+        for (name, rate) in [
+            ("min_obs_rate", self.min_obs_rate),
+            ("min_pos_rate", self.min_pos_rate),
+        ] {
+            if rate.is_some_and(|r| r.is_nan() || !(0.0..=1.0).contains(&r)) {
+                return Err(DiscrustError::InvalidParameter(format!(
+                    "{} must be between 0 and 1",
+                    name
+                )));
+            }
+        }
+        if self
+            .max_pvalue
+            .is_some_and(|p| p.is_nan() || !(0.0..=1.0).contains(&p))
+        {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "max_pvalue must be between 0 and 1",
+            )));
+        }
+        if self.min_bin_width.is_some_and(|w| w.is_nan() || (w < 0.0)) {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "min_bin_width must not be negative",
+            )));
+        }
+        if self
+            .max_bin_pct
+            .is_some_and(|p| p.is_nan() || (p <= 0.0) || (p > 1.0))
+        {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "max_bin_pct must be greater than 0, and at most 1",
+            )));
+        }
+        if self.max_candidates.is_some_and(|n| n < 2) {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "max_candidates must be at least 2",
+            )));
+        }
+        if self.cv_folds.is_some() && (self.target_type != TargetType::Binary) {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "cv_folds can only be used with a binary target",
+            )));
+        }
+        if (self.target_type == TargetType::Multiclass)
+            && (self.max_pvalue.is_some()
+                || self.enforce_monotonicity
+                || self.merge_exceptions
+                || !matches!(
+                    self.mono,
+                    MonotonicTrend::Auto | MonotonicTrend::Unconstrained
+                ))
+        {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "max_pvalue, enforce_monotonicity, merge_exceptions and a monotonic trend can not be used with a multiclass target",
+            )));
+        }
+        if self.cv_folds.is_some_and(|k| k < 2) {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "cv_folds must be at least 2",
+            )));
+        }
+        if self.cv_agreement.is_nan() || !(0.0..=1.0).contains(&self.cv_agreement) {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "cv_agreement must be between 0 and 1",
+            )));
+        }
+        if let Strategy::Manual { splits, .. } = &self.strategy {
+            if splits.iter().any(|s| s.is_nan()) || splits.windows(2).any(|w| w[0] >= w[1]) {
+                return Err(DiscrustError::InvalidParameter(String::from(
+                    "manual splits must be strictly increasing, and not NaN",
+                )));
+            }
+        }
+        if let (Closed::Left, FeatureType::Datetime { snap, .. }) = (self.closed, self.feature_type)
+        {
+            if snap != DateSnap::None {
+                return Err(DiscrustError::InvalidParameter(String::from(
+                    "bins closed on the left can not be used with a snapped datetime feature, as its splits are the end of a period",
+                )));
+            }
+        }
+        if let Strategy::Chaid { max_children } = self.strategy {
+            if max_children < 2 {
+                return Err(DiscrustError::InvalidParameter(String::from(
+                    "max_children must be at least 2",
+                )));
+            }
+            if self.cv_folds.is_some()
+                || matches!(self.mono, MonotonicTrend::Peak | MonotonicTrend::Valley)
+            {
+                return Err(DiscrustError::InvalidParameter(String::from(
+                    "cv_folds, and a peak or valley trend, can not be used with the chaid strategy",
+                )));
+            }
+        }
+        if self
+            .min_iv_gain_ratio
+            .is_some_and(|r| !r.is_finite() || (r < 0.0))
+        {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "min_iv_gain_ratio must be a finite value of at least 0",
+            )));
+        }
+        if self.mdlp
+            && ((self.target_type == TargetType::Continuous)
+                || matches!(self.strategy, Strategy::OptimalDP { .. }))
+        {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "mdlp can not be used with a continuous target, or the optimal dp strategy",
+            )));
+        }
+        if let Strategy::OptimalDP { n_atoms } = self.strategy {
+            if !(2..=500).contains(&n_atoms) {
+                return Err(DiscrustError::InvalidParameter(String::from(
+                    "n_atoms must be between 2 and 500",
+                )));
+            }
+            if self.cv_folds.is_some()
+                || self.max_bin_pct.is_some()
+                || matches!(self.mono, MonotonicTrend::Peak | MonotonicTrend::Valley)
+            {
+                return Err(DiscrustError::InvalidParameter(String::from(
+                    "cv_folds, max_bin_pct, and a peak or valley trend, can not be used with the optimal dp strategy",
+                )));
+            }
+        }
+        if self.strategy.unsupervised_bins() == Some(0) {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "n_bins must be at least 1",
+            )));
+        }
+        Ok(())
+    }
+
+    // Grow the tree on the aggregated values of the feature, of `n_records`
+    // records, and store the splits, searching for them on the `threads`.
+    // The splits are mapped back to the original scale of the values with
+    // `to_original`.
+    pub(crate) fn fit_feature<F: Fn(f64) -> f64>(
+        &mut self,
+        mut feature: Feature,
+        to_original: F,
+        n_records: usize,
+        threads: &Threads,
+    ) -> Result<FitResult, DiscrustError> {
+        feature.set_woe_regularization(self.woe_regularization);
+        let (total_ones, total_zero) = feature.class_totals();
+        let minimums = self.resolve_minimums(total_ones + total_zero, total_ones);
+        self.check_root_constraints(&feature, &minimums)?;
+        let (min_obs, min_pos) = (minimums.min_obs.1, minimums.min_pos.1);
+        let max_bin_ct = self.max_bin_pct.map(|p| p * (total_ones + total_zero));
+        let midpoints = self.uses_midpoints();
+        let split_point = if midpoints {
+            SplitPoint::Midpoint
+        } else {
+            SplitPoint::Observed
         };
-        let feature = Feature::new(x, y, w, &e)?;
-        let root_node = Node::new(
-            &feature,
-            Some(self.min_obs),
-            Some(self.min_iv),
-            Some(self.min_pos),
-            self.mono,
-            None,
-            None,
-            None,
-            None,
+        // A node of the tree, with the trend it requires, its statistics,
+        // and the positions of its values, by default those of the root.
+        // The parameters are copied, so the tree can be grown in place.
+        let (min_iv, min_effective_obs, approx_splits, max_pvalue) = (
+            self.min_iv,
+            self.min_effective_obs,
+            self.approx_splits,
+            self.max_pvalue,
         );
+        let (min_bin_width, closed, mdlp, min_iv_gain_ratio) = (
+            self.min_bin_width,
+            self.closed,
+            self.mdlp,
+            self.min_iv_gain_ratio,
+        );
+        let new_node = |mono, woe, iv, start, stop| {
+            Node::new(
+                &feature,
+                Some(min_obs),
+                Some(min_iv),
+                Some(min_pos),
+                min_effective_obs,
+                approx_splits,
+                max_pvalue,
+                mono,
+                woe,
+                iv,
+                start,
+                stop,
+            )
+            .with_min_bin_width(min_bin_width, closed, split_point)
+            .with_max_bin_ct(max_bin_ct)
+            .with_mdlp(mdlp)
+            .with_min_iv_gain_ratio(min_iv_gain_ratio)
+        };
+        // In midpoint mode, the split is moved halfway to the next
+        // observed value, or if bins are closed on the left, to the
+        // next observed value, the first of the upper bin at `idx`.
+        let place = |info: &SplitInfo, idx: usize| {
+            let split = to_original(info.split.unwrap());
+            match (midpoints, closed) {
+                (true, _) => (split + to_original(feature.vals_[idx])) / 2.0,
+                (false, Closed::Left) => to_original(feature.vals_[idx]),
+                (false, Closed::Right) => split,
+            }
+        };
+        let root_node = new_node(self.mono, None, None, None, None);
 
+        let search = SplitSearch::new(threads, self.tie_break);
+        self.fit_trace_.clear();
         self.root_node = Some(Box::new(root_node));
-        let mut que = VecDeque::new();
-        que.push_front(self.root_node.as_mut());
+        // The nodes waiting to be split, and their best split, or the
+        // reason they can not be split, once searched.
+        let mut que: VecDeque<(&mut Box<Node>, Option<SplitResult>)> = VecDeque::new();
+        // Unsupervised strategies don't search for splits, so the root
+        // node is never split, and the optimal partition is found at once.
+        match (
+            self.unsupervised_splits(&feature, &to_original),
+            &self.strategy,
+        ) {
+            (Some(splits), _) => self.splits_ = splits,
+            (None, Strategy::OptimalDP { n_atoms }) => {
+                let root = self.root_node.as_mut().unwrap();
+                let max_bins = self.max_bins.max(0) as usize;
+                match root.find_optimal_partition(&feature, *n_atoms, max_bins) {
+                    Ok((infos, trend)) => {
+                        if self.mono == MonotonicTrend::Auto {
+                            self.mono = trend;
+                        }
+                        let (_, splits) = split_chain(root, infos, (trend, trend), new_node, place);
+                        self.splits_.extend(splits);
+                    }
+                    Err(reason) => root.stop_reason = Some(reason),
+                }
+            }
+            (None, _) => que.push_back((self.root_node.as_mut().unwrap(), None)),
+        }
+        // The information value gained by splitting a node.
+        let gain =
+            |node: &Node, info: &SplitInfo| info.lhs_iv.unwrap() + info.rhs_iv.unwrap() - node.iv;
         let mut n_bins = 1;
-        while !que.is_empty() {
-            // If we are running this piece of code, the que is not empty, so
-            // we can always safely unwrap.
-            let mut node = que.pop_back().unwrap().unwrap();
-            let info = node.find_best_split(&feature);
-            // If this feature doesn't produce a valid
-            // split, just continue this is a terminal node.
-            let split = match info.split {
-                Some(split) => split,
-                None => continue,
+        let mut warnings = Vec::new();
+        loop {
+            // The best split of every node waiting in the queue that has not
+            // been searched is found together, once the next node to split,
+            // or for best first growth, any node, has not been searched.
+            let searched = match self.growth {
+                Growth::BreadthFirst => que.front().is_none_or(|(_, info)| info.is_some()),
+                Growth::DepthFirst => que.back().is_none_or(|(_, info)| info.is_some()),
+                Growth::BestFirst => que.iter().all(|(_, info)| info.is_some()),
+            };
+            if !searched {
+                let nodes: Vec<&Node> = que
+                    .iter()
+                    .filter(|(_, info)| info.is_none())
+                    .map(|(node, _)| &***node)
+                    .collect();
+                let results = search.best_splits(&nodes, &feature);
+                if self.verbose {
+                    self.fit_trace_.extend(
+                        nodes.iter().zip(&results).map(|(node, result)| {
+                            FitEvent::new(node, &feature, result, &to_original)
+                        }),
+                    );
+                }
+                let mut infos = results.into_iter();
+                for (_, info) in que.iter_mut().filter(|(_, info)| info.is_none()) {
+                    *info = infos.next();
+                }
+                // If a node doesn't have a valid split, it's a terminal node.
+                for (node, info) in que.iter_mut() {
+                    if let Some(Err(reason)) = info {
+                        node.stop_reason = Some(*reason);
+                    }
+                }
+                que.retain(|(_, info)| info.as_ref().is_some_and(|i| i.is_ok()));
+            }
+            let next = match self.growth {
+                Growth::BreadthFirst => 0,
+                Growth::DepthFirst => que.len().saturating_sub(1),
+                // The first of the nodes with the largest gain.
+                Growth::BestFirst => que
+                    .iter()
+                    .enumerate()
+                    .fold(None, |best: Option<(usize, f64)>, (i, (node, info))| {
+                        let g = gain(node, info.as_ref().unwrap().as_ref().unwrap());
+                        match best {
+                            Some((_, b)) if b >= g => best,
+                            _ => Some((i, g)),
+                        }
+                    })
+                    .map_or(0, |(i, _)| i),
+            };
+            // The next node has been searched, and has a valid
+            // split, unless there are no nodes left to split.
+            let (node, info) = match que.remove(next) {
+                Some((node, Some(Ok(info)))) => (node, info),
+                _ => break,
             };
             n_bins += 1;
             if n_bins > self.max_bins {
+                warnings.push(FitWarning::MaxBinsReached);
+                node.stop_reason = Some(StopReason::MaxBins);
+                for (node, _) in que.iter_mut() {
+                    node.stop_reason = Some(StopReason::MaxBins);
+                }
                 break;
             }
 
-            // If monotonicity is None, then we can set it right
-            // now based on the monotonicity of the best first
-            // split.
-            if self.mono.is_none() {
-                let split_sign = if info.lhs_woe < info.rhs_woe { 1 } else { -1 };
-                self.mono = Some(split_sign);
+            // A multiway split is stored as a chain of binary splits, each
+            // splitting the lowest bin off the rest, with every bin given
+            // the trend of the split. Otherwise each half of the split gets
+            // the trend it requires, which differ for a peak or valley.
+            let (infos, (lhs_mono, rhs_mono)) = match self.strategy {
+                Strategy::Chaid { max_children } => {
+                    // The bins left under max_bins, with those of this node.
+                    let max_children = max_children.min((self.max_bins + 2 - n_bins) as usize);
+                    let (infos, trend) = node.find_best_partition(&feature, max_children, info);
+                    n_bins += infos.len() as i64 - 1;
+                    (infos, (trend, trend))
+                }
+                _ => {
+                    let trends = node
+                        .mono
+                        .children(info.lhs_woe.unwrap(), info.rhs_woe.unwrap());
+                    (vec![info], trends)
+                }
+            };
+            // If the trend is automatic, then we can set it right
+            // now based on the monotonicity of the best first split.
+            if self.mono == MonotonicTrend::Auto {
+                self.mono = lhs_mono;
+            }
+
+            let (mut bins, splits) =
+                split_chain(node, infos, (lhs_mono, rhs_mono), new_node, place);
+            self.splits_.extend(splits);
+            // The lowest bin is queued last, when growing depth
+            // first, so it is split first.
+            if self.growth == Growth::DepthFirst {
+                bins.reverse();
+            }
+            que.extend(bins.into_iter().map(|bin| (bin, None)));
+        }
+        feature.clear_folds();
+        // Store the feature on the original scale.
+        if self.pre_transform.is_some() {
+            feature.vals_.iter_mut().for_each(|v| *v = to_original(*v));
+        }
+        // Take ownership of feature for now.
+        self.feature = Some(feature);
+        self.splits_.push(-f64::INFINITY);
+        self.splits_.push(f64::INFINITY);
+        self.splits_.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        if self.enforce_monotonicity {
+            let merged = self.merge_non_monotonic_bins()?;
+            if merged > 0 {
+                warnings.push(FitWarning::BinsMerged(merged));
             }
+        }
+        if self.merge_exceptions {
+            self.merge_exceptions_into_bins();
+        }
 
-            let idx = info.split_idx.unwrap() + node.start + 1;
+        if self.splits_.len() == 2 {
+            warnings.push(FitWarning::NoSplits);
+        }
+        if let Some(max_bin_ct) = max_bin_ct {
+            let oversized = self
+                .bin_summary()?
+                .iter()
+                .filter(|b| b.totals_ct > max_bin_ct)
+                .count();
+            if oversized > 0 {
+                warnings.push(FitWarning::MaxBinPctExceeded(oversized));
+            }
+        }
+        let exceptions = &self.feature.as_ref().unwrap().exception_values_;
+        for (v, ct) in exceptions.vals_.iter().zip(&exceptions.totals_ct_) {
+            if *ct == 0.0 {
+                warnings.push(FitWarning::ExceptionValueNotPresent(*v));
+            }
+        }
+        self.metadata_ = Some(FitMetadata::new(self, n_records, total_ones, total_zero));
+        Ok(FitResult {
+            splits: self.splits_.to_vec(),
+            bins: self.bin_summary()?,
+            exceptions: self.exception_summary()?,
+            missing: self.missing_summary()?,
+            warnings,
+        })
+    }
 
-            let lhs_node = Node::new(
-                &feature,
-                Some(self.min_obs),
-                Some(self.min_iv),
-                Some(self.min_pos),
-                self.mono,
-                info.lhs_woe,
-                info.lhs_iv,
-                Some(node.start),
-                Some(idx),
-            );
-            let rhs_node = Node::new(
-                &feature,
-                Some(self.min_obs),
-                Some(self.min_iv),
-                Some(self.min_pos),
-                self.mono,
-                info.rhs_woe,
-                info.rhs_iv,
-                Some(idx),
-                Some(node.stop),
-            );
+    /// The weight of evidence of the bin, or exception value, of each value.
+    /// The values may be any float type, such as `f32`, they are compared
+    /// with the splits as `f64`, without copying the input. A multiclass
+    /// target has a weight of evidence for each class, which are instead
+    /// returned by `predict_woe_multiclass`.
+    pub fn predict_woe<T: Float>(&self, x: &[T]) -> Result<Vec<f64>, DiscrustError> {
+        let mut res = vec![0.0; x.len()];
+        self.predict_woe_into(x, &mut res)?;
+        Ok(res)
+    }
+
+    /// The same as `predict_woe`, but the weight of evidence of each
+    /// value is written to `out`, which must be the same length as `x`,
+    /// so a fitted discretizer, which is `Send` and `Sync`, can be shared
+    /// by threads scoring into their own buffers. If an error is returned,
+    /// `out` may have been partly written.
+    pub fn predict_woe_into<T: Float>(
+        &self,
+        x: &[T],
+        out: &mut [f64],
+    ) -> Result<(), DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        if self.target_type == TargetType::Multiclass {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "a multiclass target has a weight of evidence for each class, given by predict_woe_multiclass",
+            )));
+        }
+        if out.len() != x.len() {
+            return Err(DiscrustError::LengthMismatch(
+                String::from("output"),
+                out.len(),
+                x.len(),
+            ));
+        }
+        self.check_input(x)?;
+        // We don't need the first, value, as this will be negative infinity.
+        let all_splits = &self.splits_.as_slice()[1..];
+        let bin_woe = self.bin_woe(feature);
+        for (o, v) in out.iter_mut().zip(x) {
+            *o = self.predict_record_woe(&to_f64(*v), all_splits, &bin_woe, feature)?;
+        }
+        Ok(())
+    }
+
+    /// Move a value out of the bins, and into the exception values,
+    /// after the discretizer has been fit. The statistics the value
+    /// contributed to its bin are moved to the exception values, and
+    /// the bin statistics are recomputed, the splits are unchanged.
+    /// If the value was not present when fitting, it is added as an
+    /// exception value with no records. As exception values are kept
+    /// in sorted order, this may change the index of other exception
+    /// values returned by `predict_idx`. A value added after the order
+    /// was set with `set_exception_order` is not placed in that order.
+    pub fn add_exception_value(&mut self, v: f64) -> Result<(), DiscrustError> {
+        self.check_not_compacted("a value can not be made an exception value")?;
+        let feature = self.feature.as_mut().ok_or(DiscrustError::NotFitted)?;
+        if feature.exception_values_.exception_idx(&v).is_some() {
+            return Ok(());
+        }
+        if feature.merged_value(&v).is_some() {
+            return Err(DiscrustError::InvalidParameter(format!(
+                "{} was merged into a bin, and can not be an exception value",
+                v
+            )));
+        }
+        if let Some(removed) = feature.move_to_exception(v) {
+            self.refresh_tree(Some(removed));
+        }
+        Ok(())
+    }
+
+    // Keep the fitted tree consistent with the feature, after its
+    // counts change, and the value at `removed`, if any, left `vals_`.
+    fn refresh_tree(&mut self, removed: Option<usize>) {
+        let feature = self.feature.as_ref().unwrap();
+        let mut stack = vec![self.root_node.as_mut()];
+        while let Some(Some(node)) = stack.pop() {
+            if let Some(removed) = removed {
+                if node.start > removed {
+                    node.start -= 1;
+                }
+                if node.stop > removed {
+                    node.stop -= 1;
+                }
+            }
+            let (iv, woe) = feature.range_iv_woe(node.start, node.stop);
+            node.iv = iv;
+            node.woe = woe;
+            stack.push(node.left_node.as_mut());
+            stack.push(node.right_node.as_mut());
+        }
+    }
+
+    // Merge each exception value with records into the non empty bin
+    // with the closest weight of evidence, adding its records to the
+    // last value of the bin.
+    fn merge_exceptions_into_bins(&mut self) {
+        let mut feature = self.feature.take().unwrap();
+        while let Some(idx) = feature
+            .exception_values_
+            .totals_ct_
+            .iter()
+            .position(|ct| *ct > 0.0)
+        {
+            let woe = feature.exception_values_.woe_[idx];
+            let closest = self
+                .bin_summary_with(&feature)
+                .iter()
+                .zip(self.bin_ranges(&feature))
+                .filter(|(b, _)| b.totals_ct > 0.0)
+                .min_by(|(a, _), (b, _)| {
+                    (a.woe - woe)
+                        .abs()
+                        .partial_cmp(&(b.woe - woe).abs())
+                        .unwrap_or(Ordering::Equal)
+                })
+                .map(|(_, (_, stop))| stop - 1);
+            match closest {
+                Some(into) => feature.merge_exception(idx, into),
+                // Every record is an exception value.
+                None => break,
+            }
+        }
+        self.feature = Some(feature);
+        self.refresh_tree(None);
+    }
+
+    /// Attach a label to an exception value, such as "No history" for
+    /// -1, which is used in place of the value by `predict_label`, the
+    /// summaries, and the exports. NaN may be labelled, whether it is an
+    /// exception value, or the missing bin.
+    pub fn set_exception_label(&mut self, v: f64, label: &str) -> Result<(), DiscrustError> {
+        let feature = self.feature.as_mut().ok_or(DiscrustError::NotFitted)?;
+        if let Some(idx) = feature.exception_values_.exception_idx(&v) {
+            feature.exception_values_.set_label(idx, label);
+        } else if v.is_nan() && !feature.missing_.vals_.is_empty() {
+            feature.missing_.set_label(0, label);
+        } else {
+            return Err(DiscrustError::UnknownExceptionValue(v));
+        }
+        Ok(())
+    }
+
+    /// Set the order of the exception values, which must contain each
+    /// exception value once. The first value has the index -1 in
+    /// `predict_idx`, the next -2, and so on, and they are listed in this
+    /// order by the summaries and the exports. By default the exception
+    /// values are sorted, with NaN first. The missing bin keeps the index
+    /// after the last exception value.
+    pub fn set_exception_order(&mut self, order: &[f64]) -> Result<(), DiscrustError> {
+        let feature = self.feature.as_mut().ok_or(DiscrustError::NotFitted)?;
+        let exceptions = &mut feature.exception_values_;
+        let positions = order
+            .iter()
+            .map(|v| {
+                exceptions
+                    .exception_idx(v)
+                    .ok_or(DiscrustError::UnknownExceptionValue(*v))
+            })
+            .collect::<Result<Vec<usize>, DiscrustError>>()?;
+        let mut seen = vec![false; exceptions.vals_.len()];
+        if (positions.len() != seen.len())
+            || positions
+                .iter()
+                .any(|i| std::mem::replace(&mut seen[*i], true))
+        {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "the exception order must contain each exception value once",
+            )));
+        }
+        exceptions.reorder(&positions);
+        Ok(())
+    }
+
+    /// The index of the bin, or exception value, of each value, of any
+    /// float type, as with `predict_woe`.
+    pub fn predict_idx<T: Float>(&self, x: &[T]) -> Result<Vec<i64>, DiscrustError> {
+        // We don't need the first, value, as this will be negative infinity.
+        let all_splits = &self.splits_.as_slice()[1..];
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        self.check_input(x)?;
+        let res: Vec<i64> = x
+            .iter()
+            .map(|v| self.predict_record_idx(&to_f64(*v), all_splits, feature))
+            .collect::<Result<Vec<i64>, DiscrustError>>()?;
+        Ok(res)
+    }
+
+    /// The same as `predict_idx`, but the values are sorted once, and the
+    /// bins assigned by walking the sorted values and the splits together,
+    /// rather than searching the splits for each value. Sorting usually
+    /// costs more than the search, so this is only faster when the values
+    /// are already sorted, or nearly so, such as a time ordered column,
+    /// and there are many splits. `discrust bench` compares the two.
+    pub fn predict_idx_sorted<T: Float>(&self, x: &[T]) -> Result<Vec<i64>, DiscrustError> {
+        let all_splits = &self.splits_.as_slice()[1..];
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        self.check_input(x)?;
+        let mut res = vec![0; x.len()];
+        // Exception and missing values are predicted individually,
+        // leaving the values that fall in a bin to be sorted.
+        let mut order = Vec::with_capacity(x.len());
+        for (i, v) in x.iter().map(|v| to_f64(*v)).enumerate() {
+            if feature.exception_values_.exception_idx(&v).is_some()
+                || feature.merged_value(&v).is_some()
+                || feature.in_missing_bin(&v)
+                || self.is_unseen_missing(&v, feature)
+            {
+                res[i] = self.predict_record_idx(&v, all_splits, feature)?;
+            } else {
+                order.push(i);
+            }
+        }
+        order.sort_unstable_by(|a, b| nan_safe_compare(&x[*a], &x[*b]));
+        let mut bin = 0;
+        for i in order {
+            let v = to_f64(x[i]);
+            while all_splits
+                .get(bin)
+                .is_some_and(|s| !self.closed.in_lower(&v, s))
+            {
+                bin += 1;
+            }
+            if bin == all_splits.len() {
+                return Err(DiscrustError::Prediction);
+            }
+            res[i] = bin as i64;
+        }
+        Ok(res)
+    }
+
+    /// The lower and upper bounds of the bin each value falls in, as two
+    /// vectors with an entry for each value, for reports that need the
+    /// range of the bin. An exception value is bounded by the value
+    /// itself, as in `exception_summary`, and missing values, in the
+    /// missing bin, or predicted with their own weight of evidence, have
+    /// bounds of NaN. Exception values merged into a bin have its bounds.
+    pub fn predict_bounds<T: Float>(&self, x: &[T]) -> Result<(Vec<f64>, Vec<f64>), DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        let exceptions = &feature.exception_values_.vals_;
+        Ok(self
+            .predict_idx(x)?
+            .iter()
+            .map(|i| match *i {
+                i if i < 0 => {
+                    let v = exceptions
+                        .get((-i - 1) as usize)
+                        .cloned()
+                        .unwrap_or(f64::NAN);
+                    (v, v)
+                }
+                i => (self.splits_[i as usize], self.splits_[i as usize + 1]),
+            })
+            .unzip())
+    }
+
+    /// The label of the bin, or exception value, each value falls in,
+    /// rendered according to `label_format`.
+    pub fn predict_label<T: Float>(&self, x: &[T]) -> Result<Vec<String>, DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        let (bin_labels, exception_labels) = self.labels(feature);
+        let res = self
+            .predict_idx(x)?
+            .iter()
+            .map(|i| match *i {
+                // Missing values in the missing bin, or predicted with their own
+                // weight of evidence, have the index after the last exception value.
+                i if i < 0 => exception_labels
+                    .get((-i - 1) as usize)
+                    .cloned()
+                    .unwrap_or_else(|| self.missing_label(feature)),
+                i => bin_labels[i as usize].to_owned(),
+            })
+            .collect();
+        Ok(res)
+    }
+
+    // The labels of each bin, and each exception value, in the
+    // order of the indexes returned by `predict_idx`.
+    pub(crate) fn labels(&self, feature: &Feature) -> (Vec<String>, Vec<String>) {
+        self.labels_with(feature, &self.label_format)
+    }
+
+    // The labels of each bin, and each exception value,
+    // rendered according to `format`.
+    fn labels_with(&self, feature: &Feature, format: &LabelFormat) -> (Vec<String>, Vec<String>) {
+        let bin_labels = self
+            .splits_
+            .windows(2)
+            .map(|w| format.format_bin(w[0], w[1], &self.feature_type, self.closed))
+            .collect();
+        let exceptions = &feature.exception_values_;
+        let exception_labels = (0..exceptions.vals_.len())
+            .map(|i| self.exception_label(exceptions, i, format))
+            .collect();
+        (bin_labels, exception_labels)
+    }
+
+    // The label set for the exception value at `idx`, or
+    // otherwise the value rendered according to `format`.
+    fn exception_label(
+        &self,
+        exceptions: &ExceptionValues,
+        idx: usize,
+        format: &LabelFormat,
+    ) -> String {
+        match exceptions.label(idx) {
+            Some(label) => label.to_string(),
+            None => format.format_value(exceptions.vals_[idx], &self.feature_type),
+        }
+    }
+
+    // The label of the missing bin, or of missing
+    // values predicted with their own weight of evidence.
+    pub(crate) fn missing_label(&self, feature: &Feature) -> String {
+        match feature.missing_.label(0) {
+            Some(label) => label.to_string(),
+            None => self.label_format.missing_label.to_owned(),
+        }
+    }
+
+    /// Check that the splits are a valid binning for this discretizer. The
+    /// splits must be increasing, start at negative infinity, and end at
+    /// infinity. Every other split must be a value observed when fitting,
+    /// or in midpoint mode, lie strictly between two observed values.
+    /// This is useful to check splits that have been edited by hand.
+    pub fn validate_splits(&self) -> Result<(), DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        let n = self.splits_.len();
+        if (n < 2)
+            | (self.splits_.first() != Some(&f64::NEG_INFINITY))
+            | (self.splits_.last() != Some(&f64::INFINITY))
+        {
+            return Err(DiscrustError::InvalidSplits(String::from(
+                "splits must start at -inf and end at inf",
+            )));
+        }
+        if self.splits_.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(DiscrustError::InvalidSplits(String::from(
+                "splits must be strictly increasing",
+            )));
+        }
+        // Uniform splits are the edges of equal width bins, and manual
+        // splits are given, so neither are expected to be observed values.
+        if let Strategy::Uniform { .. } | Strategy::Manual { .. } = self.strategy {
+            return Ok(());
+        }
+        let midpoints = self.uses_midpoints();
+        let vals = &feature.vals_;
+        let invalid: Vec<f64> = self.splits_[1..(n - 1)]
+            .iter()
+            .filter(|s| {
+                let i = vals.partition_point(|v| v < s);
+                let observed = vals.get(i) == Some(s);
+                if midpoints {
+                    observed | (i == 0) | (i == vals.len())
+                } else {
+                    !observed
+                }
+            })
+            .cloned()
+            .collect();
+        if invalid.is_empty() {
+            Ok(())
+        } else if midpoints {
+            Err(DiscrustError::InvalidSplits(format!(
+                "{:?} do not lie strictly between two observed values",
+                invalid
+            )))
+        } else {
+            Err(DiscrustError::InvalidSplits(format!(
+                "{:?} are not observed values",
+                invalid
+            )))
+        }
+    }
+
+    // The splits of the quantile, uniform and manual strategies, or `None`
+    // for the strategies that search for splits. Quantile splits are always
+    // observed values, uniform splits are only snapped to the end of
+    // their period for datetime features, and manual splits are as given.
+    fn unsupervised_splits<F: Fn(f64) -> f64>(
+        &self,
+        feature: &Feature,
+        to_original: F,
+    ) -> Option<Vec<f64>> {
+        if let Strategy::Manual { splits, .. } = &self.strategy {
+            // The infinite bounds are added once the splits are found.
+            return Some(splits.iter().filter(|s| s.is_finite()).cloned().collect());
+        }
+        let n_bins = self.strategy.unsupervised_bins()?;
+        let vals = &feature.vals_;
+        if vals.is_empty() {
+            return Some(Vec::new());
+        }
+        let last = vals.len() - 1;
+        let mut splits: Vec<f64> = match self.strategy {
+            Strategy::Quantile { .. } => {
+                let (cuml_totals, _, _) = feature.cumulative_counts();
+                let total = cuml_totals[last];
+                (1..n_bins)
+                    .map(|i| cuml_totals.partition_point(|c| *c < total * i as f64 / n_bins as f64))
+                    // The largest value is never a split, as its upper bin would be empty.
+                    .filter(|idx| *idx < last)
+                    .map(|idx| match self.closed {
+                        Closed::Left => to_original(vals[idx + 1]),
+                        Closed::Right => to_original(vals[idx]),
+                    })
+                    .collect()
+            }
+            _ => {
+                let lower = to_original(vals[0]);
+                let upper = to_original(vals[last]);
+                let width = (upper - lower) / n_bins as f64;
+                (1..n_bins)
+                    .map(|i| self.feature_type.snap(lower + width * i as f64))
+                    .filter(|s| match self.closed {
+                        Closed::Left => (*s > lower) & (*s <= upper),
+                        Closed::Right => (*s >= lower) & (*s < upper),
+                    })
+                    .collect()
+            }
+        };
+        splits.sort_by(nan_safe_compare);
+        splits.dedup();
+        Some(splits)
+    }
+
+    fn uses_midpoints(&self) -> bool {
+        (self.split_point == SplitPoint::Midpoint)
+            & (self.feature_type == FeatureType::Numeric)
+            & matches!(
+                self.strategy,
+                Strategy::Tree | Strategy::Chaid { .. } | Strategy::OptimalDP { .. }
+            )
+    }
+
+    /// Check that no exception value is also one of the values aggregated
+    /// in the bins. If a value were both, its records would be counted in
+    /// a bin, but it would always be predicted as an exception value.
+    /// The conflicting values are returned in the error.
+    pub fn validate_exceptions(&self) -> Result<(), DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        let conflicts: Vec<f64> = feature
+            .exception_values_
+            .vals_
+            .iter()
+            .filter(|v| {
+                feature
+                    .vals_
+                    .binary_search_by(|x| nan_safe_compare(x, v))
+                    .is_ok()
+            })
+            .cloned()
+            .collect();
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(DiscrustError::ExceptionConflict(conflicts))
+        }
+    }
+
+    /// Summary statistics for each of the bins defined by `splits_`,
+    /// in the same order as the bin indexes returned by `predict_idx`.
+    pub fn bin_summary(&self) -> Result<Vec<BinSummary>, DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        Ok(self.bin_summary_with(feature))
+    }
+
+    /// Summary statistics for each exception value, in the order of
+    /// `exception_values_`, the same order as the negative indexes
+    /// returned by `predict_idx`.
+    pub fn exception_summary(&self) -> Result<Vec<ExceptionSummary>, DiscrustError> {
+        let exceptions = &self
+            .feature
+            .as_ref()
+            .ok_or(DiscrustError::NotFitted)?
+            .exception_values_;
+        Ok((0..exceptions.vals_.len())
+            .map(|i| {
+                let (mean, std) = exceptions.mean_std(i);
+                ExceptionSummary {
+                    value: exceptions.vals_[i],
+                    totals_ct: exceptions.totals_ct_[i],
+                    ones_ct: exceptions.ones_ct_[i],
+                    zero_ct: exceptions.zero_ct_[i],
+                    effective_ct: exceptions.effective_ct(i),
+                    event_rate: exceptions.ones_ct_[i] / exceptions.totals_ct_[i],
+                    mean,
+                    std,
+                    woe: exceptions.woe_[i],
+                    iv: exceptions.iv_[i],
+                    label: self.exception_label(exceptions, i, &self.label_format),
+                }
+            })
+            .collect())
+    }
+
+    /// Summary statistics of the missing bin, the records that were NaN
+    /// when fitting with `special_missing`, and NaN was not an exception
+    /// value. `None` if there were no such records. The missing bin has
+    /// the index after the last exception value in `predict_idx`.
+    pub fn missing_summary(&self) -> Result<Option<ExceptionSummary>, DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        if !feature.in_missing_bin(&f64::NAN) {
+            return Ok(None);
+        }
+        let label = self.missing_label(feature);
+        let missing = &feature.missing_;
+        let (mean, std) = missing.mean_std(0);
+        Ok(Some(ExceptionSummary {
+            value: f64::NAN,
+            totals_ct: missing.totals_ct_[0],
+            ones_ct: missing.ones_ct_[0],
+            zero_ct: missing.zero_ct_[0],
+            effective_ct: missing.effective_ct(0),
+            event_rate: missing.ones_ct_[0] / missing.totals_ct_[0],
+            mean,
+            std,
+            woe: missing.woe_[0],
+            iv: missing.iv_[0],
+            label,
+        }))
+    }
+
+    /// The weight of evidence of each bin, followed by each exception value,
+    /// and the missing bin, if any, which is the value predicted by
+    /// `predict_woe` for each index returned by `predict_idx`. Bin `i` is at
+    /// position `i`, and the exception value with the negative index `-k` at
+    /// position `n_bins + k - 1`, as given by `index_position`. Bins, and
+    /// exception values, with no records have a weight of evidence of zero.
+    pub fn bin_woe_(&self) -> Result<Vec<f64>, DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        let exceptions = &feature.exception_values_;
+        let mut woe = self.bin_woe(feature);
+        woe.extend(
+            exceptions
+                .woe_
+                .iter()
+                .zip(&exceptions.totals_ct_)
+                .map(|(w, ct)| if *ct == 0.0 { 0.0 } else { *w }),
+        );
+        woe.extend(self.missing_summary()?.map(|m| m.woe));
+        Ok(woe)
+    }
+
+    /// The information value of each bin, followed by each exception value,
+    /// and the missing bin, in the same positions as `bin_woe_`. Bins, and
+    /// exception values, with no records have an information value of zero.
+    pub fn bin_iv_(&self) -> Result<Vec<f64>, DiscrustError> {
+        let iv = self
+            .bin_summary()?
+            .iter()
+            .map(|b| (b.totals_ct, b.iv))
+            .chain(
+                self.exception_summary()?
+                    .iter()
+                    .chain(&self.missing_summary()?)
+                    .map(|e| (e.totals_ct, e.iv)),
+            )
+            .map(|(ct, iv)| if ct == 0.0 { 0.0 } else { iv })
+            .collect();
+        Ok(iv)
+    }
+
+    /// The information value of the feature, the sum of `bin_iv_` over the
+    /// bins, the exception values, and the missing bin, used to compare
+    /// the predictive power of features.
+    pub fn total_iv_(&self) -> Result<f64, DiscrustError> {
+        Ok(self.bin_iv_()?.iter().sum())
+    }
+
+    /// The position in `bin_woe_`, and `bin_iv_`, of an index returned by
+    /// `predict_idx`, or `None` for the index of missing values predicted
+    /// with their own weight of evidence, when there is no missing bin.
+    pub fn index_position(&self, idx: i64) -> Option<usize> {
+        let position = if idx < 0 {
+            self.splits_.len().saturating_sub(1) + (-idx - 1) as usize
+        } else {
+            idx as usize
+        };
+        if position < self.n_positions() {
+            Some(position)
+        } else {
+            None
+        }
+    }
+
+    // The number of bins, exception values, and the missing
+    // bin if there is one, the length of `bin_woe_`.
+    pub(crate) fn n_positions(&self) -> usize {
+        let n_bins = self.splits_.len().saturating_sub(1);
+        n_bins
+            + self.feature.as_ref().map_or(0, |f| {
+                f.exception_values_.vals_.len() + f.in_missing_bin(&f64::NAN) as usize
+            })
+    }
+
+    /// The fitted splits rendered as strings, according to the
+    /// `feature_type` and `label_format` of the discretizer.
+    pub fn split_labels(&self) -> Vec<String> {
+        self.splits_
+            .iter()
+            .map(|s| self.label_format.format_value(*s, &self.feature_type))
+            .collect()
+    }
+
+    /// A label for each bin, followed by each exception value, and the
+    /// missing bin, if any, in the same positions as `bin_woe_`, such as
+    /// `(-inf, 6.95]`, `-1` and `Missing`, for reports. These are the same
+    /// labels as `predict_label`, with values shown with `precision`
+    /// decimal places, if it is passed. Exception values that have been
+    /// given a label with `set_exception_label` use that label.
+    pub fn bin_labels(&self, precision: Option<usize>) -> Result<Vec<String>, DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        let format = LabelFormat {
+            decimals: precision.or(self.label_format.decimals),
+            ..self.label_format.clone()
+        };
+        let (mut labels, exception_labels) = self.labels_with(feature, &format);
+        labels.extend(exception_labels);
+        if self.missing_summary()?.is_some() {
+            labels.push(self.missing_label(feature));
+        }
+        Ok(labels)
+    }
+
+    /// A new unfitted discretizer, with the same parameters
+    /// as this discretizer. If `mono` was automatic, and this
+    /// discretizer has been fit, the copy will use the
+    /// monotonicity determined while fitting.
+    pub fn unfitted_copy(&self) -> Self {
+        Discretizer {
+            min_obs: self.min_obs,
+            max_bins: self.max_bins,
+            min_iv: self.min_iv,
+            min_pos: self.min_pos,
+            min_effective_obs: self.min_effective_obs,
+            approx_splits: self.approx_splits,
+            max_candidates: self.max_candidates,
+            min_obs_rate: self.min_obs_rate,
+            min_pos_rate: self.min_pos_rate,
+            min_pos_auto: self.min_pos_auto,
+            max_pvalue: self.max_pvalue,
+            min_bin_width: self.min_bin_width,
+            max_bin_pct: self.max_bin_pct,
+            mdlp: self.mdlp,
+            min_iv_gain_ratio: self.min_iv_gain_ratio,
+            growth: self.growth,
+            verbose: self.verbose,
+            n_threads: self.n_threads,
+            mono: self.mono,
+            feature_type: self.feature_type,
+            pre_transform: self.pre_transform,
+            clip_quantiles: self.clip_quantiles,
+            enforce_monotonicity: self.enforce_monotonicity,
+            woe_regularization: self.woe_regularization,
+            special_missing: self.special_missing,
+            cv_folds: self.cv_folds,
+            cv_agreement: self.cv_agreement,
+            merge_exceptions: self.merge_exceptions,
+            target_type: self.target_type,
+            strategy: self.strategy.clone(),
+            split_point: self.split_point,
+            closed: self.closed,
+            tie_break: self.tie_break,
+            on_missing: self.on_missing,
+            label_format: self.label_format.clone(),
+            input_spec: self.input_spec.clone(),
+            tags: self.tags.clone(),
+            root_node: None,
+            splits_: Vec::new(),
+            feature: None,
+            categories_: Vec::new(),
+            metadata_: None,
+            partial_: None,
+            fit_trace_: Vec::new(),
+        }
+    }
+
+    /// The value of each fitting parameter, as a string.
+    pub fn parameters(&self) -> BTreeMap<String, String> {
+        let mut parameters = BTreeMap::new();
+        let mut add = |k: &str, v: String| {
+            parameters.insert(k.to_string(), v);
+        };
+        add("min_obs", self.min_obs.to_string());
+        add("max_bins", self.max_bins.to_string());
+        add("min_iv", self.min_iv.to_string());
+        add("min_pos", self.min_pos.to_string());
+        add("min_obs_rate", format!("{:?}", self.min_obs_rate));
+        add("min_pos_rate", format!("{:?}", self.min_pos_rate));
+        add("min_pos_auto", self.min_pos_auto.to_string());
+        add("min_effective_obs", format!("{:?}", self.min_effective_obs));
+        add("approx_splits", format!("{:?}", self.approx_splits));
+        add("max_candidates", format!("{:?}", self.max_candidates));
+        add("max_pvalue", format!("{:?}", self.max_pvalue));
+        add("min_bin_width", format!("{:?}", self.min_bin_width));
+        add("max_bin_pct", format!("{:?}", self.max_bin_pct));
+        add("mdlp", format!("{:?}", self.mdlp));
+        add("min_iv_gain_ratio", format!("{:?}", self.min_iv_gain_ratio));
+        add("growth", format!("{:?}", self.growth));
+        add("verbose", format!("{:?}", self.verbose));
+        add("mono", format!("{:?}", self.mono));
+        add("feature_type", format!("{:?}", self.feature_type));
+        add("pre_transform", format!("{:?}", self.pre_transform));
+        add("clip_quantiles", format!("{:?}", self.clip_quantiles));
+        add(
+            "enforce_monotonicity",
+            self.enforce_monotonicity.to_string(),
+        );
+        add("woe_regularization", self.woe_regularization.to_string());
+        add("special_missing", self.special_missing.to_string());
+        add("cv_folds", format!("{:?}", self.cv_folds));
+        add("cv_agreement", self.cv_agreement.to_string());
+        add("merge_exceptions", self.merge_exceptions.to_string());
+        add("target_type", format!("{:?}", self.target_type));
+        add("strategy", format!("{:?}", self.strategy));
+        add("split_point", format!("{:?}", self.split_point));
+        add("closed", format!("{:?}", self.closed));
+        add("tie_break", format!("{:?}", self.tie_break));
+        parameters
+    }
+
+    /// Apply the fitted bins to each level of the `segment` variable
+    /// and compare the weight of evidence of every bin, and exception
+    /// value, across segments. Bins where the weight of evidence
+    /// flips sign, or differs by more than `max_woe_diff` between
+    /// segments, are marked as inconsistent.
+    pub fn segment_consistency<K: Ord + Clone>(
+        &self,
+        x: &[f64],
+        y: &[f64],
+        w: &[f64],
+        segment: &[K],
+        max_woe_diff: f64,
+    ) -> Result<Vec<BinConsistency<K>>, DiscrustError> {
+        let fitted = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        check_segment(segment, x.len())?;
+        check_records(x.len(), y, Some(w))?;
+        let exception_values = &fitted.exception_values_.vals_;
+        let n_bins = self.splits_.len() - 1;
+        let n_excp = exception_values.len();
+        // The missing bin follows the exception values, if there is one.
+        let n_missing = fitted.in_missing_bin(&f64::NAN) as usize;
+        let mut bin_woe: Vec<BTreeMap<K, f64>> = vec![BTreeMap::new(); n_bins + n_excp + n_missing];
+        for (k, idx) in segment_indices(segment) {
+            let (x_, y_, w_) = take_records(&idx, x, y, w);
+            let feature = self.subset_feature(&x_, &y_, &w_, exception_values)?;
+            let woe = self
+                .bin_summary_with(&feature)
+                .into_iter()
+                .map(|b| b.woe)
+                .chain(feature.exception_values_.woe_.iter().cloned())
+                .chain(feature.missing_.woe_.iter().take(n_missing).cloned());
+            for (b, v) in woe.enumerate() {
+                if v.is_finite() {
+                    bin_woe[b].insert(k.clone(), v);
+                }
+            }
+        }
+        Ok(bin_woe
+            .into_iter()
+            .enumerate()
+            .map(|(b, seg_woe)| {
+                // Exception values, then the missing bin,
+                // are indexed starting at -1.
+                let bin = if b < n_bins {
+                    b as i64
+                } else {
+                    -((b - n_bins + 1) as i64)
+                };
+                BinConsistency::new(bin, seg_woe, max_woe_diff)
+            })
+            .collect())
+    }
+
+    // The counts of a subset of the records, such as a segment, built
+    // with the missing bin, target type and regularization of this
+    // discretizer, to be summarised with its splits.
+    pub(crate) fn subset_feature(
+        &self,
+        x: &[f64],
+        y: &[f64],
+        w: &[f64],
+        exception_values: &[f64],
+    ) -> Result<Feature, DiscrustError> {
+        let mut feature = Feature::build(
+            x,
+            y,
+            Some(w),
+            exception_values,
+            self.special_missing,
+            self.target_type,
+            &Threads::default(),
+        )?;
+        feature.set_woe_regularization(self.woe_regularization);
+        Ok(feature)
+    }
+
+    // Summarise any feature, using the splits of this
+    // discretizer to define the bins.
+    pub(crate) fn bin_summary_with(&self, feature: &Feature) -> Vec<BinSummary> {
+        self.bin_ranges(feature)
+            .into_iter()
+            .zip(self.splits_.windows(2))
+            .map(|((start, stop), bounds)| {
+                let (totals_ct, ones_ct, zero_ct) = feature.range_totals_ones_zero_ct(start, stop);
+                let (iv, woe) = feature.range_iv_woe(start, stop);
+                let (_, mean, var) = feature.range_ct_mean_var(start, stop);
+                BinSummary {
+                    lower: bounds[0],
+                    upper: bounds[1],
+                    totals_ct,
+                    ones_ct,
+                    zero_ct,
+                    effective_ct: feature.effective_ct(start, stop),
+                    event_rate: ones_ct / totals_ct,
+                    mean,
+                    std: var.sqrt(),
+                    woe,
+                    iv,
+                    label: self.label_format.format_bin(
+                        bounds[0],
+                        bounds[1],
+                        &self.feature_type,
+                        self.closed,
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    // The minimum counts required in a bin, the larger of the absolute
+    // threshold, and the rate applied to the total weight, along with
+    // the name of the parameter that set it.
+    fn resolve_minimums(&self, total: f64, total_ones: f64) -> Minimums {
+        let resolve = |absolute: (&'static str, f64), rate: (&'static str, Option<f64>)| match rate
+        {
+            (name, Some(r)) if r * total > absolute.1 => (name, r * total),
+            _ => absolute,
+        };
+        let min_obs = resolve(
+            ("min_obs", self.min_obs),
+            ("min_obs_rate", self.min_obs_rate),
+        );
+        let min_pos = if self.min_pos_auto && (self.target_type == TargetType::Binary) {
+            ("min_pos_auto", (min_obs.1 * total_ones / total).max(1.0))
+        } else {
+            ("min_pos", self.min_pos)
+        };
+        Minimums {
+            min_obs,
+            min_pos: resolve(min_pos, ("min_pos_rate", self.min_pos_rate)),
+        }
+    }
+
+    // Splits are only made on values that are not exception values, if
+    // these records are too few to satisfy min_obs or min_pos, the only
+    // possible binning is a single bin. If every record is an exception
+    // value, there is nothing to split, and the fit is still allowed.
+    // Manual splits are not constrained, so are always allowed.
+    fn check_root_constraints(
+        &self,
+        feature: &Feature,
+        minimums: &Minimums,
+    ) -> Result<(), DiscrustError> {
+        let n = feature.vals_.len();
+        if (n == 0) || matches!(self.strategy, Strategy::Manual { .. }) {
+            return Ok(());
+        }
+        let (totals_ct, ones_ct, _) = feature.range_totals_ones_zero_ct(0, n);
+        let (parameter, required, available) = match self.min_effective_obs {
+            Some(min_effective_obs) => (
+                "min_effective_obs",
+                min_effective_obs,
+                feature.effective_ct(0, n),
+            ),
+            None => (minimums.min_obs.0, minimums.min_obs.1, totals_ct),
+        };
+        let mut unsatisfiable = Vec::new();
+        if available < required {
+            unsatisfiable.push(UnsatisfiableConstraint {
+                parameter: parameter.to_string(),
+                required,
+                available,
+            });
+        }
+        let (min_pos_parameter, min_pos) = minimums.min_pos;
+        let available = match self.target_type {
+            TargetType::Binary => ones_ct,
+            TargetType::Continuous => f64::INFINITY,
+            TargetType::Multiclass => feature.range_min_class_ct(0, n),
+        };
+        if available < min_pos {
+            unsatisfiable.push(UnsatisfiableConstraint {
+                parameter: min_pos_parameter.to_string(),
+                required: min_pos,
+                available,
+            });
+        }
+        if unsatisfiable.is_empty() {
+            Ok(())
+        } else {
+            Err(DiscrustError::InsufficientData(unsatisfiable))
+        }
+    }
+
+    // The start and stop positions in the feature's sorted unique
+    // values of each bin defined by the splits.
+    pub(crate) fn bin_ranges(&self, feature: &Feature) -> Vec<(usize, usize)> {
+        let bounds: Vec<usize> = self
+            .splits_
+            .iter()
+            .map(|s| {
+                feature
+                    .vals_
+                    .partition_point(|v| self.closed.in_lower(v, s))
+            })
+            .collect();
+        bounds.windows(2).map(|b| (b[0], b[1])).collect()
+    }
+
+    fn predict_record_idx(
+        &self,
+        v: &f64,
+        all_splits: &[f64],
+        feature: &Feature,
+    ) -> Result<i64, DiscrustError> {
+        // If it's an exception value, we return the index negative value.
+        // We start this at -1. So we add 1, to the zero indexed result
+        // of the `exception_idx` function.
+        if let Some(i) = feature.exception_values_.exception_idx(v) {
+            return Ok(-((i + 1) as i64));
+        }
+        if let Some(into) = feature.merged_value(v) {
+            return self.predict_record_idx(&into, all_splits, feature);
+        }
+        if feature.in_missing_bin(v) {
+            return Ok(-((feature.exception_values_.vals_.len() + 1) as i64));
+        }
+        if self.is_unseen_missing(v, feature) {
+            return match self.on_missing {
+                OnMissing::Error => Err(DiscrustError::UnseenMissing(*v)),
+                OnMissing::LowestBin => Ok(0),
+                OnMissing::HighestBin => Ok(all_splits.len() as i64 - 1),
+                OnMissing::WoeValue(_) => Ok(-((feature.exception_values_.vals_.len() + 1) as i64)),
+            };
+        }
+        // If the value is below the bin edge, we are in that position bin.
+        // The splits are sorted, so this is the number of splits the value
+        // falls above.
+        let idx = self.closed.position(all_splits, v);
+        if idx == all_splits.len() {
+            return Err(DiscrustError::Prediction);
+        }
+        Ok(idx as i64)
+    }
+    // -1, 4, 10
+    fn predict_record_woe(
+        &self,
+        v: &f64,
+        all_splits: &[f64],
+        bin_woe: &[f64],
+        feature: &Feature,
+    ) -> Result<f64, DiscrustError> {
+        let excp_idx = feature.exception_values_.exception_idx(v);
+        if let Some(idx) = excp_idx {
+            if feature.exception_values_.totals_ct_[idx] == 0.0 {
+                return Ok(0.0);
+            }
+            return Ok(feature.exception_values_.woe_[idx]);
+        }
+        if let Some(into) = feature.merged_value(v) {
+            return self.predict_record_woe(&into, all_splits, bin_woe, feature);
+        }
+        if feature.in_missing_bin(v) {
+            return Ok(feature.missing_.woe_[0]);
+        }
+        if let (OnMissing::WoeValue(woe), true) =
+            (self.on_missing, self.is_unseen_missing(v, feature))
+        {
+            return Ok(woe);
+        }
+        let idx = self.predict_record_idx(v, all_splits, feature)?;
+        Ok(bin_woe[idx as usize])
+    }
+
+    // A value that is missing, NaN or a special code of the input
+    // spec, and is neither an exception value, nor in the missing bin.
+    pub(crate) fn is_unseen_missing(&self, v: &f64, feature: &Feature) -> bool {
+        (v.is_nan() || self.input_spec.as_ref().is_some_and(|s| s.is_code(v)))
+            && feature.exception_values_.exception_idx(v).is_none()
+            && feature.merged_value(v).is_none()
+            && !feature.in_missing_bin(v)
+    }
+
+    // The weight of evidence of each bin, bins with
+    // no records have a weight of evidence of zero.
+    pub(crate) fn bin_woe(&self, feature: &Feature) -> Vec<f64> {
+        self.bin_summary_with(feature)
+            .iter()
+            .map(|b| if b.totals_ct == 0.0 { 0.0 } else { b.woe })
+            .collect()
+    }
+}
+
+// Split `node` at each of `infos` in turn, as a chain of binary splits,
+// each splitting the lowest bin off the rest. The lower half of each split
+// is made by `new_node` with the trend `lhs_mono`, and the upper half with
+// `rhs_mono`, and the split is placed by `place`. The bins are returned
+// from lowest to highest, with the splits.
+fn split_chain<N, P>(
+    mut node: &mut Box<Node>,
+    infos: Vec<SplitInfo>,
+    (lhs_mono, rhs_mono): (MonotonicTrend, MonotonicTrend),
+    new_node: N,
+    place: P,
+) -> (Vec<&mut Box<Node>>, Vec<f64>)
+where
+    N: Fn(MonotonicTrend, Option<f64>, Option<f64>, Option<usize>, Option<usize>) -> Node,
+    P: Fn(&SplitInfo, usize) -> f64,
+{
+    let mut bins = Vec::with_capacity(infos.len() + 1);
+    let mut splits = Vec::with_capacity(infos.len());
+    for mut info in infos {
+        let idx = info.split_idx.unwrap() + node.start + 1;
+        let split = place(&info, idx);
+        let lhs_node = new_node(
+            lhs_mono,
+            info.lhs_woe,
+            info.lhs_iv,
+            Some(node.start),
+            Some(idx),
+        );
+        let rhs_node = new_node(
+            rhs_mono,
+            info.rhs_woe,
+            info.rhs_iv,
+            Some(idx),
+            Some(node.stop),
+        );
+
+        // Add the split info here, after we use it, to avoid a move.
+        info.split = Some(split);
+        node.split_info = info;
+
+        node.left_node = Some(Box::new(lhs_node));
+        node.right_node = Some(Box::new(rhs_node));
+        let (lhs, rhs) = (
+            node.left_node.as_mut().unwrap(),
+            node.right_node.as_mut().unwrap(),
+        );
+        bins.push(lhs);
+        node = rhs;
+        splits.push(split);
+    }
+    bins.push(node);
+    (bins, splits)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::two_proportion_pvalue;
+    use std::fs;
+    // [-inf, 6.95, 7.125, 7.7292, 10.4625, 15.1, 50.4958, 52.0, 73.5, 79.65, inf]
+    #[test]
+    fn test_discretizer() {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(",").map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
+        }
+        let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), Some(1));
+        let w_ = vec![1.0; fare.len()];
+        let splits = disc.fit(&fare, &survived, Some(&w_), None).unwrap().splits;
+
+        disc.predict_idx(&fare).unwrap();
+        disc.predict_woe(&fare).unwrap();
+
+        assert_eq!(
+            splits,
+            vec![
+                -f64::INFINITY,
+                6.95,
+                7.125,
+                7.7292,
+                10.4625,
+                15.1,
+                50.4958,
+                52.0,
+                73.5,
+                79.65,
+                f64::INFINITY
+            ]
+        );
+
+        // Test predictions run without error.
+        // println!("{:?}", disc.predict(&fare));
+    }
+
+    #[test]
+    fn test_discretizer_mono_n1() {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(",").map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push((split[1] == 0.0) as i64 as f64);
+        }
+        let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), Some(-1));
+        let w_ = vec![1.0; fare.len()];
+        let splits = disc.fit(&fare, &survived, Some(&w_), None).unwrap().splits;
+        assert_eq!(
+            splits,
+            vec![
+                -f64::INFINITY,
+                6.95,
+                7.125,
+                7.7292,
+                10.4625,
+                15.1,
+                50.4958,
+                52.0,
+                73.5,
+                79.65,
+                f64::INFINITY
+            ]
+        );
+        // println!("{:?}", disc.predict(&fare));
+    }
+
+    #[test]
+    fn test_discretizer_mono_none() {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(",").map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
+        }
+        let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), None);
+        let w_ = vec![1.0; fare.len()];
+        let splits = disc.fit(&fare, &survived, Some(&w_), None).unwrap().splits;
+        assert_eq!(
+            splits,
+            vec![
+                -f64::INFINITY,
+                6.95,
+                7.125,
+                7.7292,
+                10.4625,
+                15.1,
+                50.4958,
+                52.0,
+                73.5,
+                79.65,
+                f64::INFINITY
+            ]
+        );
+        // println!("{:?}", disc.predict(&fare));
+    }
+
+    #[test]
+    fn test_bin_summary() {
+        let x_ = vec![1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0, 5.0, 5.0];
+        let y_ = vec![1.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(2.0), Some(10), Some(0.001), Some(1.0), Some(-1));
+        assert!(disc.bin_summary().is_err());
+        disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        let summary = disc.bin_summary().unwrap();
+        assert_eq!(summary.len(), disc.splits_.len() - 1);
+        assert_eq!(summary[0].lower, -f64::INFINITY);
+        assert_eq!(summary[summary.len() - 1].upper, f64::INFINITY);
+        let totals: f64 = summary.iter().map(|b| b.totals_ct).sum();
+        let ones: f64 = summary.iter().map(|b| b.ones_ct).sum();
+        assert_eq!(totals, 10.0);
+        assert_eq!(ones, 5.0);
+        for b in summary {
+            assert_eq!(b.totals_ct, b.ones_ct + b.zero_ct);
+            assert_eq!(b.totals_ct, b.effective_ct);
+            assert_eq!(b.event_rate, b.ones_ct / b.totals_ct);
+        }
+    }
+
+    #[test]
+    fn test_on_missing() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+        let woe = disc.predict_woe(&[1.0, 2.0]).unwrap();
+        let x = [f64::NAN, -99.0, -1.0];
+
+        // By default missing values fall in the lowest bin, special codes
+        // of the input spec are only missing if a spec is attached.
+        assert_eq!(disc.predict_idx(&x).unwrap(), vec![0, 0, -1]);
+        let mut spec = InputSpec::new("bureau");
+        spec.special_codes = vec![(String::from("no file"), -99.0)];
+        disc.input_spec = Some(spec);
+
+        disc.on_missing = OnMissing::HighestBin;
+        assert_eq!(disc.predict_idx(&x).unwrap(), vec![1, 1, -1]);
+        assert_eq!(disc.predict_woe(&x[..1]).unwrap(), vec![woe[1]]);
+
+        disc.on_missing = OnMissing::WoeValue(0.25);
+        assert_eq!(disc.predict_idx(&x).unwrap(), vec![-2, -2, -1]);
+        let missing_woe = disc.predict_woe(&x).unwrap();
+        assert_eq!(missing_woe[..2], [0.25, 0.25]);
+        assert_eq!(
+            disc.predict_label(&x[..1]).unwrap(),
+            vec![disc.label_format.missing_label.to_owned()]
+        );
+
+        disc.on_missing = OnMissing::Error;
+        match disc.predict_woe(&x[1..2]) {
+            Err(DiscrustError::UnseenMissing(v)) => assert_eq!(v, -99.0),
+            r => panic!("Unexpected result {:?}", r),
+        }
+        assert!(disc.predict_idx(&x[..1]).is_err());
+        assert!(disc.predict_woe(&x[2..]).is_ok());
+    }
+
+    #[test]
+    fn test_exception_summary() {
+        let x_ = vec![1.0, 1.0, 2.0, 2.0, -1.0, -1.0, -1.0, f64::NAN];
+        let y_ = vec![1.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.exception_summary().is_err());
+        let res = disc
+            .fit(&x_, &y_, Some(&w_), Some(vec![-1.0, f64::NAN, -5.0]))
+            .unwrap();
+        let summary = disc.exception_summary().unwrap();
+        assert_eq!(res.exceptions.len(), 3);
+        assert_eq!(res.exceptions[2], summary[2]);
+        // The exception values are sorted, with NaN first.
+        assert!(summary[0].value.is_nan());
+        assert_eq!(summary[0].event_rate, 0.0);
+        assert_eq!(summary[1].value, -5.0);
+        assert_eq!(summary[1].totals_ct, 0.0);
+        assert!(summary[1].event_rate.is_nan());
+        assert_eq!(summary[2].value, -1.0);
+        assert_eq!(summary[2].ones_ct, 2.0);
+        assert_eq!(summary[2].event_rate, 2.0 / 3.0);
+        assert_eq!(summary[2].effective_ct, 3.0);
+        assert_eq!(summary[1].effective_ct, 0.0);
+        // With variable weights, the effective count is smaller.
+        let w_ = vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 2.0, 1.0];
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0, f64::NAN, -5.0]))
+            .unwrap();
+        let summary = disc.exception_summary().unwrap();
+        assert_eq!(summary[2].effective_ct, 16.0 / 6.0);
+    }
+
+    #[test]
+    fn test_segment_consistency() {
+        let x_ = vec![
+            1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, -1.0, -1.0,
+        ];
+        let y_ = vec![
+            1.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0,
+        ];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+        assert_eq!(disc.splits_.len(), 3);
+
+        // The same relationship in both segments.
+        let segment = vec![0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 0, 1];
+        let report = disc
+            .segment_consistency(&x_, &y_, &w_, &segment, 1.0)
+            .unwrap();
+        assert_eq!(report.len(), 3);
+        assert_eq!(report[2].bin, -1);
+        assert!(report[0..2].iter().all(|b| !b.inconsistent));
+
+        // Reverse the relationship in the second segment.
+        let y_flip: Vec<f64> = y_
+            .iter()
+            .zip(&segment)
+            .map(|(y, s)| if *s == 1 { 1.0 - y } else { *y })
+            .collect();
+        let report = disc
+            .segment_consistency(&x_, &y_flip, &w_, &segment, 1.0)
+            .unwrap();
+        assert!(report[0].sign_flip);
+        assert!(report[1].inconsistent);
+    }
+
+    #[test]
+    fn test_discretizer_datetime() {
+        use crate::datetime::{DateSnap, TimeUnit};
+        // Daily timestamps for 2020, with the event occurring
+        // in the first half of the year.
+        let x_: Vec<f64> = (18262..18628)
+            .map(|d| d as f64 * 86400.0 + 3600.0)
+            .collect();
+        let y_: Vec<f64> = (0..x_.len())
+            .map(|i| ((i < 182) & (i % 3 != 0)) as i64 as f64)
+            .collect();
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(5.0), Some(4), Some(0.001), Some(1.0), None);
+        disc.feature_type = FeatureType::Datetime {
+            unit: TimeUnit::Seconds,
+            snap: DateSnap::Month,
+        };
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+        // All splits are the last second of a month.
+        for s in &disc.splits_[1..(disc.splits_.len() - 1)] {
+            assert_eq!(disc.feature_type.snap(*s), *s);
+            assert_eq!((s + 1.0) % 86400.0, 0.0);
+        }
+        let labels = disc.split_labels();
+        assert_eq!(labels[0], "-inf");
+        assert_eq!(
+            disc.predict_label(&[18302.0]).unwrap(),
+            vec![format!("({}, {}]", labels[0], labels[1])]
+        );
+        assert_eq!(labels[1], "2020-02-29");
+        assert!(labels[1..(labels.len() - 1)].iter().all(|l| l.len() == 10));
+        assert_eq!(disc.predict_idx(&[-1.0]).unwrap(), vec![-1]);
+    }
+
+    #[test]
+    fn test_discretizer_pre_transform() {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
+        }
+        let w_ = vec![1.0; fare.len()];
+        let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), Some(1));
+        let splits = disc.fit(&fare, &survived, Some(&w_), None).unwrap().splits;
+
+        for t in [
+            PreTransform::Log1p,
+            PreTransform::Sqrt,
+            PreTransform::Custom(|v| 2.0 * v),
+        ] {
+            let mut disc_t = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), Some(1));
+            disc_t.pre_transform = Some(t);
+            // The splits are reported on the original scale.
+            assert_eq!(
+                disc_t
+                    .fit(&fare, &survived, Some(&w_), None)
+                    .unwrap()
+                    .splits,
+                splits
+            );
+            assert_eq!(
+                disc_t.predict_woe(&fare).unwrap(),
+                disc.predict_woe(&fare).unwrap()
+            );
+            assert_eq!(disc_t.bin_summary().unwrap(), disc.bin_summary().unwrap());
+        }
+
+        // Values outside of the domain are an error, unless they
+        // are exception values.
+        let mut fare_neg = fare.clone();
+        fare_neg[0] = -2.0;
+        for t in [PreTransform::Log1p, PreTransform::Sqrt] {
+            let mut disc_t = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), Some(1));
+            disc_t.pre_transform = Some(t);
+            assert!(matches!(
+                disc_t.fit(&fare_neg, &survived, Some(&w_), None),
+                Err(DiscrustError::InvalidParameter(_))
+            ));
+            assert!(disc_t
+                .fit(&fare_neg, &survived, Some(&w_), Some(vec![-2.0]))
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn test_discretizer_clip_quantiles() {
+        let mut x_: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        let mut y_: Vec<f64> = (0..100).map(|i| (i % 4 == 0) as i64 as f64).collect();
+        // A handful of extreme, all positive, values.
+        x_.extend(vec![1000.0, 1001.0, 1002.0, 1003.0, 1004.0, 1005.0]);
+        y_.extend(vec![1.0; 6]);
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), Some(0));
+        disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        assert!(disc.splits_.contains(&99.0));
+
+        disc.clip_quantiles = Some((0.0, 0.9));
+        disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        assert!(disc.splits_.iter().all(|s| !s.is_finite() || *s < 95.0));
+        assert_eq!(
+            disc.predict_idx(&[2000.0]).unwrap(),
+            vec![(disc.splits_.len() - 2) as i64]
+        );
+
+        disc.clip_quantiles = Some((0.9, 0.1));
+        assert!(disc.fit(&x_, &y_, Some(&w_), None).is_err());
+    }
+
+    #[test]
+    fn test_fit_result_warnings() {
+        let x_ = vec![1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(2), Some(0.001), Some(1.0), Some(-1));
+        let res = disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+        assert_eq!(res.splits, disc.splits_);
+        assert_eq!(res.bins.len(), 2);
+        assert_eq!(
+            res.warnings,
+            vec![
+                FitWarning::MaxBinsReached,
+                FitWarning::ExceptionValueNotPresent(-1.0)
+            ]
+        );
+
+        let mut disc = Discretizer::new(Some(5.0), Some(2), Some(0.001), Some(1.0), Some(-1));
+        let res = disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        assert_eq!(res.warnings, vec![FitWarning::NoSplits]);
+    }
+
+    #[test]
+    fn test_discretizer_merge_only() {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
+        }
+        let w_ = vec![1.0; fare.len()];
+        let edges = [5.0, 7.5, 10.0, 20.0, 30.0, 50.0, 100.0];
+        let mut disc = Discretizer::new(Some(5.0), Some(4), Some(0.001), Some(1.0), Some(1));
+        disc.strategy = Strategy::MergeOnly {
+            edges: edges.to_vec(),
+        };
+        let splits = disc.fit(&fare, &survived, Some(&w_), None).unwrap().splits;
+        assert!(splits.len() > 2);
+        for s in &splits[1..(splits.len() - 1)] {
+            assert!(edges.contains(s));
+        }
+        // Predictions work on the original values.
+        let expected: Vec<i64> = fare
+            .iter()
+            .map(|v| splits[1..].iter().position(|s| v <= s).unwrap() as i64)
+            .collect();
+        assert_eq!(disc.predict_idx(&fare).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_unsupervised_strategies() {
+        let x_: Vec<f64> = (1..=10).map(|v| v as f64).collect();
+        let y_ = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0];
+        let w_ = vec![1.0; x_.len()];
+        // The constraints are not applied, so min_iv can't remove splits.
+        let mut disc = Discretizer::new(Some(1.0), Some(2), Some(100.0), Some(1.0), None);
+        disc.strategy = Strategy::Quantile { n_bins: 4 };
+        let res = disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        assert_eq!(
+            res.splits,
+            vec![f64::NEG_INFINITY, 3.0, 5.0, 8.0, f64::INFINITY]
+        );
+        assert!(disc.validate_splits().is_ok());
+        let counts: Vec<f64> = res.bins.iter().map(|b| b.totals_ct).collect();
+        assert_eq!(counts, vec![3.0, 2.0, 3.0, 2.0]);
+        assert_eq!(res.bins[0].ones_ct, 1.0);
+
+        disc.strategy = Strategy::Uniform { n_bins: 3 };
+        let res = disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        assert_eq!(res.splits, vec![f64::NEG_INFINITY, 4.0, 7.0, f64::INFINITY]);
+        assert!(disc.validate_splits().is_ok());
+        assert_eq!(
+            disc.predict_idx(&[4.0, 4.5, 7.0, 100.0]).unwrap(),
+            vec![0, 1, 1, 2]
+        );
+
+        // Repeated values produce fewer bins.
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 2.0, 3.0];
+        disc.strategy = Strategy::Quantile { n_bins: 4 };
+        let res = disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        assert_eq!(res.splits, vec![f64::NEG_INFINITY, 1.0, f64::INFINITY]);
+
+        disc.strategy = Strategy::Uniform { n_bins: 0 };
+        assert!(matches!(
+            disc.fit(&x_, &y_, Some(&w_), None),
+            Err(DiscrustError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_splits() {
+        let x_: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, -1.0, f64::NAN];
+        let y_ = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0];
+        assert!(Discretizer::from_splits(&[5.0, 2.0], &[]).is_err());
+        let mut disc = Discretizer::from_splits(&[2.5, 5.0, 100.0], &[-1.0]).unwrap();
+        assert!(matches!(
+            Discretizer::default().fit_stats(&x_, &y_, None),
+            Err(DiscrustError::InvalidParameter(_))
+        ));
+        let res = disc.fit_stats(&x_, &y_, None).unwrap();
+        // The splits are kept, even those with no records above them.
+        assert_eq!(
+            res.splits,
+            vec![f64::NEG_INFINITY, 2.5, 5.0, 100.0, f64::INFINITY]
+        );
+        assert!(disc.validate_splits().is_ok());
+        let counts: Vec<f64> = res.bins.iter().map(|b| b.totals_ct).collect();
+        assert_eq!(counts, vec![2.0, 3.0, 3.0, 0.0]);
+        assert_eq!(res.exceptions[0].value, -1.0);
+        assert_eq!(res.missing.unwrap().totals_ct, 1.0);
+        assert_eq!(
+            disc.predict_idx(&[2.0, 2.6, -1.0, 1000.0]).unwrap(),
+            vec![0, 1, -1, 3]
+        );
+        assert!(disc.tree().unwrap().is_terminal());
+    }
+
+    #[test]
+    fn test_bin_labels() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.5, 2.5, 2.5, 2.5, -1.0, -2.0, f64::NAN];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.bin_labels(None).is_err());
+        disc.fit(&x_, &y_, None, Some(vec![-1.0, -2.0])).unwrap();
+        disc.set_exception_label(-2.0, "Closed").unwrap();
+        let labels = disc.bin_labels(None).unwrap();
+        assert_eq!(
+            labels,
+            vec!["(-inf, 1]", "(1, inf]", "Closed", "-1", "Missing"]
+        );
+        assert_eq!(labels.len(), disc.bin_woe_().unwrap().len());
+        // The labels match those of predict_label, and the summaries.
+        let x_test = [1.0, 2.5, -1.0, -2.0, f64::NAN];
+        for (idx, label) in disc
+            .predict_idx(&x_test)
+            .unwrap()
+            .into_iter()
+            .zip(disc.predict_label(&x_test).unwrap())
+        {
+            assert_eq!(labels[disc.index_position(idx).unwrap()], label);
+        }
+        assert_eq!(labels[3], disc.exception_summary().unwrap()[1].label);
+        assert_eq!(disc.bin_labels(Some(2)).unwrap()[0], "(-inf, 1.00]");
+        assert_eq!(disc.bin_labels(Some(2)).unwrap()[3], "-1.00");
+    }
+
+    #[test]
+    fn test_predict_bounds() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, f64::NAN];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.predict_bounds(&x_).is_err());
+        disc.fit(&x_, &y_, None, Some(vec![-1.0])).unwrap();
+        let (lower, upper) = disc.predict_bounds(&[0.5, 1.5, -1.0, f64::NAN]).unwrap();
+        assert_eq!(lower[..3], [f64::NEG_INFINITY, 1.0, -1.0]);
+        assert_eq!(upper[..3], [1.0, f64::INFINITY, -1.0]);
+        assert!(lower[3].is_nan() && upper[3].is_nan());
+    }
+
+    #[test]
+    fn test_max_candidates() {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
+        }
+        let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), None);
+        disc.max_candidates = Some(16);
+        let res = disc.fit(&fare, &survived, None, None).unwrap();
+        assert!(res.splits.len() > 2);
+        let feature = disc.feature.as_ref().unwrap();
+        assert!(feature.vals_.len() <= 16);
+        // The splits are still observed values, and every record is counted.
+        assert!(disc.validate_splits().is_ok());
+        let total: f64 = res.bins.iter().map(|b| b.totals_ct).sum();
+        assert_eq!(total, fare.len() as f64);
+
+        // A histogram discretizer has the default parameters, with
+        // the values aggregated into the given number of buckets.
+        let mut hist = Discretizer::new_histogram(16);
+        assert_eq!(hist.max_candidates, Some(16));
+        assert_eq!(hist.max_bins, Discretizer::default().max_bins);
+        hist.fit(&fare, &survived, None, None).unwrap();
+        assert!(hist.feature.as_ref().unwrap().vals_.len() <= 16);
+
+        // With more candidates than unique values, the fit is exact.
+        let mut exact = disc.unfitted_copy();
+        exact.max_candidates = None;
+        disc.max_candidates = Some(fare.len());
+        assert_eq!(
+            disc.fit(&fare, &survived, None, None).unwrap().splits,
+            exact.fit(&fare, &survived, None, None).unwrap().splits
+        );
+
+        // The folds are counted against the buckets.
+        let mut folds = Discretizer::new_histogram(16);
+        folds.cv_folds = Some(3);
+        folds.cv_agreement = 0.0;
+        let res = folds.fit(&fare, &survived, None, None).unwrap();
+        assert_eq!(res.splits, hist.splits_);
+
+        let x_: Vec<f64> = (1..=8).map(|v| v as f64).collect();
+        disc.max_candidates = Some(1);
+        let y_ = vec![0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0];
+        assert!(matches!(
+            disc.fit(&x_, &y_, None, None),
+            Err(DiscrustError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_closed_left() {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
+        }
+        let mut right = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), None);
+        let mut left = right.unfitted_copy();
+        left.closed = Closed::Left;
+        let right_res = right.fit(&fare, &survived, None, None).unwrap();
+        let left_res = left.fit(&fare, &survived, None, None).unwrap();
+        // The records on each side of every split are the same, but
+        // each split is the first value of the upper bin.
+        assert_eq!(left_res.splits.len(), right_res.splits.len());
+        let counts = |res: &FitResult| res.bins.iter().map(|b| b.totals_ct).collect::<Vec<_>>();
+        assert_eq!(counts(&left_res), counts(&right_res));
+        assert_eq!(
+            left.predict_idx(&fare).unwrap(),
+            right.predict_idx(&fare).unwrap()
+        );
+        assert!(left.validate_splits().is_ok());
+        let split = left.splits_[1];
+        assert!(right.splits_[1] < split);
+        assert_eq!(left.predict_idx(&[split]).unwrap(), vec![1]);
+        assert_eq!(
+            left.predict_idx(&[f64::INFINITY]).unwrap()[0],
+            left.splits_.len() as i64 - 2
+        );
+        assert!(left_res.bins[0].label.starts_with("[-inf, "));
+        assert!(left_res.bins[0].label.ends_with(')'));
+
+        // Quantile splits, and merged pre-bins, are also the lower edge of their bin.
+        let x_: Vec<f64> = (1..=10).map(|v| v as f64).collect();
+        let y_ = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        disc.closed = Closed::Left;
+        disc.strategy = Strategy::Quantile { n_bins: 4 };
+        let res = disc.fit(&x_, &y_, None, None).unwrap();
+        assert_eq!(
+            res.splits,
+            vec![f64::NEG_INFINITY, 4.0, 6.0, 9.0, f64::INFINITY]
+        );
+        disc.strategy = Strategy::MergeOnly {
+            edges: vec![3.0, 6.0, 8.0],
+        };
+        disc.mono = MonotonicTrend::Unconstrained;
+        let res = disc.fit(&x_, &y_, None, None).unwrap();
+        assert!(res.splits[1..(res.splits.len() - 1)]
+            .iter()
+            .all(|s| [3.0, 6.0, 8.0].contains(s)));
+
+        disc.feature_type = FeatureType::Datetime {
+            unit: crate::datetime::TimeUnit::Days,
+            snap: DateSnap::Month,
+        };
+        assert!(disc.fit(&x_, &y_, None, None).is_err());
+    }
+
+    #[test]
+    fn test_woe_regularization() {
+        let x_ = vec![1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, 3.0, 3.0, 3.0];
+        let y_ = vec![0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 1.0, 1.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(0.0), None);
+        let res = disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        assert!(res.bins.iter().any(|b| b.woe.is_infinite()));
+
+        disc.woe_regularization = 0.5;
+        let res = disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        assert!(res
+            .bins
+            .iter()
+            .all(|b| b.woe.is_finite() & b.iv.is_finite()));
+        assert!(disc.predict_woe(&x_).unwrap().iter().all(|v| v.is_finite()));
+        assert_eq!(disc.unfitted_copy().woe_regularization, 0.5);
+
+        disc.woe_regularization = -1.0;
+        assert!(matches!(
+            disc.fit(&x_, &y_, Some(&w_), None),
+            Err(DiscrustError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_peak_and_valley_trends() {
+        // The event rate rises to the middle of the range, then falls.
+        let x_: Vec<f64> = (0..600).map(|i| (i / 10) as f64).collect();
+        let rate = |v: f64| 0.1 + 0.8 * (1.0 - (v - 30.0).abs() / 30.0);
+        let y_: Vec<f64> = x_
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (((i % 10) as f64) < 10.0 * rate(*v)) as i64 as f64)
+            .collect();
+        let w_ = vec![1.0; x_.len()];
+        let woe = |disc: &Discretizer| -> Vec<f64> {
+            disc.bin_summary().unwrap().iter().map(|b| b.woe).collect()
+        };
+        // A single change of direction.
+        let changes = |woe: &[f64]| {
+            let signs: Vec<bool> = woe.windows(2).map(|w| w[0] < w[1]).collect();
+            signs.windows(2).filter(|s| s[0] != s[1]).count()
+        };
+
+        let mut disc = Discretizer::new(Some(20.0), Some(10), Some(0.001), Some(1.0), Some(1));
+        disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        let ascending = woe(&disc);
+        assert!(ascending.windows(2).all(|w| w[0] < w[1]));
+
+        disc.mono = MonotonicTrend::Peak;
+        disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        let peak = woe(&disc);
+        assert!(peak.len() > ascending.len());
+        assert_eq!(changes(&peak), 1);
+        assert!(peak[0] < peak[1]);
+        assert_eq!(disc.mono, MonotonicTrend::Peak);
+
+        // Flipping the target turns the peak into a valley.
+        let y_flipped: Vec<f64> = y_.iter().map(|v| 1.0 - v).collect();
+        disc.mono = MonotonicTrend::Valley;
+        disc.fit(&x_, &y_flipped, Some(&w_), None).unwrap();
+        let valley = woe(&disc);
+        assert_eq!(changes(&valley), 1);
+        assert!(valley[0] > valley[1]);
+
+        disc.mono = MonotonicTrend::Auto;
+        disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        assert!(matches!(
+            disc.mono,
+            MonotonicTrend::Ascending | MonotonicTrend::Descending
+        ));
+    }
+
+    #[test]
+    fn test_max_pvalue() {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
+        }
+        let w_ = vec![1.0; fare.len()];
+        let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), Some(1));
+        let all_splits = disc.fit(&fare, &survived, Some(&w_), None).unwrap().splits;
+        disc.max_pvalue = Some(0.01);
+        let res = disc.fit(&fare, &survived, Some(&w_), None).unwrap();
+        assert!(res.splits.len() < all_splits.len());
+        // Every split of the tree was significant, when it was made.
+        let root = disc.root_node.as_ref().unwrap();
+        let info = &root.split_info;
+        let feature = disc.feature.as_ref().unwrap();
+        let ((lhs_ct, lhs_ones), (rhs_ct, rhs_ones)) =
+            feature.split_totals_ct_ones_ct(info.split_idx.unwrap(), root.start, root.stop);
+        assert!(two_proportion_pvalue((lhs_ct, lhs_ones), (rhs_ct, rhs_ones)) <= 0.01);
+
+        disc.max_pvalue = Some(1.5);
+        assert!(matches!(
+            disc.fit(&fare, &survived, Some(&w_), None),
+            Err(DiscrustError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_min_bin_width() {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
+        }
+        let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), Some(0));
+        let all_splits = disc.fit(&fare, &survived, None, None).unwrap().splits;
+        let narrowest = |splits: &[f64]| {
+            splits
+                .windows(2)
+                .map(|w| w[1] - w[0])
+                .fold(f64::INFINITY, f64::min)
+        };
+        assert!(narrowest(&all_splits) < 2.0);
+        for split_point in [SplitPoint::Observed, SplitPoint::Midpoint] {
+            disc.split_point = split_point;
+            disc.min_bin_width = Some(2.0);
+            let splits = disc.fit(&fare, &survived, None, None).unwrap().splits;
+            assert!(splits.len() > 2);
+            assert!(narrowest(&splits) >= 2.0);
+        }
+
+        disc.min_bin_width = Some(-1.0);
+        assert!(matches!(
+            disc.fit(&fare, &survived, None, None),
+            Err(DiscrustError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_max_bin_pct() {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
+        }
+        let total = fare.len() as f64;
+        let largest = |res: &FitResult| {
+            res.bins
+                .iter()
+                .map(|b| b.totals_ct / total)
+                .fold(0.0, f64::max)
+        };
+        // A high min_iv leaves a few large bins.
+        let mut disc = Discretizer::new(Some(5.0), Some(20), Some(0.2), Some(1.0), Some(0));
+        let res = disc.fit(&fare, &survived, None, None).unwrap();
+        assert!(largest(&res) > 0.3);
+        disc.max_bin_pct = Some(0.3);
+        let capped = disc.fit(&fare, &survived, None, None).unwrap();
+        assert!(capped.splits.len() > res.splits.len());
+        assert!(largest(&capped) <= 0.3);
+        assert!(capped.warnings.is_empty());
+
+        // A single value can not be split, so the bin holding it remains.
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 2.0, 3.0];
+        let y_ = vec![1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(0.0), Some(0));
+        disc.max_bin_pct = Some(0.5);
+        let res = disc.fit(&x_, &y_, None, None).unwrap();
+        assert_eq!(res.warnings, vec![FitWarning::MaxBinPctExceeded(1)]);
+
+        disc.max_bin_pct = Some(0.0);
+        assert!(disc.fit(&x_, &y_, None, None).is_err());
+    }
+
+    #[test]
+    fn test_growth() {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
+        }
+        let total_iv = |res: &FitResult| res.bins.iter().map(|b| b.iv).sum::<f64>();
+        let mut disc = Discretizer::new(Some(5.0), Some(100), Some(0.001), Some(1.0), Some(0));
+        disc.woe_regularization = 0.5;
+        let all_splits = disc.fit(&fare, &survived, None, None).unwrap().splits;
+        let mut ivs = Vec::new();
+        for growth in [Growth::BreadthFirst, Growth::DepthFirst, Growth::BestFirst] {
+            // Without a binding max_bins, the order the nodes
+            // are split in does not change the splits.
+            disc.growth = growth;
+            disc.max_bins = 100;
+            let res = disc.fit(&fare, &survived, None, None).unwrap();
+            assert_eq!(res.splits, all_splits);
+            disc.max_bins = 5;
+            let res = disc.fit(&fare, &survived, None, None).unwrap();
+            assert_eq!(res.bins.len(), 5);
+            assert!(res.warnings.contains(&FitWarning::MaxBinsReached));
+            ivs.push(total_iv(&res));
+        }
+        // Best first spends the bins on the most informative splits.
+        assert!(ivs[2] > ivs[0]);
+        assert!(ivs[2] > ivs[1]);
+    }
+
+    #[test]
+    fn test_chaid() {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
+        }
+        let total_iv = |res: &FitResult| res.bins.iter().map(|b| b.iv).sum::<f64>();
+        let mut disc = Discretizer::new(Some(5.0), Some(3), Some(0.001), Some(1.0), None);
+        let tree = disc.fit(&fare, &survived, None, None).unwrap();
+        assert_eq!(tree.bins.len(), 3);
+
+        // The root is split into three bins in one step, with
+        // at least the information value of two binary splits.
+        disc.strategy = Strategy::Chaid { max_children: 3 };
+        let res = disc.fit(&fare, &survived, None, None).unwrap();
+        assert_eq!(res.bins.len(), 3);
+        assert!(total_iv(&res) >= total_iv(&tree));
+        let woe: Vec<f64> = res.bins.iter().map(|b| b.woe).collect();
+        assert!(woe.windows(2).all(|w| w[0] < w[1]) || woe.windows(2).all(|w| w[0] >= w[1]));
+        let tree_node = disc.tree().unwrap();
+        assert_eq!(tree_node.totals_ct, fare.len() as f64);
+        assert_eq!(disc.predict_idx(&[fare[0]]).unwrap().len(), 1);
+
+        // Without a binding max_bins, the bins are split further.
+        disc.max_bins = 10;
+        let res = disc.fit(&fare, &survived, None, None).unwrap();
+        assert!(res.bins.len() > 3);
+        assert!(res.bins.len() <= 10);
 
-            // Add the split info here, after we use it, to avoid a move.
-            node.split_info = info;
+        disc.strategy = Strategy::Chaid { max_children: 1 };
+        assert!(disc.fit(&fare, &survived, None, None).is_err());
+        disc.strategy = Strategy::Chaid { max_children: 3 };
+        disc.mono = MonotonicTrend::Peak;
+        assert!(disc.fit(&fare, &survived, None, None).is_err());
+    }
 
-            node.left_node = Some(Box::new(lhs_node));
-            node.right_node = Some(Box::new(rhs_node));
-            que.push_front(node.left_node.as_mut());
-            que.push_front(node.right_node.as_mut());
-            self.splits_.push(split);
+    #[test]
+    fn test_optimal_dp() {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
         }
-        // Take ownership of feature for now.
-        self.feature = Some(feature);
-        self.splits_.push(-f64::INFINITY);
-        self.splits_.push(f64::INFINITY);
-        self.splits_.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        Ok(self.splits_.to_vec())
-    }
+        let total_iv = |res: &FitResult| res.bins.iter().map(|b| b.iv).sum::<f64>();
+        let mut disc = Discretizer::new(Some(5.0), Some(5), Some(0.001), Some(1.0), Some(0));
+        disc.woe_regularization = 0.5;
+        disc.growth = Growth::BestFirst;
+        let tree = disc.fit(&fare, &survived, None, None).unwrap();
 
-    pub fn predict_woe(&self, x: &[f64]) -> Result<Vec<f64>, DiscrustError> {
-        // First we check if this is an exception value, to do this, we need
-        // to check if the value is present in the exception struct.
-        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
-        let res: Vec<f64> = x
+        // With an atom for every unique value, the partition is
+        // exact, so no tree of as many bins has more information.
+        disc.strategy = Strategy::OptimalDP { n_atoms: 500 };
+        let res = disc.fit(&fare, &survived, None, None).unwrap();
+        assert_eq!(res.bins.len(), 5);
+        assert!(total_iv(&res) >= total_iv(&tree));
+        assert!(res
+            .bins
             .iter()
-            .map(|v| self.predict_record_woe(v, feature))
-            .collect::<Result<Vec<f64>, DiscrustError>>()?;
-        Ok(res)
-    }
+            .all(|b| (b.totals_ct >= 5.0) & (b.ones_ct >= 1.0)));
+        assert_eq!(disc.tree().unwrap().totals_ct, fare.len() as f64);
 
-    pub fn predict_idx(&self, x: &[f64]) -> Result<Vec<i64>, DiscrustError> {
-        // We don't need the first, value, as this will be negative infinity.
-        let all_splits = &self.splits_.as_slice()[1..];
-        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
-        let res: Vec<i64> = x
-            .iter()
-            .map(|v| self.predict_record_idx(v, all_splits, feature))
-            .collect::<Result<Vec<i64>, DiscrustError>>()?;
-        Ok(res)
+        // An automatic trend is replaced by the trend of the bins.
+        disc.mono = MonotonicTrend::Auto;
+        disc.strategy = Strategy::OptimalDP { n_atoms: 20 };
+        let res = disc.fit(&fare, &survived, None, None).unwrap();
+        assert!(res.bins.len() <= 5);
+        let woe: Vec<f64> = res.bins.iter().map(|b| b.woe).collect();
+        match disc.mono {
+            MonotonicTrend::Ascending => assert!(woe.windows(2).all(|w| w[0] < w[1])),
+            MonotonicTrend::Descending => assert!(woe.windows(2).all(|w| w[0] >= w[1])),
+            t => panic!("unexpected trend {:?}", t),
+        }
+
+        disc.min_iv = 10.0;
+        let res = disc.fit(&fare, &survived, None, None).unwrap();
+        assert!(res.warnings.contains(&FitWarning::NoSplits));
+        disc.strategy = Strategy::OptimalDP { n_atoms: 1 };
+        assert!(disc.fit(&fare, &survived, None, None).is_err());
     }
 
-    fn predict_record_idx(
-        &self,
-        v: &f64,
-        all_splits: &[f64],
-        feature: &Feature,
-    ) -> Result<i64, DiscrustError> {
-        // If it's an exception value, we return the index negative value.
-        // We start this at -1. So we add 1, to the zero indexed result
-        // of the `exception_idx` function.
-        if let Some(i) = feature.exception_values_.exception_idx(v) {
-            return Ok(-((i + 1) as i64));
+    #[test]
+    fn test_mdlp() {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
         }
-        let idx = all_splits
-            .iter()
-            // If the value is less than, or equal to the bin edge, we are in that
-            // position bin.
-            .position(|x| matches!(nan_safe_compare(x, v), Ordering::Greater | Ordering::Equal))
-            .ok_or(DiscrustError::Prediction)?;
-        Ok(idx as i64)
+        let mut disc = Discretizer::new(Some(5.0), Some(100), Some(0.0), Some(1.0), Some(0));
+        let all = disc.fit(&fare, &survived, None, None).unwrap();
+
+        // The test stops the tree well before every split
+        // with a positive information value is made.
+        disc.mdlp = true;
+        let res = disc.fit(&fare, &survived, None, None).unwrap();
+        assert!(res.bins.len() > 1);
+        assert!(res.bins.len() < all.bins.len());
+        let report = disc.fit_report().unwrap();
+        assert!(report.stop_reasons.contains_key(&StopReason::Mdlp));
+        assert!(!report.stop_reasons.contains_key(&StopReason::MinIv));
+
+        disc.target_type = TargetType::Continuous;
+        assert!(disc.fit(&fare, &survived, None, None).is_err());
     }
-    // -1, 4, 10
-    fn predict_record_woe(&self, v: &f64, feature: &Feature) -> Result<f64, DiscrustError> {
-        let excp_idx = feature.exception_values_.exception_idx(v);
-        if let Some(idx) = excp_idx {
-            if feature.exception_values_.totals_ct_[idx] == 0.0 {
-                return Ok(0.0);
-            }
-            return Ok(feature.exception_values_.woe_[idx]);
+
+    #[test]
+    fn test_min_iv_gain_ratio() {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
         }
-        let mut node = self.root_node.as_ref().ok_or(DiscrustError::NotFitted)?;
-        let w: f64;
-        loop {
-            if node.is_terminal() {
-                w = node.woe.to_owned();
-                break;
-            }
-            // If this is not a terminal node, split will always be
-            // populated, which is why we are OK to use unwrap here.
-            if v > &node.split_info.split.unwrap() {
-                node = node.right_node.as_ref().unwrap();
-            } else {
-                node = node.left_node.as_ref().unwrap();
+        let mut disc = Discretizer::new(Some(5.0), Some(100), Some(0.001), Some(1.0), Some(0));
+        let all = disc.fit(&fare, &survived, None, None).unwrap();
+
+        // Every split gains at least half of the information value of its node.
+        disc.min_iv_gain_ratio = Some(0.5);
+        let res = disc.fit(&fare, &survived, None, None).unwrap();
+        assert!(res.bins.len() > 1);
+        assert!(res.bins.len() < all.bins.len());
+        let mut stack = vec![disc.tree().unwrap()];
+        while let Some(node) = stack.pop() {
+            if let (Some(left), Some(right)) = (node.left, node.right) {
+                assert!(left.iv + right.iv - node.iv > 0.5 * node.iv);
+                stack.push(*left);
+                stack.push(*right);
             }
         }
-        Ok(w)
+        let report = disc.fit_report().unwrap();
+        assert!(report.stop_reasons.contains_key(&StopReason::MinIvGain));
+
+        disc.min_iv_gain_ratio = Some(-1.0);
+        assert!(disc.fit(&fare, &survived, None, None).is_err());
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use std::fs;
-    // [-inf, 6.95, 7.125, 7.7292, 10.4625, 15.1, 50.4958, 52.0, 73.5, 79.65, inf]
     #[test]
-    fn test_discretizer() {
+    fn test_continuous_target() {
+        // The mean of the performance steps up at 4 and 8.
+        let x_: Vec<f64> = (1..=12).map(|v| v as f64).collect();
+        let y_ = vec![1.0, 2.0, 1.5, 1.5, 5.0, 6.0, 5.5, 5.5, 9.0, 10.0, 9.5, 9.5];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(2.0), Some(10), Some(0.01), Some(0.0), Some(1));
+        disc.target_type = TargetType::Continuous;
+        let res = disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        assert_eq!(res.splits, vec![f64::NEG_INFINITY, 4.0, 8.0, f64::INFINITY]);
+        let means: Vec<f64> = res.bins.iter().map(|b| b.mean).collect();
+        assert_eq!(means, vec![1.5, 5.5, 9.5]);
+        assert_eq!(res.bins.iter().map(|b| b.woe).collect::<Vec<f64>>(), means);
+        assert!((res.bins[0].std - 0.125_f64.sqrt()).abs() < 1e-12);
+        assert_eq!(disc.predict_woe(&[0.0, 6.0, 12.0]).unwrap(), means);
+        // The bins explain most of the variance.
+        let r_squared = disc.total_iv_().unwrap();
+        assert!((0.95..1.0).contains(&r_squared));
+
+        // A mean that falls breaks the ascending trend.
+        let mut y_desc = y_.to_vec();
+        y_desc[8..].iter_mut().for_each(|v| *v = 0.0);
+        let res = disc.fit(&x_, &y_desc, Some(&w_), None).unwrap();
+        assert!(res.bins.windows(2).all(|b| b[0].mean < b[1].mean));
+
+        // Exception values, and the missing bin, have the mean of their records.
+        let mut x_e = x_.to_vec();
+        x_e[0] = -1.0;
+        x_e[1] = f64::NAN;
+        let res = disc.fit(&x_e, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+        assert_eq!(res.exceptions[0].woe, 1.0);
+        assert_eq!(res.missing.as_ref().unwrap().mean, 2.0);
+        assert_eq!(disc.predict_woe(&[-1.0, f64::NAN]).unwrap(), vec![1.0, 2.0]);
+
+        // The batches of `partial_fit` keep the sums of the performance.
+        disc.partial_fit(&x_[..6], &y_[..6], Some(&w_[..6]), None)
+            .unwrap();
+        disc.partial_fit(&x_[6..], &y_[6..], Some(&w_[6..]), None)
+            .unwrap();
+        let batched = disc.finalize().unwrap();
+        assert_eq!(
+            batched.splits,
+            vec![f64::NEG_INFINITY, 4.0, 8.0, f64::INFINITY]
+        );
+
+        disc.cv_folds = Some(5);
+        assert!(disc.fit(&x_, &y_, Some(&w_), None).is_err());
+    }
+
+    #[test]
+    fn test_cv_folds() {
         let mut fare: Vec<f64> = Vec::new();
         let mut survived: Vec<f64> = Vec::new();
         let file = fs::read_to_string("resources/data.csv")
             .expect("Something went wrong reading the file");
         for l in file.lines() {
-            let split: Vec<f64> = l.split(",").map(|x| x.parse::<f64>().unwrap()).collect();
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
             fare.push(split[0]);
             survived.push(split[1]);
         }
-        let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), Some(1));
         let w_ = vec![1.0; fare.len()];
-        let splits = disc.fit(&fare, &survived, &w_, None).unwrap();
-
-        disc.predict_idx(&fare).unwrap();
-        disc.predict_woe(&fare).unwrap();
-
+        // Small bins, that are likely to be fit to noise.
+        let mut disc = Discretizer::new(Some(1.0), Some(50), Some(0.0), Some(0.0), None);
+        let all_splits = disc.fit(&fare, &survived, Some(&w_), None).unwrap().splits;
+        disc.cv_folds = Some(5);
+        let cv_splits = disc.fit(&fare, &survived, Some(&w_), None).unwrap().splits;
+        assert!(cv_splits.len() > 2);
+        assert!(cv_splits.len() < all_splits.len());
+        // The folds are the same each time.
         assert_eq!(
-            splits,
-            vec![
-                -f64::INFINITY,
-                6.95,
-                7.125,
-                7.7292,
-                10.4625,
-                15.1,
-                50.4958,
-                52.0,
-                73.5,
-                79.65,
-                f64::INFINITY
-            ]
+            disc.fit(&fare, &survived, Some(&w_), None).unwrap().splits,
+            cv_splits
+        );
+        // Requiring fewer folds allows more splits.
+        disc.cv_agreement = 0.6;
+        let lenient = disc.fit(&fare, &survived, Some(&w_), None).unwrap().splits;
+        assert!(lenient.len() >= cv_splits.len());
+        disc.cv_agreement = 0.0;
+        assert_eq!(
+            disc.fit(&fare, &survived, Some(&w_), None).unwrap().splits,
+            all_splits
         );
 
-        // Test predictions run without error.
-        // println!("{:?}", disc.predict(&fare));
+        disc.cv_folds = Some(1);
+        assert!(disc.fit(&fare, &survived, Some(&w_), None).is_err());
+        disc.cv_folds = Some(5);
+        disc.cv_agreement = 1.5;
+        assert!(disc.fit(&fare, &survived, Some(&w_), None).is_err());
+        assert!(disc.partial_fit(&fare, &survived, Some(&w_), None).is_err());
     }
 
     #[test]
-    fn test_discretizer_mono_n1() {
+    fn test_predict_idx_sorted() {
         let mut fare: Vec<f64> = Vec::new();
         let mut survived: Vec<f64> = Vec::new();
         let file = fs::read_to_string("resources/data.csv")
             .expect("Something went wrong reading the file");
         for l in file.lines() {
-            let split: Vec<f64> = l.split(",").map(|x| x.parse::<f64>().unwrap()).collect();
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
             fare.push(split[0]);
-            survived.push((split[1] == 0.0) as i64 as f64);
+            survived.push(split[1]);
         }
-        let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), Some(-1));
+        fare[3] = -1.0;
         let w_ = vec![1.0; fare.len()];
-        let splits = disc.fit(&fare, &survived, &w_, None).unwrap();
+        let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), Some(1));
+        disc.fit(&fare, &survived, Some(&w_), Some(vec![-1.0]))
+            .unwrap();
+        let mut x = fare.to_vec();
+        x.extend([f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 7.125, 7.1251]);
+        let idx = disc.predict_idx(&x).unwrap();
+        assert_eq!(disc.predict_idx_sorted(&x).unwrap(), idx);
+        assert_eq!(idx[3], -1);
+        // Values equal to a split fall in the bin it closes.
+        assert_eq!(idx[x.len() - 2] + 1, idx[x.len() - 1]);
+        assert!(disc.predict_idx_sorted::<f64>(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_bin_woe_and_iv() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.bin_woe_().is_err());
+        disc.on_missing = OnMissing::WoeValue(0.5);
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0, -2.0]))
+            .unwrap();
+        let woe = disc.bin_woe_().unwrap();
+        let iv = disc.bin_iv_().unwrap();
+        assert_eq!(woe.len(), 4);
+        assert_eq!(iv.len(), 4);
+        // -2.0 has no records.
+        assert_eq!(woe[2], 0.0);
+        assert_eq!(iv[2], 0.0);
+        let x = [1.0, 2.0, -1.0, -2.0];
+        let idx = disc.predict_idx(&x).unwrap();
+        let predicted = disc.predict_woe(&x).unwrap();
+        for (i, p) in idx.iter().zip(predicted) {
+            assert_eq!(woe[disc.index_position(*i).unwrap()], p);
+        }
+        let summary = disc.bin_summary().unwrap();
+        assert_eq!(iv[0], summary[0].iv);
+        assert_eq!(disc.total_iv_().unwrap(), iv.iter().sum::<f64>());
+        assert!(disc.total_iv_().unwrap() > 0.0);
+        let missing = disc.predict_idx(&[f64::NAN]).unwrap()[0];
+        assert_eq!(disc.index_position(missing), None);
+    }
+
+    #[test]
+    fn test_predict_f32() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.5, 2.5, 2.5, 2.5, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+        let x32: Vec<f32> = vec![0.1, 1.0, 1.1, 2.5, 300.0, -1.0, f32::NAN];
+        let x64: Vec<f64> = x32.iter().map(|v| *v as f64).collect();
         assert_eq!(
-            splits,
-            vec![
-                -f64::INFINITY,
-                6.95,
-                7.125,
-                7.7292,
-                10.4625,
-                15.1,
-                50.4958,
-                52.0,
-                73.5,
-                79.65,
-                f64::INFINITY
-            ]
+            disc.predict_idx(&x32).unwrap(),
+            disc.predict_idx(&x64).unwrap()
         );
-        // println!("{:?}", disc.predict(&fare));
+        assert_eq!(
+            disc.predict_idx_sorted(&x32).unwrap(),
+            disc.predict_idx(&x64).unwrap()
+        );
+        assert_eq!(
+            disc.predict_woe(&x32).unwrap(),
+            disc.predict_woe(&x64).unwrap()
+        );
+        assert_eq!(
+            disc.predict_label(&x32).unwrap(),
+            disc.predict_label(&x64).unwrap()
+        );
+        // A feature can be built from f32 values.
+        let x32: Vec<f32> = x_.iter().map(|v| *v as f32).collect();
+        let feature = Feature::new(&x32, &y_, Some(&w_), &[-1.0]).unwrap();
+        assert_eq!(feature.unique_values(), &[1.0, 2.5]);
     }
 
     #[test]
-    fn test_discretizer_mono_none() {
+    fn test_predict_woe_into() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.5, 2.5, 2.5, 2.5, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        let mut out = vec![0.0; 4];
+        assert!(disc.predict_woe_into(&x_[..4], &mut out).is_err());
+        disc.fit(&x_, &y_, None, Some(vec![-1.0])).unwrap();
+        assert!(matches!(
+            disc.predict_woe_into(&x_, &mut out),
+            Err(DiscrustError::LengthMismatch(c, 4, 9)) if c == "output"
+        ));
+
+        // Threads share the fitted discretizer, each scoring into its own buffer.
+        let x = [0.1, 1.0, 2.5, 300.0, -1.0, f64::NAN];
+        let expected = disc.predict_woe(&x).unwrap();
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    let mut out = vec![0.0; x.len()];
+                    for _ in 0..10 {
+                        disc.predict_woe_into(&x, &mut out).unwrap();
+                        assert_eq!(out, expected);
+                    }
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn test_special_missing() {
+        let x_ = vec![
+            1.0,
+            1.0,
+            1.0,
+            1.0,
+            2.0,
+            2.0,
+            2.0,
+            2.0,
+            -1.0,
+            f64::NAN,
+            f64::NAN,
+        ];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0];
+        let w_ = vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 2.0];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        let res = disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+        // The missing bin is reported apart from the exception values.
+        assert_eq!(res.exceptions.len(), 1);
+        let missing = res.missing.unwrap();
+        assert_eq!(missing.totals_ct, 3.0);
+        assert_eq!(missing.ones_ct, 1.0);
+        assert_eq!(missing.event_rate, 1.0 / 3.0);
+        let (iv, woe) = crate::feature::iv_woe(1.0, 2.0, 6.0, 6.0, 0.0);
+        assert_eq!((missing.woe, missing.iv), (woe, iv));
+        // Missing values take the index after the last exception value.
+        assert_eq!(disc.predict_idx(&[f64::NAN, -1.0]).unwrap(), vec![-2, -1]);
+        assert_eq!(disc.predict_idx_sorted(&[f64::NAN]).unwrap(), vec![-2]);
+        assert_eq!(disc.predict_woe(&[f64::NAN]).unwrap(), vec![woe]);
+        assert_eq!(disc.predict_label(&[f64::NAN]).unwrap(), vec!["Missing"]);
+        let bin_woe = disc.bin_woe_().unwrap();
+        assert_eq!(bin_woe.len(), 4);
+        assert_eq!(disc.index_position(-2), Some(3));
+        assert_eq!(bin_woe[3], woe);
+        assert_eq!(disc.bin_iv_().unwrap()[3], iv);
+
+        // NaN passed as an exception value is an exception value as before.
+        let res = disc
+            .fit(&x_, &y_, Some(&w_), Some(vec![-1.0, f64::NAN]))
+            .unwrap();
+        assert_eq!(res.exceptions.len(), 2);
+        assert!(res.missing.is_none());
+
+        // Without any missing values, there is no missing bin.
+        disc.on_missing = OnMissing::Error;
+        let res = disc.fit(&x_[..9], &y_[..9], Some(&w_[..9]), None).unwrap();
+        assert!(res.missing.is_none());
+        assert!(disc.predict_idx(&[f64::NAN]).is_err());
+
+        disc.special_missing = false;
+        for e in [None, Some(vec![-1.0])] {
+            assert!(matches!(
+                disc.fit(&x_, &y_, Some(&w_), e),
+                Err(DiscrustError::ContainsNaN(_))
+            ));
+        }
+        assert_eq!(disc.parameters()["special_missing"], "false");
+        assert!(!disc.unfitted_copy().special_missing);
+    }
+
+    #[test]
+    fn test_discretizer_only_exceptions() {
+        let x_ = vec![-1.0, -1.0, -2.0, f64::NAN, -2.0, -1.0];
+        let y_ = vec![1.0, 0.0, 1.0, 0.0, 0.0, 1.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        let res = disc
+            .fit(&x_, &y_, Some(&w_), Some(vec![-1.0, -2.0, f64::NAN]))
+            .unwrap();
+        assert_eq!(res.splits, vec![-f64::INFINITY, f64::INFINITY]);
+        assert_eq!(res.warnings, vec![FitWarning::NoSplits]);
+        assert_eq!(res.bins[0].totals_ct, 0.0);
+
+        let excp = &disc.feature.as_ref().unwrap().exception_values_;
+        let woe = disc.predict_woe(&[-2.0, -1.0, 5.0]).unwrap();
+        assert_eq!(woe, vec![excp.woe_[1], excp.woe_[2], 0.0]);
+        assert_eq!(
+            disc.predict_idx(&[-2.0, -1.0, 5.0]).unwrap(),
+            vec![-2, -3, 0]
+        );
+    }
+
+    #[test]
+    fn test_validate_splits() {
+        let x_ = vec![1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(2.0), Some(10), Some(0.001), Some(1.0), Some(-1));
+        assert!(disc.validate_splits().is_err());
+        disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        assert!(disc.validate_splits().is_ok());
+        let observed = disc.splits_.to_vec();
+        disc.splits_[1] += 0.5;
+        assert!(disc.validate_splits().is_err());
+        disc.splits_.reverse();
+        assert!(disc.validate_splits().is_err());
+
+        // The same bins, with splits halfway between observed values.
+        disc.split_point = SplitPoint::Midpoint;
+        disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        let expected: Vec<f64> = observed
+            .iter()
+            .map(|s| if s.is_finite() { s + 0.5 } else { *s })
+            .collect();
+        assert_eq!(disc.splits_, expected);
+        assert!(disc.validate_splits().is_ok());
+        assert_eq!(disc.predict_idx(&x_).unwrap(), {
+            let mut d = Discretizer::new(Some(2.0), Some(10), Some(0.001), Some(1.0), Some(-1));
+            d.fit(&x_, &y_, Some(&w_), None).unwrap();
+            d.predict_idx(&x_).unwrap()
+        });
+        disc.splits_[1] = 2.0;
+        assert!(disc.validate_splits().is_err());
+        disc.splits_[1] = 10.0;
+        assert!(disc.validate_splits().is_err());
+    }
+
+    #[test]
+    fn test_validate_exceptions() {
+        let x_ = vec![1.0, 1.0, 2.0, 2.0, 3.0, 3.0, -1.0];
+        let y_ = vec![1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.validate_exceptions().is_err());
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+        assert!(disc.validate_exceptions().is_ok());
+        disc.feature.as_mut().unwrap().exception_values_.vals_[0] = 2.0;
+        match disc.validate_exceptions() {
+            Err(DiscrustError::ExceptionConflict(v)) => assert_eq!(v, vec![2.0]),
+            r => panic!("Unexpected result {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_min_rates() {
         let mut fare: Vec<f64> = Vec::new();
         let mut survived: Vec<f64> = Vec::new();
         let file = fs::read_to_string("resources/data.csv")
             .expect("Something went wrong reading the file");
         for l in file.lines() {
-            let split: Vec<f64> = l.split(",").map(|x| x.parse::<f64>().unwrap()).collect();
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
             fare.push(split[0]);
             survived.push(split[1]);
         }
-        let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), None);
-        let w_ = vec![1.0; fare.len()];
-        let splits = disc.fit(&fare, &survived, &w_, None).unwrap();
+        let w_ = vec![2.0; fare.len()];
+        let total = 2.0 * fare.len() as f64;
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), Some(1));
+        disc.min_obs_rate = Some(0.1);
+        disc.min_pos_rate = Some(0.02);
+        let res = disc.fit(&fare, &survived, Some(&w_), None).unwrap();
+        assert!(res.splits.len() > 2);
+        for b in &res.bins {
+            assert!(b.totals_ct >= 0.1 * total);
+            assert!(b.ones_ct >= 0.02 * total);
+        }
+        // A larger absolute threshold is still used.
+        let mut disc =
+            Discretizer::new(Some(0.2 * total), Some(10), Some(0.001), Some(1.0), Some(1));
+        disc.min_obs_rate = Some(0.1);
+        let res = disc.fit(&fare, &survived, Some(&w_), None).unwrap();
+        assert!(res.bins.iter().all(|b| b.totals_ct >= 0.2 * total));
+
+        disc.min_pos_rate = Some(0.9);
+        match disc.fit(&fare, &survived, Some(&w_), None) {
+            Err(DiscrustError::InsufficientData(c)) => {
+                assert_eq!(c[0].parameter, "min_pos_rate");
+                assert_eq!(c[0].required, 0.9 * total);
+            }
+            r => panic!("Unexpected result {:?}", r),
+        }
+        disc.min_pos_rate = Some(-0.1);
+        assert!(matches!(
+            disc.fit(&fare, &survived, Some(&w_), None),
+            Err(DiscrustError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_min_pos_auto() {
+        // A rare event, one in twenty records.
+        let x_: Vec<f64> = (0..400).map(|i| (i / 4) as f64).collect();
+        let y_: Vec<f64> = (0..400)
+            .map(|i| ((i % 20 == 0) || (i >= 360 && i % 4 == 0)) as i64 as f64)
+            .collect();
+        let total_ones: f64 = y_.iter().sum();
+        let mut disc = Discretizer::new(Some(40.0), Some(10), Some(0.001), Some(40.0), None);
+        assert!(matches!(
+            disc.fit(&x_, &y_, None, None),
+            Err(DiscrustError::InsufficientData(_))
+        ));
+        // The minimum is the ones a bin of min_obs records holds at the
+        // event rate, which allows the split around the rare event.
+        disc.min_pos_auto = true;
+        let res = disc.fit(&x_, &y_, None, None).unwrap();
+        assert!(res.splits.len() > 2);
+        let min_pos = 40.0 * total_ones / 400.0;
+        assert!(res.bins.iter().all(|b| b.ones_ct >= min_pos));
+        assert!(disc.parameters()["min_pos_auto"] == "true");
+        assert!(disc.unfitted_copy().min_pos_auto);
+
+        // It is at least one, and min_pos_rate still applies.
+        disc.min_obs = 1.0;
+        disc.min_pos_rate = Some(0.9);
+        match disc.fit(&x_, &y_, None, None) {
+            Err(DiscrustError::InsufficientData(c)) => {
+                assert_eq!(c[0].parameter, "min_pos_rate");
+            }
+            r => panic!("Unexpected result {:?}", r),
+        }
+        disc.min_pos_rate = None;
+        let res = disc.fit(&x_, &y_, None, None).unwrap();
+        assert!(res.bins.iter().all(|b| b.ones_ct >= 1.0));
+    }
+
+    #[test]
+    fn test_insufficient_data() {
+        let x_ = vec![1.0, 2.0, 3.0, -1.0, -1.0];
+        let y_ = vec![1.0, 0.0, 0.0, 1.0, 1.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(2.0), None);
+        let err = disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap_err();
+        match err {
+            DiscrustError::InsufficientData(c) => {
+                assert_eq!(c.len(), 2);
+                assert_eq!(c[0].parameter, "min_obs");
+                assert_eq!(c[0].available, 3.0);
+                assert_eq!(c[1].parameter, "min_pos");
+                assert_eq!(c[1].available, 1.0);
+            }
+            e => panic!("Unexpected error {:?}", e),
+        }
+        let mut disc = Discretizer::new(Some(3.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_records() {
+        let x_ = vec![1.0, 1.0, 2.0, 2.0, 3.0, 3.0];
+        let y_ = vec![1.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(matches!(
+            disc.fit(&[], &[], None, None),
+            Err(DiscrustError::EmptyInput)
+        ));
+        assert!(matches!(
+            disc.fit(&x_, &y_[1..], None, None),
+            Err(DiscrustError::LengthMismatch(c, 5, 6)) if c == "y"
+        ));
+        assert!(matches!(
+            disc.fit(&x_, &y_, Some(&w_[1..]), None),
+            Err(DiscrustError::LengthMismatch(c, 5, 6)) if c == "weight"
+        ));
+        let w_neg = vec![1.0, -1.0, 1.0, 1.0, 1.0, 1.0];
+        assert!(matches!(
+            disc.fit(&x_, &y_, Some(&w_neg), None),
+            Err(DiscrustError::InvalidWeight(_))
+        ));
+        let y_bad = vec![1.0, 0.0, 2.0, 0.0, 0.0, 0.0];
+        assert!(matches!(
+            disc.fit(&x_, &y_bad, Some(&w_), None),
+            Err(DiscrustError::InvalidTarget(_))
+        ));
+        assert!(disc.partial_fit(&x_, &y_bad, None, None).is_err());
+        // The same performance is valid for a continuous target.
+        disc.target_type = TargetType::Continuous;
+        assert!(disc.fit(&x_, &y_bad, Some(&w_), None).is_ok());
+    }
+
+    #[test]
+    fn test_predict_label() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2000.0, 2000.0, 2000.0, 2000.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.predict_label(&[1.0]).is_err());
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0, f64::NAN]))
+            .unwrap();
+        disc.label_format = LabelFormat {
+            decimals: Some(1),
+            thousands_separator: true,
+            missing_label: String::from("No Value"),
+            ..LabelFormat::default()
+        };
         assert_eq!(
-            splits,
-            vec![
-                -f64::INFINITY,
-                6.95,
-                7.125,
-                7.7292,
-                10.4625,
-                15.1,
-                50.4958,
-                52.0,
-                73.5,
-                79.65,
-                f64::INFINITY
-            ]
+            disc.predict_label(&[1.0, 2000.0, -1.0, f64::NAN]).unwrap(),
+            vec!["(-inf, 1.0]", "(1.0, inf]", "-1.0", "No Value"]
         );
-        // println!("{:?}", disc.predict(&fare));
+        assert_eq!(disc.split_labels(), vec!["-inf", "1.0", "inf"]);
+    }
+
+    #[test]
+    fn test_add_exception_value() {
+        let x_ = vec![1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), Some(-1));
+        assert!(disc.add_exception_value(2.0).is_err());
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+        let splits = disc.splits_.to_vec();
+        disc.add_exception_value(3.0).unwrap();
+        disc.add_exception_value(10.0).unwrap();
+        assert_eq!(disc.splits_, splits);
+
+        // The feature should match fitting with these as exception values.
+        let mut refit = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), Some(-1));
+        refit
+            .fit(&x_, &y_, Some(&w_), Some(vec![-1.0, 3.0, 10.0]))
+            .unwrap();
+        let fitted = disc.feature.as_ref().unwrap();
+        let expected = refit.feature.as_ref().unwrap();
+        assert_eq!(fitted.unique_values(), expected.unique_values());
+        assert_eq!(fitted.cumulative_counts(), expected.cumulative_counts());
+        let (fitted_excp, expected_excp) = (&fitted.exception_values_, &expected.exception_values_);
+        assert_eq!(fitted_excp.vals_, expected_excp.vals_);
+        assert_eq!(fitted_excp.totals_ct_, expected_excp.totals_ct_);
+        assert_eq!(fitted_excp.ones_ct_, expected_excp.ones_ct_);
+        assert_eq!(fitted_excp.woe_[..2], expected_excp.woe_[..2]);
+        assert_eq!(
+            disc.predict_idx(&[-1.0, 3.0, 10.0]).unwrap(),
+            vec![-1, -2, -3]
+        );
+        assert!(disc.validate_exceptions().is_ok());
+        let bins = disc.bin_summary().unwrap();
+        let woe = disc.predict_woe(&[1.0, 4.0]).unwrap();
+        assert_eq!(woe, vec![bins[0].woe, bins[bins.len() - 1].woe]);
+    }
+
+    #[test]
+    fn test_exception_labels_and_order() {
+        let x_ = vec![1.0, 1.0, 1.0, 2.0, 2.0, 2.0, -1.0, -2.0, -2.0, f64::NAN];
+        let y_ = vec![1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), Some(-1));
+        assert!(disc.set_exception_label(-1.0, "No history").is_err());
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0, -2.0]))
+            .unwrap();
+        disc.set_exception_label(-1.0, "No history").unwrap();
+        disc.set_exception_label(f64::NAN, "Not asked").unwrap();
+        assert!(matches!(
+            disc.set_exception_label(5.0, "Five"),
+            Err(DiscrustError::UnknownExceptionValue(_))
+        ));
+        assert_eq!(
+            disc.predict_label(&[-1.0, -2.0, f64::NAN]).unwrap(),
+            vec!["No history", "-2", "Not asked"]
+        );
+        assert_eq!(disc.missing_summary().unwrap().unwrap().label, "Not asked");
+        assert_eq!(disc.bin_summary().unwrap()[0].label, "(-inf, 1]");
+
+        // Exception values are sorted by default, and can be reordered,
+        // taking their statistics and labels with them.
+        assert_eq!(disc.predict_idx(&[-2.0, -1.0]).unwrap(), vec![-1, -2]);
+        let woe = disc.predict_woe(&[-2.0, -1.0]).unwrap();
+        disc.set_exception_order(&[-1.0, -2.0]).unwrap();
+        assert_eq!(
+            disc.predict_idx(&[-2.0, -1.0, f64::NAN]).unwrap(),
+            vec![-2, -1, -3]
+        );
+        assert_eq!(disc.predict_woe(&[-2.0, -1.0]).unwrap(), woe);
+        let exceptions = disc.exception_summary().unwrap();
+        assert_eq!(exceptions[0].label, "No history");
+        assert_eq!(exceptions[1].totals_ct, 2.0);
+        assert!(disc.set_exception_order(&[-1.0]).is_err());
+        assert!(disc.set_exception_order(&[-1.0, -1.0]).is_err());
+        assert!(disc.set_exception_order(&[-1.0, 3.0]).is_err());
+    }
+
+    #[test]
+    fn test_merge_exceptions() {
+        let x_ = vec![
+            1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, -1.0, -1.0, -1.0, -2.0, -2.0, -2.0, -2.0,
+        ];
+        let y_ = vec![
+            0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+        ];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        disc.merge_exceptions = true;
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0, -2.0, -3.0]))
+            .unwrap();
+        assert_eq!(disc.splits_, vec![f64::NEG_INFINITY, 1.0, f64::INFINITY]);
+        // -1.0 has the same weight of evidence as the bin of 2.0, and -2.0
+        // the same as the bin of 1.0, while -3.0 has no records, so it is kept.
+        let feature = disc.feature.as_ref().unwrap();
+        assert_eq!(feature.exception_values_.vals_, vec![-3.0]);
+        assert_eq!(feature.merged_vals_, vec![-2.0, -1.0]);
+        assert_eq!(feature.merged_into_, vec![1.0, 2.0]);
+        let bins = disc.bin_summary().unwrap();
+        assert_eq!(
+            bins.iter().map(|b| b.totals_ct).collect::<Vec<f64>>(),
+            vec![8.0, 8.0]
+        );
+        assert_eq!(bins[1].ones_ct, 6.0);
+        assert_eq!(bins[1].effective_ct, 8.0);
+        assert_eq!(
+            disc.predict_idx(&[-3.0, -2.0, -1.0, 2.0]).unwrap(),
+            vec![-1, 0, 1, 1]
+        );
+        assert_eq!(
+            disc.predict_idx_sorted(&[-3.0, -2.0, -1.0, 2.0]).unwrap(),
+            vec![-1, 0, 1, 1]
+        );
+        let woe = disc.predict_woe(&[-1.0, 2.0]).unwrap();
+        assert_eq!(woe, vec![bins[1].woe, bins[1].woe]);
+        assert!(disc
+            .to_json_logic("x", crate::ExportOutput::Index)
+            .unwrap()
+            .contains("{\"==\":[{\"var\":\"x\"},-1]},1"));
+        assert!(disc.add_exception_value(-1.0).is_err());
+
+        disc.target_type = TargetType::Multiclass;
+        assert!(disc.fit(&x_, &y_, Some(&w_), None).is_err());
     }
 
     #[test]
@@ -331,8 +3607,9 @@ mod test {
         let w_ = vec![1.0; fare.len()];
         fare[10] = f64::NAN;
         let splits = disc
-            .fit(&fare, &survived, &w_, Some(vec![f64::NAN]))
-            .unwrap();
+            .fit(&fare, &survived, Some(&w_), Some(vec![f64::NAN]))
+            .unwrap()
+            .splits;
         assert_eq!(
             splits,
             vec![
@@ -351,4 +3628,28 @@ mod test {
         );
         // println!("{:?}", disc.predict(&fare));
     }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_fit_matches_serial() {
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        let (fare, survived): (Vec<f64>, Vec<f64>) = file
+            .lines()
+            .map(|l| {
+                let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+                (split[0], split[1])
+            })
+            .unzip();
+        let w_ = vec![1.0; fare.len()];
+        let mut serial = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), None);
+        serial.fit(&fare, &survived, Some(&w_), None).unwrap();
+        for n_threads in [0, 1, 4] {
+            let mut parallel = serial.unfitted_copy();
+            parallel.n_threads = Some(n_threads);
+            parallel.fit(&fare, &survived, Some(&w_), None).unwrap();
+            assert_eq!(parallel.splits_, serial.splits_);
+            assert_eq!(parallel.mono, serial.mono);
+        }
+    }
 }
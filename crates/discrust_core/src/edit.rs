@@ -0,0 +1,103 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::utils::nan_safe_compare;
+
+impl Discretizer {
+    /// Merge the bins from `i` to `j`, inclusive, into a single bin, by
+    /// removing the splits between them, such as when hand tuning the bins
+    /// found by `fit`. The statistics of every bin are computed from the
+    /// splits, so the weight of evidence, and information value, of the
+    /// merged bin are those of all of its records. The indexes of the bins
+    /// above the merged bin, returned by `predict_idx`, are reduced.
+    pub fn merge_bins(&mut self, i: usize, j: usize) -> Result<(), DiscrustError> {
+        if self.feature.is_none() {
+            return Err(DiscrustError::NotFitted);
+        }
+        let n_bins = self.splits_.len() - 1;
+        if (i >= j) || (j >= n_bins) {
+            return Err(DiscrustError::InvalidParameter(format!(
+                "bins {} to {} can not be merged, there are {} bins",
+                i, j, n_bins
+            )));
+        }
+        // The lower edge of bin k is split k, so the
+        // upper edges of bins i to j - 1 are removed.
+        self.splits_.drain((i + 1)..=j);
+        Ok(())
+    }
+
+    /// Split the bin that `value` falls in, so that values less than or
+    /// equal to `value` fall in the lower bin. For a categorical feature,
+    /// the value is a position in `categories_`. The split does not need
+    /// to be an observed value, `validate_splits` can be used to check it.
+    /// The indexes of the bins above the new split are increased.
+    pub fn split_bin_at(&mut self, value: f64) -> Result<(), DiscrustError> {
+        self.check_not_compacted("a bin can not be split")?;
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        if !value.is_finite() {
+            return Err(DiscrustError::InvalidParameter(format!(
+                "{} is not a finite value, and can not be a split",
+                value
+            )));
+        }
+        if feature.exception_values_.exception_idx(&value).is_some() {
+            return Err(DiscrustError::InvalidParameter(format!(
+                "{} is an exception value, and can not be a split",
+                value
+            )));
+        }
+        match self
+            .splits_
+            .binary_search_by(|s| nan_safe_compare(s, &value))
+        {
+            Ok(_) => Err(DiscrustError::InvalidParameter(format!(
+                "{} is already a split",
+                value
+            ))),
+            Err(idx) => {
+                self.splits_.insert(idx, value);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_merge_and_split_bins() {
+        let x_: Vec<f64> = (1..=10).map(|v| v as f64).collect();
+        let y_ = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0];
+        let mut disc = Discretizer::from_splits(&[2.0, 4.0, 7.0], &[-1.0]).unwrap();
+        assert!(disc.merge_bins(0, 1).is_err());
+        assert!(disc.split_bin_at(3.0).is_err());
+        disc.fit_stats(&x_, &y_, None).unwrap();
+
+        disc.merge_bins(1, 2).unwrap();
+        assert_eq!(
+            disc.splits_,
+            vec![f64::NEG_INFINITY, 2.0, 7.0, f64::INFINITY]
+        );
+        let bins = disc.bin_summary().unwrap();
+        assert_eq!(bins[1].totals_ct, 5.0);
+        assert_eq!(bins[1].ones_ct, 3.0);
+        assert_eq!(disc.predict_idx(&[8.0]).unwrap(), vec![2]);
+        assert!(disc.merge_bins(1, 1).is_err());
+        assert!(disc.merge_bins(1, 3).is_err());
+
+        disc.split_bin_at(5.5).unwrap();
+        assert_eq!(
+            disc.splits_,
+            vec![f64::NEG_INFINITY, 2.0, 5.5, 7.0, f64::INFINITY]
+        );
+        let bins = disc.bin_summary().unwrap();
+        assert_eq!(bins[1].totals_ct, 3.0);
+        assert_eq!(bins[2].totals_ct, 2.0);
+        assert_eq!(disc.predict_idx(&[5.0, 6.0]).unwrap(), vec![1, 2]);
+        assert!(disc.split_bin_at(7.0).is_err());
+        assert!(disc.split_bin_at(-1.0).is_err());
+        assert!(disc.split_bin_at(f64::NAN).is_err());
+    }
+}
@@ -0,0 +1,181 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::segment::take_records;
+use crate::utils::SplitMix64;
+
+/// Several discretizers, each fit on a random subsample of the
+/// records, that together give a less variable binning than any
+/// one discretizer fit on all of the records.
+pub struct EnsembleDiscretizer {
+    pub models: Vec<Discretizer>,
+}
+
+impl Discretizer {
+    /// Fit `n_models` copies of this discretizer, each on a subsample,
+    /// drawn without replacement, of `row_subsample` of the records.
+    /// The same `seed` always draws the same subsamples.
+    #[allow(clippy::too_many_arguments)]
+    pub fn fit_ensemble(
+        &self,
+        x: &[f64],
+        y: &[f64],
+        w: &[f64],
+        exception_values: Option<Vec<f64>>,
+        n_models: usize,
+        row_subsample: f64,
+        seed: u64,
+    ) -> Result<EnsembleDiscretizer, DiscrustError> {
+        if n_models == 0 {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "n_models must be greater than 0",
+            )));
+        }
+        if !((row_subsample > 0.0) && (row_subsample <= 1.0)) {
+            return Err(DiscrustError::InvalidParameter(format!(
+                "row_subsample must be greater than 0 and at most 1, but {} was passed",
+                row_subsample
+            )));
+        }
+        let n_rows = ((x.len() as f64) * row_subsample).round() as usize;
+        let mut rng = SplitMix64(seed);
+        let mut idx: Vec<usize> = (0..x.len()).collect();
+        let mut models = Vec::with_capacity(n_models);
+        for _ in 0..n_models {
+            // A partial Fisher-Yates shuffle, the first n_rows
+            // positions are a sample without replacement.
+            for i in 0..n_rows {
+                let j = i + rng.below(x.len() - i);
+                idx.swap(i, j);
+            }
+            let mut sample = idx[..n_rows].to_vec();
+            sample.sort_unstable();
+            let (x_, y_, w_) = take_records(&sample, x, y, w);
+            let mut disc = self.unfitted_copy();
+            disc.fit(&x_, &y_, Some(&w_), exception_values.clone())?;
+            models.push(disc);
+        }
+        Ok(EnsembleDiscretizer { models })
+    }
+}
+
+impl EnsembleDiscretizer {
+    /// The weight of evidence of each value, averaged over the models.
+    pub fn predict_woe(&self, x: &[f64]) -> Result<Vec<f64>, DiscrustError> {
+        let mut res = vec![0.0; x.len()];
+        for disc in self.models.iter() {
+            let woe = disc.predict_woe(x)?;
+            res.iter_mut().zip(woe).for_each(|(r, v)| *r += v);
+        }
+        let n = self.models.len() as f64;
+        res.iter_mut().for_each(|r| *r /= n);
+        Ok(res)
+    }
+
+    /// Each split found by any of the models, in increasing order,
+    /// with the share of the models that found it. The splits at
+    /// negative and positive infinity are not included.
+    pub fn split_frequencies(&self) -> Vec<(f64, f64)> {
+        let mut splits: Vec<f64> = self
+            .models
+            .iter()
+            .flat_map(|d| d.splits_.iter().cloned().filter(|s| s.is_finite()))
+            .collect();
+        splits.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let n = self.models.len() as f64;
+        let mut frequencies: Vec<(f64, f64)> = Vec::new();
+        for s in splits {
+            match frequencies.last_mut() {
+                Some((v, ct)) if *v == s => *ct += 1.0,
+                _ => frequencies.push((s, 1.0)),
+            }
+        }
+        frequencies.iter_mut().for_each(|(_, ct)| *ct /= n);
+        frequencies
+    }
+
+    /// The consensus edges, the splits found by at least
+    /// `min_share` of the models, bounded by negative and
+    /// positive infinity like `splits_`.
+    pub fn consensus_splits(&self, min_share: f64) -> Vec<f64> {
+        let mut splits = vec![f64::NEG_INFINITY];
+        splits.extend(
+            self.split_frequencies()
+                .into_iter()
+                .filter(|(_, share)| *share >= min_share)
+                .map(|(s, _)| s),
+        );
+        splits.push(f64::INFINITY);
+        splits
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+
+    fn titanic() -> (Vec<f64>, Vec<f64>) {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
+        }
+        (fare, survived)
+    }
+
+    #[test]
+    fn test_fit_ensemble() {
+        let (fare, survived) = titanic();
+        let w_ = vec![1.0; fare.len()];
+        let disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc
+            .fit_ensemble(&fare, &survived, &w_, None, 0, 0.5, 0)
+            .is_err());
+        assert!(disc
+            .fit_ensemble(&fare, &survived, &w_, None, 3, 1.5, 0)
+            .is_err());
+        let ensemble = disc
+            .fit_ensemble(&fare, &survived, &w_, None, 5, 0.8, 42)
+            .unwrap();
+        assert_eq!(ensemble.models.len(), 5);
+        let repeated = disc
+            .fit_ensemble(&fare, &survived, &w_, None, 5, 0.8, 42)
+            .unwrap();
+        assert_eq!(ensemble.split_frequencies(), repeated.split_frequencies());
+
+        // Averaged weight of evidence.
+        let woe = ensemble.predict_woe(&fare[..3]).unwrap();
+        let expected: f64 = ensemble
+            .models
+            .iter()
+            .map(|d| d.predict_woe(&fare[..1]).unwrap()[0])
+            .sum::<f64>()
+            / 5.0;
+        assert!((woe[0] - expected).abs() < 1e-12);
+
+        let frequencies = ensemble.split_frequencies();
+        assert!(frequencies.windows(2).all(|f| f[0].0 < f[1].0));
+        assert!(frequencies.iter().all(|(_, s)| (*s > 0.0) & (*s <= 1.0)));
+        let consensus = ensemble.consensus_splits(0.5);
+        assert_eq!(consensus[0], f64::NEG_INFINITY);
+        assert_eq!(consensus[consensus.len() - 1], f64::INFINITY);
+        assert!(ensemble.consensus_splits(1.1).len() == 2);
+    }
+
+    #[test]
+    fn test_full_sample_matches_fit() {
+        let (fare, survived) = titanic();
+        let w_ = vec![1.0; fare.len()];
+        let disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), None);
+        let ensemble = disc
+            .fit_ensemble(&fare, &survived, &w_, None, 2, 1.0, 0)
+            .unwrap();
+        let mut fitted = disc.unfitted_copy();
+        fitted.fit(&fare, &survived, Some(&w_), None).unwrap();
+        assert_eq!(ensemble.consensus_splits(1.0), fitted.splits_);
+    }
+}
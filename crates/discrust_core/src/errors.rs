@@ -1,13 +1,134 @@
+use crate::spec::SpecViolation;
+use num::Float;
+use std::fmt;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum DiscrustError {
     #[error("Calling a method that requires object to be fit, when `fit` has not been called.")]
     NotFitted,
-    #[error("NaN found in {0}.")]
-    ContainsNaN(String),
+    #[error("The {} column contains {}{}.", .0.column, .0, nan_hint(.0.column))]
+    ContainsNaN(#[source] NanValues),
     #[error("Unable to calculate prediction.")]
     Prediction,
+    #[error("Invalid parameter: {0}")]
+    InvalidParameter(String),
+    #[error("Segment {0} was not present when fitting.")]
+    UnknownSegment(String),
+    #[error("Category {0} was not present when fitting.")]
+    UnknownCategory(String),
+    #[error("Invalid splits: {0}")]
+    InvalidSplits(String),
+    #[error("Exception values {0:?} are also values in the bins.")]
+    ExceptionConflict(Vec<f64>),
+    #[error("Insufficient data to satisfy {}.", format_constraints(.0))]
+    InsufficientData(Vec<UnsatisfiableConstraint>),
+    #[error("Missing value {0} was not an exception value when fitting.")]
+    UnseenMissing(f64),
+    #[error("Input from {0} does not match its spec: {}.", format_violations(.1))]
+    InputSpecViolation(String, Vec<SpecViolation>),
+    #[error("Calling `finalize` when no records have been passed to `partial_fit`.")]
+    NoPartialFit,
+    #[error("Unable to read {0}: {1}")]
+    ReadCsv(String, String),
+    /// Returned by `fit_parquet`, of the discrust_arrow crate.
+    #[error("Unable to read {0}: {1}")]
+    ReadParquet(String, String),
+    #[error("Invalid target: {0}")]
+    InvalidTarget(String),
+    #[error("{0} is not an exception value.")]
+    UnknownExceptionValue(f64),
+    #[error("Invalid weight: {0}")]
+    InvalidWeight(String),
+    #[error("The {0} column has {1} values, but the x column has {2}.")]
+    LengthMismatch(String, usize, usize),
+    #[error("No records were passed to fit.")]
+    EmptyInput,
+}
+
+/// A column of the records passed to fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    X,
+    Y,
+    Weight,
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Column::X => "x",
+            Column::Y => "y",
+            Column::Weight => "weight",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The NaN values of a column, where they are not allowed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{count} NaN values, the first at index {first_index}")]
+pub struct NanValues {
+    pub column: Column,
+    /// The number of NaN values in the column.
+    pub count: usize,
+    /// The index of the first record with a NaN value.
+    pub first_index: usize,
+}
+
+impl NanValues {
+    /// The NaN values of `values`, or None if it has none.
+    pub(crate) fn find<T: Float>(column: Column, values: &[T]) -> Option<NanValues> {
+        let first_index = values.iter().position(|v| v.is_nan())?;
+        Some(NanValues {
+            column,
+            count: values[first_index..].iter().filter(|v| v.is_nan()).count(),
+            first_index,
+        })
+    }
+}
+
+// How NaN values in the column can be allowed, if they can be.
+fn nan_hint(column: Column) -> &'static str {
+    match column {
+        Column::X => {
+            ", pass NaN as an exception value, or set special_missing, to bin them separately"
+        }
+        Column::Y | Column::Weight => "",
+    }
+}
+
+/// A fitting parameter that the data can not satisfy, such
+/// as `min_obs` when there are fewer records than this.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsatisfiableConstraint {
+    /// The name of the parameter.
+    pub parameter: String,
+    /// The value of the parameter.
+    pub required: f64,
+    /// The weighted total available in the data.
+    pub available: f64,
+}
+
+fn format_constraints(constraints: &[UnsatisfiableConstraint]) -> String {
+    constraints
+        .iter()
+        .map(|c| {
+            format!(
+                "{} (required {}, available {})",
+                c.parameter, c.required, c.available
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn format_violations(violations: &[SpecViolation]) -> String {
+    violations
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
 }
 
 // Error for when something is called on the discretizer when it
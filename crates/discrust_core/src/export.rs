@@ -0,0 +1,211 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::strategy::OnMissing;
+
+/// The value an exported binning should produce
+/// for each record.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportOutput {
+    /// The weight of evidence of the bin, matching `predict_woe`.
+    Woe,
+    /// The bin index, matching `predict_idx`.
+    Index,
+    /// The bin label, matching `predict_label`.
+    Label,
+}
+
+// A bin, or exception value, of a fitted discretizer,
+// with the value it should be mapped to.
+pub(crate) struct ExportRule {
+    pub exception: Option<f64>,
+    pub upper: f64,
+    pub value: f64,
+    pub label: String,
+}
+
+impl Discretizer {
+    // The rules that map a value to its output, in the order they should be
+    // checked, exception values first, then the exception values merged
+    // into a bin, then each bin by its upper bound.
+    pub(crate) fn export_rules(
+        &self,
+        output: ExportOutput,
+    ) -> Result<Vec<ExportRule>, DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        let excp = &feature.exception_values_;
+        let (bin_labels, exception_labels) = self.labels(feature);
+        let mut rules: Vec<ExportRule> = excp
+            .vals_
+            .iter()
+            .enumerate()
+            .map(|(i, v)| ExportRule {
+                exception: Some(*v),
+                upper: *v,
+                value: match output {
+                    ExportOutput::Woe if excp.totals_ct_[i] == 0.0 => 0.0,
+                    ExportOutput::Woe => excp.woe_[i],
+                    ExportOutput::Index | ExportOutput::Label => -((i + 1) as f64),
+                },
+                label: exception_labels[i].to_owned(),
+            })
+            .collect();
+        // The missing bin is matched like an exception value of NaN.
+        if let Some(m) = self.missing_summary()? {
+            rules.push(ExportRule {
+                exception: Some(f64::NAN),
+                upper: f64::NAN,
+                value: match output {
+                    ExportOutput::Woe => m.woe,
+                    ExportOutput::Index | ExportOutput::Label => -((excp.vals_.len() + 1) as f64),
+                },
+                label: m.label,
+            });
+        }
+        let bins = self.bin_summary()?;
+        let bin_value = |i: usize| match output {
+            ExportOutput::Woe if bins[i].totals_ct == 0.0 => 0.0,
+            ExportOutput::Woe => bins[i].woe,
+            ExportOutput::Index | ExportOutput::Label => i as f64,
+        };
+        for (v, into) in feature.merged_vals_.iter().zip(&feature.merged_into_) {
+            let i = self.closed.position(&self.splits_[1..], into);
+            rules.push(ExportRule {
+                exception: Some(*v),
+                upper: *v,
+                value: bin_value(i),
+                label: bin_labels[i].to_owned(),
+            });
+        }
+        rules.extend(bins.iter().enumerate().map(|(i, b)| ExportRule {
+            exception: None,
+            upper: b.upper,
+            value: bin_value(i),
+            label: bin_labels[i].to_owned(),
+        }));
+        Ok(rules)
+    }
+
+    // The (value, label) a missing value is exported as, those of the
+    // exception value of NaN, or the missing bin, if there is one,
+    // otherwise set by `on_missing`, and None if it is an error.
+    pub(crate) fn missing_export(
+        &self,
+        rules: &[ExportRule],
+        output: ExportOutput,
+    ) -> Result<Option<(f64, String)>, DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        if let Some(r) = rules
+            .iter()
+            .find(|r| r.exception.is_some_and(|e| e.is_nan()))
+        {
+            return Ok(Some((r.value, r.label.to_owned())));
+        }
+        let bins: Vec<&ExportRule> = rules.iter().filter(|r| r.exception.is_none()).collect();
+        let bin = |r: &ExportRule| Some((r.value, r.label.to_owned()));
+        Ok(match self.on_missing {
+            OnMissing::Error => None,
+            OnMissing::LowestBin => bin(bins[0]),
+            OnMissing::HighestBin => bin(bins[bins.len() - 1]),
+            OnMissing::WoeValue(woe) => {
+                let value = match output {
+                    ExportOutput::Woe => woe,
+                    _ => -((feature.exception_values_.vals_.len() + 1) as f64),
+                };
+                Some((value, self.missing_label(feature)))
+            }
+        })
+    }
+
+    /// Export the fitted binning as a JSON Logic rule, mapping the
+    /// variable `var` to its weight of evidence, bin index or label. Missing
+    /// values, if NaN is an exception value, or there is a missing bin, are
+    /// matched by `null`.
+    pub fn to_json_logic(&self, var: &str, output: ExportOutput) -> Result<String, DiscrustError> {
+        let var = format!("{{\"var\":{}}}", json_string(var));
+        let rules = self.export_rules(output)?;
+        let mut branches: Vec<String> = Vec::new();
+        let (last, rules) = rules.split_last().ok_or(DiscrustError::NotFitted)?;
+        for r in rules {
+            let condition = match r.exception {
+                Some(e) => format!("{{\"==\":[{},{}]}}", var, json_number(e)),
+                None => format!(
+                    "{{\"{}\":[{},{}]}}",
+                    self.closed.below(),
+                    var,
+                    json_number(r.upper)
+                ),
+            };
+            branches.push(condition);
+            branches.push(json_value(r, output));
+        }
+        branches.push(json_value(last, output));
+        Ok(format!("{{\"if\":[{}]}}", branches.join(",")))
+    }
+}
+
+fn json_value(rule: &ExportRule, output: ExportOutput) -> String {
+    match output {
+        ExportOutput::Label => json_string(&rule.label),
+        _ => json_number(rule.value),
+    }
+}
+
+// Non finite values have no JSON representation, so they are null.
+pub(crate) fn json_number(v: f64) -> String {
+    if v.is_finite() {
+        format!("{}", v)
+    } else {
+        String::from("null")
+    }
+}
+
+pub(crate) fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn test_to_json_logic() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, f64::NAN, f64::NAN];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.to_json_logic("x", ExportOutput::Index).is_err());
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![f64::NAN])).unwrap();
+        assert_eq!(
+            disc.to_json_logic("fare", ExportOutput::Index).unwrap(),
+            "{\"if\":[{\"==\":[{\"var\":\"fare\"},null]},-1,{\"<=\":[{\"var\":\"fare\"},1]},0,1]}"
+        );
+        let woe = disc.predict_woe(&[f64::NAN, 1.0, 2.0]).unwrap();
+        assert_eq!(
+            disc.to_json_logic("fare", ExportOutput::Woe).unwrap(),
+            format!(
+                "{{\"if\":[{{\"==\":[{{\"var\":\"fare\"}},null]}},{},{{\"<=\":[{{\"var\":\"fare\"}},1]}},{},{}]}}",
+                woe[0], woe[1], woe[2]
+            )
+        );
+        assert_eq!(
+            disc.to_json_logic("fare", ExportOutput::Label).unwrap(),
+            "{\"if\":[{\"==\":[{\"var\":\"fare\"},null]},\"Missing\",{\"<=\":[{\"var\":\"fare\"},1]},\"(-inf, 1]\",\"(1, inf]\"]}"
+        );
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a\"b\\c"), "\"a\\\"b\\\\c\"");
+    }
+}
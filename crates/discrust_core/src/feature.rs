@@ -1,5 +1,10 @@
-use crate::utils::nan_safe_compare;
-use crate::DiscrustError;
+use crate::errors::{Column, DiscrustError, NanValues};
+use crate::parallel::Threads;
+use crate::strategy::{Closed, TargetType};
+use crate::utils::{nan_safe_compare, to_f64, SplitMix64};
+use num::Float;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::{cmp::Ordering, collections::HashMap};
 
 /// A Feature struct
@@ -9,30 +14,252 @@ use std::{cmp::Ordering, collections::HashMap};
 /// to compute information value and weight of evidence
 /// for arbitrary ranges of the data.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Feature {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float::vec"))]
     pub vals_: Vec<f64>,
     cuml_ones_ct_: Vec<f64>,
     cuml_zero_ct_: Vec<f64>,
     cuml_totals_ct_: Vec<f64>,
+    cuml_totals_sq_ct_: Vec<f64>,
+    // Cumulative counts from right to left, so the sum of any range
+    // that ends at the last value is read directly, rather than found
+    // by subtracting two large cumulative counts.
+    cuml_rl_ones_ct_: Vec<f64>,
+    cuml_rl_zero_ct_: Vec<f64>,
+    cuml_rl_totals_ct_: Vec<f64>,
+    cuml_rl_totals_sq_ct_: Vec<f64>,
     total_ones_: f64,
     total_zero_: f64,
+    // The cumulative weighted sums of the performance, and of its
+    // square, not kept for a binary performance.
+    #[cfg_attr(feature = "serde", serde(default))]
+    cuml_y_ct_: Vec<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    cuml_y_sq_ct_: Vec<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    cuml_rl_y_ct_: Vec<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    cuml_rl_y_sq_ct_: Vec<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    total_y_: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    total_y_sq_: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) target_type_: TargetType,
+    // The cumulative counts of each class of a multiclass performance,
+    // aligned with `vals_`, and the total count of each class, only
+    // kept once they are set with `set_classes`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    cuml_class_ct_: Vec<Vec<f64>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    total_class_ct_: Vec<f64>,
+    // Added to the ones and zero counts of a range of values,
+    // before the weight of evidence is calculated.
+    woe_regularization_: f64,
     pub exception_values_: ExceptionValues,
+    /// The records that were missing (NaN), when NaN is not an exception
+    /// value, and the feature was built with `with_missing`. This holds
+    /// the single value NaN, or no values if missing values are errors.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub missing_: ExceptionValues,
+    /// The exception values that were merged into a bin with
+    /// `merge_exception`, and the unique value each was merged into,
+    /// which they are predicted as.
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::serde_float::vec"))]
+    pub merged_vals_: Vec<f64>,
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::serde_float::vec"))]
+    pub merged_into_: Vec<f64>,
+    // The counts of the records held out in each cross validation
+    // fold, only kept while the splits are searched for.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    folds_: Vec<FoldCounts>,
+    // The share of the folds a split must hold up on.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    fold_agreement_: f64,
+    // Whether the values were collapsed to one for each bin by
+    // `compact`, so the counts of the values within a bin are lost.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) compacted_: bool,
+    /// The smallest, and largest, of the values the feature was fit on,
+    /// that were not missing or exception values, if there were any.
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::serde_float::option"))]
+    pub min_: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::serde_float::option"))]
+    pub max_: Option<f64>,
 }
 
+// The seed the records are assigned to cross validation folds with,
+// so the same records are always assigned to the same folds.
+const FOLD_SEED: u64 = 0x5EED;
+
+// How many values are kept, for each bucket, while the records are
+// aggregated into buckets, so the buckets found from the first records
+// can still be refined by those that follow.
+const SKETCH_RESOLUTION: usize = 8;
+
+// The cumulative counts, aligned with `vals_`, and the total
+// counts, of the records held out in a cross validation fold.
 #[derive(Debug)]
+struct FoldCounts {
+    cuml_ones_ct_: Vec<f64>,
+    cuml_zero_ct_: Vec<f64>,
+    total_ones_: f64,
+    total_zero_: f64,
+}
+
+impl FoldCounts {
+    // The (ones, zero) counts of the held out
+    // records with values in `vals_[start..stop]`.
+    fn range_ones_zero_ct(&self, start: usize, stop: usize) -> (f64, f64) {
+        (
+            range_sum(&self.cuml_ones_ct_, start, stop),
+            range_sum(&self.cuml_zero_ct_, start, stop),
+        )
+    }
+}
+
+// The weight of the record at `i`, which is 1 for unweighted records.
+fn weight(w: Option<&[f64]>, i: usize) -> f64 {
+    w.map_or(1.0, |w| w[i])
+}
+
+/// Check that there is a performance, and a weight, if any, for each
+/// of the `n` values of `x`, and that the weights are finite, and not
+/// negative.
+pub(crate) fn check_records(n: usize, y: &[f64], w: Option<&[f64]>) -> Result<(), DiscrustError> {
+    if y.len() != n {
+        return Err(DiscrustError::LengthMismatch(String::from("y"), y.len(), n));
+    }
+    if let Some(nan) = NanValues::find(Column::Y, y) {
+        return Err(DiscrustError::ContainsNaN(nan));
+    }
+    match w {
+        Some(w) => check_weights(w, n),
+        None => Ok(()),
+    }
+}
+
+/// Check that there is a weight for each of the `n` values of `x`,
+/// and that the weights are finite, and not negative.
+pub(crate) fn check_weights(w: &[f64], n: usize) -> Result<(), DiscrustError> {
+    if w.len() != n {
+        return Err(DiscrustError::LengthMismatch(
+            String::from("weight"),
+            w.len(),
+            n,
+        ));
+    }
+    if let Some(nan) = NanValues::find(Column::Weight, w) {
+        return Err(DiscrustError::ContainsNaN(nan));
+    }
+    if let Some(v) = w.iter().find(|v| v.is_infinite() || **v < 0.0) {
+        return Err(DiscrustError::InvalidWeight(format!(
+            "the weight {} is not a finite, non-negative value",
+            v
+        )));
+    }
+    Ok(())
+}
+
+// The sum of `x[start..stop]`, given the cumulative sums of `x`.
+fn range_sum(cuml: &[f64], start: usize, stop: usize) -> f64 {
+    if start == stop {
+        0.0
+    } else if start == 0 {
+        cuml[stop - 1]
+    } else {
+        cuml[stop - 1] - cuml[start - 1]
+    }
+}
+
+/// Information value and weight of evidence of a group of records,
+/// with `regularization` added to both the ones and zero counts, so
+/// a group with no ones or no zeros still has a finite weight of evidence.
+pub(crate) fn iv_woe(
+    ones_ct: f64,
+    zero_ct: f64,
+    total_ones: f64,
+    total_zero: f64,
+    regularization: f64,
+) -> (f64, f64) {
+    let ones_dist = (ones_ct + regularization) / total_ones;
+    let zero_dist = (zero_ct + regularization) / total_zero;
+    let woe = (ones_dist / zero_dist).ln();
+    let iv = (ones_dist - zero_dist) * woe;
+    (iv, woe)
+}
+
+/// The one-vs-rest information value and weight of evidence of each
+/// class of a multiclass performance, for a group of records with
+/// `class_ct` of each class, where there are `class_totals` in all.
+pub(crate) fn class_iv_woe(
+    class_ct: &[f64],
+    class_totals: &[f64],
+    regularization: f64,
+) -> Vec<(f64, f64)> {
+    let ct: f64 = class_ct.iter().sum();
+    let total: f64 = class_totals.iter().sum();
+    class_ct
+        .iter()
+        .zip(class_totals)
+        .map(|(c, t)| iv_woe(*c, ct - c, *t, total - t, regularization))
+        .collect()
+}
+
+/// The share of the variance of a continuous performance explained by
+/// a group of records, and its weighted mean, given the weighted count,
+/// and sum of the performance, of the group, and of all of the records.
+/// The mean is shrunk towards the overall mean, as if `regularization`
+/// records with the overall mean were added to the group.
+pub(crate) fn share_mean(
+    totals_ct: f64,
+    y_ct: f64,
+    total_ct: f64,
+    total_y: f64,
+    total_y_sq: f64,
+    regularization: f64,
+) -> (f64, f64) {
+    let overall_mean = total_y / total_ct;
+    let total_sum_sq = total_y_sq - total_y * overall_mean;
+    let mean = (y_ct + regularization * overall_mean) / (totals_ct + regularization);
+    let share = totals_ct * (mean - overall_mean).powi(2) / total_sum_sq;
+    (share, mean)
+}
+
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ExceptionValues {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float::vec"))]
     pub vals_: Vec<f64>,
     pub ones_ct_: Vec<f64>,
     pub zero_ct_: Vec<f64>,
     pub totals_ct_: Vec<f64>,
+    /// The weighted sums of the performance, and of its square.
+    pub y_ct_: Vec<f64>,
+    pub y_sq_ct_: Vec<f64>,
+    /// The sum of the squared weights, for the effective
+    /// count of a bin the values are merged into.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub totals_sq_ct_: Vec<f64>,
+    /// The weighted count of each class of a multiclass
+    /// performance, for each value.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub class_ct_: Vec<Vec<f64>>,
+    /// The label of each value, if one was set with
+    /// `Discretizer::set_exception_label`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub labels_: Vec<Option<String>>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float::vec"))]
     pub iv_: Vec<f64>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float::vec"))]
     pub woe_: Vec<f64>,
 }
 
 impl ExceptionValues {
     fn new(exception_values: &[f64]) -> Self {
         let mut vals_ = exception_values.to_vec();
-        vals_.sort_by(|i, j| nan_safe_compare(i, j));
+        vals_.sort_by(nan_safe_compare);
         vals_.dedup();
         let vals_len = vals_.len();
         ExceptionValues {
@@ -40,6 +267,11 @@ impl ExceptionValues {
             ones_ct_: vec![0.0; vals_len],
             zero_ct_: vec![0.0; vals_len],
             totals_ct_: vec![0.0; vals_len],
+            y_ct_: vec![0.0; vals_len],
+            y_sq_ct_: vec![0.0; vals_len],
+            totals_sq_ct_: vec![0.0; vals_len],
+            class_ct_: Vec::new(),
+            labels_: vec![None; vals_len],
             iv_: vec![0.0; vals_len],
             woe_: vec![0.0; vals_len],
         }
@@ -60,19 +292,175 @@ impl ExceptionValues {
         self.totals_ct_[idx] += w;
         self.ones_ct_[idx] += w * y;
         self.zero_ct_[idx] += w * ((y < &1.0) as i64 as f64);
+        self.y_ct_[idx] += w * y;
+        self.y_sq_ct_[idx] += w * y * y;
+        self.totals_sq_ct_[idx] += w * w;
+    }
+
+    // Add a new exception value, keeping the values in sorted order.
+    // The counts are the (totals, ones, zero, y, y_sq, totals_sq) counts.
+    fn insert_exception_value(&mut self, v: f64, counts: [f64; 6]) {
+        let idx = self
+            .vals_
+            .partition_point(|x| nan_safe_compare(x, &v) == Ordering::Less);
+        self.vals_.insert(idx, v);
+        self.totals_ct_.insert(idx, counts[0]);
+        self.ones_ct_.insert(idx, counts[1]);
+        self.zero_ct_.insert(idx, counts[2]);
+        self.y_ct_.insert(idx, counts[3]);
+        self.y_sq_ct_.insert(idx, counts[4]);
+        self.totals_sq_ct_.resize(self.vals_.len() - 1, 0.0);
+        self.totals_sq_ct_.insert(idx, counts[5]);
+        self.iv_.insert(idx, 0.0);
+        self.woe_.insert(idx, 0.0);
+        self.labels_.resize(self.vals_.len() - 1, None);
+        self.labels_.insert(idx, None);
+    }
+
+    /// The label of the value at `idx`, if one has been set.
+    pub fn label(&self, idx: usize) -> Option<&str> {
+        self.labels_.get(idx).and_then(|l| l.as_deref())
+    }
+
+    pub(crate) fn set_label(&mut self, idx: usize, label: &str) {
+        self.labels_.resize(self.vals_.len(), None);
+        self.labels_[idx] = Some(label.to_string());
+    }
+
+    // Put the values in a new order, where `order[i]` is the current
+    // position of the value that is moved to position `i`.
+    pub(crate) fn reorder(&mut self, order: &[usize]) {
+        self.labels_.resize(self.vals_.len(), None);
+        fn take<T: Clone>(v: &mut Vec<T>, order: &[usize]) {
+            // Values only kept for some performances may be empty.
+            if !v.is_empty() {
+                *v = order.iter().map(|i| v[*i].clone()).collect();
+            }
+        }
+        take(&mut self.vals_, order);
+        take(&mut self.ones_ct_, order);
+        take(&mut self.zero_ct_, order);
+        take(&mut self.totals_ct_, order);
+        take(&mut self.y_ct_, order);
+        take(&mut self.y_sq_ct_, order);
+        take(&mut self.totals_sq_ct_, order);
+        take(&mut self.class_ct_, order);
+        take(&mut self.labels_, order);
+        take(&mut self.iv_, order);
+        take(&mut self.woe_, order);
+    }
+
+    // Remove the value at `idx`, along with its counts.
+    fn remove(&mut self, idx: usize) {
+        fn remove_at<T>(v: &mut Vec<T>, idx: usize) {
+            // Values only kept for some performances may be missing.
+            if idx < v.len() {
+                v.remove(idx);
+            }
+        }
+        remove_at(&mut self.vals_, idx);
+        remove_at(&mut self.ones_ct_, idx);
+        remove_at(&mut self.zero_ct_, idx);
+        remove_at(&mut self.totals_ct_, idx);
+        remove_at(&mut self.y_ct_, idx);
+        remove_at(&mut self.y_sq_ct_, idx);
+        remove_at(&mut self.totals_sq_ct_, idx);
+        remove_at(&mut self.class_ct_, idx);
+        remove_at(&mut self.labels_, idx);
+        remove_at(&mut self.iv_, idx);
+        remove_at(&mut self.woe_, idx);
+    }
+
+    // The values with the counts of both, which must have the same values.
+    fn merge(&self, other: &ExceptionValues) -> ExceptionValues {
+        let add = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(x, y)| x + y).collect();
+        ExceptionValues {
+            vals_: self.vals_.to_vec(),
+            ones_ct_: add(&self.ones_ct_, &other.ones_ct_),
+            zero_ct_: add(&self.zero_ct_, &other.zero_ct_),
+            totals_ct_: add(&self.totals_ct_, &other.totals_ct_),
+            y_ct_: add(&self.y_ct_, &other.y_ct_),
+            y_sq_ct_: add(&self.y_sq_ct_, &other.y_sq_ct_),
+            totals_sq_ct_: add(&self.totals_sq_ct_, &other.totals_sq_ct_),
+            // Batches are only counted for a binary, or continuous, performance.
+            class_ct_: Vec::new(),
+            labels_: self.labels_.to_vec(),
+            iv_: vec![0.0; self.vals_.len()],
+            woe_: vec![0.0; self.vals_.len()],
+        }
     }
 
-    fn calculate_iv_woe(&mut self, total_ones: f64, total_zero: f64) {
+    fn calculate_iv_woe(&mut self, total_ones: f64, total_zero: f64, regularization: f64) {
         for i in 0..self.vals_.len() {
-            let ones_dist = self.ones_ct_[i] / total_ones;
-            let zero_dist = self.zero_ct_[i] / total_zero;
-            let woe = (ones_dist / zero_dist).ln();
-            let iv = (ones_dist - zero_dist) * woe;
+            let (iv, woe) = iv_woe(
+                self.ones_ct_[i],
+                self.zero_ct_[i],
+                total_ones,
+                total_zero,
+                regularization,
+            );
             self.woe_[i] = woe;
             self.iv_[i] = iv;
         }
     }
 
+    // For a continuous performance, the `woe_` is the mean,
+    // and the `iv_` the share of the variance explained.
+    fn calculate_share_mean(
+        &mut self,
+        total_ct: f64,
+        total_y: f64,
+        total_y_sq: f64,
+        regularization: f64,
+    ) {
+        for i in 0..self.vals_.len() {
+            let (share, mean) = share_mean(
+                self.totals_ct_[i],
+                self.y_ct_[i],
+                total_ct,
+                total_y,
+                total_y_sq,
+                regularization,
+            );
+            self.woe_[i] = mean;
+            self.iv_[i] = share;
+        }
+    }
+
+    // For a multiclass performance, the `iv_` is the sum of the one-vs-rest
+    // information values of the classes, and the `woe_` is NaN, as each
+    // class has its own weight of evidence.
+    fn calculate_class_iv(&mut self, class_totals: &[f64], regularization: f64) {
+        for i in 0..self.vals_.len() {
+            self.iv_[i] = self.class_ct_.get(i).map_or(0.0, |class_ct| {
+                class_iv_woe(class_ct, class_totals, regularization)
+                    .iter()
+                    .map(|(iv, _)| iv)
+                    .sum()
+            });
+            self.woe_[i] = f64::NAN;
+        }
+    }
+
+    /// The Kish effective sample size of the value at `idx`, NaN if
+    /// the squared weights were not kept, as for a discretizer saved
+    /// before they were.
+    pub fn effective_ct(&self, idx: usize) -> f64 {
+        match self.totals_sq_ct_.get(idx) {
+            Some(sq_ct) if *sq_ct > 0.0 => self.totals_ct_[idx].powi(2) / sq_ct,
+            Some(_) => 0.0,
+            None => f64::NAN,
+        }
+    }
+
+    /// The weighted mean, and standard deviation,
+    /// of the performance of the value at `idx`.
+    pub fn mean_std(&self, idx: usize) -> (f64, f64) {
+        let mean = self.y_ct_[idx] / self.totals_ct_[idx];
+        let var = self.y_sq_ct_[idx] / self.totals_ct_[idx] - mean * mean;
+        (mean, var.max(0.0).sqrt())
+    }
+
     pub fn to_hashmap(&self) -> HashMap<String, Vec<f64>> {
         let mut hmp = HashMap::new();
         // hmp.
@@ -89,41 +477,932 @@ impl ExceptionValues {
 impl Feature {
     /// Generate a new feature from a vector and it's
     /// binary performance. All vectors provided should be
-    /// of the same length.
+    /// of the same length. The values of `x` may be any float
+    /// type, such as `f32`, and are stored as `f64`.
     ///
     /// # Arguments
     ///
     /// * `x` - A reference to a vector that will be used
-    ///     discretized.
+    ///   discretized.
     /// * `y` - A reference to a vector of 1s (positive class)
-    ///     and 0s (negative class).
-    /// * `w` - A reference to a vector of weights. If the feature
-    ///     should be unweighted, pass in a vector of 1s, `vec![1.0; y.len()]`.
-    pub fn new(
+    ///   and 0s (negative class).
+    /// * `w` - An optional reference to a vector of non-negative
+    ///   weights. If None, every record has a weight of 1, without
+    ///   a vector of weights being allocated.
+    pub fn new<T: Float + Sync>(
+        x: &[T],
+        y: &[f64],
+        w: Option<&[f64]>,
+        exception_values: &[f64],
+    ) -> Result<Self, DiscrustError> {
+        let threads = Threads::default();
+        Feature::build(
+            x,
+            y,
+            w,
+            exception_values,
+            false,
+            TargetType::Binary,
+            &threads,
+        )
+    }
+
+    /// The same as `new`, but if NaN is not an exception value, missing
+    /// values are collected in `missing_`, with their own counts and weight
+    /// of evidence, rather than returning an error.
+    pub fn with_missing<T: Float + Sync>(
+        x: &[T],
+        y: &[f64],
+        w: Option<&[f64]>,
+        exception_values: &[f64],
+    ) -> Result<Self, DiscrustError> {
+        let threads = Threads::default();
+        Feature::build(
+            x,
+            y,
+            w,
+            exception_values,
+            true,
+            TargetType::Binary,
+            &threads,
+        )
+    }
+
+    pub(crate) fn build<T: Float + Sync>(
+        x: &[T],
+        y: &[f64],
+        w: Option<&[f64]>,
+        exception_values: &[f64],
+        special_missing: bool,
+        target_type: TargetType,
+        threads: &Threads,
+    ) -> Result<Self, DiscrustError> {
+        Ok(
+            ValueCounts::new(x, y, w, exception_values, special_missing, None, threads)?
+                .into_feature(target_type),
+        )
+    }
+
+    /// Collapse the values of each of the `ranges`, the bins of a fitted
+    /// discretizer, to a single value holding the counts of all of them,
+    /// so the feature only grows with the number of bins. The value kept
+    /// is the largest of the range, or the smallest if bins are closed on
+    /// the left, which is the split at the closed end. Empty ranges are
+    /// dropped, as are the counts of the cross validation folds.
+    pub(crate) fn compact(self, ranges: &[(usize, usize)], closed: Closed) -> Feature {
+        let ranges: Vec<(usize, usize)> = ranges
+            .iter()
+            .filter(|(start, stop)| start < stop)
+            .copied()
+            .collect();
+        let vals_ = ranges
+            .iter()
+            .map(|(start, stop)| match closed {
+                Closed::Left => self.vals_[*start],
+                Closed::Right => self.vals_[stop - 1],
+            })
+            .collect();
+        let mut totals_ct = Vec::with_capacity(ranges.len());
+        let mut ones_ct = Vec::with_capacity(ranges.len());
+        let mut zero_ct = Vec::with_capacity(ranges.len());
+        let mut totals_sq_ct = Vec::with_capacity(ranges.len());
+        let mut y_ct = Vec::new();
+        let mut y_sq_ct = Vec::new();
+        let mut class_ct = vec![Vec::with_capacity(ranges.len()); self.n_classes()];
+        for (start, stop) in ranges {
+            let (totals, ones, zero) = self.range_totals_ones_zero_ct(start, stop);
+            totals_ct.push(totals);
+            ones_ct.push(ones);
+            zero_ct.push(zero);
+            totals_sq_ct.push(sum_of_cuml_subarray(
+                &self.cuml_totals_sq_ct_,
+                &self.cuml_rl_totals_sq_ct_,
+                start,
+                stop - 1,
+            ));
+            // The sums of the performance are not
+            // kept for a binary performance.
+            if !self.cuml_y_ct_.is_empty() {
+                let (y, y_sq) = self.range_y_ct(start, stop);
+                y_ct.push(y);
+                y_sq_ct.push(y_sq);
+            }
+            for (ct, c) in class_ct.iter_mut().zip(self.range_class_ct(start, stop)) {
+                ct.push(c);
+            }
+        }
+        Feature {
+            vals_,
+            cuml_ones_ct_: cumulative(&ones_ct),
+            cuml_zero_ct_: cumulative(&zero_ct),
+            cuml_totals_ct_: cumulative(&totals_ct),
+            cuml_totals_sq_ct_: cumulative(&totals_sq_ct),
+            cuml_rl_ones_ct_: cumulative_rl(&ones_ct),
+            cuml_rl_zero_ct_: cumulative_rl(&zero_ct),
+            cuml_rl_totals_ct_: cumulative_rl(&totals_ct),
+            cuml_rl_totals_sq_ct_: cumulative_rl(&totals_sq_ct),
+            cuml_y_ct_: cumulative(&y_ct),
+            cuml_y_sq_ct_: cumulative(&y_sq_ct),
+            cuml_rl_y_ct_: cumulative_rl(&y_ct),
+            cuml_rl_y_sq_ct_: cumulative_rl(&y_sq_ct),
+            cuml_class_ct_: class_ct.iter().map(|ct| cumulative(ct)).collect(),
+            folds_: Vec::new(),
+            compacted_: true,
+            ..self
+        }
+    }
+
+    /// Set the count added to the ones and zero counts of every range
+    /// of values, and exception value, when the weight of evidence is
+    /// calculated. Zero, the default, is the unadjusted weight of evidence.
+    pub fn set_woe_regularization(&mut self, regularization: f64) {
+        self.woe_regularization_ = regularization;
+        self.calculate_exception_stats();
+    }
+
+    // Calculate the information value and weight of evidence, or for a
+    // continuous performance the share and mean, of the exception values
+    // and missing bin.
+    fn calculate_exception_stats(&mut self) {
+        let regularization = self.woe_regularization_;
+        for exceptions in [&mut self.exception_values_, &mut self.missing_] {
+            match self.target_type_ {
+                TargetType::Binary => {
+                    exceptions.calculate_iv_woe(self.total_ones_, self.total_zero_, regularization)
+                }
+                TargetType::Continuous => exceptions.calculate_share_mean(
+                    self.total_ones_ + self.total_zero_,
+                    self.total_y_,
+                    self.total_y_sq_,
+                    regularization,
+                ),
+                TargetType::Multiclass => {
+                    exceptions.calculate_class_iv(&self.total_class_ct_, regularization)
+                }
+            }
+        }
+    }
+
+    // The value each of `x` is counted as, once the values have been
+    // aggregated into buckets, the first value at or above it, or the
+    // last at or below it if bins are closed on the left. Exception and
+    // missing values are kept as they are.
+    pub(crate) fn bucket_values(&self, x: &[f64], closed: Closed) -> Vec<f64> {
+        x.iter()
+            .map(|v| {
+                if v.is_nan() || self.exception_values_.exception_idx(v).is_some() {
+                    return *v;
+                }
+                match closed {
+                    Closed::Right => *self
+                        .vals_
+                        .get(self.vals_.partition_point(|p| p < v))
+                        .unwrap_or(v),
+                    Closed::Left => match self.vals_.partition_point(|p| p <= v) {
+                        0 => *v,
+                        i => self.vals_[i - 1],
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Assign each record to one of `n_folds` folds, so that each split
+    /// can be checked against the records it was not chosen on. A split
+    /// is only allowed if its information value gain, measured on the held
+    /// out records of a fold with the weight of evidence of the other folds,
+    /// is positive on at least the `agreement` share of the folds. The
+    /// records must be the same as those the feature was built from.
+    pub fn set_folds(
+        &mut self,
         x: &[f64],
         y: &[f64],
-        w: &[f64],
+        w: Option<&[f64]>,
+        n_folds: usize,
+        agreement: f64,
+    ) {
+        let n = self.vals_.len();
+        let mut ones_ct = vec![vec![0.0; n]; n_folds];
+        let mut zero_ct = vec![vec![0.0; n]; n_folds];
+        let mut totals = vec![(0.0, 0.0); n_folds];
+        let mut rng = SplitMix64(FOLD_SEED);
+        for (i, (v, y_)) in x.iter().zip(y).enumerate() {
+            let w_ = weight(w, i);
+            let fold = rng.below(n_folds);
+            let (ct, total) = if *y_ == 1.0 {
+                (&mut ones_ct[fold], &mut totals[fold].0)
+            } else {
+                (&mut zero_ct[fold], &mut totals[fold].1)
+            };
+            *total += w_;
+            // Exception values, and missing values, are not in any bin,
+            // but are part of the totals the distributions are taken of.
+            if let Ok(i) = self.vals_.binary_search_by(|p| nan_safe_compare(p, v)) {
+                ct[i] += w_;
+            }
+        }
+        self.folds_ = ones_ct
+            .iter()
+            .zip(&zero_ct)
+            .zip(totals)
+            .map(|((ones, zero), (total_ones, total_zero))| FoldCounts {
+                cuml_ones_ct_: cumulative(ones),
+                cuml_zero_ct_: cumulative(zero),
+                total_ones_: total_ones,
+                total_zero_: total_zero,
+            })
+            .collect();
+        self.fold_agreement_ = agreement;
+    }
+
+    /// Count the records of each class of a multiclass performance, whose
+    /// labels must be `0, 1, ..., k - 1`, with at least two classes. The
+    /// records must be the same as those the feature was built from.
+    pub fn set_classes<T: Float>(
+        &mut self,
+        x: &[T],
+        y: &[f64],
+        w: Option<&[f64]>,
+    ) -> Result<(), DiscrustError> {
+        if let Some(c) = y.iter().find(|c| (c.fract() != 0.0) || (**c < 0.0)) {
+            return Err(DiscrustError::InvalidTarget(format!(
+                "the class {} is not a label of 0, 1, 2, ...",
+                c
+            )));
+        }
+        let n_classes = y.iter().fold(0.0, |m: f64, c| m.max(*c)) as usize + 1;
+        if n_classes < 2 {
+            return Err(DiscrustError::InvalidTarget(String::from(
+                "a multiclass performance must have at least two classes",
+            )));
+        }
+        let mut class_ct = vec![vec![0.0; self.vals_.len()]; n_classes];
+        let mut exception_ct = vec![vec![0.0; n_classes]; self.exception_values_.vals_.len()];
+        let mut missing_ct = vec![vec![0.0; n_classes]; self.missing_.vals_.len()];
+        let mut totals = vec![0.0; n_classes];
+        for (i, (v, c)) in x.iter().zip(y).enumerate() {
+            let w_ = weight(w, i);
+            let v = to_f64(*v);
+            let c = *c as usize;
+            totals[c] += w_;
+            if let Some(i) = self.exception_values_.exception_idx(&v) {
+                exception_ct[i][c] += w_;
+            } else if v.is_nan() {
+                // Missing values are in the missing bin, as
+                // otherwise the feature could not have been built.
+                missing_ct[0][c] += w_;
+            } else if let Ok(i) = self.vals_.binary_search_by(|p| nan_safe_compare(p, &v)) {
+                class_ct[c][i] += w_;
+            }
+        }
+        self.cuml_class_ct_ = class_ct.iter().map(|ct| cumulative(ct)).collect();
+        self.total_class_ct_ = totals;
+        self.exception_values_.class_ct_ = exception_ct;
+        self.missing_.class_ct_ = missing_ct;
+        self.calculate_exception_stats();
+        Ok(())
+    }
+
+    /// The number of classes of a multiclass performance,
+    /// zero until they are set with `set_classes`.
+    pub fn n_classes(&self) -> usize {
+        self.total_class_ct_.len()
+    }
+
+    /// The weighted count of each class of a multiclass
+    /// performance, for the records with values in `vals_[start..stop]`.
+    pub fn range_class_ct(&self, start: usize, stop: usize) -> Vec<f64> {
+        self.cuml_class_ct_
+            .iter()
+            .map(|cuml| range_sum(cuml, start, stop))
+            .collect()
+    }
+
+    /// The smallest weighted count of any class of a multiclass
+    /// performance, for the records with values in `vals_[start..stop]`.
+    pub fn range_min_class_ct(&self, start: usize, stop: usize) -> f64 {
+        self.cuml_class_ct_
+            .iter()
+            .map(|cuml| range_sum(cuml, start, stop))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// The one-vs-rest weight of evidence of each class of a multiclass
+    /// performance, for the records with values in `vals_[start..stop]`.
+    pub fn range_class_woe(&self, start: usize, stop: usize) -> Vec<f64> {
+        self.class_woe(&self.range_class_ct(start, stop))
+    }
+
+    /// The one-vs-rest weight of evidence of each class of a multiclass
+    /// performance, for the value at `idx` of `exceptions`, which is
+    /// either `exception_values_`, or `missing_`.
+    pub fn exception_class_woe(&self, exceptions: &ExceptionValues, idx: usize) -> Vec<f64> {
+        self.class_woe(&exceptions.class_ct_[idx])
+    }
+
+    fn class_woe(&self, class_ct: &[f64]) -> Vec<f64> {
+        class_iv_woe(class_ct, &self.total_class_ct_, self.woe_regularization_)
+            .into_iter()
+            .map(|(_, woe)| woe)
+            .collect()
+    }
+
+    /// Discard the fold counts, once the splits have been found.
+    pub fn clear_folds(&mut self) {
+        self.folds_ = Vec::new();
+    }
+
+    /// Whether splitting `vals_[start..stop]` at `split_idx` holds up on
+    /// enough of the folds set with `set_folds`, always true if there are
+    /// no folds. On each fold, the weight of evidence of the two halves,
+    /// and of the whole, is taken from the records of the other folds, and
+    /// the gain is `sum((ones_dist - zero_dist) * (woe_half - woe_whole))`
+    /// over the halves, with the distributions of the held out records.
+    pub fn split_holds_across_folds(&self, split_idx: usize, start: usize, stop: usize) -> bool {
+        if self.folds_.is_empty() {
+            return true;
+        }
+        let split_idx = split_idx + 1 + start;
+        let ranges = [(start, split_idx), (split_idx, stop)];
+        let held_up = self
+            .folds_
+            .iter()
+            .filter(|fold| {
+                let train_ones = self.total_ones_ - fold.total_ones_;
+                let train_zero = self.total_zero_ - fold.total_zero_;
+                let train_woe = |start: usize, stop: usize| {
+                    let (_, ones, zero) = self.range_totals_ones_zero_ct(start, stop);
+                    let (fold_ones, fold_zero) = fold.range_ones_zero_ct(start, stop);
+                    iv_woe(
+                        ones - fold_ones,
+                        zero - fold_zero,
+                        train_ones,
+                        train_zero,
+                        self.woe_regularization_,
+                    )
+                    .1
+                };
+                let whole_woe = train_woe(start, stop);
+                let gain: f64 = ranges
+                    .iter()
+                    .map(|(a, b)| {
+                        let (ones, zero) = fold.range_ones_zero_ct(*a, *b);
+                        let dist = ones / fold.total_ones_ - zero / fold.total_zero_;
+                        dist * (train_woe(*a, *b) - whole_woe)
+                    })
+                    .sum();
+                // NaN, from a fold with no ones or no zeros, is not a gain.
+                gain > 0.0
+            })
+            .count();
+        // Allow for the rounding of the required number of folds.
+        held_up as f64 + 1e-9 >= self.fold_agreement_ * self.folds_.len() as f64
+    }
+
+    /// Whether the value is missing, and missing values had records
+    /// in `missing_` when the feature was built.
+    pub fn in_missing_bin(&self, v: &f64) -> bool {
+        v.is_nan() && self.missing_.totals_ct_.first().is_some_and(|ct| *ct > 0.0)
+    }
+
+    /// The sorted unique values of the feature,
+    /// excluding any exception values.
+    pub fn unique_values(&self) -> &[f64] {
+        &self.vals_
+    }
+
+    /// The cumulative weighted (totals, ones, zero) counts, aligned
+    /// with `unique_values`, these are the aggregates used when
+    /// searching for splits.
+    pub fn cumulative_counts(&self) -> (&[f64], &[f64], &[f64]) {
+        (
+            &self.cuml_totals_ct_,
+            &self.cuml_ones_ct_,
+            &self.cuml_zero_ct_,
+        )
+    }
+
+    /// The total weighted (ones, zero) counts, including
+    /// any exception values.
+    pub fn class_totals(&self) -> (f64, f64) {
+        (self.total_ones_, self.total_zero_)
+    }
+
+    /// Move a value out of the unique values, and into the exception
+    /// values, along with the records it contributed. Returns the position
+    /// the value was removed from in `vals_`, or None if the value was
+    /// not present, in which case it's added as an exception with no records.
+    /// A missing value instead takes the records of `missing_`, if any.
+    pub fn move_to_exception(&mut self, v: f64) -> Option<usize> {
+        let removed = self
+            .vals_
+            .binary_search_by(|x| nan_safe_compare(x, &v))
+            .ok();
+        let (mut totals_ct, mut ones_ct, mut zero_ct) = (0.0, 0.0, 0.0);
+        let (mut y_ct, mut y_sq_ct, mut sq_ct) = (0.0, 0.0, 0.0);
+        let mut class_ct = vec![0.0; self.n_classes()];
+        // Missing values take the records of the missing bin with them.
+        if v.is_nan() && !self.missing_.vals_.is_empty() {
+            let missing = std::mem::take(&mut self.missing_);
+            (totals_ct, ones_ct, zero_ct) = (
+                missing.totals_ct_[0],
+                missing.ones_ct_[0],
+                missing.zero_ct_[0],
+            );
+            (y_ct, y_sq_ct) = (missing.y_ct_[0], missing.y_sq_ct_[0]);
+            sq_ct = missing.totals_sq_ct_.first().copied().unwrap_or(0.0);
+            if let Some(ct) = missing.class_ct_.first() {
+                class_ct = ct.to_vec();
+            }
+        }
+        if let Some(i) = removed {
+            (totals_ct, ones_ct, zero_ct) = self.range_totals_ones_zero_ct(i, i + 1);
+            (y_ct, y_sq_ct) = self.range_y_ct(i, i + 1);
+            class_ct = self.range_class_ct(i, i + 1);
+            sq_ct =
+                sum_of_cuml_subarray(&self.cuml_totals_sq_ct_, &self.cuml_rl_totals_sq_ct_, i, i);
+            self.vals_.remove(i);
+            for (cuml, cuml_rl, ct) in [
+                (
+                    &mut self.cuml_totals_ct_,
+                    &mut self.cuml_rl_totals_ct_,
+                    totals_ct,
+                ),
+                (&mut self.cuml_ones_ct_, &mut self.cuml_rl_ones_ct_, ones_ct),
+                (&mut self.cuml_zero_ct_, &mut self.cuml_rl_zero_ct_, zero_ct),
+                (
+                    &mut self.cuml_totals_sq_ct_,
+                    &mut self.cuml_rl_totals_sq_ct_,
+                    sq_ct,
+                ),
+                (&mut self.cuml_y_ct_, &mut self.cuml_rl_y_ct_, y_ct),
+                (&mut self.cuml_y_sq_ct_, &mut self.cuml_rl_y_sq_ct_, y_sq_ct),
+            ] {
+                // The sums of the performance are not
+                // kept for a binary performance.
+                if cuml.is_empty() {
+                    continue;
+                }
+                cuml.remove(i);
+                cuml[i..].iter_mut().for_each(|c| *c -= ct);
+                cuml_rl.remove(i);
+                cuml_rl[..i].iter_mut().for_each(|c| *c -= ct);
+            }
+            for (cuml, ct) in self.cuml_class_ct_.iter_mut().zip(&class_ct) {
+                cuml.remove(i);
+                cuml[i..].iter_mut().for_each(|c| *c -= ct);
+            }
+        }
+        self.exception_values_
+            .insert_exception_value(v, [totals_ct, ones_ct, zero_ct, y_ct, y_sq_ct, sq_ct]);
+        if !class_ct.is_empty() {
+            let idx = self.exception_values_.exception_idx(&v).unwrap();
+            self.exception_values_.class_ct_.insert(idx, class_ct);
+        }
+        self.calculate_exception_stats();
+        removed
+    }
+
+    /// Merge the exception value at `idx` into the unique value at
+    /// position `into` of `vals_`, adding its records to those of the
+    /// unique value. The exception value is removed from the exception
+    /// values, and is recorded in `merged_vals_`.
+    pub fn merge_exception(&mut self, idx: usize, into: usize) {
+        let e = &self.exception_values_;
+        let (totals_ct, ones_ct, zero_ct) = (e.totals_ct_[idx], e.ones_ct_[idx], e.zero_ct_[idx]);
+        let (y_ct, y_sq_ct) = (e.y_ct_[idx], e.y_sq_ct_[idx]);
+        let sq_ct = e.totals_sq_ct_.get(idx).copied().unwrap_or(0.0);
+        let class_ct = e.class_ct_.get(idx).cloned().unwrap_or_default();
+        self.merged_vals_.push(e.vals_[idx]);
+        self.merged_into_.push(self.vals_[into]);
+        for (cuml, cuml_rl, ct) in [
+            (
+                &mut self.cuml_totals_ct_,
+                &mut self.cuml_rl_totals_ct_,
+                totals_ct,
+            ),
+            (&mut self.cuml_ones_ct_, &mut self.cuml_rl_ones_ct_, ones_ct),
+            (&mut self.cuml_zero_ct_, &mut self.cuml_rl_zero_ct_, zero_ct),
+            (
+                &mut self.cuml_totals_sq_ct_,
+                &mut self.cuml_rl_totals_sq_ct_,
+                sq_ct,
+            ),
+            (&mut self.cuml_y_ct_, &mut self.cuml_rl_y_ct_, y_ct),
+            (&mut self.cuml_y_sq_ct_, &mut self.cuml_rl_y_sq_ct_, y_sq_ct),
+        ] {
+            if cuml.is_empty() {
+                continue;
+            }
+            cuml[into..].iter_mut().for_each(|c| *c += ct);
+            cuml_rl[..=into].iter_mut().for_each(|c| *c += ct);
+        }
+        for (cuml, ct) in self.cuml_class_ct_.iter_mut().zip(&class_ct) {
+            cuml[into..].iter_mut().for_each(|c| *c += ct);
+        }
+        self.exception_values_.remove(idx);
+        self.calculate_exception_stats();
+    }
+
+    /// The unique value a value is predicted as, if it is an
+    /// exception value that was merged into a bin.
+    pub fn merged_value(&self, v: &f64) -> Option<f64> {
+        self.merged_vals_
+            .iter()
+            .position(|x| nan_safe_compare(x, v) == Ordering::Equal)
+            .map(|i| self.merged_into_[i])
+    }
+
+    /// Split the feature and calculate information value
+    /// and weight of evidence for the records bellow and
+    /// above the split.
+    pub fn split_iv_woe(
+        &self,
+        split_idx: usize,
+        start: usize,
+        stop: usize,
+    ) -> ((f64, f64), (f64, f64)) {
+        // vals_ is in sorted order, so we need to find
+        // the first position of the record that is less
+        // than our split_value
+        // This means the split_idx, will be one after our actual
+        // split value, thus the left hand side will include
+        // the split value.
+        let split_idx = split_idx + 1 + start;
+
+        let lhs = self.range_iv_woe(start, split_idx);
+        let rhs = self.range_iv_woe(split_idx, stop);
+        (lhs, rhs)
+    }
+
+    pub fn split_totals_ct_ones_ct(
+        &self,
+        split_idx: usize,
+        start: usize,
+        stop: usize,
+    ) -> ((f64, f64), (f64, f64)) {
+        let split_idx = split_idx + 1 + start;
+
+        let lhs_ct = sum_of_cuml_subarray(
+            &self.cuml_totals_ct_,
+            &self.cuml_rl_totals_ct_,
+            start,
+            split_idx - 1,
+        );
+        let lhs_ones = sum_of_cuml_subarray(
+            &self.cuml_ones_ct_,
+            &self.cuml_rl_ones_ct_,
+            start,
+            split_idx - 1,
+        );
+
+        let rhs_ct = sum_of_cuml_subarray(
+            &self.cuml_totals_ct_,
+            &self.cuml_rl_totals_ct_,
+            split_idx,
+            stop - 1,
+        );
+        let rhs_ones = sum_of_cuml_subarray(
+            &self.cuml_ones_ct_,
+            &self.cuml_rl_ones_ct_,
+            split_idx,
+            stop - 1,
+        );
+
+        ((lhs_ct, lhs_ones), (rhs_ct, rhs_ones))
+    }
+
+    /// Split the feature and calculate the Kish effective sample
+    /// size, `sum(w)^2 / sum(w^2)`, of the records bellow and above
+    /// the split. With unit weights this is equal to the record count.
+    pub fn split_effective_ct(&self, split_idx: usize, start: usize, stop: usize) -> (f64, f64) {
+        let split_idx = split_idx + 1 + start;
+        (
+            self.effective_ct(start, split_idx),
+            self.effective_ct(split_idx, stop),
+        )
+    }
+
+    /// Weighted total, ones, and zero counts for the records
+    /// with values in `vals_[start..stop]`.
+    pub fn range_totals_ones_zero_ct(&self, start: usize, stop: usize) -> (f64, f64, f64) {
+        if start == stop {
+            return (0.0, 0.0, 0.0);
+        }
+        (
+            sum_of_cuml_subarray(
+                &self.cuml_totals_ct_,
+                &self.cuml_rl_totals_ct_,
+                start,
+                stop - 1,
+            ),
+            sum_of_cuml_subarray(&self.cuml_ones_ct_, &self.cuml_rl_ones_ct_, start, stop - 1),
+            sum_of_cuml_subarray(&self.cuml_zero_ct_, &self.cuml_rl_zero_ct_, start, stop - 1),
+        )
+    }
+
+    /// Candidate split positions, relative to `start`, placed at `n`
+    /// weighted quantiles of the records in `vals_[start..stop]`.
+    /// The positions follow the same convention as the `split_idx`
+    /// passed to `split_iv_woe`.
+    pub fn quantile_split_candidates(&self, n: usize, start: usize, stop: usize) -> Vec<usize> {
+        if stop - start < 2 {
+            return Vec::new();
+        }
+        let offset = if start == 0 {
+            0.0
+        } else {
+            self.cuml_totals_ct_[start - 1]
+        };
+        let total = self.cuml_totals_ct_[stop - 1] - offset;
+        let eval_ct = &self.cuml_totals_ct_[start..(stop - 1)];
+        let mut candidates: Vec<usize> = (1..=n)
+            .map(|k| offset + total * (k as f64) / ((n + 1) as f64))
+            // The first position where the cumulative weight
+            // reaches the quantile.
+            .map(|q| eval_ct.partition_point(|c| *c < q))
+            .filter(|i| *i < eval_ct.len())
+            .collect();
+        candidates.dedup();
+        candidates
+    }
+
+    /// Information value and weight of evidence for the
+    /// records with values in `vals_[start..stop]`. For a continuous
+    /// performance, these are the share of the variance explained,
+    /// and the mean of the performance. For a multiclass performance,
+    /// the information value is the sum of the one-vs-rest information
+    /// values of the classes, and the weight of evidence is NaN.
+    pub fn range_iv_woe(&self, start: usize, stop: usize) -> (f64, f64) {
+        let (totals_ct, ones_ct, zero_ct) = self.range_totals_ones_zero_ct(start, stop);
+        match self.target_type_ {
+            TargetType::Binary => iv_woe(
+                ones_ct,
+                zero_ct,
+                self.total_ones_,
+                self.total_zero_,
+                self.woe_regularization_,
+            ),
+            TargetType::Continuous => share_mean(
+                totals_ct,
+                self.range_y_ct(start, stop).0,
+                self.total_ones_ + self.total_zero_,
+                self.total_y_,
+                self.total_y_sq_,
+                self.woe_regularization_,
+            ),
+            TargetType::Multiclass => {
+                let iv = class_iv_woe(
+                    &self.range_class_ct(start, stop),
+                    &self.total_class_ct_,
+                    self.woe_regularization_,
+                )
+                .iter()
+                .map(|(iv, _)| iv)
+                .sum();
+                (iv, f64::NAN)
+            }
+        }
+    }
+
+    /// The weighted sums of the performance, and of its square, for the
+    /// records with values in `vals_[start..stop]`. For a binary
+    /// performance both are the weighted count of ones.
+    pub fn range_y_ct(&self, start: usize, stop: usize) -> (f64, f64) {
+        if start == stop {
+            return (0.0, 0.0);
+        }
+        match self.target_type_ {
+            TargetType::Binary => {
+                let ones_ct = self.range_totals_ones_zero_ct(start, stop).1;
+                (ones_ct, ones_ct)
+            }
+            TargetType::Continuous | TargetType::Multiclass => (
+                sum_of_cuml_subarray(&self.cuml_y_ct_, &self.cuml_rl_y_ct_, start, stop - 1),
+                sum_of_cuml_subarray(&self.cuml_y_sq_ct_, &self.cuml_rl_y_sq_ct_, start, stop - 1),
+            ),
+        }
+    }
+
+    /// The weighted count, and the weighted mean and variance of the
+    /// performance, of the records with values in `vals_[start..stop]`.
+    pub fn range_ct_mean_var(&self, start: usize, stop: usize) -> (f64, f64, f64) {
+        let (totals_ct, _, _) = self.range_totals_ones_zero_ct(start, stop);
+        let (y_ct, y_sq_ct) = self.range_y_ct(start, stop);
+        let mean = y_ct / totals_ct;
+        // Rounding can leave a tiny negative variance.
+        let var = (y_sq_ct / totals_ct - mean * mean).max(0.0);
+        (totals_ct, mean, var)
+    }
+
+    /// Kish effective sample size for the records with
+    /// values in `vals_[start..stop]`.
+    pub fn effective_ct(&self, start: usize, stop: usize) -> f64 {
+        if start == stop {
+            return 0.0;
+        }
+        let ct = sum_of_cuml_subarray(
+            &self.cuml_totals_ct_,
+            &self.cuml_rl_totals_ct_,
+            start,
+            stop - 1,
+        );
+        let sq_ct = sum_of_cuml_subarray(
+            &self.cuml_totals_sq_ct_,
+            &self.cuml_rl_totals_sq_ct_,
+            start,
+            stop - 1,
+        );
+        (ct * ct) / sq_ct
+    }
+}
+
+/// The weighted counts of each unique value of a feature, and of
+/// each exception value, before they are accumulated. Counts from
+/// several batches of records can be merged, before the feature
+/// is built from them.
+#[derive(Debug)]
+pub(crate) struct ValueCounts {
+    vals_: Vec<f64>,
+    totals_ct: Vec<f64>,
+    totals_sq_ct: Vec<f64>,
+    ones_ct: Vec<f64>,
+    zero_ct: Vec<f64>,
+    y_ct: Vec<f64>,
+    y_sq_ct: Vec<f64>,
+    total_ones_: f64,
+    total_zero_: f64,
+    total_y_: f64,
+    total_y_sq_: f64,
+    exception_values_: ExceptionValues,
+    missing_: ExceptionValues,
+}
+
+impl ValueCounts {
+    /// Count the records, on the `threads` of the fit, the records are
+    /// sorted, and their counts accumulated, in parallel. Otherwise on
+    /// the calling thread.
+    ///
+    /// If `buckets` is set, to `(n, closed)`, the values are aggregated
+    /// into at most `n` buckets, of about the same weight, in a single
+    /// pass. The records are counted in runs, in the order they are
+    /// passed, and only the records of each run are sorted. The counts
+    /// of each run are merged into those before, and adjacent values
+    /// combined, so the values kept never grow past a fixed multiple of
+    /// `n`. The buckets are at approximate weighted quantiles.
+    pub(crate) fn new<T: Float + Sync>(
+        x: &[T],
+        y: &[f64],
+        w: Option<&[f64]>,
         exception_values: &[f64],
+        special_missing: bool,
+        buckets: Option<(usize, Closed)>,
+        threads: &Threads,
     ) -> Result<Self, DiscrustError> {
-        // Make exception values.
-        let mut exception_values_ = ExceptionValues::new(exception_values);
+        check_records(x.len(), y, w)?;
+        let (e, missing) = (exception_values, special_missing);
+        match w {
+            Some(w) => ValueCounts::count(x, y, |i| w[i], e, missing, buckets, threads),
+            // Unweighted records don't look up a weight for each record,
+            // and the multiplications by a weight of 1 are compiled away.
+            None => ValueCounts::count(x, y, |_| 1.0, e, missing, buckets, threads),
+        }
+    }
 
-        // Define all of the stats we will use
-        let mut vals_ = Vec::new();
-        let mut cuml_ones_ct_ = Vec::new();
-        let mut cuml_zero_ct_ = Vec::new();
-        let mut cuml_totals_ct_ = Vec::new();
+    // Count the records, where `weight(i)` is the weight of record `i`.
+    #[allow(clippy::too_many_arguments)]
+    fn count<T: Float + Sync, W: Fn(usize) -> f64 + Sync>(
+        x: &[T],
+        y: &[f64],
+        weight: W,
+        exception_values: &[f64],
+        special_missing: bool,
+        buckets: Option<(usize, Closed)>,
+        threads: &Threads,
+    ) -> Result<Self, DiscrustError> {
+        let nan_exception = exception_values.iter().any(|v| v.is_nan());
+        if !(special_missing || nan_exception) {
+            if let Some(nan) = NanValues::find(Column::X, x) {
+                return Err(DiscrustError::ContainsNaN(nan));
+            }
+        }
+        let special_missing = special_missing && !nan_exception;
         // First we will get the index needed to sort the vector x.
-        let mut sort_tuples: Vec<(usize, &f64)> = x.iter().enumerate().collect();
-        let no_exceptions = exception_values.is_empty();
-        if no_exceptions {
-            // Now sort these tuples by the float values of x.
-            sort_tuples.sort_by(|a, b| a.1.partial_cmp(b.1).unwrap());
+        let mut sort_tuples: Vec<(usize, &T)> = x.iter().enumerate().collect();
+        // Missing values are only checked for when there are exception
+        // values, so any NaN is sent through the same checks.
+        let no_exceptions =
+            exception_values.is_empty() && !special_missing && !x.iter().any(|v| v.is_nan());
+        let compare = |a: &(usize, &T), b: &(usize, &T)| {
+            if no_exceptions {
+                a.1.partial_cmp(b.1).unwrap()
+            } else {
+                nan_safe_compare(a.1, b.1)
+            }
+        };
+        let accumulate = |sorted: &[(usize, &T)]| {
+            ValueCounts::accumulate(
+                x,
+                y,
+                &weight,
+                sorted.iter().map(|(i, _)| *i),
+                exception_values,
+                special_missing,
+                no_exceptions,
+            )
+        };
+
+        #[cfg(feature = "rayon")]
+        let pool = threads.pool().filter(|_| !x.is_empty());
+        #[cfg(not(feature = "rayon"))]
+        let _ = threads;
+
+        if let Some((n, closed)) = buckets {
+            let run = SKETCH_RESOLUTION * n;
+            let count_run = |records: &mut [(usize, &T)]| {
+                records.sort_by(compare);
+                accumulate(records)
+            };
+            let combine = |a: ValueCounts, b: ValueCounts| a.merge(b).compress(run, closed);
+            #[cfg(feature = "rayon")]
+            if let Some(pool) = pool {
+                use rayon::prelude::*;
+                let counts = pool.install(|| {
+                    sort_tuples
+                        .par_chunks_mut(run)
+                        .map(count_run)
+                        .collect::<Result<Vec<ValueCounts>, DiscrustError>>()
+                })?;
+                return Ok(counts
+                    .into_iter()
+                    .reduce(combine)
+                    .unwrap()
+                    .compress(n, closed));
+            }
+            let mut counts: Option<ValueCounts> = None;
+            for records in sort_tuples.chunks_mut(run) {
+                let run_counts = count_run(records)?;
+                counts = Some(match counts {
+                    Some(counts) => combine(counts, run_counts),
+                    None => run_counts,
+                });
+            }
+            return Ok(match counts {
+                Some(counts) => counts,
+                None => accumulate(&[])?,
+            }
+            .compress(n, closed));
+        }
+
+        #[cfg(feature = "rayon")]
+        if let Some(pool) = pool {
+            use rayon::prelude::*;
+            return pool.install(|| {
+                sort_tuples.par_sort_by(compare);
+                // Each thread accumulates the counts of a run of the sorted
+                // records, the runs are then merged in order, where a value
+                // at the end of one run, and start of the next, is combined.
+                let run = sort_tuples.len().div_ceil(rayon::current_num_threads());
+                let counts = sort_tuples
+                    .par_chunks(run)
+                    .map(accumulate)
+                    .collect::<Result<Vec<ValueCounts>, DiscrustError>>()?;
+                Ok(counts.into_iter().reduce(ValueCounts::merge).unwrap())
+            });
+        }
+        sort_tuples.sort_by(compare);
+        accumulate(&sort_tuples)
+    }
+
+    // Accumulate the counts of the records at `sort_index`,
+    // which must be in sorted order of their values.
+    fn accumulate<T: Float, W: Fn(usize) -> f64>(
+        x: &[T],
+        y: &[f64],
+        weight: W,
+        sort_index: impl Iterator<Item = usize>,
+        exception_values: &[f64],
+        special_missing: bool,
+        no_exceptions: bool,
+    ) -> Result<Self, DiscrustError> {
+        // Make exception values.
+        let mut exception_values_ = ExceptionValues::new(exception_values);
+        let mut missing_ = if special_missing {
+            ExceptionValues::new(&[f64::NAN])
         } else {
-            sort_tuples.sort_by(|a, b| nan_safe_compare(a.1, b.1));
-            
+            ExceptionValues::default()
         };
-        let sort_index = sort_tuples.iter().map(|(i, _)| *i);
+
+        // Define all of the stats we will use, these are the counts
+        // of each unique value, and are accumulated once all records
+        // have been seen.
+        let mut vals_ = Vec::new();
+        let mut ones_ct = Vec::new();
+        let mut zero_ct = Vec::new();
+        let mut totals_ct = Vec::new();
+        let mut totals_sq_ct = Vec::new();
+        let mut y_ct = Vec::new();
+        let mut y_sq_ct = Vec::new();
 
         let mut totals_idx = 0;
         let mut first_value = true;
@@ -132,22 +1411,26 @@ impl Feature {
         let mut w_;
         let mut total_ones_ = 0.0;
         let mut total_zero_ = 0.0;
+        let mut total_y_ = 0.0;
+        let mut total_y_sq_ = 0.0;
         for i in sort_index {
+            let v = to_f64(x[i]);
             y_ = y[i];
-            w_ = w[i];
-            // Some error checking
-            if y_.is_nan() {
-                return Err(DiscrustError::ContainsNaN(String::from("y column")));
-            }
-            if w_.is_nan() {
-                return Err(DiscrustError::ContainsNaN(String::from("weight column")));
-            }
+            w_ = weight(i);
+            total_y_ += w_ * y_;
+            total_y_sq_ += w_ * y_ * y_;
             if !no_exceptions {
-                let e_idx = exception_values_.exception_idx(&x[i]);
-                if x[i].is_nan() && e_idx == None {
-                    return Err(DiscrustError::ContainsNaN(String::from(
-                        "x column, but NaN is not an exception value",
-                    )));
+                let e_idx = exception_values_.exception_idx(&v);
+                // NaN values that are not an exception value were checked
+                // for before counting, so are collected in the missing bin.
+                if v.is_nan() && e_idx.is_none() {
+                    missing_.update_exception_values(0, &w_, &y_);
+                    if y_ == 1.0 {
+                        total_ones_ += w_;
+                    } else {
+                        total_zero_ += w_;
+                    }
+                    continue;
                 }
                 // If the value is equal to one of our exception_values_ update the exception_values_
                 // and continue.
@@ -161,128 +1444,265 @@ impl Feature {
                     continue;
                 }
             }
-            // If this is the first value, add to our vectors
-            // Initializing them.
-            if first_value {
-                x_ = x[i];
-                cuml_totals_ct_.push(w_);
-                if y_ == 1.0 {
-                    total_ones_ += w_;
-                    cuml_ones_ct_.push(w_);
-                    cuml_zero_ct_.push(0.0);
-                } else {
-                    total_zero_ += w_;
-                    cuml_ones_ct_.push(0.0);
-                    cuml_zero_ct_.push(w_);
+            // If this is the first value, or a new value, add
+            // a new entry to our vectors.
+            if first_value || x_ < v {
+                if !first_value {
+                    totals_idx += 1;
                 }
+                x_ = v;
                 vals_.push(x_);
-            // If this is a new value, push, and cumulate
-            // this won't panic, because we know these
-            // vectors have values
-            } else if x_ < x[i] {
-                let t_last = cuml_totals_ct_[totals_idx];
-                let o_last = cuml_ones_ct_[totals_idx];
-                let z_last = cuml_zero_ct_[totals_idx];
-                x_ = x[i];
-                cuml_totals_ct_.push(w_ + t_last);
-                if y_ == 1.0 {
-                    total_ones_ += w_;
-                    cuml_ones_ct_.push(w_ + o_last);
-                    cuml_zero_ct_.push(z_last);
-                } else {
-                    total_zero_ += w_;
-                    cuml_ones_ct_.push(o_last);
-                    cuml_zero_ct_.push(w_ + z_last);
-                }
-                vals_.push(x_);
-                totals_idx += 1;
+                totals_ct.push(0.0);
+                totals_sq_ct.push(0.0);
+                ones_ct.push(0.0);
+                zero_ct.push(0.0);
+                y_ct.push(0.0);
+                y_sq_ct.push(0.0);
+            }
+            totals_ct[totals_idx] += w_;
+            totals_sq_ct[totals_idx] += w_ * w_;
+            y_ct[totals_idx] += w_ * y_;
+            y_sq_ct[totals_idx] += w_ * y_ * y_;
+            if y_ == 1.0 {
+                total_ones_ += w_;
+                ones_ct[totals_idx] += w_;
             } else {
-                cuml_totals_ct_[totals_idx] += w_;
-                if y_ == 1.0 {
-                    total_ones_ += w_;
-                    cuml_ones_ct_[totals_idx] += w_;
-                } else {
-                    total_zero_ += w_;
-                    cuml_zero_ct_[totals_idx] += w_;
-                }
+                total_zero_ += w_;
+                zero_ct[totals_idx] += w_;
             }
             first_value = false;
         }
-        exception_values_.calculate_iv_woe(total_ones_, total_zero_);
-
-        Ok(Feature {
+        Ok(ValueCounts {
             vals_,
-            cuml_ones_ct_,
-            cuml_zero_ct_,
-            cuml_totals_ct_,
+            totals_ct,
+            totals_sq_ct,
+            ones_ct,
+            zero_ct,
+            y_ct,
+            y_sq_ct,
             total_ones_,
             total_zero_,
+            total_y_,
+            total_y_sq_,
             exception_values_,
+            missing_,
         })
     }
 
-    /// Split the feature and calculate information value
-    /// and weight of evidence for the records bellow and
-    /// above the split.
-    pub fn split_iv_woe(
-        &self,
-        split_idx: usize,
-        start: usize,
-        stop: usize,
-    ) -> ((f64, f64), (f64, f64)) {
-        // vals_ is in sorted order, so we need to find
-        // the first position of the record that is less
-        // than our split_value
-        // This means the split_idx, will be one after our actual
-        // split value, thus the left hand side will include
-        // the split value.
-        let split_idx = split_idx + 1 + start;
-
-        // Accumulate the left hand side.
-        let lhs_zero_dist =
-            sum_of_cuml_subarray(&self.cuml_zero_ct_, start, split_idx - 1) / self.total_zero_;
-        let lhs_ones_dist =
-            sum_of_cuml_subarray(&self.cuml_ones_ct_, start, split_idx - 1) / self.total_ones_;
-        let lhs_woe = (lhs_ones_dist / lhs_zero_dist).ln();
-        let lhs_iv = (lhs_ones_dist - lhs_zero_dist) * lhs_woe;
-
-        // Accumulate the right hand side.
-        let rhs_zero_dist =
-            sum_of_cuml_subarray(&self.cuml_zero_ct_, split_idx, stop - 1) / self.total_zero_;
-        let rhs_ones_dist =
-            sum_of_cuml_subarray(&self.cuml_ones_ct_, split_idx, stop - 1) / self.total_ones_;
-        let rhs_woe = (rhs_ones_dist / rhs_zero_dist).ln();
-        let rhs_iv = (rhs_ones_dist - rhs_zero_dist) * rhs_woe;
-
-        ((lhs_iv, lhs_woe), (rhs_iv, rhs_woe))
+    /// Whether the other counts were made with the same exception
+    /// values, and missing bin, so they can be merged.
+    pub(crate) fn compatible(&self, other: &ValueCounts) -> bool {
+        let same = |a: &ExceptionValues, b: &ExceptionValues| {
+            (a.vals_.len() == b.vals_.len())
+                && a.vals_
+                    .iter()
+                    .zip(&b.vals_)
+                    .all(|(x, y)| nan_safe_compare(x, y) == Ordering::Equal)
+        };
+        same(&self.exception_values_, &other.exception_values_)
+            && same(&self.missing_, &other.missing_)
     }
 
-    pub fn split_totals_ct_ones_ct(
-        &self,
-        split_idx: usize,
-        start: usize,
-        stop: usize,
-    ) -> ((f64, f64), (f64, f64)) {
-        let split_idx = split_idx + 1 + start;
+    /// Add the counts of another batch of records, which must have been
+    /// counted with the same exception values. The unique values of both
+    /// are merged, keeping them in sorted order.
+    pub(crate) fn merge(self, other: ValueCounts) -> ValueCounts {
+        let n = self.vals_.len() + other.vals_.len();
+        let mut merged = ValueCounts {
+            vals_: Vec::with_capacity(n),
+            totals_ct: Vec::with_capacity(n),
+            totals_sq_ct: Vec::with_capacity(n),
+            ones_ct: Vec::with_capacity(n),
+            zero_ct: Vec::with_capacity(n),
+            y_ct: Vec::with_capacity(n),
+            y_sq_ct: Vec::with_capacity(n),
+            total_ones_: self.total_ones_ + other.total_ones_,
+            total_zero_: self.total_zero_ + other.total_zero_,
+            total_y_: self.total_y_ + other.total_y_,
+            total_y_sq_: self.total_y_sq_ + other.total_y_sq_,
+            exception_values_: self.exception_values_.merge(&other.exception_values_),
+            missing_: self.missing_.merge(&other.missing_),
+        };
+        let (mut i, mut j) = (0, 0);
+        while (i < self.vals_.len()) || (j < other.vals_.len()) {
+            let from_self = self
+                .vals_
+                .get(i)
+                .filter(|v| other.vals_.get(j).is_none_or(|o| *v <= o));
+            let from_other = other
+                .vals_
+                .get(j)
+                .filter(|v| self.vals_.get(i).is_none_or(|s| *v <= s));
+            merged.vals_.push(*from_self.or(from_other).unwrap());
+            merged.totals_ct.push(0.0);
+            merged.totals_sq_ct.push(0.0);
+            merged.ones_ct.push(0.0);
+            merged.zero_ct.push(0.0);
+            merged.y_ct.push(0.0);
+            merged.y_sq_ct.push(0.0);
+            let last = merged.vals_.len() - 1;
+            for (counts, k, taken) in [
+                (&self, &mut i, from_self.is_some()),
+                (&other, &mut j, from_other.is_some()),
+            ] {
+                if taken {
+                    merged.totals_ct[last] += counts.totals_ct[*k];
+                    merged.totals_sq_ct[last] += counts.totals_sq_ct[*k];
+                    merged.ones_ct[last] += counts.ones_ct[*k];
+                    merged.zero_ct[last] += counts.zero_ct[*k];
+                    merged.y_ct[last] += counts.y_ct[*k];
+                    merged.y_sq_ct[last] += counts.y_sq_ct[*k];
+                    *k += 1;
+                }
+            }
+        }
+        merged
+    }
 
-        let lhs_ct = sum_of_cuml_subarray(&self.cuml_totals_ct_, start, split_idx - 1);
-        let lhs_ones = sum_of_cuml_subarray(&self.cuml_ones_ct_, start, split_idx - 1);
+    // Combine runs of adjacent values, so at most `n` values are kept, each
+    // holding about the same share of the total weight. A value is put in
+    // the run of the weight before it, so a value is never split across
+    // runs. The value kept is the largest of the run, or the smallest if
+    // bins are closed on the left, so the splits are still observed values.
+    fn compress(self, n: usize, closed: Closed) -> ValueCounts {
+        let total: f64 = self.totals_ct.iter().sum();
+        if (self.vals_.len() <= n) || (total <= 0.0) {
+            return self;
+        }
+        let mut compressed = ValueCounts {
+            vals_: Vec::with_capacity(n),
+            totals_ct: Vec::with_capacity(n),
+            totals_sq_ct: Vec::with_capacity(n),
+            ones_ct: Vec::with_capacity(n),
+            zero_ct: Vec::with_capacity(n),
+            y_ct: Vec::with_capacity(n),
+            y_sq_ct: Vec::with_capacity(n),
+            ..self
+        };
+        let mut before = 0.0;
+        let mut last_run = None;
+        for k in 0..self.vals_.len() {
+            let run = ((before / total * n as f64) as usize).min(n - 1);
+            before += self.totals_ct[k];
+            if last_run != Some(run) {
+                last_run = Some(run);
+                compressed.vals_.push(self.vals_[k]);
+                compressed.totals_ct.push(0.0);
+                compressed.totals_sq_ct.push(0.0);
+                compressed.ones_ct.push(0.0);
+                compressed.zero_ct.push(0.0);
+                compressed.y_ct.push(0.0);
+                compressed.y_sq_ct.push(0.0);
+            } else if closed == Closed::Right {
+                *compressed.vals_.last_mut().unwrap() = self.vals_[k];
+            }
+            let last = compressed.vals_.len() - 1;
+            compressed.totals_ct[last] += self.totals_ct[k];
+            compressed.totals_sq_ct[last] += self.totals_sq_ct[k];
+            compressed.ones_ct[last] += self.ones_ct[k];
+            compressed.zero_ct[last] += self.zero_ct[k];
+            compressed.y_ct[last] += self.y_ct[k];
+            compressed.y_sq_ct[last] += self.y_sq_ct[k];
+        }
+        compressed
+    }
 
-        let rhs_ct = sum_of_cuml_subarray(&self.cuml_totals_ct_, split_idx, stop - 1);
-        let rhs_ones = sum_of_cuml_subarray(&self.cuml_ones_ct_, split_idx, stop - 1);
+    /// Build the feature, the sums of the performance
+    /// are not kept for a binary performance.
+    pub(crate) fn into_feature(self, target_type: TargetType) -> Feature {
+        let ValueCounts {
+            vals_,
+            totals_ct,
+            totals_sq_ct,
+            ones_ct,
+            zero_ct,
+            y_ct,
+            y_sq_ct,
+            total_ones_,
+            total_zero_,
+            total_y_,
+            total_y_sq_,
+            exception_values_,
+            missing_,
+        } = self;
+        let (y_ct, y_sq_ct) = match target_type {
+            TargetType::Binary => (Vec::new(), Vec::new()),
+            TargetType::Continuous | TargetType::Multiclass => (y_ct, y_sq_ct),
+        };
+        let (min_, max_) = (vals_.first().copied(), vals_.last().copied());
 
-        ((lhs_ct, lhs_ones), (rhs_ct, rhs_ones))
+        let mut feature = Feature {
+            vals_,
+            cuml_ones_ct_: cumulative(&ones_ct),
+            cuml_zero_ct_: cumulative(&zero_ct),
+            cuml_totals_ct_: cumulative(&totals_ct),
+            cuml_totals_sq_ct_: cumulative(&totals_sq_ct),
+            cuml_rl_ones_ct_: cumulative_rl(&ones_ct),
+            cuml_rl_zero_ct_: cumulative_rl(&zero_ct),
+            cuml_rl_totals_ct_: cumulative_rl(&totals_ct),
+            cuml_rl_totals_sq_ct_: cumulative_rl(&totals_sq_ct),
+            total_ones_,
+            total_zero_,
+            cuml_y_ct_: cumulative(&y_ct),
+            cuml_y_sq_ct_: cumulative(&y_sq_ct),
+            cuml_rl_y_ct_: cumulative_rl(&y_ct),
+            cuml_rl_y_sq_ct_: cumulative_rl(&y_sq_ct),
+            total_y_,
+            total_y_sq_,
+            target_type_: target_type,
+            cuml_class_ct_: Vec::new(),
+            total_class_ct_: Vec::new(),
+            woe_regularization_: 0.0,
+            exception_values_,
+            missing_,
+            merged_vals_: Vec::new(),
+            merged_into_: Vec::new(),
+            folds_: Vec::new(),
+            fold_agreement_: 1.0,
+            compacted_: false,
+            min_,
+            max_,
+        };
+        feature.calculate_exception_stats();
+        feature
     }
 }
 
-fn sum_of_cuml_subarray(x: &[f64], start: usize, stop: usize) -> f64 {
+// The sum of the values from start to stop inclusive, given the left to right
+// cumulative sum `x`, and the right to left cumulative sum `x_rl`. Ranges at
+// either end are read directly, rather than by subtraction.
+fn sum_of_cuml_subarray(x: &[f64], x_rl: &[f64], start: usize, stop: usize) -> f64 {
     if start == 0 {
         x[stop]
+    } else if stop == x.len() - 1 {
+        x_rl[start]
     } else {
         x[stop] - x[start - 1]
     }
 }
 
+fn cumulative(x: &[f64]) -> Vec<f64> {
+    x.iter()
+        .scan(0.0, |acc, &v| {
+            *acc += v;
+            Some(*acc)
+        })
+        .collect()
+}
+
+fn cumulative_rl(x: &[f64]) -> Vec<f64> {
+    let mut cuml_rl: Vec<f64> = x
+        .iter()
+        .rev()
+        .scan(0.0, |acc, &v| {
+            *acc += v;
+            Some(*acc)
+        })
+        .collect();
+    cuml_rl.reverse();
+    cuml_rl
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -291,22 +1711,22 @@ mod test {
         let x_ = vec![1.0, 1.0, 3.0, 2.0, 2.0, 3.0, 3.0, 3.0];
         let y_ = vec![1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0];
         let w_ = vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0];
-        let _f = Feature::new(&x_, &y_, &w_, &Vec::new());
-        assert!(true);
+        let f = Feature::new(&x_, &y_, Some(&w_), &Vec::new());
+        assert!(f.is_ok());
     }
     #[test]
     fn test_feature_fit() {
         let x_ = vec![1.0, 1.0, 3.0, 2.0, 2.0, 3.0, 3.0, 3.0];
         let y_ = vec![1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0];
         let w_ = vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0];
-        let f = Feature::new(&x_, &y_, &w_, &Vec::new()).unwrap();
+        let f = Feature::new(&x_, &y_, Some(&w_), &Vec::new()).unwrap();
         assert_eq!(f.vals_, vec![1.0, 2.0, 3.0]);
         assert_eq!(f.cuml_totals_ct_, vec![2.0, 4.0, 8.0]);
 
         let x_ = vec![2.0, 2.0, 1.0, 1.0];
         let y_ = vec![1.0, 1.0, 1.0, 0.0];
         let w_ = vec![3.0, 3.0, 1.0, 1.0];
-        let f = Feature::new(&x_, &y_, &w_, &Vec::new()).unwrap();
+        let f = Feature::new(&x_, &y_, Some(&w_), &Vec::new()).unwrap();
         assert_eq!(f.vals_, vec![1.0, 2.0]);
         assert_eq!(f.cuml_totals_ct_, vec![2.0, 8.0]);
         assert_eq!(f.cuml_ones_ct_, vec![1.0, 7.0]);
@@ -317,12 +1737,225 @@ mod test {
         // assert_eq!(f.cuml_zero_dist_, vec![1.0 / 1.0, 1.0]);
     }
 
+    #[test]
+    fn test_unweighted_feature() {
+        let x_ = vec![1.0, 1.0, 3.0, 2.0, 2.0, 3.0, -1.0, 3.0];
+        let y_ = vec![1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0];
+        let w_ = vec![1.0; x_.len()];
+        let weighted = Feature::new(&x_, &y_, Some(&w_), &[-1.0]).unwrap();
+        let f = Feature::new(&x_, &y_, None, &[-1.0]).unwrap();
+        assert_eq!(f.cumulative_counts(), weighted.cumulative_counts());
+        assert_eq!(f.cuml_totals_sq_ct_, weighted.cuml_totals_sq_ct_);
+        assert_eq!(f.exception_values_.totals_ct_, vec![1.0]);
+
+        assert!(matches!(
+            Feature::new(&x_, &y_, Some(&w_[1..]), &[-1.0]),
+            Err(DiscrustError::LengthMismatch(..))
+        ));
+        let mut w_bad = w_.to_vec();
+        w_bad[2] = -1.0;
+        assert!(matches!(
+            Feature::new(&x_, &y_, Some(&w_bad), &[-1.0]),
+            Err(DiscrustError::InvalidWeight(_))
+        ));
+        w_bad[2] = f64::NAN;
+        assert!(matches!(
+            Feature::new(&x_, &y_, Some(&w_bad), &[-1.0]),
+            Err(DiscrustError::ContainsNaN(NanValues {
+                column: Column::Weight,
+                count: 1,
+                first_index: 2
+            }))
+        ));
+        // The error reports where the NaN values are, and its source
+        // is the NaN values themselves.
+        let mut x_nan = x_.to_vec();
+        x_nan[1] = f64::NAN;
+        x_nan[3] = f64::NAN;
+        let e = Feature::new(&x_nan, &y_, Some(&w_), &[-1.0]).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "The x column contains 2 NaN values, the first at index 1, pass NaN as an exception value, or set special_missing, to bin them separately."
+        );
+        assert_eq!(
+            std::error::Error::source(&e).unwrap().to_string(),
+            "2 NaN values, the first at index 1"
+        );
+        assert!(Feature::new(&x_nan, &y_, Some(&w_), &[f64::NAN]).is_ok());
+    }
+
+    #[test]
+    fn test_feature_accessors() {
+        let x_ = vec![2.0, 2.0, 1.0, 1.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 0.0];
+        let w_ = vec![3.0, 3.0, 1.0, 1.0, 1.0];
+        let f = Feature::new(&x_, &y_, Some(&w_), &[-1.0]).unwrap();
+        assert_eq!(f.unique_values(), &[1.0, 2.0]);
+        let (totals, ones, zero) = f.cumulative_counts();
+        assert_eq!(totals, &[2.0, 8.0]);
+        assert_eq!(ones, &[1.0, 7.0]);
+        assert_eq!(zero, &[1.0, 1.0]);
+        assert_eq!(f.class_totals(), (7.0, 2.0));
+    }
+
+    #[test]
+    fn test_feature_with_missing() {
+        let x_ = vec![2.0, f64::NAN, 1.0, 1.0, f64::NAN];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 0.0];
+        let w_ = vec![3.0, 2.0, 1.0, 1.0, 1.0];
+        assert!(Feature::new(&x_, &y_, Some(&w_), &Vec::new()).is_err());
+        let mut f = Feature::with_missing(&x_, &y_, Some(&w_), &Vec::new()).unwrap();
+        assert_eq!(f.unique_values(), &[1.0, 2.0]);
+        assert_eq!(f.missing_.totals_ct_, vec![3.0]);
+        assert_eq!(f.missing_.ones_ct_, vec![2.0]);
+        // Missing records count towards the totals.
+        assert_eq!(f.class_totals(), (6.0, 2.0));
+        assert!(f.in_missing_bin(&f64::NAN));
+        assert!(!f.in_missing_bin(&1.0));
+        // Moving NaN to the exception values takes the missing records.
+        assert_eq!(f.move_to_exception(f64::NAN), None);
+        assert!(!f.in_missing_bin(&f64::NAN));
+        assert_eq!(f.exception_values_.totals_ct_, vec![3.0]);
+
+        let f = Feature::with_missing(&x_, &y_, Some(&w_), &[f64::NAN]).unwrap();
+        assert!(f.missing_.vals_.is_empty());
+        assert_eq!(f.exception_values_.totals_ct_, vec![3.0]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_counts_match_serial() {
+        // Runs of the same value, exception values and missing values
+        // fall across the boundaries of the runs each thread counts.
+        let x_: Vec<f64> = (0..101)
+            .map(|i| match i % 7 {
+                0 => f64::NAN,
+                1 => -1.0,
+                _ => (i % 13) as f64,
+            })
+            .collect();
+        let y_: Vec<f64> = (0..101).map(|i| (i % 3 == 0) as i64 as f64).collect();
+        let w_: Vec<f64> = (0..101).map(|i| 1.0 + (i % 4) as f64).collect();
+        let serial = Feature::build(
+            &x_,
+            &y_,
+            Some(&w_),
+            &[-1.0],
+            true,
+            TargetType::Binary,
+            &Threads::default(),
+        )
+        .unwrap();
+        for n_threads in [1, 3, 8] {
+            let parallel = Feature::build(
+                &x_,
+                &y_,
+                Some(&w_),
+                &[-1.0],
+                true,
+                TargetType::Binary,
+                &Threads::new(Some(n_threads)).unwrap(),
+            )
+            .unwrap();
+            assert_eq!(parallel.vals_, serial.vals_);
+            assert_eq!(parallel.cuml_totals_ct_, serial.cuml_totals_ct_);
+            assert_eq!(parallel.cuml_ones_ct_, serial.cuml_ones_ct_);
+            assert_eq!(parallel.cuml_totals_sq_ct_, serial.cuml_totals_sq_ct_);
+            assert_eq!(parallel.class_totals(), serial.class_totals());
+            assert_eq!(
+                parallel.exception_values_.totals_ct_,
+                serial.exception_values_.totals_ct_
+            );
+            assert_eq!(parallel.missing_.ones_ct_, serial.missing_.ones_ct_);
+        }
+        let y_nan = vec![f64::NAN; x_.len()];
+        assert!(Feature::build(
+            &x_,
+            &y_nan,
+            None,
+            &[-1.0],
+            true,
+            TargetType::Binary,
+            &Threads::new(Some(3)).unwrap()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_histogram_counts() {
+        // With a single run, each bucket is represented by its largest
+        // value, or its smallest when bins are closed on the left.
+        let x_: Vec<f64> = (1..=8).map(|v| v as f64).collect();
+        let y_ = [0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0];
+        let buckets = |x: &[f64], e: &[f64], n: usize, closed: Closed| {
+            let y: Vec<f64> = (0..x.len()).map(|i| y_[i % 8]).collect();
+            ValueCounts::new(
+                x,
+                &y,
+                None,
+                e,
+                false,
+                Some((n, closed)),
+                &Threads::default(),
+            )
+            .unwrap()
+            .into_feature(TargetType::Binary)
+        };
+        let feature = buckets(&x_, &[8.0], 4, Closed::Right);
+        assert_eq!(feature.vals_, vec![2.0, 4.0, 6.0, 7.0]);
+        assert_eq!(feature.exception_values_.totals_ct_, vec![1.0]);
+        assert_eq!(
+            feature.bucket_values(&[1.0, 2.5, 7.0, 8.0], Closed::Right),
+            vec![2.0, 4.0, 7.0, 8.0]
+        );
+        let feature = buckets(&x_, &[], 4, Closed::Left);
+        assert_eq!(feature.vals_, vec![1.0, 3.0, 5.0, 7.0]);
+        assert_eq!(
+            feature.bucket_values(&[1.0, 2.5, 8.0], Closed::Left),
+            vec![1.0, 1.0, 7.0]
+        );
+
+        // Across many runs, every record is still counted, and the
+        // buckets are close to the quantiles of the values.
+        let x_: Vec<f64> = (0..10_000).map(|i| ((i * 7_919) % 10_000) as f64).collect();
+        let feature = buckets(&x_, &[], 4, Closed::Right);
+        assert!(feature.vals_.len() <= 4);
+        assert_eq!(*feature.cuml_totals_ct_.last().unwrap(), 10_000.0);
+        assert_eq!(*feature.vals_.last().unwrap(), 9_999.0);
+        for (v, q) in feature.vals_.iter().zip([2_500.0, 5_000.0, 7_500.0]) {
+            assert!((v - q).abs() < 500.0, "{} is not close to {}", v, q);
+        }
+
+        // The counts are the same in parallel.
+        #[cfg(feature = "rayon")]
+        for n_threads in [1, 3] {
+            let y: Vec<f64> = (0..x_.len()).map(|i| y_[i % 8]).collect();
+            let parallel = ValueCounts::new(
+                &x_,
+                &y,
+                None,
+                &[],
+                false,
+                Some((4, Closed::Right)),
+                &Threads::new(Some(n_threads)).unwrap(),
+            )
+            .unwrap()
+            .into_feature(TargetType::Binary);
+            assert!(parallel.vals_.len() <= 4);
+            assert_eq!(
+                parallel.cuml_totals_ct_.last(),
+                feature.cuml_totals_ct_.last()
+            );
+            assert_eq!(parallel.cuml_ones_ct_.last(), feature.cuml_ones_ct_.last());
+        }
+    }
+
     #[test]
     fn test_split_iv_woe() {
         let x_ = vec![6.2375, 6.4375, 0.0, 0.0, 4.0125, 5.0, 6.45, 6.4958, 6.4958];
         let y_ = vec![0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0];
         let w_ = vec![1.0; x_.len()];
-        let f = Feature::new(&x_, &y_, &w_, &Vec::new()).unwrap();
+        let f = Feature::new(&x_, &y_, Some(&w_), &Vec::new()).unwrap();
         assert_eq!(
             // 0, 4, 5, (Split on 5.0)
             f.split_iv_woe(2, 0, f.vals_.len()),
@@ -345,12 +1978,34 @@ mod test {
             )
         )
     }
+    #[test]
+    fn test_woe_regularization() {
+        let x_ = vec![1.0, 1.0, 2.0, 2.0, 3.0, 3.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut f = Feature::new(&x_, &y_, Some(&w_), &[-1.0]).unwrap();
+        assert_eq!(f.range_iv_woe(0, 1).1, f64::INFINITY);
+        assert_eq!(f.exception_values_.woe_[0], f64::INFINITY);
+        f.set_woe_regularization(0.5);
+        let (iv, woe) = f.range_iv_woe(0, 1);
+        assert_eq!(woe, ((2.5_f64 / 4.0) / (0.5 / 3.0)).ln());
+        assert!(iv.is_finite());
+        assert_eq!(
+            f.exception_values_.woe_[0],
+            ((1.5_f64 / 4.0) / (0.5 / 3.0)).ln()
+        );
+        let ((_, lhs_woe), (_, rhs_woe)) = f.split_iv_woe(1, 0, 3);
+        assert_eq!(lhs_woe, f.range_iv_woe(0, 2).1);
+        assert_eq!(rhs_woe, f.range_iv_woe(2, 3).1);
+        assert!(rhs_woe.is_finite());
+    }
+
     #[test]
     fn test_split_totals_ct_ones_ct() {
         let x_ = vec![6.2375, 6.4375, 0.0, 0.0, 4.0125, 5.0, 6.45, 6.4958, 6.4958];
         let y_ = vec![0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0];
         let w_ = vec![1.0; x_.len()];
-        let f = Feature::new(&x_, &y_, &w_, &Vec::new()).unwrap();
+        let f = Feature::new(&x_, &y_, Some(&w_), &Vec::new()).unwrap();
         assert_eq!(
             f.split_totals_ct_ones_ct(2, 0, f.vals_.len()),
             ((4.0, 2.0), (5.0, 3.0))
@@ -360,26 +2015,60 @@ mod test {
         assert_eq!(f.split_totals_ct_ones_ct(1, 1, 5), ((2.0, 1.0), (2.0, 1.0)))
     }
     #[test]
-    fn test_accumulate() {
-        let v = vec![1.0, 2.0, 3.0, 4.0];
-        let cuml_v: Vec<f64> = v
-            .iter()
-            .scan(0.0, |acc, &x| {
-                *acc = *acc + x;
-                Some(*acc)
-            })
-            .collect();
-        let mut cuml_rl_v: Vec<f64> = v
-            .iter()
-            .rev()
-            .scan(0.0, |acc, &x| {
-                *acc = *acc + x;
-                Some(*acc)
-            })
-            .collect();
-        cuml_rl_v.reverse();
+    fn test_split_effective_ct() {
+        let x_ = vec![1.0, 1.0, 2.0, 2.0, 3.0, 3.0];
+        let y_ = vec![1.0, 0.0, 1.0, 0.0, 1.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let f = Feature::new(&x_, &y_, Some(&w_), &Vec::new()).unwrap();
+        // Unit weights, the effective count is the record count.
+        assert_eq!(f.split_effective_ct(0, 0, f.vals_.len()), (2.0, 4.0));
 
+        // A single dominant weight pulls the effective count
+        // towards one.
+        let w_ = vec![1.0, 1.0, 1.0, 1.0, 1.0, 9.0];
+        let f = Feature::new(&x_, &y_, Some(&w_), &Vec::new()).unwrap();
+        assert_eq!(
+            f.split_effective_ct(1, 0, f.vals_.len()),
+            (4.0, 100.0 / 82.0)
+        );
+    }
+
+    #[test]
+    fn test_quantile_split_candidates() {
+        let x_: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        let y_: Vec<f64> = (0..100).map(|i| (i % 2) as f64).collect();
+        let w_ = vec![1.0; x_.len()];
+        let f = Feature::new(&x_, &y_, Some(&w_), &Vec::new()).unwrap();
+        assert_eq!(f.quantile_split_candidates(3, 0, 100), vec![24, 49, 74]);
+        assert_eq!(f.quantile_split_candidates(1, 50, 100), vec![24]);
+        // More candidates than values.
+        assert_eq!(f.quantile_split_candidates(10, 0, 3), vec![0, 1]);
+        assert!(f.quantile_split_candidates(10, 0, 1).is_empty());
+    }
+
+    #[test]
+    fn test_accumulate() {
+        let v = [1.0, 2.0, 3.0, 4.0];
+        let cuml_v = cumulative(&v);
+        let cuml_rl_v = cumulative_rl(&v);
         assert_eq!(cuml_v, vec![1.0, 3.0, 6.0, 10.0]);
         assert_eq!(cuml_rl_v, vec![10.0, 9.0, 7.0, 4.0]);
+        assert_eq!(sum_of_cuml_subarray(&cuml_v, &cuml_rl_v, 0, 1), 3.0);
+        assert_eq!(sum_of_cuml_subarray(&cuml_v, &cuml_rl_v, 1, 2), 5.0);
+        assert_eq!(sum_of_cuml_subarray(&cuml_v, &cuml_rl_v, 2, 3), 7.0);
+    }
+
+    #[test]
+    fn test_rhs_sums_are_exact() {
+        // A large value followed by small values, the right hand side
+        // sums would lose precision if found by subtraction.
+        let x_ = vec![1.0, 2.0, 3.0];
+        let y_ = vec![0.0, 1.0, 1.0];
+        let w_ = vec![1e16, 1.0, 1.0];
+        let f = Feature::new(&x_, &y_, Some(&w_), &Vec::new()).unwrap();
+        let ((lhs_ct, _), (rhs_ct, rhs_ones)) = f.split_totals_ct_ones_ct(0, 0, 3);
+        assert_eq!(lhs_ct, 1e16);
+        assert_eq!(rhs_ct, 2.0);
+        assert_eq!(rhs_ones, 2.0);
     }
 }
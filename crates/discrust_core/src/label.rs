@@ -0,0 +1,172 @@
+use crate::datetime::FeatureType;
+use crate::strategy::Closed;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How bin edges and bins are rendered as strings, when
+/// labelling values, or exporting a fitted binning.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LabelFormat {
+    /// The number of decimal places to show, if None
+    /// the shortest exact representation is used.
+    pub decimals: Option<usize>,
+    /// Values with an absolute value greater than or equal
+    /// to this are shown in scientific notation.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float::option"))]
+    pub scientific_threshold: Option<f64>,
+    /// The bracket used at the lower, open, end of a bin.
+    pub open_bracket: char,
+    /// The bracket used at the upper, closed, end of a bin.
+    pub closed_bracket: char,
+    /// The label used for missing, NaN, values.
+    pub missing_label: String,
+    /// Group the digits of whole numbers in thousands
+    /// with a comma.
+    pub thousands_separator: bool,
+}
+
+impl Default for LabelFormat {
+    fn default() -> Self {
+        LabelFormat {
+            decimals: None,
+            scientific_threshold: None,
+            open_bracket: '(',
+            closed_bracket: ']',
+            missing_label: String::from("Missing"),
+            thousands_separator: false,
+        }
+    }
+}
+
+impl LabelFormat {
+    /// Render a single value, such as a bin edge. Datetime
+    /// features are always rendered as dates.
+    pub fn format_value(&self, v: f64, feature_type: &FeatureType) -> String {
+        if v.is_nan() {
+            return self.missing_label.to_owned();
+        }
+        if let FeatureType::Datetime { .. } = feature_type {
+            return feature_type.format_value(v);
+        }
+        if !v.is_finite() {
+            return format!("{}", v);
+        }
+        if let Some(t) = self.scientific_threshold {
+            if (v != 0.0) & (v.abs() >= t) {
+                return match self.decimals {
+                    Some(d) => format!("{:.*e}", d, v),
+                    None => format!("{:e}", v),
+                };
+            }
+        }
+        let s = match self.decimals {
+            Some(d) => format!("{:.*}", d, v),
+            None => format!("{}", v),
+        };
+        if self.thousands_separator {
+            group_thousands(&s)
+        } else {
+            s
+        }
+    }
+
+    /// Render the bin between `lower` and `upper`, such as `(6.95, 7.125]`.
+    /// If bins are closed on the left, the brackets are mirrored and
+    /// swapped, so the closed bracket is at the lower end, `[6.95, 7.125)`.
+    pub fn format_bin(
+        &self,
+        lower: f64,
+        upper: f64,
+        feature_type: &FeatureType,
+        closed: Closed,
+    ) -> String {
+        let (lower_bracket, upper_bracket) = match closed {
+            Closed::Left => (mirror(self.closed_bracket), mirror(self.open_bracket)),
+            Closed::Right => (self.open_bracket, self.closed_bracket),
+        };
+        format!(
+            "{}{}, {}{}",
+            lower_bracket,
+            self.format_value(lower, feature_type),
+            self.format_value(upper, feature_type),
+            upper_bracket
+        )
+    }
+}
+
+// The bracket facing the other way, so `]` for `[`.
+fn mirror(bracket: char) -> char {
+    match bracket {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        c => c,
+    }
+}
+
+// Insert a comma between every three digits of the whole part of a number.
+fn group_thousands(s: &str) -> String {
+    let (sign, unsigned) = match s.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", s),
+    };
+    let (whole, fraction) = match unsigned.find('.') {
+        Some(i) => unsigned.split_at(i),
+        None => (unsigned, ""),
+    };
+    let mut grouped = String::with_capacity(s.len() + whole.len() / 3);
+    for (i, c) in whole.chars().enumerate() {
+        if (i > 0) & ((whole.len() - i) % 3 == 0) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    format!("{}{}{}", sign, grouped, fraction)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_format_value() {
+        let ft = FeatureType::Numeric;
+        let fmt = LabelFormat::default();
+        assert_eq!(fmt.format_value(6.95, &ft), "6.95");
+        assert_eq!(fmt.format_value(f64::NAN, &ft), "Missing");
+        assert_eq!(fmt.format_value(f64::NEG_INFINITY, &ft), "-inf");
+        let fmt = LabelFormat {
+            decimals: Some(2),
+            scientific_threshold: Some(1e9),
+            thousands_separator: true,
+            ..LabelFormat::default()
+        };
+        assert_eq!(fmt.format_value(-1234567.891, &ft), "-1,234,567.89");
+        assert_eq!(fmt.format_value(123.0, &ft), "123.00");
+        assert_eq!(fmt.format_value(1.5e10, &ft), "1.50e10");
+    }
+
+    #[test]
+    fn test_format_bin() {
+        let ft = FeatureType::Numeric;
+        let fmt = LabelFormat {
+            open_bracket: ']',
+            closed_bracket: ']',
+            ..LabelFormat::default()
+        };
+        assert_eq!(
+            fmt.format_bin(f64::NEG_INFINITY, 6.95, &ft, Closed::Right),
+            "]-inf, 6.95]"
+        );
+        assert_eq!(
+            fmt.format_bin(6.95, f64::INFINITY, &ft, Closed::Left),
+            "[6.95, inf["
+        );
+        assert_eq!(
+            LabelFormat::default().format_bin(1.0, 2.0, &ft, Closed::Left),
+            "[1, 2)"
+        );
+    }
+}
@@ -1,9 +1,76 @@
+mod builder;
+mod categorical;
+mod codes;
+mod compact;
+#[cfg(feature = "csv")]
+mod csv_file;
+mod datetime;
 mod discretize;
+mod edit;
+mod ensemble;
 mod errors;
+mod export;
 mod feature;
+mod label;
+mod metadata;
+mod monitor;
+mod monotonic;
+mod multiclass;
 mod node;
+mod onehot;
+mod onnx;
+mod parallel;
+mod partial;
+mod partition;
+mod performance;
+mod pipeline;
+mod pmml;
+pub mod prelude;
+mod privacy;
+mod rank;
+mod recompute;
+mod report;
+mod scorecard;
+mod segment;
+#[cfg(feature = "serde")]
+mod serde_float;
+mod set;
+mod spec;
+mod sql;
+mod stability;
+mod strategy;
+mod summary;
+mod transform;
+mod tree;
 mod utils;
 
+pub use builder::DiscretizerBuilder;
+pub use codes::{BinCodes, MISSING_CODE};
+pub use datetime::{DateSnap, FeatureType, TimeUnit};
 pub use discretize::Discretizer;
-pub use errors::DiscrustError;
+pub use ensemble::EnsembleDiscretizer;
+pub use errors::{Column, DiscrustError, NanValues, UnsatisfiableConstraint};
+pub use export::ExportOutput;
 pub use feature::ExceptionValues;
+pub use label::LabelFormat;
+pub use metadata::FitMetadata;
+pub use monitor::PredictReport;
+pub use pipeline::{Pipeline, PipelineOutput};
+pub use privacy::NoisedBinSummary;
+pub use rank::{rank_features, FeatureRank};
+pub use report::{FitEvent, FitReport, StopReason};
+pub use scorecard::{Scorecard, ScorecardRow, ScorecardScaling};
+pub use segment::{BinConsistency, SegmentMode, SegmentedDiscretizer};
+pub use set::DiscretizerSet;
+pub use spec::{InputSpec, SpecViolation};
+pub use sql::SqlDialect;
+pub use stability::{
+    BinFrequency, CharacteristicAnalysis, CharacteristicBin, ScoreDistribution, ShiftSimulation,
+    ShiftedBin,
+};
+pub use strategy::{
+    Closed, Growth, MonotonicTrend, OnMissing, SplitPoint, Strategy, TargetType, TieBreak,
+};
+pub use summary::{BinSummary, ExceptionSummary, FitResult, FitWarning};
+pub use transform::PreTransform;
+pub use tree::TreeNode;
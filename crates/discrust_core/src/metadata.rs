@@ -0,0 +1,156 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::export::{json_number, json_string, ExportOutput};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+#[cfg(feature = "std-time")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Where a fitted discretizer came from, recorded when it is fit,
+/// so a binning artifact can be traced back to the code, parameters
+/// and data that produced it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FitMetadata {
+    /// The version of discrust_core the discretizer was fit with.
+    pub crate_version: String,
+    /// When the discretizer was fit, in seconds since the Unix epoch.
+    /// Without the `std-time` feature, such as on WebAssembly, where there
+    /// is no system clock, this is zero, unless set with `set_fitted_at`.
+    pub fitted_at: u64,
+    /// The value of each fitting parameter.
+    pub parameters: BTreeMap<String, String>,
+    /// The number of records the discretizer was fit on.
+    pub n_records: usize,
+    /// The sum of the sample weights.
+    pub total_weight: f64,
+    /// The weighted share of the records that are ones.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float"))]
+    pub event_rate: f64,
+    /// The user supplied tags of the discretizer, when it was fit.
+    pub tags: BTreeMap<String, String>,
+}
+
+impl FitMetadata {
+    pub(crate) fn new(disc: &Discretizer, n_records: usize, ones: f64, zero: f64) -> Self {
+        let total_weight = ones + zero;
+        FitMetadata {
+            crate_version: String::from(env!("CARGO_PKG_VERSION")),
+            fitted_at: now(),
+            parameters: disc.parameters(),
+            n_records,
+            total_weight,
+            event_rate: ones / total_weight,
+            tags: disc.tags.clone(),
+        }
+    }
+
+    /// The metadata as a JSON object.
+    pub fn to_json(&self) -> String {
+        let map = |m: &BTreeMap<String, String>| {
+            let entries: Vec<String> = m
+                .iter()
+                .map(|(k, v)| format!("{}:{}", json_string(k), json_string(v)))
+                .collect();
+            format!("{{{}}}", entries.join(","))
+        };
+        format!(
+            "{{\"crate_version\":{},\"fitted_at\":{},\"parameters\":{},\"n_records\":{},\"total_weight\":{},\"event_rate\":{},\"tags\":{}}}",
+            json_string(&self.crate_version),
+            self.fitted_at,
+            map(&self.parameters),
+            self.n_records,
+            json_number(self.total_weight),
+            json_number(self.event_rate),
+            map(&self.tags),
+        )
+    }
+}
+
+// The seconds since the Unix epoch, a clock set before
+// the epoch is recorded as the epoch.
+#[cfg(feature = "std-time")]
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(not(feature = "std-time"))]
+fn now() -> u64 {
+    0
+}
+
+impl Discretizer {
+    /// The metadata recorded when the discretizer was fit.
+    pub fn metadata(&self) -> Result<&FitMetadata, DiscrustError> {
+        self.metadata_.as_ref().ok_or(DiscrustError::NotFitted)
+    }
+
+    /// Set when the discretizer was fit, in seconds since the Unix epoch,
+    /// for targets without a system clock, where the caller has the time.
+    pub fn set_fitted_at(&mut self, fitted_at: u64) -> Result<(), DiscrustError> {
+        let metadata = self.metadata_.as_mut().ok_or(DiscrustError::NotFitted)?;
+        metadata.fitted_at = fitted_at;
+        Ok(())
+    }
+
+    /// Export the fitted binning as a JSON document, with the `metadata`
+    /// of the fit, and the JSON Logic rule returned by `to_json_logic`
+    /// under the `json_logic` key.
+    pub fn to_json_logic_document(
+        &self,
+        var: &str,
+        output: ExportOutput,
+    ) -> Result<String, DiscrustError> {
+        let logic = self.to_json_logic(var, output)?;
+        Ok(format!(
+            "{{\"metadata\":{},\"json_logic\":{}}}",
+            self.metadata()?.to_json(),
+            logic
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_metadata() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+        let w_ = vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 3.0];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        disc.tags
+            .insert(String::from("model"), String::from("pd_v2"));
+        assert!(disc.metadata().is_err());
+        disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        let metadata = disc.metadata().unwrap();
+        assert_eq!(metadata.crate_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(metadata.fitted_at > 0, cfg!(feature = "std-time"));
+        assert_eq!(metadata.n_records, 8);
+        assert_eq!(metadata.total_weight, 10.0);
+        assert_eq!(metadata.event_rate, 0.4);
+        assert_eq!(metadata.parameters["min_obs"], "1");
+        assert_eq!(metadata.parameters["max_bins"], "10");
+        assert_eq!(metadata.tags["model"], "pd_v2");
+        assert!(disc.unfitted_copy().metadata().is_err());
+        assert!(disc.unfitted_copy().set_fitted_at(1).is_err());
+        assert_eq!(disc.unfitted_copy().tags, disc.tags);
+
+        disc.set_fitted_at(1_700_000_000).unwrap();
+        assert_eq!(disc.metadata().unwrap().fitted_at, 1_700_000_000);
+
+        let document = disc
+            .to_json_logic_document("x", ExportOutput::Index)
+            .unwrap();
+        assert!(document.starts_with("{\"metadata\":{\"crate_version\":"));
+        assert!(document.contains("\"tags\":{\"model\":\"pd_v2\"}"));
+        assert!(document.ends_with(&format!(
+            ",\"json_logic\":{}}}",
+            disc.to_json_logic("x", ExportOutput::Index).unwrap()
+        )));
+    }
+}
@@ -0,0 +1,178 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::feature::Feature;
+use crate::utils::{nan_safe_compare, to_f64};
+use num::Float;
+use std::cmp::Ordering;
+
+/// The counts of the records of a sample predicted by a fitted
+/// discretizer, by how each was binned, for monitoring the quality
+/// of the data being scored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PredictReport {
+    pub n_records: usize,
+    /// Exception values, including exception values merged into a bin.
+    pub exception_ct: usize,
+    /// Missing values, in the missing bin, or predicted according
+    /// to `on_missing`.
+    pub missing_ct: usize,
+    /// Values below the smallest value the discretizer was fit on,
+    /// which are predicted in the lowest bin.
+    pub below_range_ct: usize,
+    /// Values above the largest value the discretizer was fit on,
+    /// which are predicted in the highest bin.
+    pub above_range_ct: usize,
+}
+
+impl PredictReport {
+    /// The number of values outside the range the discretizer was fit on.
+    pub fn out_of_range_ct(&self) -> usize {
+        self.below_range_ct + self.above_range_ct
+    }
+}
+
+impl Discretizer {
+    /// Flag the values of `x` outside the range of the values the
+    /// discretizer was fit on, which are silently predicted in the
+    /// lowest, or highest, bin. Exception and missing values are never
+    /// out of range, nor is any value of a discretizer loaded from a
+    /// version that did not record the range.
+    pub fn out_of_range<T: Float>(&self, x: &[T]) -> Result<Vec<bool>, DiscrustError> {
+        let feature = self.range_feature(x)?;
+        Ok(x.iter()
+            .map(|v| self.range_side(&to_f64(*v), feature) != Ordering::Equal)
+            .collect())
+    }
+
+    /// Count the values of `x` by how they are binned, the exception
+    /// values, missing values, and values outside the range of the
+    /// values the discretizer was fit on.
+    pub fn predict_report<T: Float>(&self, x: &[T]) -> Result<PredictReport, DiscrustError> {
+        let feature = self.range_feature(x)?;
+        let mut report = PredictReport {
+            n_records: x.len(),
+            exception_ct: 0,
+            missing_ct: 0,
+            below_range_ct: 0,
+            above_range_ct: 0,
+        };
+        for v in x.iter().map(|v| to_f64(*v)) {
+            if feature.exception_values_.exception_idx(&v).is_some()
+                || feature.merged_value(&v).is_some()
+            {
+                report.exception_ct += 1;
+            } else if feature.in_missing_bin(&v) || self.is_unseen_missing(&v, feature) {
+                report.missing_ct += 1;
+            } else {
+                match self.range_side(&v, feature) {
+                    Ordering::Less => report.below_range_ct += 1,
+                    Ordering::Greater => report.above_range_ct += 1,
+                    Ordering::Equal => (),
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    // The smallest, and largest, of the values of `x` the splits are
+    // fit on, leaving out missing values, and the exception values `e`.
+    pub(crate) fn value_range(&self, x: &[f64], e: &[f64]) -> (Option<f64>, Option<f64>) {
+        let mut range: (Option<f64>, Option<f64>) = (None, None);
+        for v in x {
+            if v.is_nan()
+                || self.input_spec.as_ref().is_some_and(|s| s.is_code(v))
+                || e.iter()
+                    .any(|ev| nan_safe_compare(ev, v) == Ordering::Equal)
+            {
+                continue;
+            }
+            range = (
+                Some(range.0.map_or(*v, |m| m.min(*v))),
+                Some(range.1.map_or(*v, |m| m.max(*v))),
+            );
+        }
+        range
+    }
+
+    // The fitted feature, once the values of `x` have been checked.
+    fn range_feature<T: Float>(&self, x: &[T]) -> Result<&Feature, DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        if !self.categories_.is_empty() {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "a categorical feature has no range of numeric values",
+            )));
+        }
+        self.check_input(x)?;
+        Ok(feature)
+    }
+
+    // Whether a value falls below, or above, the range of the values
+    // the discretizer was fit on. Exception and missing values are
+    // always within the range.
+    pub(crate) fn range_side(&self, v: &f64, feature: &Feature) -> Ordering {
+        if feature.exception_values_.exception_idx(v).is_some()
+            || feature.merged_value(v).is_some()
+            || feature.in_missing_bin(v)
+            || self.is_unseen_missing(v, feature)
+        {
+            return Ordering::Equal;
+        }
+        match (feature.min_, feature.max_) {
+            (Some(min), _) if *v < min => Ordering::Less,
+            (_, Some(max)) if *v > max => Ordering::Greater,
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_out_of_range() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.out_of_range(&x_).is_err());
+        disc.fit(&x_, &y_, None, Some(vec![-1.0])).unwrap();
+        let feature = disc.feature.as_ref().unwrap();
+        assert_eq!((feature.min_, feature.max_), (Some(1.0), Some(2.0)));
+
+        let x_new = [0.5, 1.0, 1.5, 2.0, 10.0, -1.0, f64::NAN];
+        assert_eq!(
+            disc.out_of_range(&x_new).unwrap(),
+            vec![true, false, false, false, true, false, false]
+        );
+        // The out of range values are still predicted in the edge bins.
+        assert_eq!(disc.predict_idx(&[0.5, 10.0]).unwrap(), vec![0, 1]);
+        let report = disc.predict_report(&x_new).unwrap();
+        assert_eq!(
+            report,
+            PredictReport {
+                n_records: 7,
+                exception_ct: 1,
+                missing_ct: 1,
+                below_range_ct: 1,
+                above_range_ct: 1,
+            }
+        );
+        assert_eq!(report.out_of_range_ct(), 2);
+    }
+
+    #[test]
+    fn test_value_range_before_clipping() {
+        // The range is of the values as passed, before they are clipped.
+        let x_: Vec<f64> = (1..=100).map(|v| v as f64).collect();
+        let y_: Vec<f64> = (1..=100).map(|v| (v % 3 == 0) as i32 as f64).collect();
+        let mut disc = Discretizer::builder()
+            .min_obs(5.0)
+            .clip_quantiles((0.1, 0.9))
+            .build()
+            .unwrap();
+        disc.fit(&x_, &y_, None, None).unwrap();
+        let feature = disc.feature.as_ref().unwrap();
+        assert_eq!((feature.min_, feature.max_), (Some(1.0), Some(100.0)));
+        assert_eq!(disc.out_of_range(&[0.0, 50.0]).unwrap(), vec![true, false]);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::strategy::MonotonicTrend;
+
+impl Discretizer {
+    /// Merge adjacent bins until the weight of evidence of the bins,
+    /// from the lowest to the highest, follows the trend of `mono`.
+    /// The tree only requires the trend between the two halves of each
+    /// split, so bins in different branches can still break it. For a
+    /// peak, or valley, the turning point is the bin with the highest,
+    /// or lowest, weight of evidence. Each pass merges the first pair of
+    /// bins that breaks the trend. Returns the number of bins merged.
+    pub fn merge_non_monotonic_bins(&mut self) -> Result<usize, DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        let mut merged = 0;
+        loop {
+            let woe: Vec<f64> = self
+                .bin_summary_with(feature)
+                .iter()
+                .map(|b| b.woe)
+                .collect();
+            let turn = match self.mono {
+                MonotonicTrend::Peak => extreme_position(&woe, |a, b| a > b),
+                MonotonicTrend::Valley => extreme_position(&woe, |a, b| a < b),
+                _ => 0,
+            };
+            let trend = |i: usize| match self.mono {
+                MonotonicTrend::Peak if i < turn => MonotonicTrend::Ascending,
+                MonotonicTrend::Peak => MonotonicTrend::Descending,
+                MonotonicTrend::Valley if i < turn => MonotonicTrend::Descending,
+                MonotonicTrend::Valley => MonotonicTrend::Ascending,
+                t => t,
+            };
+            let violation = woe
+                .windows(2)
+                .enumerate()
+                .position(|(i, w)| !trend(i).allows(w[0], w[1]));
+            match violation {
+                // The split between the two bins is removed.
+                Some(i) => {
+                    self.splits_.remove(i + 1);
+                    merged += 1;
+                }
+                None => return Ok(merged),
+            }
+        }
+    }
+}
+
+// The position of the value that is preferred over every other value.
+fn extreme_position<F: Fn(f64, f64) -> bool>(v: &[f64], prefer: F) -> usize {
+    let mut best = 0;
+    for (i, x) in v.iter().enumerate() {
+        if prefer(*x, v[best]) {
+            best = i;
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_merge_non_monotonic_bins() {
+        let x_: Vec<f64> = (1..=8).map(|v| v as f64).collect();
+        let y_ = vec![0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(0.0), Some(1));
+        assert!(disc.merge_non_monotonic_bins().is_err());
+        disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        // Bins of two records each, where the
+        // third breaks the ascending trend.
+        disc.splits_ = vec![f64::NEG_INFINITY, 2.0, 4.0, 6.0, f64::INFINITY];
+        assert_eq!(disc.merge_non_monotonic_bins().unwrap(), 1);
+        assert_eq!(
+            disc.splits_,
+            vec![f64::NEG_INFINITY, 2.0, 4.0, f64::INFINITY]
+        );
+        let woe: Vec<f64> = disc.bin_summary().unwrap().iter().map(|b| b.woe).collect();
+        assert!(woe.windows(2).all(|w| w[0] < w[1]));
+
+        // Nothing is merged when no trend is required.
+        disc.splits_ = vec![f64::NEG_INFINITY, 2.0, 4.0, 6.0, f64::INFINITY];
+        disc.mono = MonotonicTrend::Unconstrained;
+        assert_eq!(disc.merge_non_monotonic_bins().unwrap(), 0);
+        disc.mono = MonotonicTrend::Descending;
+        assert_eq!(disc.merge_non_monotonic_bins().unwrap(), 2);
+        assert_eq!(disc.splits_, vec![f64::NEG_INFINITY, 6.0, f64::INFINITY]);
+    }
+
+    #[test]
+    fn test_fit_enforce_monotonicity() {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
+        }
+        let w_ = vec![1.0; fare.len()];
+        let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), Some(1));
+        disc.enforce_monotonicity = true;
+        let res = disc.fit(&fare, &survived, Some(&w_), None).unwrap();
+        assert!(res.bins.windows(2).all(|b| b[0].woe < b[1].woe));
+        assert_eq!(disc.merge_non_monotonic_bins().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_merge_non_monotonic_bins_peak() {
+        assert_eq!(extreme_position(&[0.0, 2.0, 1.0, 2.0], |a, b| a > b), 1);
+        let x_: Vec<f64> = (1..=10).map(|v| v as f64).collect();
+        let y_ = vec![0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(0.0), None);
+        disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        disc.mono = MonotonicTrend::Peak;
+        disc.splits_ = vec![f64::NEG_INFINITY, 2.0, 4.0, 6.0, 8.0, f64::INFINITY];
+        assert_eq!(disc.merge_non_monotonic_bins().unwrap(), 0);
+        disc.splits_ = vec![f64::NEG_INFINITY, 2.0, 3.0, 4.0, 6.0, 8.0, f64::INFINITY];
+        // The bin of 3.0 alone is the peak, and the bin of 4.0
+        // is followed by a higher bin on the falling side.
+        assert_eq!(disc.merge_non_monotonic_bins().unwrap(), 1);
+        assert_eq!(
+            disc.splits_,
+            vec![f64::NEG_INFINITY, 2.0, 3.0, 6.0, 8.0, f64::INFINITY]
+        );
+    }
+}
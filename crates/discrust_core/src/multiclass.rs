@@ -0,0 +1,146 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::strategy::{OnMissing, TargetType};
+use num::Float;
+
+impl Discretizer {
+    /// The one-vs-rest weight of evidence of each class of a multiclass
+    /// target, for each bin, followed by each exception value, and the
+    /// missing bin, in the same positions as `bin_woe_`. There is a vector
+    /// for each class, in the order of the class labels. Bins, and exception
+    /// values, with no records have a weight of evidence of zero.
+    pub fn bin_woe_multiclass_(&self) -> Result<Vec<Vec<f64>>, DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        if self.target_type != TargetType::Multiclass {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "a weight of evidence for each class requires a multiclass target",
+            )));
+        }
+        // The weighted count, and weight of evidence of each class, of each position.
+        let mut positions: Vec<(f64, Vec<f64>)> = self
+            .bin_ranges(feature)
+            .into_iter()
+            .map(|(start, stop)| {
+                (
+                    feature.range_totals_ones_zero_ct(start, stop).0,
+                    feature.range_class_woe(start, stop),
+                )
+            })
+            .collect();
+        let exceptions = &feature.exception_values_;
+        positions.extend((0..exceptions.vals_.len()).map(|i| {
+            (
+                exceptions.totals_ct_[i],
+                feature.exception_class_woe(exceptions, i),
+            )
+        }));
+        if feature.in_missing_bin(&f64::NAN) {
+            positions.push((
+                feature.missing_.totals_ct_[0],
+                feature.exception_class_woe(&feature.missing_, 0),
+            ));
+        }
+        Ok((0..feature.n_classes())
+            .map(|c| {
+                positions
+                    .iter()
+                    .map(|(ct, woe)| if *ct == 0.0 { 0.0 } else { woe[c] })
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// The one-vs-rest weight of evidence of each class of a multiclass
+    /// target, of the bin, or exception value, of each value, with a vector
+    /// for each class. Missing values given a weight of evidence by
+    /// `on_missing` are predicted that weight of evidence for every class.
+    pub fn predict_woe_multiclass<T: Float>(
+        &self,
+        x: &[T],
+    ) -> Result<Vec<Vec<f64>>, DiscrustError> {
+        let bin_woe = self.bin_woe_multiclass_()?;
+        let idx = self.predict_idx(x)?;
+        let missing_woe = match self.on_missing {
+            OnMissing::WoeValue(woe) => woe,
+            _ => f64::NAN,
+        };
+        Ok(bin_woe
+            .iter()
+            .map(|class_woe| {
+                idx.iter()
+                    .map(|i| {
+                        self.index_position(*i)
+                            .map_or(missing_woe, |p| class_woe[p])
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::feature::iv_woe;
+    use crate::strategy::MonotonicTrend;
+
+    #[test]
+    fn test_multiclass_target() {
+        // Class 0 is most common up to 4, class 1 up to 8, and class 2 above.
+        let x_: Vec<f64> = (1..=12).flat_map(|v| [v as f64, v as f64]).collect();
+        let y_ = vec![
+            2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 2.0, 1.0, 1.0, 0.0, 1.0, 0.0,
+            2.0, 2.0, 2.0, 2.0, 1.0, 2.0, 2.0,
+        ];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(4.0), Some(10), Some(0.01), Some(1.0), None);
+        disc.target_type = TargetType::Multiclass;
+        assert!(disc.bin_woe_multiclass_().is_err());
+        let res = disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        assert_eq!(res.splits, vec![f64::NEG_INFINITY, 4.0, 8.0, f64::INFINITY]);
+        assert_eq!(disc.mono, MonotonicTrend::Unconstrained);
+        assert!(res.bins.iter().all(|b| b.woe.is_nan()));
+        assert!(disc.predict_woe(&x_).is_err());
+
+        // The first bin has 6 of the 8 records of class 0, 1 of class 1, and 1 of class 2.
+        let woe = disc.predict_woe_multiclass(&[1.0, 12.0]).unwrap();
+        assert_eq!(woe.len(), 3);
+        assert_eq!(woe[0][0], iv_woe(6.0, 2.0, 8.0, 16.0, 0.0).1);
+        assert_eq!(woe[1][0], iv_woe(1.0, 7.0, 8.0, 16.0, 0.0).1);
+        assert_eq!(woe[2][1], disc.bin_woe_multiclass_().unwrap()[2][2]);
+        // The information value of each bin is the sum over the classes.
+        let iv: f64 = [(6.0, 2.0), (1.0, 7.0), (1.0, 7.0)]
+            .iter()
+            .map(|(c, rest)| iv_woe(*c, *rest, 8.0, 16.0, 0.0).0)
+            .sum();
+        assert!((res.bins[0].iv - iv).abs() < 1e-12);
+
+        // Exception values, and values moved to them, keep their class counts.
+        let res = disc.fit(&x_, &y_, Some(&w_), Some(vec![11.0])).unwrap();
+        assert!(res.exceptions[0].woe.is_nan());
+        disc.add_exception_value(4.0).unwrap();
+        let woe = disc.predict_woe_multiclass(&[4.0, 11.0, 1.0]).unwrap();
+        assert_eq!(woe[0][0], iv_woe(1.0, 1.0, 8.0, 16.0, 0.0).1);
+        assert_eq!(woe[2][1], iv_woe(1.0, 1.0, 8.0, 16.0, 0.0).1);
+        assert_eq!(woe[0][2], iv_woe(5.0, 1.0, 8.0, 16.0, 0.0).1);
+
+        // Every class must have a count of at least `min_pos` in each bin.
+        let mut disc = Discretizer::new(Some(4.0), Some(10), Some(0.01), Some(3.0), None);
+        disc.target_type = TargetType::Multiclass;
+        let res = disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        assert_eq!(res.splits, vec![f64::NEG_INFINITY, f64::INFINITY]);
+
+        let mut y_bad = y_.to_vec();
+        y_bad[0] = 0.5;
+        assert!(matches!(
+            disc.fit(&x_, &y_bad, Some(&w_), None),
+            Err(DiscrustError::InvalidTarget(_))
+        ));
+        assert!(disc
+            .fit(&x_, &vec![0.0; x_.len()], Some(&w_), None)
+            .is_err());
+        assert!(disc.partial_fit(&x_, &y_, Some(&w_), None).is_err());
+        disc.mono = MonotonicTrend::Ascending;
+        assert!(disc.fit(&x_, &y_, Some(&w_), None).is_err());
+    }
+}
@@ -1,18 +1,41 @@
 use crate::feature::Feature;
+use crate::partition::{best_partition, Partition};
+use crate::report::StopReason;
+use crate::strategy::{Closed, MonotonicTrend, SplitPoint, TargetType, TieBreak};
+use crate::utils::{mdlp_accepts, two_mean_pvalue, two_proportion_pvalue};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::cmp::PartialEq;
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SplitInfo {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float::option"))]
     pub split: Option<f64>,
     pub split_idx: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float::option"))]
     pub lhs_iv: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float::option"))]
     pub lhs_woe: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float::option"))]
     pub rhs_iv: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float::option"))]
     pub rhs_woe: Option<f64>,
 }
 
 impl SplitInfo {
-    pub fn new(split: f64, split_idx: usize, lhs_iv: f64, lhs_woe: f64, rhs_iv: f64, rhs_woe: f64) -> Self {
+    pub fn new(
+        split: f64,
+        split_idx: usize,
+        lhs_iv: f64,
+        lhs_woe: f64,
+        rhs_iv: f64,
+        rhs_woe: f64,
+    ) -> Self {
         SplitInfo {
             split: Some(split),
             split_idx: Some(split_idx),
@@ -36,28 +59,56 @@ impl SplitInfo {
 
 pub type NodePtr = Option<Box<Node>>;
 
+// The most values at weighted quantiles of a node searched for the cuts
+// of a multiway split, as the search grows with the cube of the count.
+const MAX_PARTITION_CANDIDATES: usize = 32;
+/// The best split of a node, or the reason it can not be split.
+pub type SplitResult = Result<SplitInfo, StopReason>;
+
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Node {
     min_obs: f64,
     min_iv: f64,
     min_pos: f64,
-    mono: Option<i8>,
+    min_effective_obs: Option<f64>,
+    approx_splits: Option<usize>,
+    max_pvalue: Option<f64>,
+    min_bin_width: Option<f64>,
+    max_bin_ct: Option<f64>,
+    mdlp: bool,
+    min_iv_gain_ratio: Option<f64>,
+    closed: Closed,
+    split_point: SplitPoint,
+    pub(crate) mono: MonotonicTrend,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float"))]
     pub woe: f64,
+    // Not used when predicting, but kept so the
+    // fitted tree can be inspected.
+    #[allow(dead_code)]
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float"))]
     pub iv: f64,
     pub start: usize,
     pub stop: usize,
     pub left_node: NodePtr,
     pub right_node: NodePtr,
     pub split_info: SplitInfo,
+    /// Why the node was not split, None if it was split, or
+    /// the tree was not grown by searching for splits.
+    pub stop_reason: Option<StopReason>,
 }
 
 impl Node {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         feature: &Feature,
         min_obs: Option<f64>,
         min_iv: Option<f64>,
         min_pos: Option<f64>,
-        mono: Option<i8>,
+        min_effective_obs: Option<f64>,
+        approx_splits: Option<usize>,
+        max_pvalue: Option<f64>,
+        mono: MonotonicTrend,
         woe: Option<f64>,
         iv: Option<f64>,
         start: Option<usize>,
@@ -74,6 +125,15 @@ impl Node {
             min_obs,
             min_iv,
             min_pos,
+            min_effective_obs,
+            approx_splits,
+            max_pvalue,
+            min_bin_width: None,
+            max_bin_ct: None,
+            mdlp: false,
+            min_iv_gain_ratio: None,
+            closed: Closed::default(),
+            split_point: SplitPoint::default(),
             mono,
             woe,
             iv,
@@ -82,9 +142,47 @@ impl Node {
             left_node: None,
             right_node: None,
             split_info: SplitInfo::new_empty(),
+            stop_reason: None,
         }
     }
 
+    /// Reject splits that would create a bin narrower than `min_bin_width`,
+    /// where the bins are closed on the `closed` side, and split at
+    /// `split_point`, so the width of a bin is between its splits.
+    pub(crate) fn with_min_bin_width(
+        mut self,
+        min_bin_width: Option<f64>,
+        closed: Closed,
+        split_point: SplitPoint,
+    ) -> Self {
+        self.min_bin_width = min_bin_width;
+        self.closed = closed;
+        self.split_point = split_point;
+        self
+    }
+
+    /// Split the node while it holds more than `max_bin_ct` of the weight,
+    /// even if the information value of the split is below `min_iv`.
+    pub(crate) fn with_max_bin_ct(mut self, max_bin_ct: Option<f64>) -> Self {
+        self.max_bin_ct = max_bin_ct;
+        self
+    }
+
+    /// Accept a split if it passes the minimum description length
+    /// test, in place of requiring the minimum information value.
+    pub(crate) fn with_mdlp(mut self, mdlp: bool) -> Self {
+        self.mdlp = mdlp;
+        self
+    }
+
+    /// Reject splits that gain less than `min_iv_gain_ratio` of the
+    /// node's information value over the node.
+    pub(crate) fn with_min_iv_gain_ratio(mut self, min_iv_gain_ratio: Option<f64>) -> Self {
+        self.min_iv_gain_ratio = min_iv_gain_ratio;
+        self
+    }
+
+    #[allow(dead_code)]
     pub fn is_terminal(&self) -> bool {
         self.left_node.is_none() && self.right_node.is_none()
     }
@@ -92,83 +190,401 @@ impl Node {
     fn eval_values<'a>(&self, feature: &'a Feature) -> &'a [f64] {
         // We do not need to evaluate the last value, as this is not a
         // valid value because there are no records greater than it.
+        // If the node is empty, which is the case when every record
+        // is an exception value, there are no values to evaluate.
+        if self.stop <= self.start {
+            return &[];
+        }
         feature.vals_[self.start..(self.stop - 1)].as_ref()
     }
 
-    pub fn find_best_split(&mut self, feature: &Feature) -> SplitInfo {
-        // loop through all the unique levels
-        // of the feature, identifying the split
-        // that generates the maximum information
-        // value
-        let mut best_iv = 0.0;
-        let mut best_lhs_iv = 0.0;
-        let mut best_lhs_woe = 0.0;
-        let mut best_rhs_iv = 0.0;
-        let mut best_rhs_woe = 0.0;
-        let mut best_split = -f64::INFINITY;
-        let mut best_split_idx = 0;
-
-        for (i, v) in self.eval_values(feature).iter().enumerate() {
-            let ((lhs_ct, lhs_ones), (rhs_ct, rhs_ones)) =
-                feature.split_totals_ct_ones_ct(i, self.start, self.stop);
-            // Min response
-            if (lhs_ones < self.min_pos) | (rhs_ones < self.min_pos) {
-                continue;
+    // The positions, relative to start, of the values to evaluate. If
+    // approximate splits are requested, only the values at weighted
+    // quantiles of the node are evaluated.
+    fn candidates(&self, feature: &Feature) -> Vec<usize> {
+        match self.approx_splits {
+            Some(n) => feature.quantile_split_candidates(n, self.start, self.stop),
+            None => (0..self.eval_values(feature).len()).collect(),
+        }
+    }
+
+    // Where the feature would be split between the values at `idx - 1` and
+    // `idx`, which is infinite beyond the first and last value.
+    fn edge(&self, feature: &Feature, idx: usize) -> f64 {
+        if idx == 0 {
+            return f64::NEG_INFINITY;
+        }
+        if idx >= feature.vals_.len() {
+            return f64::INFINITY;
+        }
+        let (below, above) = (feature.vals_[idx - 1], feature.vals_[idx]);
+        match (self.split_point, self.closed) {
+            (SplitPoint::Midpoint, _) => (below + above) / 2.0,
+            (SplitPoint::Observed, Closed::Left) => above,
+            (SplitPoint::Observed, Closed::Right) => below,
+        }
+    }
+
+    // Evaluate splitting the node after the value at position `i`, returning
+    // the first of the node's constraints the split does not satisfy.
+    fn evaluate_split(&self, feature: &Feature, i: usize) -> SplitResult {
+        let v = self.eval_values(feature)[i];
+        let ((lhs_ct, lhs_ones), (rhs_ct, rhs_ones)) =
+            feature.split_totals_ct_ones_ct(i, self.start, self.stop);
+        let split_idx = i + 1 + self.start;
+        // Min bin width, the distance between the splits of each half.
+        if let Some(min_bin_width) = self.min_bin_width {
+            let split = self.edge(feature, split_idx);
+            if (split - self.edge(feature, self.start) < min_bin_width)
+                | (self.edge(feature, self.stop) - split < min_bin_width)
+            {
+                return Err(StopReason::MinBinWidth);
+            }
+        }
+
+        // Min response, which applies to the count of every class of a
+        // multiclass performance, and not to a continuous performance.
+        let (lhs_pos, rhs_pos) = match feature.target_type_ {
+            TargetType::Binary => (lhs_ones, rhs_ones),
+            TargetType::Continuous => (f64::INFINITY, f64::INFINITY),
+            TargetType::Multiclass => (
+                feature.range_min_class_ct(self.start, split_idx),
+                feature.range_min_class_ct(split_idx, self.stop),
+            ),
+        };
+        if (lhs_pos < self.min_pos) | (rhs_pos < self.min_pos) {
+            return Err(StopReason::MinPos);
+        }
+
+        // Min observations count, if a minimum effective sample
+        // size was provided, it is used in place of the raw count.
+        match self.min_effective_obs {
+            Some(min_effective_obs) => {
+                let (lhs_eff, rhs_eff) = feature.split_effective_ct(i, self.start, self.stop);
+                if (lhs_eff < min_effective_obs) | (rhs_eff < min_effective_obs) {
+                    return Err(StopReason::MinObs);
+                }
+            }
+            None => {
+                if (lhs_ct < self.min_obs) | (rhs_ct < self.min_obs) {
+                    return Err(StopReason::MinObs);
+                }
             }
+        }
 
-            // Min observations count
-            if (lhs_ct < self.min_obs) | (rhs_ct < self.min_obs) {
-                continue;
+        // The event rates, or the means of a continuous
+        // performance, of the two halves must differ significantly.
+        if let Some(max_pvalue) = self.max_pvalue {
+            let pvalue = if feature.target_type_ == TargetType::Continuous {
+                two_mean_pvalue(
+                    feature.range_ct_mean_var(self.start, split_idx),
+                    feature.range_ct_mean_var(split_idx, self.stop),
+                )
+            } else {
+                two_proportion_pvalue((lhs_ct, lhs_ones), (rhs_ct, rhs_ones))
+            };
+            if pvalue > max_pvalue {
+                return Err(StopReason::MaxPvalue);
             }
+        }
 
-            // Get information value for split.
-            let ((lhs_iv, lhs_woe), (rhs_iv, rhs_woe)) =
-                feature.split_iv_woe(i, self.start, self.stop);
+        // Get information value for split.
+        let ((lhs_iv, lhs_woe), (rhs_iv, rhs_woe)) = feature.split_iv_woe(i, self.start, self.stop);
 
-            let total_iv = lhs_iv + rhs_iv;
-            if total_iv < self.min_iv {
-                continue;
+        // A split must have a positive information value
+        // to be considered, as well as meeting the minimum,
+        // unless the node holds too much of the weight.
+        // With the minimum description length test, the
+        // split must pass it in place of the minimum.
+        let total_iv = lhs_iv + rhs_iv;
+        let oversized = self.max_bin_ct.is_some_and(|m| lhs_ct + rhs_ct > m);
+        if self.mdlp {
+            let (lhs_class_ct, rhs_class_ct) = match feature.target_type_ {
+                TargetType::Multiclass => (
+                    feature.range_class_ct(self.start, split_idx),
+                    feature.range_class_ct(split_idx, self.stop),
+                ),
+                _ => (
+                    vec![lhs_ct - lhs_ones, lhs_ones],
+                    vec![rhs_ct - rhs_ones, rhs_ones],
+                ),
+            };
+            if total_iv.is_nan() | (!oversized & !mdlp_accepts(&lhs_class_ct, &rhs_class_ct)) {
+                return Err(StopReason::Mdlp);
+            }
+        } else if total_iv.is_nan() | (!oversized & ((total_iv < self.min_iv) | (total_iv <= 0.0)))
+        {
+            return Err(StopReason::MinIv);
+        }
+        if let Some(ratio) = self.min_iv_gain_ratio {
+            if !oversized & (total_iv - self.iv <= ratio * self.iv) {
+                return Err(StopReason::MinIvGain);
             }
+        }
 
-            // Monotonicity check
-            // We want to make sure the relationship between the
-            // parent node and these two nodes is following the
-            // monotonic requirements.
-            // If a monotonicity of None was passed, then we will chose the
-            // monotonicity of the best first split.
-            let split_sign = if lhs_woe < rhs_woe { 1 } else { -1 };
-            let check_mono = self.mono.unwrap_or(0);
-            if check_mono != 0 {
-                if check_mono == -1 {
-                    if split_sign == 1 {
-                        continue;
-                    }
-                } else if split_sign == -1 {
-                    continue;
-                }
+        // Monotonicity check
+        // We want to make sure the relationship between the
+        // parent node and these two nodes is following the
+        // monotonic requirements.
+        // If an automatic trend was passed, then we will chose the
+        // monotonicity of the best first split.
+        if !self.mono.allows(lhs_woe, rhs_woe) {
+            return Err(StopReason::Monotonicity);
+        }
+
+        // When cross validating, the split must also hold up on the
+        // records it was not chosen on.
+        if !feature.split_holds_across_folds(i, self.start, self.stop) {
+            return Err(StopReason::CrossValidation);
+        }
+        Ok(SplitInfo::new(v, i, lhs_iv, lhs_woe, rhs_iv, rhs_woe))
+    }
+
+    // Whether the records in `vals_[start..stop]` may be a bin of a
+    // multiway split, satisfying the same constraints as each half of
+    // a binary split.
+    fn bin_allowed(&self, feature: &Feature, start: usize, stop: usize) -> bool {
+        if let Some(min_bin_width) = self.min_bin_width {
+            if self.edge(feature, stop) - self.edge(feature, start) < min_bin_width {
+                return false;
             }
-            // Collect best
-            if total_iv > best_iv {
-                best_iv = total_iv;
-                best_split = *v;
-                best_split_idx = i;
-                best_lhs_iv = lhs_iv;
-                best_lhs_woe = lhs_woe;
-                best_rhs_iv = rhs_iv;
-                best_rhs_woe = rhs_woe;
+        }
+        let (ct, ones, _) = feature.range_totals_ones_zero_ct(start, stop);
+        let pos = match feature.target_type_ {
+            TargetType::Binary => ones,
+            TargetType::Continuous => f64::INFINITY,
+            TargetType::Multiclass => feature.range_min_class_ct(start, stop),
+        };
+        let obs = match self.min_effective_obs {
+            Some(min_effective_obs) => feature.effective_ct(start, stop) >= min_effective_obs,
+            None => ct >= self.min_obs,
+        };
+        (pos >= self.min_pos) & obs
+    }
+
+    // Whether the adjacent bins `vals_[start..cut]` and `vals_[cut..stop]`
+    // of a multiway split differ significantly.
+    fn bins_differ(&self, feature: &Feature, start: usize, cut: usize, stop: usize) -> bool {
+        let max_pvalue = match self.max_pvalue {
+            Some(p) => p,
+            None => return true,
+        };
+        let pvalue = if feature.target_type_ == TargetType::Continuous {
+            two_mean_pvalue(
+                feature.range_ct_mean_var(start, cut),
+                feature.range_ct_mean_var(cut, stop),
+            )
+        } else {
+            let (lhs_ct, lhs_ones, _) = feature.range_totals_ones_zero_ct(start, cut);
+            let (rhs_ct, rhs_ones, _) = feature.range_totals_ones_zero_ct(cut, stop);
+            two_proportion_pvalue((lhs_ct, lhs_ones), (rhs_ct, rhs_ones))
+        };
+        pvalue <= max_pvalue
+    }
+
+    // The best partition of the node into a number of bins in `n_bins`,
+    // cut at values at `n` weighted quantiles of the node, and `extra`.
+    fn best_partition(
+        &self,
+        feature: &Feature,
+        n: usize,
+        extra: Option<usize>,
+        n_bins: RangeInclusive<usize>,
+    ) -> Option<Partition> {
+        let mut bounds: Vec<usize> = feature
+            .quantile_split_candidates(n, self.start, self.stop)
+            .into_iter()
+            .chain(extra)
+            .map(|i| self.start + i + 1)
+            .chain([self.start, self.stop])
+            .collect();
+        bounds.sort_unstable();
+        bounds.dedup();
+        best_partition(
+            feature,
+            &bounds,
+            n_bins,
+            self.mono,
+            |start, stop| self.bin_allowed(feature, start, stop),
+            |start, cut, stop| self.bins_differ(feature, start, cut, stop),
+        )
+    }
+
+    // The split of each node of the chain of binary splits the bins of
+    // `partition` are stored in, each splitting the lowest bin off the rest.
+    fn chain_splits(&self, feature: &Feature, partition: &Partition) -> Vec<SplitInfo> {
+        let mut start = self.start;
+        partition
+            .cuts
+            .iter()
+            .map(|cut| {
+                let (lhs_iv, lhs_woe) = feature.range_iv_woe(start, *cut);
+                let (rhs_iv, rhs_woe) = feature.range_iv_woe(*cut, self.stop);
+                let info = SplitInfo::new(
+                    feature.vals_[cut - 1],
+                    cut - start - 1,
+                    lhs_iv,
+                    lhs_woe,
+                    rhs_iv,
+                    rhs_woe,
+                );
+                start = *cut;
+                info
+            })
+            .collect()
+    }
+
+    /// Split the node into as many as `max_children` bins in one step,
+    /// with the cuts chosen jointly to maximize the total information
+    /// value, from its best binary split, `split`, and values at weighted
+    /// quantiles of the node. The bins are stored as a chain of binary
+    /// splits, each splitting the lowest bin off the rest, the split of
+    /// each node of the chain is returned, with the trend of the bins.
+    pub(crate) fn find_best_partition(
+        &self,
+        feature: &Feature,
+        max_children: usize,
+        split: SplitInfo,
+    ) -> (Vec<SplitInfo>, MonotonicTrend) {
+        let n = self.approx_splits.map_or(MAX_PARTITION_CANDIDATES, |n| {
+            n.min(MAX_PARTITION_CANDIDATES)
+        });
+        // The best binary split satisfies every constraint of the
+        // partitions, so one is always found, with at least its
+        // information value.
+        match self.best_partition(feature, n, split.split_idx, 2..=max_children) {
+            Some(p) => (self.chain_splits(feature, &p), p.trend),
+            None => {
+                let (trend, _) = self
+                    .mono
+                    .children(split.lhs_woe.unwrap(), split.rhs_woe.unwrap());
+                (vec![split], trend)
+            }
+        }
+    }
+
+    /// Find the partition of the node into at most `max_bins` bins with
+    /// the highest total information value, cut at values splitting the
+    /// node into `n_atoms` atoms of about the same weight. Each bin must
+    /// satisfy the node's constraints, and each pair of adjacent bins
+    /// must follow the trend and differ significantly, as for a multiway
+    /// split, and the total information value must be at least `min_iv`.
+    /// The splits of the chain the bins are stored in are returned, with
+    /// the trend of the bins, or the reason the node can not be split.
+    pub(crate) fn find_optimal_partition(
+        &self,
+        feature: &Feature,
+        n_atoms: usize,
+        max_bins: usize,
+    ) -> Result<(Vec<SplitInfo>, MonotonicTrend), StopReason> {
+        if max_bins < 2 {
+            return Err(StopReason::MaxBins);
+        }
+        match self.best_partition(feature, n_atoms - 1, None, 2..=max_bins) {
+            Some(p) if p.iv.is_nan() | (p.iv < self.min_iv) | (p.iv <= 0.0) => {
+                Err(StopReason::MinIv)
             }
+            Some(p) => Ok((self.chain_splits(feature, &p), p.trend)),
+            // The reason the best binary split is rejected, or if it is
+            // not, that none of the cuts between the atoms can be used.
+            None => Err(self
+                .find_best_split(feature, TieBreak::default())
+                .err()
+                .unwrap_or(StopReason::NoCandidates)),
         }
-        if best_iv == 0.0 {
-            SplitInfo::new_empty()
+    }
+
+    /// Find the split of this node with the highest information
+    /// value, that satisfies all of the node's constraints. If there
+    /// is none, the reason the node can not be split is returned.
+    pub fn find_best_split(&self, feature: &Feature, tie_break: TieBreak) -> SplitResult {
+        // loop through all the unique levels
+        // of the feature, identifying the split
+        // that generates the maximum information
+        // value
+        self.candidates(feature)
+            .into_iter()
+            .map(|i| self.evaluate_split(feature, i))
+            .reduce(|a, b| self.better_result(feature, tie_break, a, b))
+            .unwrap_or(Err(StopReason::NoCandidates))
+    }
+
+    /// The same as `find_best_split`, with the candidate
+    /// splits evaluated in parallel.
+    #[cfg(feature = "rayon")]
+    pub fn par_find_best_split(&self, feature: &Feature, tie_break: TieBreak) -> SplitResult {
+        self.candidates(feature)
+            .into_par_iter()
+            .map(|i| self.evaluate_split(feature, i))
+            .reduce_with(|a, b| self.better_result(feature, tie_break, a, b))
+            .unwrap_or(Err(StopReason::NoCandidates))
+    }
+
+    /// The number of candidate splits of the node, and the number
+    /// rejected by each of the node's constraints.
+    pub(crate) fn rejected_candidates(
+        &self,
+        feature: &Feature,
+    ) -> (usize, BTreeMap<StopReason, usize>) {
+        let candidates = self.candidates(feature);
+        let mut rejected = BTreeMap::new();
+        for i in &candidates {
+            if let Err(reason) = self.evaluate_split(feature, *i) {
+                *rejected.entry(reason).or_insert(0) += 1;
+            }
+        }
+        (candidates.len(), rejected)
+    }
+
+    // The better of two evaluated candidates, where `a` has a lower value
+    // than `b`. If neither is a valid split, the reason of the candidate
+    // that met the most constraints is kept, as the one that stopped the
+    // most promising split.
+    fn better_result(
+        &self,
+        feature: &Feature,
+        tie_break: TieBreak,
+        a: SplitResult,
+        b: SplitResult,
+    ) -> SplitResult {
+        match (a, b) {
+            (Ok(a), Ok(b)) => Ok(self.better_split(feature, tie_break, a, b)),
+            (Ok(s), Err(_)) | (Err(_), Ok(s)) => Ok(s),
+            (Err(a), Err(b)) => Err(a.max(b)),
+        }
+    }
+
+    // The split with the highest information value, where `a` has a lower
+    // value than `b`. Exact ties are broken by `tie_break`, which does not
+    // depend on the order the candidates are compared in, so the same split
+    // is found when they are evaluated in parallel.
+    fn better_split(
+        &self,
+        feature: &Feature,
+        tie_break: TieBreak,
+        a: SplitInfo,
+        b: SplitInfo,
+    ) -> SplitInfo {
+        let total_iv = |s: &SplitInfo| s.lhs_iv.unwrap() + s.rhs_iv.unwrap();
+        // The difference between the weight on each side of the split.
+        let imbalance = |s: &SplitInfo| {
+            let ((lhs_ct, _), (rhs_ct, _)) =
+                feature.split_totals_ct_ones_ct(s.split_idx.unwrap(), self.start, self.stop);
+            (lhs_ct - rhs_ct).abs()
+        };
+        let b_is_better = if total_iv(&b) != total_iv(&a) {
+            total_iv(&b) > total_iv(&a)
         } else {
-            SplitInfo::new(
-                best_split,
-                best_split_idx,
-                best_lhs_iv,
-                best_lhs_woe,
-                best_rhs_iv,
-                best_rhs_woe,
-            )
+            match tie_break {
+                TieBreak::Lowest => false,
+                TieBreak::Highest => true,
+                TieBreak::Median => imbalance(&b) < imbalance(&a),
+            }
+        };
+        if b_is_better {
+            b
+        } else {
+            a
         }
     }
 }
@@ -177,18 +593,62 @@ impl Node {
 mod test {
     use super::*;
     use std::fs;
+    #[test]
+    fn test_tie_break() {
+        // The values of 1 and 4 mirror each other, with the ones and zero
+        // counts swapped, so splitting after 1, or after 3, is an exact tie.
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0, 4.0, 4.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let w_ = vec![1.0; x_.len()];
+        let f = Feature::new(&x_, &y_, Some(&w_), &Vec::new()).unwrap();
+        let n = Node::new(
+            &f,
+            Some(1.0),
+            None,
+            Some(0.0),
+            None,
+            None,
+            None,
+            MonotonicTrend::Unconstrained,
+            None,
+            None,
+            None,
+            None,
+        );
+        let lowest = n.find_best_split(&f, TieBreak::Lowest).unwrap();
+        let highest = n.find_best_split(&f, TieBreak::Highest).unwrap();
+        assert_eq!(lowest.split, Some(1.0));
+        assert_eq!(highest.split, Some(3.0));
+        assert_eq!(
+            lowest.lhs_iv.unwrap() + lowest.rhs_iv.unwrap(),
+            highest.lhs_iv.unwrap() + highest.rhs_iv.unwrap()
+        );
+
+        // Given a tie, the median prefers the most even split of the weight.
+        let mut even = n.evaluate_split(&f, 1).unwrap();
+        even.lhs_iv = lowest.lhs_iv;
+        even.rhs_iv = lowest.rhs_iv;
+        let best = n.better_split(&f, TieBreak::Median, highest, even);
+        assert_eq!(best.split, Some(2.0));
+        let best = n.better_split(&f, TieBreak::Median, lowest, best);
+        assert_eq!(best.split, Some(2.0));
+    }
+
     #[test]
     fn test_find_best_split() {
         let x_ = vec![6.2375, 6.4375, 0.0, 0.0, 4.0125, 5.0, 6.45, 6.4958, 6.4958];
         let y_ = vec![0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0];
         let w_ = vec![1.0; x_.len()];
-        let f = Feature::new(&x_, &y_, &w_, &Vec::new()).unwrap();
-        let mut n = Node::new(
+        let f = Feature::new(&x_, &y_, Some(&w_), &Vec::new()).unwrap();
+        let n = Node::new(
             &f,
             Some(1.0),
             None,
             Some(0.0),
-            Some(1),
+            None,
+            None,
+            None,
+            MonotonicTrend::Ascending,
             None,
             None,
             None,
@@ -202,7 +662,7 @@ mod test {
             0.3064140580738649,
             0.8754687373538999,
         );
-        assert_eq!(n.find_best_split(&f), comp_info);
+        assert_eq!(n.find_best_split(&f, TieBreak::Lowest), Ok(comp_info));
     }
 
     #[test]
@@ -215,35 +675,53 @@ mod test {
         let y_ = vec![0.0, 1.0, 0.0, 1.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0];
         let w_ = vec![1.0; x_.len()];
 
-        let f = Feature::new(&x_, &y_, &w_, &vec![-1.0, 100.0]).unwrap();
-        let mut n = Node::new(
+        let f = Feature::new(&x_, &y_, Some(&w_), &[-1.0, 100.0]).unwrap();
+        let n = Node::new(
             &f,
             Some(1.0),
             None,
             Some(0.0),
-            Some(1),
+            None,
+            None,
+            None,
+            MonotonicTrend::Ascending,
             None,
             None,
             None,
             None,
         );
         println!("{:?}", f.exception_values_);
-        assert_eq!(n.find_best_split(&f).split.unwrap(), 6.2375);
+        assert_eq!(
+            n.find_best_split(&f, TieBreak::Lowest)
+                .unwrap()
+                .split
+                .unwrap(),
+            6.2375
+        );
 
-        let f = Feature::new(&x_, &y_, &w_, &Vec::new()).unwrap();
-        let mut n = Node::new(
+        let f = Feature::new(&x_, &y_, Some(&w_), &Vec::new()).unwrap();
+        let n = Node::new(
             &f,
             Some(1.0),
             None,
             Some(0.0),
-            Some(1),
+            None,
+            None,
+            None,
+            MonotonicTrend::Ascending,
             None,
             None,
             None,
             None,
         );
         println!("{:?}", f.exception_values_);
-        assert_ne!(n.find_best_split(&f).split.unwrap(), 6.2375);
+        assert_ne!(
+            n.find_best_split(&f, TieBreak::Lowest)
+                .unwrap()
+                .split
+                .unwrap(),
+            6.2375
+        );
     }
 
     #[test]
@@ -258,19 +736,22 @@ mod test {
             survived.push(split[1]);
         }
         let w = vec![1.0; fare.len()];
-        let f = Feature::new(&fare, &survived, &w, &Vec::new()).unwrap();
-        let mut n = Node::new(
+        let f = Feature::new(&fare, &survived, Some(&w), &Vec::new()).unwrap();
+        let n = Node::new(
             &f,
             Some(1.0),
             None,
             Some(0.0),
-            Some(1),
+            None,
+            None,
+            None,
+            MonotonicTrend::Ascending,
             None,
             None,
             Some(4),
             Some(30),
         );
-        println!("{:?}", n.find_best_split(&f));
+        println!("{:?}", n.find_best_split(&f, TieBreak::Lowest));
         let test_info = SplitInfo {
             split: Some(6.4375),
             split_idx: Some(0),
@@ -279,6 +760,101 @@ mod test {
             rhs_iv: Some(0.08392941911181269),
             rhs_woe: Some(-1.0168034503546088),
         };
-        assert_eq!(n.find_best_split(&f), test_info);
+        assert_eq!(n.find_best_split(&f, TieBreak::Lowest), Ok(test_info));
+    }
+
+    #[test]
+    fn test_find_best_split_min_effective_obs() {
+        let x_ = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+        // The first record carries almost all of the weight, so any bin
+        // containing it has an effective size of about one.
+        let w_ = vec![50.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0];
+        let f = Feature::new(&x_, &y_, Some(&w_), &Vec::new()).unwrap();
+        let n = Node::new(
+            &f,
+            Some(1.0),
+            None,
+            Some(0.0),
+            None,
+            None,
+            None,
+            MonotonicTrend::Unconstrained,
+            None,
+            None,
+            None,
+            None,
+        );
+        assert!(n
+            .find_best_split(&f, TieBreak::Lowest)
+            .unwrap()
+            .split
+            .is_some());
+
+        let n = Node::new(
+            &f,
+            Some(1.0),
+            None,
+            Some(0.0),
+            Some(2.0),
+            None,
+            None,
+            MonotonicTrend::Unconstrained,
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(
+            n.find_best_split(&f, TieBreak::Lowest),
+            Err(StopReason::MinObs)
+        );
+    }
+
+    #[test]
+    fn test_find_best_split_approx() {
+        let x_: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        let y_: Vec<f64> = (0..100)
+            .map(|i| (((i < 30) & (i % 3 != 0)) | (i % 10 == 0)) as i64 as f64)
+            .collect();
+        let w_ = vec![1.0; x_.len()];
+        let f = Feature::new(&x_, &y_, Some(&w_), &Vec::new()).unwrap();
+        let n = Node::new(
+            &f,
+            Some(5.0),
+            None,
+            Some(1.0),
+            None,
+            None,
+            None,
+            MonotonicTrend::Unconstrained,
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(
+            n.find_best_split(&f, TieBreak::Lowest).unwrap().split,
+            Some(30.0)
+        );
+        // Only the quartiles are considered.
+        let n = Node::new(
+            &f,
+            Some(5.0),
+            None,
+            Some(1.0),
+            None,
+            Some(3),
+            None,
+            MonotonicTrend::Unconstrained,
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(
+            n.find_best_split(&f, TieBreak::Lowest).unwrap().split,
+            Some(24.0)
+        );
     }
 }
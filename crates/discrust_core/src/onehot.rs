@@ -0,0 +1,74 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use num::Float;
+
+impl Discretizer {
+    /// A row for each value, with a 0/1 indicator for each bin,
+    /// followed by one for each exception value, in the order of
+    /// `exception_values_`. So the column of a value is given by
+    /// `index_position`, the same positions as `bin_woe_`, with a last
+    /// column for the missing bin, if there is one. Otherwise, missing
+    /// values predicted with their own weight of evidence are all zero.
+    pub fn transform_onehot<T: Float>(&self, x: &[T]) -> Result<Vec<Vec<u8>>, DiscrustError> {
+        if self.feature.is_none() {
+            return Err(DiscrustError::NotFitted);
+        }
+        let n_columns = self.n_positions();
+        let rows = self
+            .predict_idx(x)?
+            .into_iter()
+            .map(|idx| {
+                let mut row = vec![0; n_columns];
+                if let Some(column) = self.index_position(idx) {
+                    row[column] = 1;
+                }
+                row
+            })
+            .collect();
+        Ok(rows)
+    }
+
+    /// The label of each column returned by `transform_onehot`.
+    pub fn onehot_labels(&self) -> Result<Vec<String>, DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        let (mut labels, exception_labels) = self.labels(feature);
+        labels.extend(exception_labels);
+        if feature.in_missing_bin(&f64::NAN) {
+            labels.push(self.missing_label(feature));
+        }
+        Ok(labels)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strategy::OnMissing;
+
+    #[test]
+    fn test_transform_onehot() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, -2.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.transform_onehot(&x_).is_err());
+        disc.on_missing = OnMissing::WoeValue(0.0);
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0, -2.0]))
+            .unwrap();
+        assert_eq!(
+            disc.transform_onehot(&[1.0, 2.0, -2.0, -1.0, f64::NAN])
+                .unwrap(),
+            vec![
+                vec![1, 0, 0, 0],
+                vec![0, 1, 0, 0],
+                // -2.0 is the first exception value, in sorted order.
+                vec![0, 0, 1, 0],
+                vec![0, 0, 0, 1],
+                vec![0, 0, 0, 0],
+            ]
+        );
+        let labels = disc.onehot_labels().unwrap();
+        assert_eq!(labels.len(), 4);
+        assert_eq!(labels[2], "-2");
+    }
+}
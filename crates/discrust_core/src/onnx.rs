@@ -0,0 +1,328 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::export::ExportOutput;
+use crate::strategy::Closed;
+
+// The ONNX tensor element types used by the graph.
+const DOUBLE: i64 = 11;
+const INT64: i64 = 7;
+const STRING: i64 = 8;
+
+// The opset of the default domain the graph's operators are
+// from, and the IR version of the first release supporting it.
+const OPSET_VERSION: i64 = 13;
+const IR_VERSION: i64 = 7;
+
+// A protocol buffer message, with its fields encoded in the
+// order they are added. Only the wire types ONNX needs, varints
+// and length delimited fields, are supported.
+#[derive(Default)]
+struct Message {
+    buf: Vec<u8>,
+}
+
+impl Message {
+    fn new() -> Self {
+        Message::default()
+    }
+
+    fn varint(&mut self, mut v: u64) {
+        while v >= 0x80 {
+            self.buf.push((v as u8) | 0x80);
+            v >>= 7;
+        }
+        self.buf.push(v as u8);
+    }
+
+    // A negative value is written as its two's complement, in ten bytes.
+    fn int(mut self, field: u64, v: i64) -> Self {
+        self.varint(field << 3);
+        self.varint(v as u64);
+        self
+    }
+
+    fn bytes(mut self, field: u64, v: &[u8]) -> Self {
+        self.varint((field << 3) | 2);
+        self.varint(v.len() as u64);
+        self.buf.extend_from_slice(v);
+        self
+    }
+
+    fn string(self, field: u64, v: &str) -> Self {
+        self.bytes(field, v.as_bytes())
+    }
+
+    fn message(self, field: u64, v: Message) -> Self {
+        self.bytes(field, &v.buf)
+    }
+}
+
+// The value a rule maps a record to, as a scalar tensor.
+fn scalar(name: &str, value: f64, label: &str, output: ExportOutput) -> Message {
+    let tensor = Message::new().string(8, name);
+    match output {
+        ExportOutput::Woe => tensor.int(2, DOUBLE).bytes(9, &value.to_le_bytes()),
+        ExportOutput::Index => tensor.int(2, INT64).bytes(9, &(value as i64).to_le_bytes()),
+        ExportOutput::Label => tensor.int(2, STRING).string(6, label),
+    }
+}
+
+fn node(op_type: &str, inputs: &[&str], output: &str) -> Message {
+    let node = inputs
+        .iter()
+        .fold(Message::new(), |node, i| node.string(1, i));
+    node.string(2, output).string(3, output).string(4, op_type)
+}
+
+// A one dimensional tensor of `elem_type`, of any length.
+fn value_info(name: &str, elem_type: i64) -> Message {
+    let shape = Message::new().message(1, Message::new().string(2, "N"));
+    let tensor = Message::new().int(1, elem_type).message(2, shape);
+    Message::new()
+        .string(1, name)
+        .message(2, Message::new().message(1, tensor))
+}
+
+impl Discretizer {
+    /// Export the fitted binning as a serialized ONNX model, mapping the
+    /// one dimensional double tensor `input` to the tensor `output_name`
+    /// of its weight of evidence, bin index or label, so the transform can
+    /// be deployed in ONNX Runtime alongside the downstream model. The
+    /// rules are checked in the same order as `to_sql`, as a chain of
+    /// `Where` operators, and the graph only uses operators of the default
+    /// domain at opset 13. Missing values, NaN, are mapped to the exception
+    /// value of NaN, or the missing bin, otherwise according to `on_missing`,
+    /// where an error is NaN, and can't be exported for an index or label.
+    pub fn to_onnx(
+        &self,
+        input: &str,
+        output_name: &str,
+        output: ExportOutput,
+    ) -> Result<Vec<u8>, DiscrustError> {
+        let mut rules = self.export_rules(output)?;
+        let mut initializers: Vec<Message> = Vec::new();
+        let mut nodes: Vec<Message> = Vec::new();
+        let name = |suffix: String| format!("{}_{}", output_name, suffix);
+
+        // The missing rule is checked first, unless
+        // there is an exception value of NaN.
+        let missing = if rules
+            .iter()
+            .any(|r| r.exception.is_some_and(|e| e.is_nan()))
+        {
+            None
+        } else {
+            match self.missing_export(&rules, output)? {
+                Some(m) => Some(m),
+                None if output == ExportOutput::Woe => Some((f64::NAN, String::new())),
+                None => {
+                    return Err(DiscrustError::InvalidParameter(String::from(
+                        "a missing value can't be exported as an index or label when on_missing is an error",
+                    )))
+                }
+            }
+        };
+        let last = rules.pop().ok_or(DiscrustError::NotFitted)?;
+
+        // Every record starts with the value of the last bin, which is
+        // replaced by the value of each preceding rule that matches it.
+        let last_name = name(String::from("last"));
+        initializers.push(scalar(&last_name, last.value, &last.label, output));
+        let shape = name(String::from("shape"));
+        nodes.push(node("Shape", &[input], &shape));
+        let mut current = name(String::from("expand"));
+        nodes.push(node("Expand", &[&last_name, &shape], &current));
+
+        let below = match self.closed {
+            Closed::Left => "Less",
+            Closed::Right => "LessOrEqual",
+        };
+        let missing_rule = missing.map(|(value, label)| (Some(f64::NAN), f64::NAN, value, label));
+        let checks = rules
+            .into_iter()
+            .map(|r| (r.exception, r.upper, r.value, r.label))
+            .rev()
+            .chain(missing_rule);
+        for (k, (exception, upper, value, label)) in checks.enumerate() {
+            let cond = name(format!("cond{}", k));
+            let threshold = name(format!("threshold{}", k));
+            match exception {
+                Some(e) if e.is_nan() => nodes.push(node("IsNaN", &[input], &cond)),
+                Some(e) => {
+                    initializers.push(scalar(&threshold, e, "", ExportOutput::Woe));
+                    nodes.push(node("Equal", &[input, &threshold], &cond));
+                }
+                None => {
+                    initializers.push(scalar(&threshold, upper, "", ExportOutput::Woe));
+                    nodes.push(node(below, &[input, &threshold], &cond));
+                }
+            }
+            let value_name = name(format!("value{}", k));
+            initializers.push(scalar(&value_name, value, &label, output));
+            let next = name(format!("where{}", k));
+            nodes.push(node("Where", &[&cond, &value_name, &current], &next));
+            current = next;
+        }
+        nodes.push(node("Identity", &[&current], output_name));
+
+        let graph = nodes
+            .into_iter()
+            .fold(Message::new(), |g, n| g.message(1, n))
+            .string(2, "discrust");
+        let graph = initializers
+            .into_iter()
+            .fold(graph, |g, t| g.message(5, t))
+            .message(11, value_info(input, DOUBLE));
+        let elem_type = match output {
+            ExportOutput::Woe => DOUBLE,
+            ExportOutput::Index => INT64,
+            ExportOutput::Label => STRING,
+        };
+        let graph = graph.message(12, value_info(output_name, elem_type));
+        let model = Message::new()
+            .int(1, IR_VERSION)
+            .string(2, "discrust")
+            .string(3, env!("CARGO_PKG_VERSION"))
+            .message(7, graph)
+            .message(8, Message::new().string(1, "").int(2, OPSET_VERSION));
+        Ok(model.buf)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strategy::OnMissing;
+    use std::convert::TryInto;
+
+    // The fields of a message, each a varint, or length delimited bytes.
+    fn fields(mut buf: &[u8]) -> Vec<(u64, u64, &[u8])> {
+        fn varint(buf: &mut &[u8]) -> u64 {
+            let mut v = 0;
+            for shift in (0..).step_by(7) {
+                let b = buf[0];
+                *buf = &buf[1..];
+                v |= ((b & 0x7f) as u64) << shift;
+                if b < 0x80 {
+                    break;
+                }
+            }
+            v
+        }
+        let mut res = Vec::new();
+        while !buf.is_empty() {
+            let key = varint(&mut buf);
+            let v = varint(&mut buf);
+            if key & 7 == 2 {
+                let (bytes, rest) = buf.split_at(v as usize);
+                buf = rest;
+                res.push((key >> 3, 0, bytes));
+            } else {
+                res.push((key >> 3, v, &[][..]));
+            }
+        }
+        res
+    }
+
+    fn strings(buf: &[u8], field: u64) -> Vec<String> {
+        fields(buf)
+            .into_iter()
+            .filter(|f| f.0 == field)
+            .map(|f| String::from_utf8(f.2.to_vec()).unwrap())
+            .collect()
+    }
+
+    // Run a numeric model on `x`, with the operators the export uses,
+    // where the comparison of a scalar broadcasts to each record.
+    fn run(model: &[u8], x: &[f64]) -> Vec<f64> {
+        let graph = fields(model).into_iter().find(|f| f.0 == 7).unwrap().2;
+        let mut values: Vec<(String, Vec<f64>)> = vec![(String::from("x"), x.to_vec())];
+        for (field, _, tensor) in fields(graph) {
+            if field == 5 {
+                let fs = fields(tensor);
+                let raw: [u8; 8] = fs.iter().find(|f| f.0 == 9).unwrap().2.try_into().unwrap();
+                let is_double = fs.iter().any(|f| f.0 == 2 && f.1 == DOUBLE as u64);
+                let v = if is_double {
+                    f64::from_le_bytes(raw)
+                } else {
+                    i64::from_le_bytes(raw) as f64
+                };
+                values.push((strings(tensor, 8).remove(0), vec![v]));
+            }
+        }
+        for (field, _, n) in fields(graph) {
+            if field != 1 {
+                continue;
+            }
+            let get = |name: &String| &values.iter().find(|v| &v.0 == name).unwrap().1;
+            let inputs: Vec<&Vec<f64>> = strings(n, 1).iter().map(get).collect();
+            let a = |i: usize| inputs[0][i];
+            let b = |i: usize| inputs[1][if inputs[1].len() == 1 { 0 } else { i }];
+            let flag = |c: bool| c as i32 as f64;
+            let res: Vec<f64> = (0..x.len())
+                .map(|i| match strings(n, 4)[0].as_str() {
+                    "Shape" | "Expand" => inputs[0][0],
+                    "IsNaN" => flag(a(i).is_nan()),
+                    "Equal" => flag(a(i) == b(i)),
+                    "Less" => flag(a(i) < b(i)),
+                    "LessOrEqual" => flag(a(i) <= b(i)),
+                    "Where" if a(i) != 0.0 => b(i),
+                    "Where" => inputs[2][i],
+                    "Identity" => a(i),
+                    op => panic!("{} is not supported", op),
+                })
+                .collect();
+            values.push((strings(n, 2).remove(0), res));
+        }
+        values.pop().unwrap().1
+    }
+
+    #[test]
+    fn test_message() {
+        assert_eq!(Message::new().int(1, 300).buf, vec![8, 0xac, 0x02]);
+        assert_eq!(Message::new().int(1, -1).buf.len(), 11);
+        assert_eq!(Message::new().string(2, "ab").buf, vec![18, 2, b'a', b'b']);
+    }
+
+    #[test]
+    fn test_to_onnx() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.to_onnx("x", "woe", ExportOutput::Woe).is_err());
+        disc.fit(&x_, &y_, None, Some(vec![-1.0])).unwrap();
+
+        let x = [-5.0, -1.0, 1.0, 1.5, 2.0, 10.0, f64::NAN];
+        let model = disc.to_onnx("x", "woe", ExportOutput::Woe).unwrap();
+        assert_eq!(run(&model, &x), disc.predict_woe(&x).unwrap());
+        let graph = fields(&model).into_iter().find(|f| f.0 == 7).unwrap().2;
+        let io: Vec<Vec<String>> = fields(graph)
+            .into_iter()
+            .filter(|f| f.0 == 11 || f.0 == 12)
+            .map(|f| strings(f.2, 1))
+            .collect();
+        assert_eq!(io, vec![vec!["x"], vec!["woe"]]);
+
+        let model = disc.to_onnx("x", "idx", ExportOutput::Index).unwrap();
+        let idx: Vec<f64> = disc
+            .predict_idx(&x)
+            .unwrap()
+            .iter()
+            .map(|i| *i as f64)
+            .collect();
+        assert_eq!(run(&model, &x), idx);
+        let model = disc.to_onnx("x", "label", ExportOutput::Label).unwrap();
+        assert!(model.windows(8).any(|w| w == b"(1, inf]"));
+
+        disc.closed = Closed::Left;
+        disc.fit(&x_, &y_, None, Some(vec![-1.0])).unwrap();
+        let model = disc.to_onnx("x", "woe", ExportOutput::Woe).unwrap();
+        assert_eq!(run(&model, &x), disc.predict_woe(&x).unwrap());
+
+        // A missing value can only be an error for the weight of evidence.
+        disc.on_missing = OnMissing::Error;
+        assert!(disc.to_onnx("x", "idx", ExportOutput::Index).is_err());
+        let model = disc.to_onnx("x", "woe", ExportOutput::Woe).unwrap();
+        assert!(run(&model, &[f64::NAN])[0].is_nan());
+    }
+}
@@ -0,0 +1,78 @@
+use crate::errors::DiscrustError;
+use crate::feature::Feature;
+use crate::node::{Node, SplitResult};
+use crate::strategy::TieBreak;
+
+/// The threads the work is done on, when built with the `rayon` feature,
+/// and a number of threads is provided. They are built once, and shared
+/// by everything done with them, such as counting the records of a fit,
+/// and searching for its splits, or fitting several discretizers. If no
+/// thread count is provided, the work is done on the calling thread.
+#[derive(Debug, Default)]
+pub(crate) struct Threads {
+    #[cfg(feature = "rayon")]
+    pool: Option<rayon::ThreadPool>,
+}
+
+impl Threads {
+    /// A thread count of zero uses one thread per core.
+    #[cfg(feature = "rayon")]
+    pub fn new(n_threads: Option<usize>) -> Result<Self, DiscrustError> {
+        let pool = n_threads
+            .map(|n| {
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(n)
+                    .build()
+                    .map_err(|e| DiscrustError::InvalidParameter(e.to_string()))
+            })
+            .transpose()?;
+        Ok(Threads { pool })
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    pub fn new(_n_threads: Option<usize>) -> Result<Self, DiscrustError> {
+        Ok(Threads {})
+    }
+
+    /// The pool of threads, or None if the work is
+    /// done on the calling thread.
+    #[cfg(feature = "rayon")]
+    pub fn pool(&self) -> Option<&rayon::ThreadPool> {
+        self.pool.as_ref()
+    }
+}
+
+/// Searches for the best split of each node waiting to be split,
+/// on the threads of a fit, the nodes, and their candidate splits,
+/// are searched in parallel. Otherwise the nodes are searched one
+/// at a time.
+pub(crate) struct SplitSearch<'a> {
+    #[cfg_attr(not(feature = "rayon"), allow(dead_code))]
+    threads: &'a Threads,
+    tie_break: TieBreak,
+}
+
+impl<'a> SplitSearch<'a> {
+    pub fn new(threads: &'a Threads, tie_break: TieBreak) -> Self {
+        SplitSearch { threads, tie_break }
+    }
+
+    /// The best split of each of the nodes, in the same order,
+    /// or the reason the node can not be split.
+    pub fn best_splits(&self, nodes: &[&Node], feature: &Feature) -> Vec<SplitResult> {
+        #[cfg(feature = "rayon")]
+        if let Some(pool) = self.threads.pool() {
+            use rayon::prelude::*;
+            return pool.install(|| {
+                nodes
+                    .par_iter()
+                    .map(|n| n.par_find_best_split(feature, self.tie_break))
+                    .collect()
+            });
+        }
+        nodes
+            .iter()
+            .map(|n| n.find_best_split(feature, self.tie_break))
+            .collect()
+    }
+}
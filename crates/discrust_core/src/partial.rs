@@ -0,0 +1,172 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::feature::ValueCounts;
+use crate::parallel::Threads;
+use crate::strategy::TargetType;
+use crate::summary::FitResult;
+
+// The aggregated records of every batch passed to `partial_fit`.
+#[derive(Debug)]
+pub(crate) struct PartialFit {
+    counts: ValueCounts,
+    n_records: usize,
+}
+
+impl Discretizer {
+    /// Add a batch of records to the statistics the discretizer is fit on,
+    /// without fitting it. Only the weighted counts of each unique value are
+    /// kept, so a dataset too large to hold in memory can be passed in
+    /// batches, and the discretizer fit on all of them with `finalize`. The
+    /// exception values must be the same for every batch. The clip quantiles,
+    /// max candidates, pre transform, cross validation folds, and the class
+    /// counts of a multiclass target, need all of the records at once, and
+    /// so can't be used. Calling `fit` discards any batches that have been
+    /// added.
+    pub fn partial_fit(
+        &mut self,
+        x: &[f64],
+        y: &[f64],
+        w: Option<&[f64]>,
+        exception_values: Option<Vec<f64>>,
+    ) -> Result<(), DiscrustError> {
+        if self.clip_quantiles.is_some()
+            || self.max_candidates.is_some()
+            || self.pre_transform.is_some()
+            || self.cv_folds.is_some()
+            || (self.target_type == TargetType::Multiclass)
+        {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "clip_quantiles, max_candidates, pre_transform, cv_folds and a multiclass target can not be used with partial_fit",
+            )));
+        }
+        self.check_target(y)?;
+        self.check_input(x)?;
+        let e = self.with_manual_exceptions(exception_values);
+        let (x, _) = self.prepare_values(x, w, &e)?;
+        let threads = Threads::new(self.n_threads)?;
+        let counts = ValueCounts::new(&x, y, w, &e, self.special_missing, None, &threads)?;
+        self.partial_ = Some(match self.partial_.take() {
+            Some(partial) => {
+                if !partial.counts.compatible(&counts) {
+                    // The batches added so far are kept.
+                    self.partial_ = Some(partial);
+                    return Err(DiscrustError::InvalidParameter(String::from(
+                        "the exception values must be the same for every batch",
+                    )));
+                }
+                PartialFit {
+                    counts: partial.counts.merge(counts),
+                    n_records: partial.n_records + y.len(),
+                }
+            }
+            None => PartialFit {
+                counts,
+                n_records: y.len(),
+            },
+        });
+        Ok(())
+    }
+
+    /// Fit the discretizer on all of the batches passed to `partial_fit`,
+    /// which gives the same splits as passing all of the records to `fit`.
+    /// The batches are discarded once the discretizer is fit.
+    pub fn finalize(&mut self) -> Result<FitResult, DiscrustError> {
+        let partial = self.partial_.take().ok_or(DiscrustError::NoPartialFit)?;
+        self.splits_ = Vec::new();
+        self.categories_ = Vec::new();
+        self.metadata_ = None;
+        self.validate_parameters()?;
+        let feature = partial.counts.into_feature(self.target_type);
+        let threads = Threads::new(self.n_threads)?;
+        self.fit_feature(feature, |v| v, partial.n_records, &threads)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strategy::Strategy;
+    use std::fs;
+
+    fn fare_survived() -> (Vec<f64>, Vec<f64>) {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
+        }
+        (fare, survived)
+    }
+
+    #[test]
+    fn test_partial_fit_matches_fit() {
+        let (mut fare, survived) = fare_survived();
+        fare[3] = -1.0;
+        fare[10] = f64::NAN;
+        let w_: Vec<f64> = (0..fare.len()).map(|i| 1.0 + (i % 3) as f64).collect();
+        let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(matches!(disc.finalize(), Err(DiscrustError::NoPartialFit)));
+        let res = disc
+            .fit(&fare, &survived, Some(&w_), Some(vec![-1.0]))
+            .unwrap();
+
+        let mut batched = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), None);
+        for start in (0..fare.len()).step_by(100) {
+            let stop = (start + 100).min(fare.len());
+            batched
+                .partial_fit(
+                    &fare[start..stop],
+                    &survived[start..stop],
+                    Some(&w_[start..stop]),
+                    Some(vec![-1.0]),
+                )
+                .unwrap();
+        }
+        let batched_res = batched.finalize().unwrap();
+        assert_eq!(batched_res.splits, res.splits);
+        assert_eq!(batched_res.exceptions, res.exceptions);
+        let (missing, batched_missing) = (res.missing.unwrap(), batched_res.missing.unwrap());
+        assert_eq!(batched_missing.totals_ct, missing.totals_ct);
+        assert_eq!(batched_missing.woe, missing.woe);
+        for (a, b) in batched_res.bins.iter().zip(&res.bins) {
+            assert!((a.woe == b.woe) || ((a.woe - b.woe).abs() < 1e-12));
+            assert!((a.totals_ct - b.totals_ct).abs() < 1e-9);
+        }
+        let metadata = batched.metadata().unwrap();
+        assert_eq!(metadata.n_records, fare.len());
+        assert_eq!(metadata.total_weight, w_.iter().sum::<f64>());
+        // The batches are discarded once fit.
+        assert!(batched.finalize().is_err());
+    }
+
+    #[test]
+    fn test_partial_fit_errors() {
+        let x_ = vec![1.0, 1.0, 2.0, 2.0, -1.0];
+        let y_ = vec![1.0, 0.0, 1.0, 0.0, 1.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(0.0), None);
+        disc.partial_fit(&x_, &y_, Some(&w_), Some(vec![-1.0]))
+            .unwrap();
+        assert!(disc.partial_fit(&x_, &y_, Some(&w_), None).is_err());
+        // The first batch is kept.
+        disc.partial_fit(&x_, &y_, Some(&w_), Some(vec![-1.0]))
+            .unwrap();
+        assert_eq!(disc.finalize().unwrap().exceptions[0].totals_ct, 2.0);
+        // Fitting discards any batches.
+        disc.partial_fit(&x_, &y_, Some(&w_), Some(vec![-1.0]))
+            .unwrap();
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+        assert!(disc.finalize().is_err());
+
+        disc.clip_quantiles = Some((0.0, 0.9));
+        assert!(disc.partial_fit(&x_, &y_, Some(&w_), None).is_err());
+        disc.clip_quantiles = None;
+        disc.strategy = Strategy::MergeOnly { edges: vec![1.5] };
+        disc.partial_fit(&x_, &y_, Some(&w_), Some(vec![-1.0]))
+            .unwrap();
+        assert_eq!(disc.finalize().unwrap().splits.len(), 3);
+    }
+}
@@ -0,0 +1,207 @@
+use crate::feature::Feature;
+use crate::strategy::MonotonicTrend;
+use std::ops::RangeInclusive;
+
+/// The bins of a partition of a range of the values of a feature, chosen
+/// jointly, rather than one split at a time.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Partition {
+    /// The positions of the first value of each bin above the lowest.
+    pub cuts: Vec<usize>,
+    /// The total information value of the bins.
+    pub iv: f64,
+    /// The trend of the weight of evidence of the bins, ascending or
+    /// descending if an automatic trend was requested.
+    pub trend: MonotonicTrend,
+}
+
+/// Find the partition of the values between the first and last of
+/// `bounds`, cut only at the positions in between, into a number of bins
+/// in `n_bins`, that has the highest total information value. Every bin
+/// must be allowed by `bin_allowed`, with its start and stop, and every
+/// pair of adjacent bins by `pair_allowed`, with the start of the lower
+/// bin, the cut between them, and the stop of the upper bin, and their
+/// weight of evidence must follow `mono`. An automatic trend tries both
+/// an ascending and a descending trend, a peak or valley is not applied.
+/// The bounds must be increasing. Returns `None` if there is no partition
+/// that satisfies the constraints. Ties are broken in favour of the
+/// fewest bins, and then the lowest cuts.
+pub(crate) fn best_partition<B, P>(
+    feature: &Feature,
+    bounds: &[usize],
+    n_bins: RangeInclusive<usize>,
+    mono: MonotonicTrend,
+    bin_allowed: B,
+    pair_allowed: P,
+) -> Option<Partition>
+where
+    B: Fn(usize, usize) -> bool,
+    P: Fn(usize, usize, usize) -> bool,
+{
+    let n = bounds.len();
+    if n < 2 {
+        return None;
+    }
+    // The information value, and weight of evidence, of the bin between
+    // each pair of bounds, if it is allowed. A bin with an undefined
+    // information value is never allowed.
+    let mut bins = vec![vec![None; n]; n];
+    for a in 0..n {
+        for b in (a + 1)..n {
+            let (iv, woe) = feature.range_iv_woe(bounds[a], bounds[b]);
+            if !iv.is_nan() && bin_allowed(bounds[a], bounds[b]) {
+                bins[a][b] = Some((iv, woe));
+            }
+        }
+    }
+    let search = |trend: MonotonicTrend| -> Option<Partition> {
+        let allows = |lower: f64, upper: f64| match trend {
+            MonotonicTrend::Ascending | MonotonicTrend::Descending => trend.allows(lower, upper),
+            _ => true,
+        };
+        // The best total information value of the bins covering the
+        // bounds up to `j`, where the highest bin starts at `i`, for
+        // each number of bins, and the start of the bin below it.
+        let max_bins = (*n_bins.end()).min(n - 1);
+        let mut best = vec![vec![vec![None; n]; n]; max_bins + 1];
+        for j in 1..n {
+            if let Some((iv, _)) = bins[0][j] {
+                best[1][j][0] = Some((iv, 0));
+            }
+        }
+        for k in 2..=max_bins {
+            for j in k..n {
+                for i in (k - 1)..j {
+                    let (iv, woe) = match bins[i][j] {
+                        Some(bin) => bin,
+                        None => continue,
+                    };
+                    for h in (k - 2)..i {
+                        let (lower_iv, _) = match (best[k - 1][i][h], bins[h][i]) {
+                            (Some(lower), Some((_, lower_woe)))
+                                if allows(lower_woe, woe)
+                                    && pair_allowed(bounds[h], bounds[i], bounds[j]) =>
+                            {
+                                lower
+                            }
+                            _ => continue,
+                        };
+                        let total = lower_iv + iv;
+                        if best[k][j][i].is_none_or(|(b, _)| total > b) {
+                            best[k][j][i] = Some((total, h));
+                        }
+                    }
+                }
+            }
+        }
+        // The best partition covering all of the bounds, with the
+        // fewest bins, and then the lowest highest bin.
+        let mut found: Option<(f64, usize, usize)> = None;
+        for (k, best_k) in best.iter().enumerate().skip((*n_bins.start()).max(1)) {
+            for (i, last) in best_k[n - 1].iter().enumerate() {
+                if let Some((iv, _)) = *last {
+                    if found.is_none_or(|(b, _, _)| iv > b) {
+                        found = Some((iv, k, i));
+                    }
+                }
+            }
+        }
+        let (iv, k, mut i) = found?;
+        let mut cuts = Vec::with_capacity(k - 1);
+        let mut j = n - 1;
+        for k in (2..=k).rev() {
+            cuts.push(bounds[i]);
+            let h = best[k][j][i].unwrap().1;
+            j = i;
+            i = h;
+        }
+        cuts.reverse();
+        Some(Partition { cuts, iv, trend })
+    };
+    match mono {
+        MonotonicTrend::Auto => {
+            match (
+                search(MonotonicTrend::Ascending),
+                search(MonotonicTrend::Descending),
+            ) {
+                (Some(a), Some(d)) if d.iv > a.iv => Some(d),
+                (Some(a), _) => Some(a),
+                (None, d) => d,
+            }
+        }
+        MonotonicTrend::Peak | MonotonicTrend::Valley => search(MonotonicTrend::Unconstrained),
+        trend => search(trend),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_best_partition() {
+        // The event rate falls, then rises, so the best unconstrained
+        // partition into three bins differs from the best monotonic one.
+        let x_: Vec<f64> = (0..12).map(|v| (v / 2) as f64).collect();
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0];
+        let mut feature = Feature::new(&x_, &y_, None, &[]).unwrap();
+        feature.set_woe_regularization(0.5);
+        let bounds: Vec<usize> = (0..=6).collect();
+        let any_bin = |_: usize, _: usize| true;
+        let any_pair = |_: usize, _: usize, _: usize| true;
+
+        let p = best_partition(
+            &feature,
+            &bounds,
+            2..=3,
+            MonotonicTrend::Unconstrained,
+            any_bin,
+            any_pair,
+        )
+        .unwrap();
+        assert_eq!(p.cuts, vec![2, 4]);
+        let total = feature.range_iv_woe(0, 2).0
+            + feature.range_iv_woe(2, 4).0
+            + feature.range_iv_woe(4, 6).0;
+        assert!((p.iv - total).abs() < 1e-12);
+
+        let p = best_partition(
+            &feature,
+            &bounds,
+            2..=3,
+            MonotonicTrend::Auto,
+            any_bin,
+            any_pair,
+        )
+        .unwrap();
+        assert_eq!(p.trend, MonotonicTrend::Descending);
+        assert_eq!(p.cuts.len(), 2);
+        assert_ne!(p.cuts, vec![2, 4]);
+        let woe: Vec<f64> = [0, p.cuts[0], p.cuts[1], 6]
+            .windows(2)
+            .map(|w| feature.range_iv_woe(w[0], w[1]).1)
+            .collect();
+        assert!(woe.windows(2).all(|w| w[0] >= w[1]));
+
+        // Bins with fewer than four records are not allowed.
+        let p = best_partition(
+            &feature,
+            &bounds,
+            2..=3,
+            MonotonicTrend::Unconstrained,
+            |a, b| feature.range_totals_ones_zero_ct(a, b).0 >= 4.0,
+            any_pair,
+        )
+        .unwrap();
+        assert!(p.cuts.len() <= 2);
+        assert!(best_partition(
+            &feature,
+            &bounds,
+            2..=3,
+            MonotonicTrend::Unconstrained,
+            |_, _| false,
+            any_pair,
+        )
+        .is_none());
+    }
+}
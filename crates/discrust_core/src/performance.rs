@@ -0,0 +1,112 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::strategy::TargetType;
+
+impl Discretizer {
+    /// The Gini coefficient of the weight of evidence against the
+    /// performance the discretizer was fit on, `2 * AUC - 1`, where
+    /// records with the same weight of evidence count as half ranked
+    /// correctly. Positive when a higher weight of evidence means more
+    /// ones, and NaN if there were no ones, or no zeros.
+    pub fn gini_(&self) -> Result<f64, DiscrustError> {
+        let mut auc = 0.0;
+        let mut ones_above = 0.0;
+        for (ones, zero) in self.ranked_shares()? {
+            auc += zero * (ones_above + ones / 2.0);
+            ones_above += ones;
+        }
+        Ok(2.0 * auc - 1.0)
+    }
+
+    /// The Kolmogorov-Smirnov statistic of the weight of evidence against
+    /// the performance the discretizer was fit on, the largest difference
+    /// between the cumulative shares of the ones and of the zeros, when the
+    /// records are ordered by their weight of evidence.
+    pub fn ks_(&self) -> Result<f64, DiscrustError> {
+        let mut ks: f64 = 0.0;
+        let (mut cum_ones, mut cum_zero) = (0.0, 0.0);
+        for (ones, zero) in self.ranked_shares()? {
+            cum_ones += ones;
+            cum_zero += zero;
+            ks = ks.max((cum_ones - cum_zero).abs());
+        }
+        Ok(ks)
+    }
+
+    // The share of the ones, and of the zeros, that were predicted each
+    // weight of evidence, from the highest to the lowest. The bins,
+    // exception values, and missing bin, with the same weight of
+    // evidence are combined, as their records can not be ranked.
+    fn ranked_shares(&self) -> Result<Vec<(f64, f64)>, DiscrustError> {
+        if self.target_type != TargetType::Binary {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "The Gini coefficient and KS statistic require a binary `target_type`.",
+            )));
+        }
+        let bins = self.bin_summary()?;
+        let exceptions = self.exception_summary()?;
+        let missing = self.missing_summary()?;
+        let counts = bins.iter().map(|b| (b.ones_ct, b.zero_ct)).chain(
+            exceptions
+                .iter()
+                .chain(&missing)
+                .map(|e| (e.ones_ct, e.zero_ct)),
+        );
+        let mut ranked: Vec<(f64, f64, f64)> = self
+            .bin_woe_()?
+            .into_iter()
+            .zip(counts)
+            .filter(|(_, (ones, zero))| ones + zero > 0.0)
+            .map(|(woe, (ones, zero))| (woe, ones, zero))
+            .collect();
+        ranked.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+        let (total_ones, total_zero) = ranked
+            .iter()
+            .fold((0.0, 0.0), |(o, z), r| (o + r.1, z + r.2));
+        let mut shares: Vec<(f64, f64)> = Vec::new();
+        let mut last_woe = None;
+        for (woe, ones, zero) in ranked {
+            let share = (ones / total_ones, zero / total_zero);
+            match shares.last_mut() {
+                Some(last) if last_woe == Some(woe) => {
+                    last.0 += share.0;
+                    last.1 += share.1;
+                }
+                _ => shares.push(share),
+            }
+            last_woe = Some(woe);
+        }
+        Ok(shares)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gini_and_ks() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, -1.0];
+        let y_ = vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 1.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.gini_().is_err());
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+        // Ranked by weight of evidence, the bin of 2.0 has 3 of the 5 ones
+        // and 1 of the 5 zeros, the exception 1 of each, and the bin of
+        // 1.0 the remaining 1 one and 3 zeros.
+        let auc = 0.2 * 0.3 + 0.2 * (0.6 + 0.1) + 0.6 * (0.8 + 0.1);
+        assert!((disc.gini_().unwrap() - (2.0 * auc - 1.0)).abs() < 1e-12);
+        assert!((disc.ks_().unwrap() - 0.4).abs() < 1e-12);
+
+        // With a single bin, every record has a weight of evidence of
+        // zero, the same as the exception, so none can be ranked.
+        disc.splits_ = vec![f64::NEG_INFINITY, f64::INFINITY];
+        assert_eq!(disc.gini_().unwrap(), 0.0);
+        assert_eq!(disc.ks_().unwrap(), 0.0);
+
+        disc.target_type = TargetType::Continuous;
+        disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        assert!(disc.ks_().is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+
+/// The output produced by a pipeline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PipelineOutput {
+    /// The linear predictor, `intercept + sum(coefficient * woe)`.
+    LogOdds,
+    /// The logistic transform of the log odds.
+    Probability,
+    /// A linear rescaling of the log odds, `offset + factor * log_odds`.
+    Score { offset: f64, factor: f64 },
+}
+
+/// Fitted discretizers chained with a linear model, such as a
+/// logistic regression fit on the weight of evidence of each feature.
+pub struct Pipeline {
+    pub discretizers: Vec<Discretizer>,
+    pub coefficients: Vec<f64>,
+    pub intercept: f64,
+}
+
+impl Pipeline {
+    /// Create a new pipeline, there must be one coefficient
+    /// for each of the fitted discretizers.
+    pub fn new(
+        discretizers: Vec<Discretizer>,
+        coefficients: Vec<f64>,
+        intercept: f64,
+    ) -> Result<Self, DiscrustError> {
+        if discretizers.len() != coefficients.len() {
+            return Err(DiscrustError::InvalidParameter(format!(
+                "{} coefficients were provided for {} discretizers",
+                coefficients.len(),
+                discretizers.len()
+            )));
+        }
+        if discretizers.iter().any(|d| d.feature.is_none()) {
+            return Err(DiscrustError::NotFitted);
+        }
+        Ok(Pipeline {
+            discretizers,
+            coefficients,
+            intercept,
+        })
+    }
+
+    /// Predict from one column of values for each discretizer.
+    pub fn predict(&self, x: &[&[f64]], output: PipelineOutput) -> Result<Vec<f64>, DiscrustError> {
+        let n_rows = check_columns(x, self.discretizers.len())?;
+        let mut log_odds = vec![self.intercept; n_rows];
+        for ((disc, coef), col) in self.discretizers.iter().zip(&self.coefficients).zip(x) {
+            for (lo, woe) in log_odds.iter_mut().zip(disc.predict_woe(col)?) {
+                *lo += coef * woe;
+            }
+        }
+        let res = match output {
+            PipelineOutput::LogOdds => log_odds,
+            PipelineOutput::Probability => log_odds
+                .iter()
+                .map(|lo| 1.0 / (1.0 + (-lo).exp()))
+                .collect(),
+            PipelineOutput::Score { offset, factor } => {
+                log_odds.iter().map(|lo| offset + factor * lo).collect()
+            }
+        };
+        Ok(res)
+    }
+}
+
+// Check there is one column for each discretizer, and that
+// every column is the same length, returning this length.
+pub(crate) fn check_columns(x: &[&[f64]], n_discretizers: usize) -> Result<usize, DiscrustError> {
+    if x.len() != n_discretizers {
+        return Err(DiscrustError::InvalidParameter(format!(
+            "{} columns were provided for {} discretizers",
+            x.len(),
+            n_discretizers
+        )));
+    }
+    let n_rows = x.first().map_or(0, |c| c.len());
+    if x.iter().any(|c| c.len() != n_rows) {
+        return Err(DiscrustError::InvalidParameter(String::from(
+            "all columns must be the same length",
+        )));
+    }
+    Ok(n_rows)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn test_pipeline() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut d1 = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        d1.fit(&x_, &y_, Some(&w_), None).unwrap();
+        let mut d2 = d1.unfitted_copy();
+        d2.fit(&x_, &y_, Some(&w_), None).unwrap();
+        let woe = d1.predict_woe(&x_).unwrap();
+
+        assert!(Pipeline::new(vec![d1.unfitted_copy()], vec![1.0], 0.0).is_err());
+        let p = Pipeline::new(vec![d1, d2], vec![0.5, 1.5], -0.25).unwrap();
+        let cols: Vec<&[f64]> = vec![&x_, &x_];
+        let log_odds = p.predict(&cols, PipelineOutput::LogOdds).unwrap();
+        assert_eq!(log_odds[0], -0.25 + 2.0 * woe[0]);
+        let prob = p.predict(&cols, PipelineOutput::Probability).unwrap();
+        assert_eq!(prob[0], 1.0 / (1.0 + (-log_odds[0]).exp()));
+        let score = p
+            .predict(
+                &cols,
+                PipelineOutput::Score {
+                    offset: 600.0,
+                    factor: 20.0,
+                },
+            )
+            .unwrap();
+        assert_eq!(score[4], 600.0 + 20.0 * log_odds[4]);
+        assert!(p.predict(&cols[..1], PipelineOutput::LogOdds).is_err());
+    }
+}
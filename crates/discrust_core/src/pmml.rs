@@ -0,0 +1,198 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::export::ExportOutput;
+use crate::strategy::Closed;
+
+// An XML element, with its attributes in the order they are written.
+struct XmlElement {
+    name: &'static str,
+    attributes: Vec<(&'static str, String)>,
+    children: Vec<XmlElement>,
+}
+
+impl XmlElement {
+    fn new(name: &'static str) -> Self {
+        XmlElement {
+            name,
+            attributes: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn attr(mut self, key: &'static str, value: String) -> Self {
+        self.attributes.push((key, value));
+        self
+    }
+
+    fn child(mut self, child: XmlElement) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    // Write the element, and its children, each on their own line,
+    // indented by two spaces for each level of nesting.
+    fn render(&self, depth: usize, out: &mut String) {
+        let indent = "  ".repeat(depth);
+        out.push_str(&indent);
+        out.push('<');
+        out.push_str(self.name);
+        for (k, v) in &self.attributes {
+            out.push_str(&format!(" {}=\"{}\"", k, xml_escape(v)));
+        }
+        if self.children.is_empty() {
+            out.push_str("/>\n");
+            return;
+        }
+        out.push_str(">\n");
+        for c in &self.children {
+            c.render(depth + 1, out);
+        }
+        out.push_str(&format!("{}</{}>\n", indent, self.name));
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// A number in the XML Schema lexical form of a double.
+fn xml_number(v: f64) -> String {
+    if v.is_nan() {
+        String::from("NaN")
+    } else if v.is_infinite() {
+        String::from(if v > 0.0 { "INF" } else { "-INF" })
+    } else {
+        format!("{}", v)
+    }
+}
+
+impl Discretizer {
+    /// Export the fitted binning as a PMML `DerivedField`, named `name`,
+    /// that maps the field `field` to its weight of evidence, bin index
+    /// or label with a `Discretize` transformation. Exception values are
+    /// written as intervals of a single value, before the bins, as the
+    /// first matching bin is used. Missing values are mapped to the
+    /// exception value of NaN, or the missing bin, otherwise according
+    /// to `on_missing`, where an error is left as a missing value.
+    pub fn to_pmml(
+        &self,
+        field: &str,
+        name: &str,
+        output: ExportOutput,
+    ) -> Result<String, DiscrustError> {
+        let rules = self.export_rules(output)?;
+        let value = |value: f64, label: &str| match output {
+            ExportOutput::Label => label.to_owned(),
+            _ => xml_number(value),
+        };
+        let mut discretize = XmlElement::new("Discretize").attr("field", field.to_owned());
+        let missing = self
+            .missing_export(&rules, output)?
+            .map(|(v, label)| value(v, &label));
+        if let Some(missing) = missing {
+            discretize = discretize.attr("mapMissingTo", missing);
+        }
+        let mut lower = f64::NEG_INFINITY;
+        for r in &rules {
+            let interval = match r.exception {
+                Some(e) if e.is_nan() => continue,
+                Some(e) => XmlElement::new("Interval")
+                    .attr("closure", String::from("closedClosed"))
+                    .attr("leftMargin", xml_number(e))
+                    .attr("rightMargin", xml_number(e)),
+                None => {
+                    let closure = match self.closed {
+                        Closed::Left => "closedOpen",
+                        Closed::Right => "openClosed",
+                    };
+                    let mut interval =
+                        XmlElement::new("Interval").attr("closure", String::from(closure));
+                    if lower.is_finite() {
+                        interval = interval.attr("leftMargin", xml_number(lower));
+                    }
+                    if r.upper.is_finite() {
+                        interval = interval.attr("rightMargin", xml_number(r.upper));
+                    }
+                    lower = r.upper;
+                    interval
+                }
+            };
+            discretize = discretize.child(
+                XmlElement::new("DiscretizeBin")
+                    .attr("binValue", value(r.value, &r.label))
+                    .child(interval),
+            );
+        }
+        let (optype, data_type) = match output {
+            ExportOutput::Woe => ("continuous", "double"),
+            ExportOutput::Index => ("categorical", "integer"),
+            ExportOutput::Label => ("categorical", "string"),
+        };
+        let derived = XmlElement::new("DerivedField")
+            .attr("name", name.to_owned())
+            .attr("optype", String::from(optype))
+            .attr("dataType", String::from(data_type))
+            .child(discretize);
+        let mut pmml = String::new();
+        derived.render(0, &mut pmml);
+        Ok(pmml)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strategy::OnMissing;
+
+    #[test]
+    fn test_to_pmml() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.to_pmml("x", "x_idx", ExportOutput::Index).is_err());
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+        assert_eq!(
+            disc.to_pmml("fare", "fare_idx", ExportOutput::Index)
+                .unwrap(),
+            concat!(
+                "<DerivedField name=\"fare_idx\" optype=\"categorical\" dataType=\"integer\">\n",
+                "  <Discretize field=\"fare\" mapMissingTo=\"0\">\n",
+                "    <DiscretizeBin binValue=\"-1\">\n",
+                "      <Interval closure=\"closedClosed\" leftMargin=\"-1\" rightMargin=\"-1\"/>\n",
+                "    </DiscretizeBin>\n",
+                "    <DiscretizeBin binValue=\"0\">\n",
+                "      <Interval closure=\"openClosed\" rightMargin=\"1\"/>\n",
+                "    </DiscretizeBin>\n",
+                "    <DiscretizeBin binValue=\"1\">\n",
+                "      <Interval closure=\"openClosed\" leftMargin=\"1\"/>\n",
+                "    </DiscretizeBin>\n",
+                "  </Discretize>\n",
+                "</DerivedField>\n",
+            )
+        );
+        let woe = disc.predict_woe(&[2.0]).unwrap();
+        let pmml = disc.to_pmml("fare", "fare_woe", ExportOutput::Woe).unwrap();
+        assert!(pmml.contains(&format!("<DiscretizeBin binValue=\"{}\">", woe[0])));
+
+        disc.on_missing = OnMissing::Error;
+        disc.set_exception_label(-1.0, "<0 & \"none\"").unwrap();
+        let pmml = disc
+            .to_pmml("fare", "fare_label", ExportOutput::Label)
+            .unwrap();
+        assert!(pmml.contains("<Discretize field=\"fare\">\n"));
+        assert!(pmml.contains("binValue=\"&lt;0 &amp; &quot;none&quot;\""));
+        assert_eq!(xml_number(f64::NEG_INFINITY), "-INF");
+    }
+}
@@ -0,0 +1,36 @@
+//! Convenience re-export of the types needed to
+//! fit and use a discretizer.
+//!
+//! ```
+//! use discrust_core::prelude::*;
+//! ```
+pub use crate::builder::DiscretizerBuilder;
+pub use crate::codes::{BinCodes, MISSING_CODE};
+pub use crate::datetime::{DateSnap, FeatureType, TimeUnit};
+pub use crate::discretize::Discretizer;
+pub use crate::ensemble::EnsembleDiscretizer;
+pub use crate::errors::{Column, DiscrustError, NanValues, UnsatisfiableConstraint};
+pub use crate::export::ExportOutput;
+pub use crate::feature::ExceptionValues;
+pub use crate::label::LabelFormat;
+pub use crate::metadata::FitMetadata;
+pub use crate::monitor::PredictReport;
+pub use crate::pipeline::{Pipeline, PipelineOutput};
+pub use crate::privacy::NoisedBinSummary;
+pub use crate::rank::{rank_features, FeatureRank};
+pub use crate::report::{FitEvent, FitReport, StopReason};
+pub use crate::scorecard::{Scorecard, ScorecardRow, ScorecardScaling};
+pub use crate::segment::{BinConsistency, SegmentMode, SegmentedDiscretizer};
+pub use crate::set::DiscretizerSet;
+pub use crate::spec::{InputSpec, SpecViolation};
+pub use crate::sql::SqlDialect;
+pub use crate::stability::{
+    BinFrequency, CharacteristicAnalysis, CharacteristicBin, ScoreDistribution, ShiftSimulation,
+    ShiftedBin,
+};
+pub use crate::strategy::{
+    Closed, Growth, MonotonicTrend, OnMissing, SplitPoint, Strategy, TargetType, TieBreak,
+};
+pub use crate::summary::{BinSummary, ExceptionSummary, FitResult, FitWarning};
+pub use crate::transform::PreTransform;
+pub use crate::tree::TreeNode;
@@ -0,0 +1,92 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::feature::iv_woe;
+use crate::summary::BinSummary;
+use crate::utils::SplitMix64;
+
+/// A bin summary with Laplace noise added to the counts, so that
+/// it can be shared without exposing the exact count of any bin.
+/// The discretizer itself is fit, and predicts, with the exact counts.
+#[derive(Debug, PartialEq)]
+pub struct NoisedBinSummary {
+    /// The summary of each bin, the counts are noised, and the weight
+    /// of evidence and information value are calculated from the
+    /// noised counts. The effective count is not reported, and is NaN.
+    pub bins: Vec<BinSummary>,
+    /// The privacy budget used to noise the counts.
+    pub epsilon: f64,
+}
+
+impl Discretizer {
+    /// Summary statistics for each bin, with noise drawn from a Laplace
+    /// distribution with scale `1 / epsilon` added to the count of ones
+    /// and zeros in each bin. Each record falls in a single bin, so the
+    /// summary is `epsilon` differentially private, as long as no record has
+    /// a sample weight greater than one. Noised counts are clipped at zero.
+    /// The same `seed` always produces the same noise.
+    pub fn noised_bin_summary(
+        &self,
+        epsilon: f64,
+        seed: u64,
+    ) -> Result<NoisedBinSummary, DiscrustError> {
+        if epsilon.is_nan() | (epsilon <= 0.0) {
+            return Err(DiscrustError::InvalidParameter(format!(
+                "epsilon must be greater than 0, but {} was passed",
+                epsilon
+            )));
+        }
+        let mut rng = SplitMix64(seed);
+        let scale = 1.0 / epsilon;
+        let mut bins = self.bin_summary()?;
+        for b in bins.iter_mut() {
+            b.ones_ct = (b.ones_ct + rng.laplace(scale)).max(0.0);
+            b.zero_ct = (b.zero_ct + rng.laplace(scale)).max(0.0);
+            b.totals_ct = b.ones_ct + b.zero_ct;
+            b.event_rate = b.ones_ct / b.totals_ct;
+            b.effective_ct = f64::NAN;
+        }
+        let total_ones: f64 = bins.iter().map(|b| b.ones_ct).sum();
+        let total_zero: f64 = bins.iter().map(|b| b.zero_ct).sum();
+        for b in bins.iter_mut() {
+            (b.iv, b.woe) = iv_woe(
+                b.ones_ct,
+                b.zero_ct,
+                total_ones,
+                total_zero,
+                self.woe_regularization,
+            );
+        }
+        Ok(NoisedBinSummary { bins, epsilon })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_noised_bin_summary() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.noised_bin_summary(1.0, 0).is_err());
+        disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        assert!(disc.noised_bin_summary(0.0, 0).is_err());
+        let noised = disc.noised_bin_summary(1.0, 42).unwrap();
+        let exact = disc.bin_summary().unwrap();
+        let repeated = disc.noised_bin_summary(1.0, 42).unwrap();
+        assert!(noised
+            .bins
+            .iter()
+            .zip(repeated.bins.iter())
+            .all(|(n, r)| n.ones_ct == r.ones_ct));
+        assert_eq!(noised.bins.len(), exact.len());
+        assert!(noised.bins.iter().zip(exact.iter()).all(|(n, e)| {
+            (n.ones_ct >= 0.0)
+                & (n.zero_ct >= 0.0)
+                & (n.ones_ct != e.ones_ct)
+                & (n.upper == e.upper)
+        }));
+    }
+}
@@ -0,0 +1,99 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::parallel::Threads;
+use crate::strategy::MonotonicTrend;
+use crate::utils::nan_safe_compare;
+
+/// The information value of a column, from a discretizer fit on it,
+/// to compare the predictive power of candidate features.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureRank {
+    pub name: String,
+    /// The total information value, of the bins, the
+    /// exception values, and the missing bin.
+    pub total_iv: f64,
+    /// The number of bins, not including the exception
+    /// values, or the missing bin.
+    pub n_bins: usize,
+    /// The trend of the fitted discretizer, the detected trend if it
+    /// was automatic, which remains automatic if no split was made.
+    pub mono: MonotonicTrend,
+}
+
+/// Fit an unfitted copy of the `config` discretizer to each of the named
+/// columns, against the binary performance `y`, and the sample weights
+/// `w`, if any, and rank the columns by their total information value,
+/// highest first. Columns with the same information value keep the order
+/// they were passed in. When built with the `rayon` feature, the columns
+/// are fit in parallel on the `n_threads` threads of `config`, which are
+/// built once, and also shared by the fit of each column. The first error
+/// of any column is returned.
+pub fn rank_features(
+    columns: &[(&str, &[f64])],
+    y: &[f64],
+    w: Option<&[f64]>,
+    config: &Discretizer,
+) -> Result<Vec<FeatureRank>, DiscrustError> {
+    let threads = Threads::new(config.n_threads)?;
+    let rank = |(name, x): &(&str, &[f64])| {
+        let mut disc = config.unfitted_copy();
+        disc.fit_on(x, y, w, None, &threads)?;
+        Ok(FeatureRank {
+            name: (*name).to_owned(),
+            total_iv: disc.total_iv_()?,
+            n_bins: disc.splits_.len() - 1,
+            mono: disc.mono,
+        })
+    };
+    #[cfg(feature = "rayon")]
+    let ranks: Result<Vec<FeatureRank>, DiscrustError> = match threads.pool() {
+        Some(pool) => {
+            use rayon::prelude::*;
+            pool.install(|| columns.par_iter().map(rank).collect())
+        }
+        None => columns.iter().map(rank).collect(),
+    };
+    #[cfg(not(feature = "rayon"))]
+    let ranks: Result<Vec<FeatureRank>, DiscrustError> = columns.iter().map(rank).collect();
+    let mut ranks = ranks?;
+    ranks.sort_by(|a, b| nan_safe_compare(&b.total_iv, &a.total_iv));
+    Ok(ranks)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rank_features() {
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+        let weak = vec![1.0, 2.0, 1.0, 2.0, 1.0, 2.0, 1.0, 2.0];
+        let strong = vec![4.0, 4.0, 3.0, 3.0, 2.0, 2.0, 1.0, 1.0];
+        let constant = vec![1.0; 8];
+        let mut config = Discretizer::new(Some(2.0), Some(10), Some(0.001), Some(1.0), None);
+        config.n_threads = Some(2);
+        let ranks = rank_features(
+            &[
+                ("constant", &constant),
+                ("weak", &weak),
+                ("strong", &strong),
+            ],
+            &y_,
+            None,
+            &config,
+        )
+        .unwrap();
+        let names: Vec<&str> = ranks.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["strong", "weak", "constant"]);
+
+        let mut disc = config.unfitted_copy();
+        disc.fit(&strong, &y_, None, None).unwrap();
+        assert_eq!(ranks[0].total_iv, disc.total_iv_().unwrap());
+        assert_eq!(ranks[0].n_bins, disc.splits_.len() - 1);
+        assert_eq!(ranks[0].mono, MonotonicTrend::Ascending);
+        assert_eq!(ranks[2].n_bins, 1);
+        assert_eq!(ranks[2].mono, MonotonicTrend::Auto);
+
+        assert!(rank_features(&[("short", &strong[..4])], &y_, None, &config).is_err());
+    }
+}
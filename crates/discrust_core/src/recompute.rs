@@ -0,0 +1,152 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::feature::Feature;
+use crate::parallel::Threads;
+use crate::strategy::TargetType;
+use crate::summary::{FitResult, FitWarning};
+
+impl Discretizer {
+    /// Recompute the statistics of the bins, exception values and missing
+    /// bin on new records, such as an out of time sample, keeping the
+    /// splits, exception values, merged exception values, and labels of
+    /// the fitted discretizer. Missing values are collected in the missing
+    /// bin as when fitting, so it is present if the new records have any.
+    /// The metadata, and the range of values checked by `out_of_range`,
+    /// still describe the records the splits were fit on.
+    pub fn recompute_stats(
+        &mut self,
+        x: &[f64],
+        y: &[f64],
+        w: Option<&[f64]>,
+    ) -> Result<FitResult, DiscrustError> {
+        let old = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        if !self.categories_.is_empty() {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "the statistics of a categorical feature can not be recomputed from numeric values",
+            )));
+        }
+        if x.is_empty() {
+            return Err(DiscrustError::EmptyInput);
+        }
+        self.check_target(y)?;
+        self.check_input(x)?;
+        // Merged exception values are counted as the value they were
+        // merged into, so their records are added to its bin.
+        let x: Vec<f64> = x
+            .iter()
+            .map(|v| old.merged_value(v).unwrap_or(*v))
+            .collect();
+        let exceptions = &old.exception_values_;
+        let mut feature = Feature::build(
+            &x,
+            y,
+            w,
+            &exceptions.vals_,
+            self.special_missing,
+            self.target_type,
+            &Threads::new(self.n_threads)?,
+        )?;
+        if self.target_type == TargetType::Multiclass {
+            feature.set_classes(&x, y, w)?;
+        }
+        // The exception values are built in sorted order, so they are
+        // put back in the order of the fitted discretizer.
+        let order: Vec<usize> = exceptions
+            .vals_
+            .iter()
+            .map(|v| feature.exception_values_.exception_idx(v).unwrap())
+            .collect();
+        feature.exception_values_.reorder(&order);
+        feature.exception_values_.labels_ = exceptions.labels_.clone();
+        if let (Some(label), false) = (old.missing_.label(0), feature.missing_.vals_.is_empty()) {
+            feature.missing_.set_label(0, label);
+        }
+        feature.merged_vals_ = old.merged_vals_.clone();
+        feature.merged_into_ = old.merged_into_.clone();
+        (feature.min_, feature.max_) = (old.min_, old.max_);
+        feature.set_woe_regularization(self.woe_regularization);
+        self.feature = Some(feature);
+        self.partial_ = None;
+        self.rebin_tree();
+
+        let mut warnings = Vec::new();
+        let exceptions = &self.feature.as_ref().unwrap().exception_values_;
+        for (v, ct) in exceptions.vals_.iter().zip(&exceptions.totals_ct_) {
+            if *ct == 0.0 {
+                warnings.push(FitWarning::ExceptionValueNotPresent(*v));
+            }
+        }
+        Ok(FitResult {
+            splits: self.splits_.to_vec(),
+            bins: self.bin_summary()?,
+            exceptions: self.exception_summary()?,
+            missing: self.missing_summary()?,
+            warnings,
+        })
+    }
+
+    // Point each node of the fitted tree at the unique values of the
+    // feature between its bounds, and recompute its statistics.
+    fn rebin_tree(&mut self) {
+        let feature = self.feature.as_ref().unwrap();
+        let closed = self.closed;
+        let mut stack = vec![(self.root_node.as_mut(), f64::NEG_INFINITY, f64::INFINITY)];
+        while let Some((Some(node), lower, upper)) = stack.pop() {
+            node.start = feature
+                .vals_
+                .partition_point(|v| closed.in_lower(v, &lower));
+            node.stop = feature
+                .vals_
+                .partition_point(|v| closed.in_lower(v, &upper));
+            let (iv, woe) = feature.range_iv_woe(node.start, node.stop);
+            node.iv = iv;
+            node.woe = woe;
+            if let Some(split) = node.split_info.split {
+                stack.push((node.left_node.as_mut(), lower, split));
+                stack.push((node.right_node.as_mut(), split, upper));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_recompute_stats() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.recompute_stats(&x_, &y_, None).is_err());
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0, -2.0]))
+            .unwrap();
+        disc.set_exception_order(&[-1.0, -2.0]).unwrap();
+        disc.set_exception_label(-1.0, "No history").unwrap();
+        let splits = disc.splits_.to_vec();
+
+        // New values, on both sides of the split, and a missing value.
+        let x_new = vec![0.5, 1.0, 1.5, 3.0, 3.0, -1.0, f64::NAN];
+        let y_new = vec![1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0];
+        let res = disc.recompute_stats(&x_new, &y_new, None).unwrap();
+        assert_eq!(res.splits, splits);
+        assert_eq!(res.bins[0].totals_ct, 2.0);
+        assert_eq!(res.bins[0].ones_ct, 1.0);
+        assert_eq!(res.bins[1].totals_ct, 3.0);
+        assert_eq!(res.exceptions[0].value, -1.0);
+        assert_eq!(res.exceptions[0].label, "No history");
+        assert_eq!(res.exceptions[0].totals_ct, 1.0);
+        assert_eq!(res.missing.unwrap().totals_ct, 1.0);
+        assert_eq!(
+            res.warnings,
+            vec![FitWarning::ExceptionValueNotPresent(-2.0)]
+        );
+        assert_eq!(disc.predict_idx(&[-1.0, -2.0]).unwrap(), vec![-1, -2]);
+
+        // The tree is consistent with the new records.
+        let tree = disc.tree().unwrap();
+        assert_eq!(tree.totals_ct, 5.0);
+        assert_eq!(tree.left.unwrap().totals_ct, 2.0);
+    }
+}
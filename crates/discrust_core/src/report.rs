@@ -0,0 +1,249 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::feature::Feature;
+use crate::node::{Node, SplitResult};
+use crate::tree::TreeNode;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Why a node of the tree was not split further. When no candidate
+/// split of a node is valid, the constraint reported is the one that
+/// rejected the candidate meeting the most constraints, checked in the
+/// order of the variants from `MinBinWidth` to `CrossValidation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StopReason {
+    /// The node holds a single value, so there is nothing to split.
+    NoCandidates,
+    /// Every split would create a bin narrower than `min_bin_width`.
+    MinBinWidth,
+    /// Every split would leave a bin with fewer ones than `min_pos`.
+    MinPos,
+    /// Every split would leave a bin with less weight than `min_obs`,
+    /// or a smaller effective sample size than `min_effective_obs`.
+    MinObs,
+    /// No split had a p-value of at most `max_pvalue`.
+    MaxPvalue,
+    /// No split gained at least `min_iv` information value.
+    MinIv,
+    /// No split passed the minimum description length test, with `mdlp`.
+    Mdlp,
+    /// No split gained `min_iv_gain_ratio` of the node's information value.
+    MinIvGain,
+    /// No split followed the monotonic trend.
+    Monotonicity,
+    /// No split held up on enough of the `cv_folds`.
+    CrossValidation,
+    /// The node was waiting to be split when `max_bins` was reached.
+    MaxBins,
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            StopReason::NoCandidates => "no_candidates",
+            StopReason::MinBinWidth => "min_bin_width",
+            StopReason::MinPos => "min_pos",
+            StopReason::MinObs => "min_obs",
+            StopReason::MaxPvalue => "max_pvalue",
+            StopReason::MinIv => "min_iv",
+            StopReason::Mdlp => "mdlp",
+            StopReason::MinIvGain => "min_iv_gain_ratio",
+            StopReason::Monotonicity => "monotonicity",
+            StopReason::CrossValidation => "cross_validation",
+            StopReason::MaxBins => "max_bins",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Why fitting stopped, the terminal nodes of the tree, and the
+/// number of them that were not split for each reason.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FitReport {
+    /// The terminal nodes of the tree, from the lowest to the highest.
+    pub terminal_nodes: Vec<TreeNode>,
+    pub stop_reasons: BTreeMap<StopReason, usize>,
+}
+
+/// The search for the best split of a node of the tree,
+/// recorded when fitting a discretizer that is `verbose`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FitEvent {
+    /// The smallest and largest value in the node,
+    /// None if the node holds no values.
+    pub range: Option<(f64, f64)>,
+    pub totals_ct: f64,
+    /// The number of candidate splits evaluated.
+    pub candidates: usize,
+    /// The number of candidate splits rejected by each constraint.
+    pub rejected: BTreeMap<StopReason, usize>,
+    /// The best valid split, and the information value it gains.
+    pub split: Option<f64>,
+    pub gain: Option<f64>,
+    /// Why the node can not be split, None if a valid split was found.
+    pub stop_reason: Option<StopReason>,
+}
+
+impl FitEvent {
+    pub(crate) fn new<F: Fn(f64) -> f64>(
+        node: &Node,
+        feature: &Feature,
+        result: &SplitResult,
+        to_original: F,
+    ) -> Self {
+        let (candidates, rejected) = node.rejected_candidates(feature);
+        let range = (node.stop > node.start).then(|| {
+            (
+                to_original(feature.vals_[node.start]),
+                to_original(feature.vals_[node.stop - 1]),
+            )
+        });
+        let (totals_ct, _, _) = feature.range_totals_ones_zero_ct(node.start, node.stop);
+        let (split, gain, stop_reason) = match result {
+            Ok(info) => (
+                info.split.map(&to_original),
+                Some(info.lhs_iv.unwrap() + info.rhs_iv.unwrap() - node.iv),
+                None,
+            ),
+            Err(reason) => (None, None, Some(*reason)),
+        };
+        FitEvent {
+            range,
+            totals_ct,
+            candidates,
+            rejected,
+            split,
+            gain,
+            stop_reason,
+        }
+    }
+}
+
+impl fmt::Display for FitEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.range {
+            Some((lower, upper)) => write!(f, "values {} to {}", lower, upper)?,
+            None => write!(f, "no values")?,
+        }
+        write!(
+            f,
+            " ({} records): {} candidates",
+            self.totals_ct, self.candidates
+        )?;
+        let rejected: Vec<String> = self
+            .rejected
+            .iter()
+            .map(|(reason, ct)| format!("{} {}", ct, reason))
+            .collect();
+        if !rejected.is_empty() {
+            write!(f, ", rejected by {}", rejected.join(", "))?;
+        }
+        match (self.split, self.gain, self.stop_reason) {
+            (Some(split), Some(gain), _) => write!(f, "; best split {}, gain {:.6}", split, gain),
+            (_, _, Some(reason)) => write!(f, "; not split, {}", reason),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Discretizer {
+    /// The search for the best split of each node of the tree, in the
+    /// order the nodes were searched, when the discretizer was last fit
+    /// with `verbose` set, to debug an unexpected binning.
+    pub fn fit_trace(&self) -> &[FitEvent] {
+        &self.fit_trace_
+    }
+
+    /// Report why each terminal node of the tree grown when the discretizer
+    /// was fit was not split further, to diagnose which constraint limited
+    /// the number of bins. The `quantile`, `uniform` and `manual` strategies
+    /// do not search for splits, so their root node has no stop reason.
+    pub fn fit_report(&self) -> Result<FitReport, DiscrustError> {
+        let tree = self.tree()?;
+        let terminal_nodes: Vec<TreeNode> = tree
+            .nodes()
+            .into_iter()
+            .filter(|n| n.is_terminal())
+            .cloned()
+            .collect();
+        let mut stop_reasons = BTreeMap::new();
+        for reason in terminal_nodes.iter().filter_map(|n| n.stop_reason) {
+            *stop_reasons.entry(reason).or_insert(0) += 1;
+        }
+        Ok(FitReport {
+            terminal_nodes,
+            stop_reasons,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fit_report() {
+        let x_: Vec<f64> = (0..30).map(|i| i as f64).collect();
+        let y_: Vec<f64> = (0..30)
+            .map(|i| ((i % 3 == 0) ^ (i >= 15)) as i64 as f64)
+            .collect();
+        let mut disc = Discretizer::new(Some(3.0), Some(10), None, Some(1.0), Some(1));
+        disc.woe_regularization = 0.5;
+        assert!(disc.fit_report().is_err());
+        disc.fit(&x_, &y_, None, None).unwrap();
+        let report = disc.fit_report().unwrap();
+        assert_eq!(
+            report.stop_reasons,
+            BTreeMap::from([(StopReason::MinObs, 2), (StopReason::Monotonicity, 2)])
+        );
+        assert_eq!(
+            report
+                .terminal_nodes
+                .iter()
+                .map(|n| n.totals_ct)
+                .sum::<f64>(),
+            30.0
+        );
+        assert!(report.terminal_nodes.iter().all(|n| n.left.is_none()));
+
+        // The upper half of the first split had a valid split,
+        // when the maximum number of bins was reached.
+        disc.max_bins = 2;
+        disc.fit(&x_, &y_, None, None).unwrap();
+        let report = disc.fit_report().unwrap();
+        assert_eq!(
+            report.stop_reasons,
+            BTreeMap::from([(StopReason::Monotonicity, 1), (StopReason::MaxBins, 1)])
+        );
+        assert_eq!(StopReason::MaxBins.to_string(), "max_bins");
+    }
+    #[test]
+    fn test_fit_trace() {
+        let x_: Vec<f64> = (0..30).map(|i| i as f64).collect();
+        let y_: Vec<f64> = (0..30)
+            .map(|i| ((i % 3 == 0) ^ (i >= 15)) as i64 as f64)
+            .collect();
+        let mut disc = Discretizer::new(Some(3.0), Some(10), None, Some(1.0), Some(1));
+        disc.woe_regularization = 0.5;
+        disc.fit(&x_, &y_, None, None).unwrap();
+        assert!(disc.fit_trace().is_empty());
+        disc.verbose = true;
+        disc.fit(&x_, &y_, None, None).unwrap();
+        let trace = disc.fit_trace();
+        // Every node of the tree was searched, once.
+        assert_eq!(trace.len(), disc.tree().unwrap().nodes().len());
+        let root = &trace[0];
+        assert_eq!(root.range, Some((0.0, 29.0)));
+        assert_eq!(root.candidates, 29);
+        assert_eq!(root.split, Some(15.0));
+        assert!(root.gain.unwrap() > 0.0);
+        assert_eq!(trace[1].stop_reason, Some(StopReason::Monotonicity));
+        assert_eq!(
+            trace[1].to_string(),
+            "values 0 to 15 (16 records): 15 candidates, rejected by 3 min_pos, 2 min_obs, 10 monotonicity; not split, monotonicity"
+        );
+    }
+}
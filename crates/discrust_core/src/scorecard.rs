@@ -0,0 +1,225 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::pipeline::{Pipeline, PipelineOutput};
+use crate::strategy::{OnMissing, TargetType};
+use std::fmt;
+
+/// How the log odds of a model are scaled to points. The score is
+/// `base_score` where the odds of a zero to a one are `base_odds`, and
+/// increases by `pdo` points each time these odds double, so a higher
+/// score means the event, a one, is less likely.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScorecardScaling {
+    pub base_score: f64,
+    pub base_odds: f64,
+    pub pdo: f64,
+}
+
+impl ScorecardScaling {
+    /// The points for each unit of the log odds of a zero to a one.
+    pub fn factor(&self) -> f64 {
+        self.pdo / 2.0_f64.ln()
+    }
+
+    /// The score where the log odds are zero.
+    pub fn offset(&self) -> f64 {
+        self.base_score - self.factor() * self.base_odds.ln()
+    }
+}
+
+/// The points of a single bin, exception value, or missing
+/// bin, of one of the features of a scorecard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScorecardRow {
+    pub feature: String,
+    pub label: String,
+    pub woe: f64,
+    pub points: f64,
+}
+
+/// Fitted discretizers, and the coefficients of a logistic regression
+/// fit on their weight of evidence, scaled to points. The points of
+/// each record are the sum of the points of its bin of each feature,
+/// where the intercept, and offset of the scaling, are split evenly
+/// between the features.
+pub struct Scorecard {
+    pub pipeline: Pipeline,
+    pub names: Vec<String>,
+    pub scaling: ScorecardScaling,
+}
+
+impl Scorecard {
+    /// Create a scorecard from discretizers fit against a binary target,
+    /// named `names`, and the coefficient of the weight of evidence of
+    /// each, with the intercept, of a model of the log odds of a one.
+    pub fn from_bins(
+        discretizers: Vec<Discretizer>,
+        names: Vec<String>,
+        coefficients: Vec<f64>,
+        intercept: f64,
+        scaling: ScorecardScaling,
+    ) -> Result<Self, DiscrustError> {
+        if names.len() != discretizers.len() {
+            return Err(DiscrustError::InvalidParameter(format!(
+                "{} names were provided for {} discretizers",
+                names.len(),
+                discretizers.len()
+            )));
+        }
+        if discretizers.is_empty() {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "a scorecard requires at least one discretizer",
+            )));
+        }
+        if discretizers
+            .iter()
+            .any(|d| d.target_type != TargetType::Binary)
+        {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "a scorecard requires discretizers fit against a binary target_type",
+            )));
+        }
+        let positive = |v: f64| v.is_finite() && v > 0.0;
+        if !positive(scaling.pdo) || !positive(scaling.base_odds) || !scaling.base_score.is_finite()
+        {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "pdo and base_odds must be finite and positive, and base_score finite",
+            )));
+        }
+        Ok(Scorecard {
+            pipeline: Pipeline::new(discretizers, coefficients, intercept)?,
+            names,
+            scaling,
+        })
+    }
+
+    // The points for a weight of evidence of a feature with the
+    // coefficient `coef`, including its share of the intercept.
+    fn points(&self, coef: f64, woe: f64) -> f64 {
+        let n = self.names.len() as f64;
+        self.scaling.offset() / n
+            - self.scaling.factor() * (coef * woe + self.pipeline.intercept / n)
+    }
+
+    /// The points of each bin, followed by each exception value, and
+    /// missing values, of each feature. Missing values are only included
+    /// if there is a missing bin, or they are given a weight of evidence
+    /// by `on_missing`.
+    pub fn points_table(&self) -> Result<Vec<ScorecardRow>, DiscrustError> {
+        let mut rows = Vec::new();
+        let features = self
+            .names
+            .iter()
+            .zip(&self.pipeline.discretizers)
+            .zip(&self.pipeline.coefficients);
+        for ((name, disc), coef) in features {
+            let feature = disc.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+            let (mut labels, exception_labels) = disc.labels(feature);
+            labels.extend(exception_labels);
+            let mut woe = disc.bin_woe_()?;
+            match (disc.missing_summary()?, disc.on_missing) {
+                (Some(m), _) => labels.push(m.label),
+                (None, OnMissing::WoeValue(w)) => {
+                    labels.push(disc.missing_label(feature));
+                    woe.push(w);
+                }
+                _ => (),
+            }
+            rows.extend(
+                labels
+                    .into_iter()
+                    .zip(woe)
+                    .map(|(label, woe)| ScorecardRow {
+                        feature: name.to_owned(),
+                        label,
+                        woe,
+                        points: self.points(*coef, woe),
+                    }),
+            );
+        }
+        Ok(rows)
+    }
+
+    /// The score of each record, from one column of values for each
+    /// feature, the sum of the points of the bins it falls in.
+    pub fn score(&self, x: &[&[f64]]) -> Result<Vec<f64>, DiscrustError> {
+        let log_odds = self.pipeline.predict(x, PipelineOutput::LogOdds)?;
+        Ok(log_odds
+            .iter()
+            .map(|lo| self.scaling.offset() - self.scaling.factor() * lo)
+            .collect())
+    }
+}
+
+impl fmt::Display for Scorecard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rows = self.points_table().map_err(|_| fmt::Error)?;
+        let width = |header: &str, col: &dyn Fn(&ScorecardRow) -> usize| {
+            rows.iter().map(col).fold(header.len(), usize::max)
+        };
+        let feature_w = width("feature", &|r| r.feature.chars().count());
+        let label_w = width("bin", &|r| r.label.chars().count());
+        writeln!(
+            f,
+            "{:<feature_w$}  {:<label_w$}  {:>10}  {:>10}",
+            "feature", "bin", "woe", "points"
+        )?;
+        for r in &rows {
+            writeln!(
+                f,
+                "{:<feature_w$}  {:<label_w$}  {:>10.4}  {:>10.2}",
+                r.feature, r.label, r.woe, r.points
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_scorecard() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut d1 = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        d1.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+        let mut d2 = d1.unfitted_copy();
+        d2.fit(&x_, &y_, Some(&w_), None).unwrap();
+        let scaling = ScorecardScaling {
+            base_score: 600.0,
+            base_odds: 50.0,
+            pdo: 20.0,
+        };
+        assert!((scaling.factor() - 28.8539).abs() < 1e-4);
+        let names = vec![String::from("a"), String::from("b")];
+        assert!(Scorecard::from_bins(
+            vec![d1.unfitted_copy()],
+            vec![String::from("a")],
+            vec![1.0],
+            0.0,
+            scaling
+        )
+        .is_err());
+        let card =
+            Scorecard::from_bins(vec![d1, d2], names, vec![0.8, 0.5], -0.2, scaling).unwrap();
+
+        // The score of each record is the sum of the points of its bins.
+        let table = card.points_table().unwrap();
+        assert_eq!(table.len(), 5);
+        assert_eq!(table[2].label, "-1");
+        let score = card.score(&[&x_, &x_]).unwrap();
+        let log_odds = card
+            .pipeline
+            .predict(&[&x_, &x_], PipelineOutput::LogOdds)
+            .unwrap();
+        assert!((score[0] - (scaling.offset() - scaling.factor() * log_odds[0])).abs() < 1e-9);
+        // Records of 1.0 are in the first bin of both features, and
+        // records of -1.0 are an exception value of the first only.
+        assert!((score[0] - (table[0].points + table[3].points)).abs() < 1e-9);
+        assert!((score[8] - (table[2].points + table[3].points)).abs() < 1e-9);
+        assert!(card.to_string().starts_with("feature  bin"));
+    }
+}
@@ -0,0 +1,417 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::feature::{check_records, Feature};
+use crate::strategy::OnMissing;
+use crate::summary::BinSummary;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+/// How the bins should be fit when a segment
+/// variable is provided.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SegmentMode {
+    /// Fit a single set of bins on all of the data,
+    /// and calculate the weight of evidence of these bins
+    /// separately for each segment.
+    Shared,
+    /// Fit a completely separate discretizer for
+    /// each segment.
+    Separate,
+}
+
+/// The weight of evidence of a single bin across each
+/// level of a segment variable.
+#[derive(Debug, PartialEq)]
+pub struct BinConsistency<K: Ord> {
+    /// The bin index, following the same convention as `predict_idx`,
+    /// so exception values have negative indexes.
+    pub bin: i64,
+    /// The weight of evidence of the bin in each segment. Segments
+    /// where the weight of evidence is not finite, such as when the
+    /// bin has no records in the segment, are left out.
+    pub segment_woe: BTreeMap<K, f64>,
+    /// True if the weight of evidence is positive in some
+    /// segments, and negative in others.
+    pub sign_flip: bool,
+    /// The difference between the largest and smallest
+    /// weight of evidence across segments.
+    pub woe_range: f64,
+    /// True if there is a sign flip, or the `woe_range`
+    /// exceeds the threshold.
+    pub inconsistent: bool,
+}
+
+impl<K: Ord> BinConsistency<K> {
+    pub(crate) fn new(bin: i64, segment_woe: BTreeMap<K, f64>, max_woe_diff: f64) -> Self {
+        let max_woe = segment_woe
+            .values()
+            .cloned()
+            .fold(f64::NEG_INFINITY, f64::max);
+        let min_woe = segment_woe.values().cloned().fold(f64::INFINITY, f64::min);
+        let sign_flip = (max_woe > 0.0) && (min_woe < 0.0);
+        let woe_range = if segment_woe.is_empty() {
+            0.0
+        } else {
+            max_woe - min_woe
+        };
+        BinConsistency {
+            bin,
+            segment_woe,
+            sign_flip,
+            woe_range,
+            inconsistent: sign_flip || (woe_range > max_woe_diff),
+        }
+    }
+}
+
+/// A discretizer that is fit across the levels
+/// of some segment variable.
+pub struct SegmentedDiscretizer<K: Ord + Clone + Debug> {
+    pub mode: SegmentMode,
+    params: Discretizer,
+    /// The discretizer fit on all the data, only
+    /// present when using the `Shared` mode.
+    pub shared_: Option<Discretizer>,
+    /// The discretizer fit for each segment, only
+    /// populated when using the `Separate` mode.
+    pub discretizers_: BTreeMap<K, Discretizer>,
+    // Feature for each segment, used to calculate
+    // segment level statistics for the shared bins.
+    segment_features_: BTreeMap<K, Feature>,
+}
+
+impl<K: Ord + Clone + Debug> SegmentedDiscretizer<K> {
+    /// Create a new segmented discretizer, each discretizer fit will
+    /// use the same parameters as the provided `discretizer`.
+    pub fn new(discretizer: &Discretizer, mode: SegmentMode) -> Self {
+        SegmentedDiscretizer {
+            mode,
+            params: discretizer.unfitted_copy(),
+            shared_: None,
+            discretizers_: BTreeMap::new(),
+            segment_features_: BTreeMap::new(),
+        }
+    }
+
+    pub fn fit(
+        &mut self,
+        x: &[f64],
+        y: &[f64],
+        w: &[f64],
+        segment: &[K],
+        exception_values: Option<Vec<f64>>,
+    ) -> Result<(), DiscrustError> {
+        check_segment(segment, x.len())?;
+        check_records(x.len(), y, Some(w))?;
+        self.shared_ = None;
+        self.discretizers_ = BTreeMap::new();
+        self.segment_features_ = BTreeMap::new();
+        let e = exception_values.unwrap_or_default();
+        let groups = segment_indices(segment);
+        match self.mode {
+            SegmentMode::Shared => {
+                let mut disc = self.params.unfitted_copy();
+                disc.fit(x, y, Some(w), Some(e.to_vec()))?;
+                for (k, idx) in groups {
+                    let (x_, y_, w_) = take_records(&idx, x, y, w);
+                    let feature = disc.subset_feature(&x_, &y_, &w_, &e)?;
+                    self.segment_features_.insert(k, feature);
+                }
+                self.shared_ = Some(disc);
+            }
+            SegmentMode::Separate => {
+                for (k, idx) in groups {
+                    let (x_, y_, w_) = take_records(&idx, x, y, w);
+                    let mut disc = self.params.unfitted_copy();
+                    disc.fit(&x_, &y_, Some(&w_), Some(e.to_vec()))?;
+                    self.discretizers_.insert(k, disc);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The bin summary for each segment.
+    pub fn bin_summary(&self) -> Result<BTreeMap<K, Vec<BinSummary>>, DiscrustError> {
+        match self.mode {
+            SegmentMode::Shared => {
+                let disc = self.shared_.as_ref().ok_or(DiscrustError::NotFitted)?;
+                Ok(self
+                    .segment_features_
+                    .iter()
+                    .map(|(k, f)| (k.clone(), disc.bin_summary_with(f)))
+                    .collect())
+            }
+            SegmentMode::Separate => {
+                if self.discretizers_.is_empty() {
+                    return Err(DiscrustError::NotFitted);
+                }
+                self.discretizers_
+                    .iter()
+                    .map(|(k, d)| Ok((k.clone(), d.bin_summary()?)))
+                    .collect()
+            }
+        }
+    }
+
+    /// A single combined report, with one row for every
+    /// bin in every segment.
+    pub fn report(&self) -> Result<Vec<(K, BinSummary)>, DiscrustError> {
+        Ok(self
+            .bin_summary()?
+            .into_iter()
+            .flat_map(|(k, bins)| bins.into_iter().map(move |b| (k.clone(), b)))
+            .collect())
+    }
+
+    pub fn predict_idx(&self, x: &[f64], segment: &[K]) -> Result<Vec<i64>, DiscrustError> {
+        check_segment(segment, x.len())?;
+        match self.mode {
+            SegmentMode::Shared => self
+                .shared_
+                .as_ref()
+                .ok_or(DiscrustError::NotFitted)?
+                .predict_idx(x),
+            SegmentMode::Separate => {
+                let mut res = vec![0; x.len()];
+                for (k, idx) in segment_indices(segment) {
+                    let disc = self.segment_discretizer(&k)?;
+                    let x_: Vec<f64> = idx.iter().map(|i| x[*i]).collect();
+                    for (i, p) in idx.iter().zip(disc.predict_idx(&x_)?) {
+                        res[*i] = p;
+                    }
+                }
+                Ok(res)
+            }
+        }
+    }
+
+    /// Weight of evidence substitution, using the weight
+    /// of evidence of each record's segment.
+    pub fn predict_woe(&self, x: &[f64], segment: &[K]) -> Result<Vec<f64>, DiscrustError> {
+        check_segment(segment, x.len())?;
+        let mut res = vec![0.0; x.len()];
+        for (k, idx) in segment_indices(segment) {
+            let x_: Vec<f64> = idx.iter().map(|i| x[*i]).collect();
+            let woe = match self.mode {
+                SegmentMode::Shared => {
+                    let disc = self.shared_.as_ref().ok_or(DiscrustError::NotFitted)?;
+                    let feature = self
+                        .segment_features_
+                        .get(&k)
+                        .ok_or_else(|| DiscrustError::UnknownSegment(format!("{:?}", k)))?;
+                    let bins = disc.bin_summary_with(feature);
+                    // Missing values are predicted in the missing bin, if
+                    // there is one, otherwise according to `on_missing`.
+                    let shared_feature = disc.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+                    let missing_woe = match disc.on_missing {
+                        _ if shared_feature.in_missing_bin(&f64::NAN) => 0.0,
+                        OnMissing::WoeValue(w) => w,
+                        _ => 0.0,
+                    };
+                    disc.predict_idx(&x_)?
+                        .into_iter()
+                        .map(|b| shared_bin_woe(b, &bins, feature, missing_woe))
+                        .collect()
+                }
+                SegmentMode::Separate => self.segment_discretizer(&k)?.predict_woe(&x_)?,
+            };
+            for (i, p) in idx.iter().zip(woe) {
+                res[*i] = p;
+            }
+        }
+        Ok(res)
+    }
+
+    fn segment_discretizer(&self, k: &K) -> Result<&Discretizer, DiscrustError> {
+        if self.discretizers_.is_empty() {
+            return Err(DiscrustError::NotFitted);
+        }
+        self.discretizers_
+            .get(k)
+            .ok_or_else(|| DiscrustError::UnknownSegment(format!("{:?}", k)))
+    }
+}
+
+// Look up the weight of evidence of a bin index. Bins, exception values,
+// or the missing bin, that were not present in the segment have a weight
+// of evidence of zero.
+fn shared_bin_woe(bin: i64, bins: &[BinSummary], feature: &Feature, missing_woe: f64) -> f64 {
+    let (ct, woe) = if bin < 0 {
+        let e_idx = (-bin - 1) as usize;
+        let excp = &feature.exception_values_;
+        // The index after the last exception value is a missing value,
+        // in the missing bin of the segment, if it has missing values.
+        if e_idx == excp.vals_.len() {
+            if feature.in_missing_bin(&f64::NAN) {
+                return feature.missing_.woe_[0];
+            }
+            return missing_woe;
+        }
+        (excp.totals_ct_[e_idx], excp.woe_[e_idx])
+    } else {
+        let b = &bins[bin as usize];
+        (b.totals_ct, b.woe)
+    };
+    if ct == 0.0 {
+        0.0
+    } else {
+        woe
+    }
+}
+
+// Check that there is a segment for each of the `n` values of `x`.
+pub(crate) fn check_segment<K>(segment: &[K], n: usize) -> Result<(), DiscrustError> {
+    if segment.len() != n {
+        return Err(DiscrustError::LengthMismatch(
+            String::from("segment"),
+            segment.len(),
+            n,
+        ));
+    }
+    Ok(())
+}
+
+// The record indexes of each level of the segment.
+pub(crate) fn segment_indices<K: Ord + Clone>(segment: &[K]) -> BTreeMap<K, Vec<usize>> {
+    let mut groups: BTreeMap<K, Vec<usize>> = BTreeMap::new();
+    for (i, k) in segment.iter().enumerate() {
+        groups.entry(k.clone()).or_default().push(i);
+    }
+    groups
+}
+
+pub(crate) fn take_records(
+    idx: &[usize],
+    x: &[f64],
+    y: &[f64],
+    w: &[f64],
+) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    (
+        idx.iter().map(|i| x[*i]).collect(),
+        idx.iter().map(|i| y[*i]).collect(),
+        idx.iter().map(|i| w[*i]).collect(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+
+    fn titanic_fare() -> (Vec<f64>, Vec<f64>) {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
+        }
+        (fare, survived)
+    }
+
+    #[test]
+    fn test_segmented_shared() {
+        let (fare, survived) = titanic_fare();
+        let w_ = vec![1.0; fare.len()];
+        let segment: Vec<usize> = (0..fare.len()).map(|i| i % 2).collect();
+        let disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), Some(1));
+        let mut seg = SegmentedDiscretizer::new(&disc, SegmentMode::Shared);
+        seg.fit(&fare, &survived, &w_, &segment, None).unwrap();
+
+        let shared = seg.shared_.as_ref().unwrap();
+        let summary = seg.bin_summary().unwrap();
+        assert_eq!(summary.len(), 2);
+        // The segment totals should add up to the shared totals.
+        let shared_summary = shared.bin_summary().unwrap();
+        for (i, b) in shared_summary.iter().enumerate() {
+            assert_eq!(
+                b.totals_ct,
+                summary[&0][i].totals_ct + summary[&1][i].totals_ct
+            );
+        }
+        assert_eq!(seg.report().unwrap().len(), 2 * shared_summary.len());
+
+        let woe = seg.predict_woe(&fare, &segment).unwrap();
+        assert_eq!(
+            woe[0],
+            summary[&0][shared.predict_idx(&fare[0..1]).unwrap()[0] as usize].woe
+        );
+        assert!(seg.predict_woe(&fare[0..1], &[2]).is_err());
+    }
+
+    #[test]
+    fn test_segmented_shared_missing() {
+        let (mut fare, survived) = titanic_fare();
+        for i in (0..fare.len()).step_by(7) {
+            fare[i] = f64::NAN;
+        }
+        let w_ = vec![1.0; fare.len()];
+        let segment: Vec<usize> = (0..fare.len()).map(|i| i % 2).collect();
+        let disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), Some(1));
+        let mut seg = SegmentedDiscretizer::new(&disc, SegmentMode::Shared);
+        seg.fit(&fare, &survived, &w_, &segment, None).unwrap();
+
+        // Each segment collects its missing values in its own missing bin.
+        let feature = &seg.segment_features_[&0];
+        let missing_ct = (0..fare.len())
+            .filter(|i| i % 2 == 0 && fare[*i].is_nan())
+            .count();
+        assert_eq!(feature.missing_.totals_ct_[0], missing_ct as f64);
+        let woe = seg.predict_woe(&fare[..1], &segment[..1]).unwrap();
+        assert_eq!(woe[0], feature.missing_.woe_[0]);
+        assert!(woe[0].is_finite());
+    }
+
+    #[test]
+    fn test_segmented_separate() {
+        let (fare, survived) = titanic_fare();
+        let w_ = vec![1.0; fare.len()];
+        let segment: Vec<&str> = (0..fare.len())
+            .map(|i| if i < 400 { "a" } else { "b" })
+            .collect();
+        let disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), Some(1));
+        let mut seg = SegmentedDiscretizer::new(&disc, SegmentMode::Separate);
+        assert!(seg.report().is_err());
+        seg.fit(&fare, &survived, &w_, &segment, None).unwrap();
+
+        let mut a = disc.unfitted_copy();
+        a.fit(&fare[..400], &survived[..400], Some(&w_[..400]), None)
+            .unwrap();
+        assert_eq!(seg.discretizers_["a"].splits_, a.splits_);
+        assert_eq!(
+            seg.predict_woe(&fare[..400], &segment[..400]).unwrap(),
+            a.predict_woe(&fare[..400]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_segmented_length_mismatch() {
+        let (fare, survived) = titanic_fare();
+        let w_ = vec![1.0; fare.len()];
+        let segment: Vec<usize> = (0..fare.len()).map(|i| i % 2).collect();
+        let disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), Some(1));
+        for mode in [SegmentMode::Shared, SegmentMode::Separate] {
+            let mut seg = SegmentedDiscretizer::new(&disc, mode);
+            assert!(matches!(
+                seg.fit(&fare, &survived, &w_, &segment[1..], None),
+                Err(DiscrustError::LengthMismatch(c, _, _)) if c == "segment"
+            ));
+            assert!(matches!(
+                seg.fit(&fare, &survived[1..], &w_, &segment, None),
+                Err(DiscrustError::LengthMismatch(c, _, _)) if c == "y"
+            ));
+            seg.fit(&fare, &survived, &w_, &segment, None).unwrap();
+            assert!(matches!(
+                seg.predict_woe(&fare, &segment[1..]),
+                Err(DiscrustError::LengthMismatch(c, _, _)) if c == "segment"
+            ));
+            assert!(matches!(
+                seg.predict_idx(&fare[1..], &segment),
+                Err(DiscrustError::LengthMismatch(c, _, _)) if c == "segment"
+            ));
+        }
+    }
+}
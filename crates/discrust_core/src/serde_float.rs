@@ -0,0 +1,165 @@
+//! Serialization of floats that may not be finite. Formats such as
+//! JSON can not represent NaN or infinity, so in human readable formats
+//! these are written as the strings "NaN", "inf" and "-inf". Other
+//! formats, such as bincode, store the float as is.
+use serde::de::{self, Deserializer, Visitor};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+struct Float(f64);
+
+impl Serialize for Float {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let v = self.0;
+        if v.is_finite() || !serializer.is_human_readable() {
+            serializer.serialize_f64(v)
+        } else if v.is_nan() {
+            serializer.serialize_str("NaN")
+        } else if v > 0.0 {
+            serializer.serialize_str("inf")
+        } else {
+            serializer.serialize_str("-inf")
+        }
+    }
+}
+
+struct FloatVisitor;
+
+impl<'de> Visitor<'de> for FloatVisitor {
+    type Value = Float;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a float, or one of \"NaN\", \"inf\" or \"-inf\"")
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Float, E> {
+        Ok(Float(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Float, E> {
+        Ok(Float(v as f64))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Float, E> {
+        Ok(Float(v as f64))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Float, E> {
+        match v {
+            "NaN" => Ok(Float(f64::NAN)),
+            "inf" => Ok(Float(f64::INFINITY)),
+            "-inf" => Ok(Float(f64::NEG_INFINITY)),
+            _ => Err(E::invalid_value(de::Unexpected::Str(v), &self)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Float {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(FloatVisitor)
+        } else {
+            deserializer.deserialize_f64(FloatVisitor)
+        }
+    }
+}
+
+pub fn serialize<S: Serializer>(v: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    Float(*v).serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    Ok(Float::deserialize(deserializer)?.0)
+}
+
+pub mod option {
+    use super::Float;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error> {
+        v.map(Float).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<f64>, D::Error> {
+        Ok(Option::<Float>::deserialize(deserializer)?.map(|f| f.0))
+    }
+}
+
+pub mod vec {
+    use super::Float;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &[f64], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(v.iter().map(|f| Float(*f)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<f64>, D::Error> {
+        Ok(Vec::<Float>::deserialize(deserializer)?
+            .into_iter()
+            .map(|f| f.0)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::discretize::Discretizer;
+    use std::fs;
+
+    fn fitted() -> (Discretizer, Vec<f64>) {
+        let mut fare: Vec<f64> = Vec::new();
+        let mut survived: Vec<f64> = Vec::new();
+        let file = fs::read_to_string("resources/data.csv")
+            .expect("Something went wrong reading the file");
+        for l in file.lines() {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            fare.push(split[0]);
+            survived.push(split[1]);
+        }
+        // An exception value with no records has a NaN weight of evidence.
+        fare[0] = f64::NAN;
+        let w_ = vec![1.0; fare.len()];
+        let mut disc = Discretizer::new(Some(5.0), Some(10), Some(0.001), Some(1.0), None);
+        disc.fit(&fare, &survived, Some(&w_), Some(vec![f64::NAN, -1.0]))
+            .unwrap();
+        (disc, fare)
+    }
+
+    fn assert_same_predictions(a: &Discretizer, b: &Discretizer, x: &[f64]) {
+        assert_eq!(a.splits_, b.splits_);
+        assert_eq!(a.mono, b.mono);
+        assert_eq!(a.metadata().unwrap(), b.metadata().unwrap());
+        assert_eq!(a.predict_idx(x).unwrap(), b.predict_idx(x).unwrap());
+        assert_eq!(a.predict_woe(x).unwrap(), b.predict_woe(x).unwrap());
+        assert_eq!(a.bin_summary().unwrap(), b.bin_summary().unwrap());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let (disc, fare) = fitted();
+        let json = serde_json::to_string(&disc).unwrap();
+        assert!(json.contains("\"-inf\""));
+        let loaded: Discretizer = serde_json::from_str(&json).unwrap();
+        assert_same_predictions(&disc, &loaded, &fare);
+        let exceptions = &loaded.feature.as_ref().unwrap().exception_values_;
+        assert!(exceptions.vals_[0].is_nan());
+    }
+
+    #[test]
+    fn test_bincode_round_trip() {
+        let (disc, fare) = fitted();
+        let bytes = bincode::serialize(&disc).unwrap();
+        let loaded: Discretizer = bincode::deserialize(&bytes).unwrap();
+        assert_same_predictions(&disc, &loaded, &fare);
+    }
+
+    #[test]
+    fn test_invalid_float() {
+        let res: Result<Vec<f64>, _> =
+            super::vec::deserialize(&mut serde_json::Deserializer::from_str("[1.0, \"big\"]"));
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::parallel::Threads;
+use crate::pipeline::{check_columns, Pipeline};
+use crate::summary::FitResult;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A discretizer for each of several named columns, fit
+/// against the same binary performance and sample weights.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DiscretizerSet {
+    pub names: Vec<String>,
+    pub discretizers: Vec<Discretizer>,
+    /// The number of threads the columns are predicted with, when built
+    /// with the `rayon` feature, taken from the template. Zero uses one
+    /// thread per core, and None the calling thread.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub n_threads: Option<usize>,
+}
+
+impl DiscretizerSet {
+    /// Create a set with an unfitted copy of the `template`
+    /// discretizer for each of the named columns.
+    pub fn new(template: &Discretizer, names: Vec<String>) -> Self {
+        let discretizers = names.iter().map(|_| template.unfitted_copy()).collect();
+        DiscretizerSet {
+            names,
+            discretizers,
+            n_threads: template.n_threads,
+        }
+    }
+
+    /// Fit each discretizer on its column, in the same order as `names`.
+    /// The same exception values are used for every column, and the
+    /// `n_threads` threads of the set are built once, for every column.
+    pub fn fit(
+        &mut self,
+        x: &[&[f64]],
+        y: &[f64],
+        w: &[f64],
+        exception_values: Option<Vec<f64>>,
+    ) -> Result<Vec<FitResult>, DiscrustError> {
+        check_columns(x, self.discretizers.len())?;
+        let threads = Threads::new(self.n_threads)?;
+        self.discretizers
+            .iter_mut()
+            .zip(x)
+            .map(|(disc, col)| disc.fit_on(col, y, Some(w), exception_values.clone(), &threads))
+            .collect()
+    }
+
+    /// The discretizer fit on the named column.
+    pub fn get(&self, name: &str) -> Option<&Discretizer> {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .map(|i| &self.discretizers[i])
+    }
+
+    /// The weight of evidence of each value, returned as a row for
+    /// each record, with a value for each column in the order of `names`.
+    pub fn predict_woe_matrix(&self, x: &[&[f64]]) -> Result<Vec<Vec<f64>>, DiscrustError> {
+        self.predict_matrix(x, |disc, col| disc.predict_woe(col))
+    }
+
+    /// The bin index of each value, as from `predict_idx`, returned as a
+    /// row for each record, with a value for each column in the order of
+    /// `names`.
+    pub fn predict_idx_matrix(&self, x: &[&[f64]]) -> Result<Vec<Vec<i64>>, DiscrustError> {
+        self.predict_matrix(x, |disc, col| disc.predict_idx(col))
+    }
+
+    // Predict each column with its discretizer, on `n_threads` threads,
+    // and collect the predictions into a row for each record.
+    fn predict_matrix<T, F>(&self, x: &[&[f64]], predict: F) -> Result<Vec<Vec<T>>, DiscrustError>
+    where
+        T: Copy + Send,
+        F: Fn(&Discretizer, &[f64]) -> Result<Vec<T>, DiscrustError> + Sync,
+    {
+        let n_rows = check_columns(x, self.discretizers.len())?;
+        let columns = self.predict_columns(x, predict)?;
+        Ok((0..n_rows)
+            .map(|i| columns.iter().map(|c| c[i]).collect())
+            .collect())
+    }
+
+    fn predict_columns<T, F>(&self, x: &[&[f64]], predict: F) -> Result<Vec<Vec<T>>, DiscrustError>
+    where
+        T: Send,
+        F: Fn(&Discretizer, &[f64]) -> Result<Vec<T>, DiscrustError> + Sync,
+    {
+        #[cfg(feature = "rayon")]
+        if let Some(pool) = Threads::new(self.n_threads)?.pool() {
+            use rayon::prelude::*;
+            return pool.install(|| {
+                self.discretizers
+                    .par_iter()
+                    .zip(x)
+                    .map(|(disc, col)| predict(disc, col))
+                    .collect()
+            });
+        }
+        self.discretizers
+            .iter()
+            .zip(x)
+            .map(|(disc, col)| predict(disc, col))
+            .collect()
+    }
+
+    /// Chain the fitted discretizers with a linear model,
+    /// with a coefficient for each column.
+    pub fn into_pipeline(
+        self,
+        coefficients: Vec<f64>,
+        intercept: f64,
+    ) -> Result<Pipeline, DiscrustError> {
+        Pipeline::new(self.discretizers, coefficients, intercept)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_discretizer_set() {
+        let x1 = vec![1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0];
+        let x2 = vec![4.0, 4.0, 3.0, 3.0, 2.0, 2.0, 1.0, 1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+        let w_ = vec![1.0; y_.len()];
+        let template = Discretizer::new(Some(2.0), Some(10), Some(0.001), Some(1.0), None);
+        let mut set = DiscretizerSet::new(&template, vec![String::from("x1"), String::from("x2")]);
+        assert!(set.predict_woe_matrix(&[&x1, &x2]).is_err());
+        assert!(set.fit(&[&x1], &y_, &w_, None).is_err());
+        let res = set.fit(&[&x1, &x2], &y_, &w_, None).unwrap();
+        assert_eq!(res.len(), 2);
+
+        let woe = set.predict_woe_matrix(&[&x1, &x2]).unwrap();
+        assert_eq!(woe.len(), 8);
+        let x1_woe = set.get("x1").unwrap().predict_woe(&x1).unwrap();
+        let x2_woe = set.get("x2").unwrap().predict_woe(&x2).unwrap();
+        for (i, row) in woe.iter().enumerate() {
+            assert_eq!(row, &vec![x1_woe[i], x2_woe[i]]);
+        }
+        assert!(set.get("x3").is_none());
+
+        // The columns predicted on several threads are the same.
+        let idx = set.predict_idx_matrix(&[&x1, &x2]).unwrap();
+        set.n_threads = Some(2);
+        assert_eq!(set.predict_woe_matrix(&[&x1, &x2]).unwrap(), woe);
+        assert_eq!(set.predict_idx_matrix(&[&x1, &x2]).unwrap(), idx);
+        assert_eq!(
+            idx[0][0],
+            set.get("x1").unwrap().predict_idx(&x1).unwrap()[0]
+        );
+        assert!(set.predict_idx_matrix(&[&x1]).is_err());
+
+        let pipeline = set.into_pipeline(vec![1.0, 1.0], 0.0).unwrap();
+        assert_eq!(pipeline.discretizers.len(), 2);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::utils::to_f64;
+use num::Float;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The values a source system is expected to provide for a feature.
+/// When attached to a discretizer, the values passed to `fit` and
+/// `predict` are checked against it, so that changes upstream, such as
+/// a new special code, or a rise in missing values, are caught rather
+/// than silently changing the bins.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InputSpec {
+    /// The name of the source system, reported with any violations.
+    pub name: String,
+    /// The smallest expected value, if any.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float::option"))]
+    pub min_value: Option<f64>,
+    /// The largest expected value, if any.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float::option"))]
+    pub max_value: Option<f64>,
+    /// Codes the source system uses for special cases, such as -999 for
+    /// "not recorded", and a name for each. These are allowed outside
+    /// of the expected range, and are usually passed as exception values.
+    pub special_codes: Vec<(String, f64)>,
+    /// The smallest share of the values that must not be missing (NaN).
+    pub min_non_null_share: Option<f64>,
+}
+
+/// A way in which values do not match an `InputSpec`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpecViolation {
+    /// Values, that are not special codes, fell below `min_value`.
+    BelowMin {
+        min_value: f64,
+        count: usize,
+        smallest: f64,
+    },
+    /// Values, that are not special codes, fell above `max_value`.
+    AboveMax {
+        max_value: f64,
+        count: usize,
+        largest: f64,
+    },
+    /// The share of values that are not missing was below `min_non_null_share`.
+    NonNullShare { required: f64, observed: f64 },
+}
+
+impl fmt::Display for SpecViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpecViolation::BelowMin {
+                min_value,
+                count,
+                smallest,
+            } => write!(
+                f,
+                "{} values below the minimum {} (smallest {})",
+                count, min_value, smallest
+            ),
+            SpecViolation::AboveMax {
+                max_value,
+                count,
+                largest,
+            } => write!(
+                f,
+                "{} values above the maximum {} (largest {})",
+                count, max_value, largest
+            ),
+            SpecViolation::NonNullShare { required, observed } => write!(
+                f,
+                "non-null share {} below the required {}",
+                observed, required
+            ),
+        }
+    }
+}
+
+impl InputSpec {
+    /// A spec for a source system, that places no constraints on the values.
+    pub fn new(name: &str) -> Self {
+        InputSpec {
+            name: name.to_string(),
+            min_value: None,
+            max_value: None,
+            special_codes: Vec::new(),
+            min_non_null_share: None,
+        }
+    }
+
+    /// The special code values, in the order they were provided.
+    pub fn codes(&self) -> Vec<f64> {
+        self.special_codes.iter().map(|(_, c)| *c).collect()
+    }
+
+    /// Whether the value is one of the special codes.
+    pub fn is_code(&self, v: &f64) -> bool {
+        self.special_codes.iter().any(|(_, c)| c == v)
+    }
+
+    /// Each way in which the values do not match the spec,
+    /// an empty vector means the values match.
+    pub fn violations<T: Float>(&self, x: &[T]) -> Vec<SpecViolation> {
+        let x = || x.iter().map(|v| to_f64(*v));
+        let mut violations = Vec::new();
+        if let Some(min_value) = self.min_value {
+            let below: Vec<f64> = x()
+                .filter(|v| (*v < min_value) && !self.is_code(v))
+                .collect();
+            if !below.is_empty() {
+                violations.push(SpecViolation::BelowMin {
+                    min_value,
+                    count: below.len(),
+                    smallest: below.iter().cloned().fold(f64::INFINITY, f64::min),
+                });
+            }
+        }
+        if let Some(max_value) = self.max_value {
+            let above: Vec<f64> = x()
+                .filter(|v| (*v > max_value) && !self.is_code(v))
+                .collect();
+            if !above.is_empty() {
+                violations.push(SpecViolation::AboveMax {
+                    max_value,
+                    count: above.len(),
+                    largest: above.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+                });
+            }
+        }
+        // An empty input has no share of missing values to check.
+        let n = x().len();
+        if let (Some(required), false) = (self.min_non_null_share, n == 0) {
+            let non_null = x().filter(|v| !v.is_nan()).count();
+            let observed = non_null as f64 / n as f64;
+            if observed < required {
+                violations.push(SpecViolation::NonNullShare { required, observed });
+            }
+        }
+        violations
+    }
+}
+
+impl Discretizer {
+    /// Check the values against the `input_spec`, if one is attached.
+    pub fn check_input<T: Float>(&self, x: &[T]) -> Result<(), DiscrustError> {
+        if let Some(spec) = &self.input_spec {
+            let violations = spec.violations(x);
+            if !violations.is_empty() {
+                return Err(DiscrustError::InputSpecViolation(
+                    spec.name.to_string(),
+                    violations,
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_violations() {
+        let mut spec = InputSpec::new("bureau");
+        spec.min_value = Some(0.0);
+        spec.max_value = Some(100.0);
+        spec.special_codes = vec![(String::from("not recorded"), -999.0)];
+        spec.min_non_null_share = Some(0.8);
+        assert!(spec.violations(&[0.0, 50.0, 100.0, -999.0]).is_empty());
+        assert!(spec.violations::<f64>(&[]).is_empty());
+        assert_eq!(
+            spec.violations(&[-1.0, -5.0, 150.0, f64::NAN, f64::NAN, 1.0]),
+            vec![
+                SpecViolation::BelowMin {
+                    min_value: 0.0,
+                    count: 2,
+                    smallest: -5.0
+                },
+                SpecViolation::AboveMax {
+                    max_value: 100.0,
+                    count: 1,
+                    largest: 150.0
+                },
+                SpecViolation::NonNullShare {
+                    required: 0.8,
+                    observed: 4.0 / 6.0
+                },
+            ]
+        );
+        assert_eq!(spec.codes(), vec![-999.0]);
+    }
+
+    #[test]
+    fn test_fit_and_predict_check_input() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        let mut spec = InputSpec::new("bureau");
+        spec.min_value = Some(0.0);
+        disc.input_spec = Some(spec);
+        match disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])) {
+            Err(DiscrustError::InputSpecViolation(name, v)) => {
+                assert_eq!(name, "bureau");
+                assert_eq!(v.len(), 1);
+            }
+            r => panic!("Unexpected result {:?}", r),
+        }
+        disc.input_spec
+            .as_mut()
+            .unwrap()
+            .special_codes
+            .push((String::from("no file"), -1.0));
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+        assert!(disc.predict_woe(&[1.0, -1.0]).is_ok());
+        assert!(disc.predict_idx(&[-2.0]).is_err());
+        assert!(disc.predict_woe(&[-2.0]).is_err());
+        // A copy keeps the spec.
+        assert!(disc.unfitted_copy().input_spec.is_some());
+    }
+}
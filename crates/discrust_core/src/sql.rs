@@ -0,0 +1,168 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::export::ExportOutput;
+
+/// The SQL dialect an exported binning is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlDialect {
+    /// Standard SQL, which has no NaN or infinite values,
+    /// these are written as NULL.
+    Ansi,
+    /// PostgreSQL, where a double precision column may also be NaN.
+    Postgres,
+    /// Spark SQL, where a double column may also be NaN, and
+    /// backslashes in string literals are escapes.
+    Spark,
+}
+
+impl SqlDialect {
+    fn number(&self, v: f64) -> String {
+        if v.is_finite() {
+            return format!("{}", v);
+        }
+        let name = if v.is_nan() {
+            "NaN"
+        } else if v > 0.0 {
+            "Infinity"
+        } else {
+            "-Infinity"
+        };
+        match self {
+            SqlDialect::Ansi => String::from("NULL"),
+            SqlDialect::Postgres => format!("CAST('{}' AS DOUBLE PRECISION)", name),
+            SqlDialect::Spark => format!("CAST('{}' AS DOUBLE)", name),
+        }
+    }
+
+    fn string(&self, s: &str) -> String {
+        let escaped = match self {
+            SqlDialect::Spark => s.replace('\\', "\\\\").replace('\'', "\\'"),
+            _ => s.replace('\'', "''"),
+        };
+        format!("'{}'", escaped)
+    }
+
+    // The condition matching a missing value of `column`,
+    // NULL, or in dialects with NaN values, NaN.
+    fn is_missing(&self, column: &str) -> String {
+        match self {
+            SqlDialect::Ansi => format!("{} IS NULL", column),
+            SqlDialect::Postgres => {
+                format!("({0} IS NULL OR {0} = {1})", column, self.number(f64::NAN))
+            }
+            SqlDialect::Spark => format!("({0} IS NULL OR isnan({0}))", column),
+        }
+    }
+}
+
+impl Discretizer {
+    /// Export the fitted binning as a SQL `CASE` expression, mapping
+    /// `column` to its weight of evidence, bin index or label. The column
+    /// is written as passed, so it may be a qualified name, or any numeric
+    /// expression, and must be quoted if required. Missing values, NULL
+    /// and NaN, are mapped to the exception value of NaN, or the missing
+    /// bin, otherwise according to `on_missing`, where an error is NULL.
+    pub fn to_sql(
+        &self,
+        column: &str,
+        dialect: SqlDialect,
+        output: ExportOutput,
+    ) -> Result<String, DiscrustError> {
+        let rules = self.export_rules(output)?;
+        let value = |value: f64, label: &str| match output {
+            ExportOutput::Label => dialect.string(label),
+            _ => dialect.number(value),
+        };
+        let mut branches: Vec<String> = Vec::new();
+        // A missing value that is not matched by an exception value of
+        // NaN, or the missing bin, is given the on_missing value.
+        if !rules
+            .iter()
+            .any(|r| r.exception.is_some_and(|e| e.is_nan()))
+        {
+            let missing = match self.missing_export(&rules, output)? {
+                Some((v, label)) => value(v, &label),
+                None => String::from("NULL"),
+            };
+            branches.push(format!(
+                "WHEN {} THEN {}",
+                dialect.is_missing(column),
+                missing
+            ));
+        }
+        let (last, rules) = rules.split_last().ok_or(DiscrustError::NotFitted)?;
+        for r in rules {
+            let condition = match r.exception {
+                Some(e) if e.is_nan() => dialect.is_missing(column),
+                Some(e) => format!("{} = {}", column, dialect.number(e)),
+                None => format!(
+                    "{} {} {}",
+                    column,
+                    self.closed.below(),
+                    dialect.number(r.upper)
+                ),
+            };
+            branches.push(format!(
+                "WHEN {} THEN {}",
+                condition,
+                value(r.value, &r.label)
+            ));
+        }
+        Ok(format!(
+            "CASE {} ELSE {} END",
+            branches.join(" "),
+            value(last.value, &last.label)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strategy::OnMissing;
+
+    #[test]
+    fn test_to_sql() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc
+            .to_sql("x", SqlDialect::Ansi, ExportOutput::Woe)
+            .is_err());
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+        assert_eq!(
+            disc.to_sql("fare", SqlDialect::Ansi, ExportOutput::Index)
+                .unwrap(),
+            "CASE WHEN fare IS NULL THEN 0 WHEN fare = -1 THEN -1 WHEN fare <= 1 THEN 0 ELSE 1 END"
+        );
+        let woe = disc.predict_woe(&[-1.0, 1.0, 2.0]).unwrap();
+        assert_eq!(
+            disc.to_sql("t.fare", SqlDialect::Spark, ExportOutput::Woe)
+                .unwrap(),
+            format!(
+                "CASE WHEN (t.fare IS NULL OR isnan(t.fare)) THEN {1} WHEN t.fare = -1 THEN {0} WHEN t.fare <= 1 THEN {1} ELSE {2} END",
+                woe[0], woe[1], woe[2]
+            )
+        );
+
+        disc.on_missing = OnMissing::Error;
+        disc.set_exception_label(-1.0, "Didn't apply").unwrap();
+        assert_eq!(
+            disc.to_sql("fare", SqlDialect::Postgres, ExportOutput::Label)
+                .unwrap(),
+            "CASE WHEN (fare IS NULL OR fare = CAST('NaN' AS DOUBLE PRECISION)) THEN NULL WHEN fare = -1 THEN 'Didn''t apply' WHEN fare <= 1 THEN '(-inf, 1]' ELSE '(1, inf]' END"
+        );
+        assert_eq!(SqlDialect::Spark.string("a\\'b"), "'a\\\\\\'b'");
+        assert_eq!(SqlDialect::Ansi.number(f64::INFINITY), "NULL");
+
+        // The missing bin is matched in place of the on_missing value.
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, f64::NAN, f64::NAN];
+        disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        assert_eq!(
+            disc.to_sql("fare", SqlDialect::Ansi, ExportOutput::Index)
+                .unwrap(),
+            "CASE WHEN fare IS NULL THEN -1 WHEN fare <= 1 THEN 0 ELSE 1 END"
+        );
+    }
+}
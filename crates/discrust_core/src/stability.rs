@@ -0,0 +1,479 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::feature::check_weights;
+use crate::utils::to_f64;
+use num::Float;
+use std::cmp::Ordering;
+
+// Percentages are floored at this value when calculating the
+// stability index, so empty bins don't produce infinite values.
+const MIN_PCT: f64 = 0.0001;
+
+/// The population shift of a single bin, between the data
+/// a discretizer was fit on, and a current sample.
+#[derive(Debug, PartialEq)]
+pub struct CharacteristicBin {
+    /// The bin index, following the same convention as `predict_idx`,
+    /// so exception values have negative indexes.
+    pub bin: i64,
+    pub reference_ct: f64,
+    pub reference_pct: f64,
+    pub current_ct: f64,
+    pub current_pct: f64,
+    /// This bin's contribution to the characteristic stability index,
+    /// `(current_pct - reference_pct) * ln(current_pct / reference_pct)`.
+    pub csi: f64,
+    /// The points assigned to this bin, if provided.
+    pub points: Option<f64>,
+    /// The change in average score due to the population shift
+    /// in this bin, `(current_pct - reference_pct) * points`.
+    pub score_impact: Option<f64>,
+}
+
+/// A characteristic stability report, comparing the distribution
+/// of a current sample across the bins of a fitted discretizer, to
+/// the distribution of the data the discretizer was fit on.
+#[derive(Debug, PartialEq)]
+pub struct CharacteristicAnalysis {
+    /// Each numeric bin, followed by each exception value, and the
+    /// missing bin, if there is one.
+    pub bins: Vec<CharacteristicBin>,
+    /// The total characteristic stability index.
+    pub csi: f64,
+    /// The total change in average score, if points were provided.
+    pub score_impact: Option<f64>,
+}
+
+impl CharacteristicAnalysis {
+    /// Run the characteristic analysis.
+    ///
+    /// # Arguments
+    ///
+    /// * `disc` - A fitted discretizer.
+    /// * `x` - The current sample of the feature.
+    /// * `w` - Weights for the current sample.
+    /// * `points` - Optional points assigned to each bin, ordered with
+    ///   the numeric bins first, followed by the exception values, in
+    ///   the order of the `vals_` of the exception values, and the
+    ///   missing bin, if there is one.
+    pub fn new(
+        disc: &Discretizer,
+        x: &[f64],
+        w: &[f64],
+        points: Option<&[f64]>,
+    ) -> Result<Self, DiscrustError> {
+        let feature = disc.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        check_weights(w, x.len())?;
+        let exceptions = &feature.exception_values_;
+        let bins = disc.bin_summary()?;
+        let n_bins = bins.len();
+        let reference_ct: Vec<f64> = bins
+            .iter()
+            .map(|b| b.totals_ct)
+            .chain(exceptions.totals_ct_.iter().cloned())
+            .chain(disc.missing_summary()?.map(|m| m.totals_ct))
+            .collect();
+        if let Some(p) = points {
+            if p.len() != reference_ct.len() {
+                return Err(DiscrustError::InvalidParameter(format!(
+                    "{} points were provided, but there are {} bins",
+                    p.len(),
+                    reference_ct.len()
+                )));
+            }
+        }
+
+        let mut current_ct = vec![0.0; reference_ct.len()];
+        for (idx, w_) in disc.predict_idx(x)?.iter().zip(w) {
+            // Missing values predicted with their own weight of evidence,
+            // when there is no missing bin, are not in any bin, and so are
+            // not counted.
+            if let Some(pos) = disc.index_position(*idx) {
+                current_ct[pos] += w_;
+            }
+        }
+        let reference_total: f64 = reference_ct.iter().sum();
+        let current_total: f64 = current_ct.iter().sum();
+
+        let bins: Vec<CharacteristicBin> = reference_ct
+            .iter()
+            .zip(&current_ct)
+            .enumerate()
+            .map(|(i, (r_ct, c_ct))| {
+                let reference_pct = r_ct / reference_total;
+                let current_pct = c_ct / current_total;
+                let csi = (current_pct - reference_pct)
+                    * (current_pct.max(MIN_PCT) / reference_pct.max(MIN_PCT)).ln();
+                let points = points.map(|p| p[i]);
+                CharacteristicBin {
+                    bin: if i < n_bins {
+                        i as i64
+                    } else {
+                        -((i - n_bins + 1) as i64)
+                    },
+                    reference_ct: *r_ct,
+                    reference_pct,
+                    current_ct: *c_ct,
+                    current_pct,
+                    csi,
+                    points,
+                    score_impact: points.map(|p| (current_pct - reference_pct) * p),
+                }
+            })
+            .collect();
+        let csi = bins.iter().map(|b| b.csi).sum();
+        let score_impact = points.map(|_| bins.iter().filter_map(|b| b.score_impact).sum());
+        Ok(CharacteristicAnalysis {
+            bins,
+            csi,
+            score_impact,
+        })
+    }
+}
+
+/// A single bin of a simulated population shift.
+#[derive(Debug, PartialEq)]
+pub struct ShiftedBin {
+    /// The bin index, following the same convention as `predict_idx`.
+    pub bin: i64,
+    /// The weight of evidence predicted for the bin.
+    pub woe: f64,
+    /// The event rate of the bin, which is assumed not to change.
+    pub event_rate: f64,
+    pub reference_pct: f64,
+    pub shifted_pct: f64,
+}
+
+/// The result of a hypothetical shift in the population
+/// across the bins of a fitted discretizer.
+#[derive(Debug, PartialEq)]
+pub struct ShiftSimulation {
+    /// Each numeric bin, followed by each exception value, and the
+    /// missing bin, if there is one.
+    pub bins: Vec<ShiftedBin>,
+    pub reference_event_rate: f64,
+    /// The overall event rate implied by the shifted population.
+    pub shifted_event_rate: f64,
+    pub reference_mean_woe: f64,
+    /// The average weight of evidence of the shifted population.
+    pub shifted_mean_woe: f64,
+}
+
+/// The weighted count of the records of a sample in a single bin.
+#[derive(Debug, PartialEq)]
+pub struct BinFrequency {
+    /// The bin index, following the same convention as `predict_idx`.
+    pub bin: i64,
+    pub label: String,
+    pub ct: f64,
+    /// The share of the weighted count of the whole sample.
+    pub pct: f64,
+}
+
+/// The distribution of a sample across the bins of a fitted discretizer.
+#[derive(Debug, PartialEq)]
+pub struct ScoreDistribution {
+    /// Each numeric bin, followed by each exception value, and the
+    /// missing bin, if there is one, in the order of `onehot_labels`.
+    pub bins: Vec<BinFrequency>,
+    /// The weighted count of missing values that are not in any bin,
+    /// as they are predicted with the weight of evidence of `on_missing`.
+    pub unbinned_ct: f64,
+    pub unbinned_pct: f64,
+    /// The weighted count of values outside the range of the values the
+    /// discretizer was fit on, which are also counted in the edge bins.
+    pub out_of_range_ct: f64,
+    pub out_of_range_pct: f64,
+    pub total_ct: f64,
+}
+
+impl Discretizer {
+    /// Count the records of a sample, such as the data being scored,
+    /// in each bin, for a quick check of drift without a reference
+    /// sample. The records are binned as by `predict_idx`, and weighted
+    /// by `w`, if provided, otherwise each record has a weight of one.
+    pub fn score_distribution<T: Float>(
+        &self,
+        x: &[T],
+        w: Option<&[f64]>,
+    ) -> Result<ScoreDistribution, DiscrustError> {
+        if x.is_empty() {
+            return Err(DiscrustError::EmptyInput);
+        }
+        if let Some(w) = w {
+            check_weights(w, x.len())?;
+        }
+        let labels = self.onehot_labels()?;
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        let n_bins = self.splits_.len() - 1;
+        let mut ct = vec![0.0; labels.len()];
+        let (mut unbinned_ct, mut out_of_range_ct) = (0.0, 0.0);
+        for (i, idx) in self.predict_idx(x)?.iter().enumerate() {
+            let w_ = w.map_or(1.0, |w| w[i]);
+            match self.index_position(*idx) {
+                Some(pos) => ct[pos] += w_,
+                None => unbinned_ct += w_,
+            }
+            if self.range_side(&to_f64(x[i]), feature) != Ordering::Equal {
+                out_of_range_ct += w_;
+            }
+        }
+        let total_ct = ct.iter().sum::<f64>() + unbinned_ct;
+        let bins = labels
+            .into_iter()
+            .zip(ct)
+            .enumerate()
+            .map(|(i, (label, ct))| BinFrequency {
+                bin: if i < n_bins {
+                    i as i64
+                } else {
+                    -((i - n_bins + 1) as i64)
+                },
+                label,
+                ct,
+                pct: ct / total_ct,
+            })
+            .collect();
+        Ok(ScoreDistribution {
+            bins,
+            unbinned_ct,
+            unbinned_pct: unbinned_ct / total_ct,
+            out_of_range_ct,
+            out_of_range_pct: out_of_range_ct / total_ct,
+            total_ct,
+        })
+    }
+
+    /// Simulate a shift in the population across the bins, where
+    /// the weighted count of each bin is multiplied by its delta. The
+    /// deltas are ordered with the numeric bins first, followed by the
+    /// exception values, and the missing bin, if there is one, so a
+    /// delta of 1.0 leaves a bin unchanged. The
+    /// event rate of each bin is assumed to stay the same, so the
+    /// implied overall event rate, and the distribution of the weight
+    /// of evidence, can be found without generating any data.
+    pub fn simulate_shift(&self, bin_deltas: &[f64]) -> Result<ShiftSimulation, DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        let exceptions = &feature.exception_values_;
+        let bins = self.bin_summary()?;
+        let n_bins = bins.len();
+        // The (totals, ones, woe) of each bin, exception value,
+        // and the missing bin.
+        let stats: Vec<(f64, f64, f64)> = bins
+            .iter()
+            .map(|b| (b.totals_ct, b.ones_ct, b.woe))
+            .chain((0..exceptions.vals_.len()).map(|i| {
+                (
+                    exceptions.totals_ct_[i],
+                    exceptions.ones_ct_[i],
+                    exceptions.woe_[i],
+                )
+            }))
+            .chain(
+                self.missing_summary()?
+                    .map(|m| (m.totals_ct, m.ones_ct, m.woe)),
+            )
+            .collect();
+        if bin_deltas.len() != stats.len() {
+            return Err(DiscrustError::InvalidParameter(format!(
+                "{} deltas were provided, but there are {} bins",
+                bin_deltas.len(),
+                stats.len()
+            )));
+        }
+        if bin_deltas.iter().any(|d| !d.is_finite() || (*d < 0.0)) {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "deltas must be finite, and not negative",
+            )));
+        }
+        let reference_total: f64 = stats.iter().map(|s| s.0).sum();
+        let shifted_total: f64 = stats.iter().zip(bin_deltas).map(|(s, d)| s.0 * d).sum();
+        if shifted_total <= 0.0 {
+            return Err(DiscrustError::InvalidParameter(String::from(
+                "the shifted population is empty",
+            )));
+        }
+        let shifted: Vec<ShiftedBin> = stats
+            .iter()
+            .zip(bin_deltas)
+            .enumerate()
+            .map(|(i, ((totals_ct, ones_ct, woe), delta))| {
+                // Empty bins are predicted with a weight of evidence of zero.
+                let (woe, event_rate) = if *totals_ct == 0.0 {
+                    (0.0, 0.0)
+                } else {
+                    (*woe, ones_ct / totals_ct)
+                };
+                ShiftedBin {
+                    bin: if i < n_bins {
+                        i as i64
+                    } else {
+                        -((i - n_bins + 1) as i64)
+                    },
+                    woe,
+                    event_rate,
+                    reference_pct: totals_ct / reference_total,
+                    shifted_pct: totals_ct * delta / shifted_total,
+                }
+            })
+            .collect();
+        let weighted_mean = |f: fn(&ShiftedBin) -> f64, pct: fn(&ShiftedBin) -> f64| {
+            shifted.iter().map(|b| f(b) * pct(b)).sum::<f64>()
+        };
+        Ok(ShiftSimulation {
+            reference_event_rate: weighted_mean(|b| b.event_rate, |b| b.reference_pct),
+            shifted_event_rate: weighted_mean(|b| b.event_rate, |b| b.shifted_pct),
+            reference_mean_woe: weighted_mean(|b| b.woe, |b| b.reference_pct),
+            shifted_mean_woe: weighted_mean(|b| b.woe, |b| b.shifted_pct),
+            bins: shifted,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strategy::OnMissing;
+    #[test]
+    fn test_characteristic_analysis() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+
+        // The same sample is perfectly stable.
+        let ca = CharacteristicAnalysis::new(&disc, &x_, &w_, None).unwrap();
+        assert_eq!(ca.bins.len(), 3);
+        assert_eq!(ca.bins[2].bin, -1);
+        assert_eq!(ca.csi, 0.0);
+        assert_eq!(ca.score_impact, None);
+
+        // Shift the whole population into the first bin.
+        let x_cur = vec![1.0; 10];
+        let ca = CharacteristicAnalysis::new(&disc, &x_cur, &w_, Some(&[10.0, 20.0, 0.0])).unwrap();
+        assert_eq!(ca.bins[0].current_pct, 1.0);
+        assert!(ca.csi > 0.0);
+        // Gains 0.6 of 10 points, loses 0.4 of 20 points, and 0.2 of 0.
+        assert!((ca.score_impact.unwrap() - (6.0 - 8.0)).abs() < 1e-10);
+
+        assert!(CharacteristicAnalysis::new(&disc, &x_cur, &w_, Some(&[1.0])).is_err());
+        // Each record of the current sample needs a weight.
+        assert!(matches!(
+            CharacteristicAnalysis::new(&disc, &x_cur, &w_[..4], None),
+            Err(DiscrustError::LengthMismatch(..))
+        ));
+    }
+
+    #[test]
+    fn test_missing_bin() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, f64::NAN];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        disc.special_missing = true;
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+
+        // The missing bin follows the exception values.
+        let ca = CharacteristicAnalysis::new(&disc, &x_, &w_, None).unwrap();
+        assert_eq!(ca.bins.len(), 4);
+        assert_eq!(ca.bins[3].bin, -2);
+        assert_eq!(ca.bins[3].reference_ct, 1.0);
+        assert_eq!(ca.bins[3].current_ct, 1.0);
+        assert_eq!(ca.csi, 0.0);
+
+        // Missing records in the current sample are counted.
+        let x_cur = vec![
+            1.0,
+            1.0,
+            2.0,
+            2.0,
+            -1.0,
+            f64::NAN,
+            f64::NAN,
+            f64::NAN,
+            f64::NAN,
+            f64::NAN,
+        ];
+        let ca =
+            CharacteristicAnalysis::new(&disc, &x_cur, &w_, Some(&[0.0, 0.0, 0.0, 10.0])).unwrap();
+        let current_ct: Vec<f64> = ca.bins.iter().map(|b| b.current_ct).collect();
+        assert_eq!(current_ct, vec![2.0, 2.0, 1.0, 5.0]);
+        assert_eq!(ca.bins[3].current_pct, 0.5);
+        assert!(ca.csi > 0.0);
+        assert!((ca.score_impact.unwrap() - 4.0).abs() < 1e-10);
+        assert!(CharacteristicAnalysis::new(&disc, &x_cur, &w_, Some(&[0.0; 3])).is_err());
+
+        // The missing bin takes a delta, with its own event rate.
+        let sim = disc.simulate_shift(&[1.0, 1.0, 1.0, 3.0]).unwrap();
+        assert_eq!(sim.bins.len(), 4);
+        assert_eq!(sim.bins[3].bin, -2);
+        assert_eq!(sim.bins[3].event_rate, 1.0);
+        assert_eq!(sim.bins[3].shifted_pct, 3.0 / 12.0);
+        let expected = (4.0 * 0.75 + 4.0 * 0.25 + 1.0 + 3.0) / 12.0;
+        assert!((sim.shifted_event_rate - expected).abs() < 1e-12);
+        assert!(disc.simulate_shift(&[1.0, 1.0, 1.0]).is_err());
+    }
+
+    #[test]
+    fn test_simulate_shift() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        disc.fit(&x_, &y_, Some(&w_), Some(vec![-1.0])).unwrap();
+
+        // No shift, the event rate is the observed event rate.
+        let sim = disc.simulate_shift(&[1.0, 1.0, 1.0]).unwrap();
+        assert!((sim.reference_event_rate - 0.5).abs() < 1e-12);
+        assert_eq!(sim.shifted_event_rate, sim.reference_event_rate);
+        assert_eq!(sim.shifted_mean_woe, sim.reference_mean_woe);
+
+        // Double the first bin, with an event rate of 0.75.
+        let sim = disc.simulate_shift(&[2.0, 1.0, 1.0]).unwrap();
+        assert_eq!(sim.bins[0].shifted_pct, 8.0 / 14.0);
+        assert_eq!(sim.bins[2].bin, -1);
+        let expected = (8.0 * 0.75 + 4.0 * 0.25 + 2.0 * 0.5) / 14.0;
+        assert!((sim.shifted_event_rate - expected).abs() < 1e-12);
+        assert!(sim.shifted_mean_woe > sim.reference_mean_woe);
+
+        assert!(disc.simulate_shift(&[1.0, 1.0]).is_err());
+        assert!(disc.simulate_shift(&[-1.0, 1.0, 1.0]).is_err());
+        assert!(disc.simulate_shift(&[0.0, 0.0, 0.0]).is_err());
+    }
+
+    #[test]
+    fn test_score_distribution() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert!(disc.score_distribution(&x_, None).is_err());
+        disc.fit(&x_, &y_, None, Some(vec![-1.0])).unwrap();
+
+        let dist = disc
+            .score_distribution(
+                &[1.0, 3.0, 3.0, -1.0, f64::NAN],
+                Some(&[1.0, 1.0, 2.0, 4.0, 2.0]),
+            )
+            .unwrap();
+        assert_eq!(dist.total_ct, 10.0);
+        let ct: Vec<f64> = dist.bins.iter().map(|b| b.ct).collect();
+        // There is no missing bin, so the missing value is in the lowest bin.
+        assert_eq!(ct, vec![3.0, 3.0, 4.0]);
+        assert_eq!(dist.bins[2].bin, -1);
+        assert_eq!(dist.bins[2].label, "-1");
+        assert_eq!(dist.bins[1].pct, 0.3);
+        assert_eq!(dist.unbinned_ct, 0.0);
+        assert_eq!(dist.out_of_range_ct, 3.0);
+        assert_eq!(dist.out_of_range_pct, 0.3);
+
+        disc.on_missing = OnMissing::WoeValue(0.0);
+        let dist = disc.score_distribution(&[1.0, f64::NAN], None).unwrap();
+        assert_eq!(dist.bins[0].ct, 1.0);
+        assert_eq!(dist.unbinned_ct, 1.0);
+        assert_eq!(dist.unbinned_pct, 0.5);
+
+        assert!(disc.score_distribution(&[1.0], Some(&[1.0, 1.0])).is_err());
+        assert!(disc.score_distribution::<f64>(&[], None).is_err());
+    }
+}
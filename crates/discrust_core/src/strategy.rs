@@ -0,0 +1,314 @@
+use crate::utils::first_greater_than;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The strategy used to find the splits of a feature.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Strategy {
+    /// Search every unique value of the feature for the
+    /// split with the highest information value.
+    #[default]
+    Tree,
+    /// Grow the tree as with `Tree`, but split each node into as many as
+    /// `max_children` bins in one step, with the cuts chosen jointly, from
+    /// the best binary split and values at weighted quantiles of the node,
+    /// to maximize the total information value of the bins. Each bin must
+    /// satisfy the node's constraints, and each pair of adjacent bins must
+    /// follow the trend and differ significantly, so a multiway split can
+    /// find bins a sequence of binary splits would miss. A peak or valley
+    /// trend, and cross validation, can not be used.
+    Chaid { max_children: usize },
+    /// Find the partition of the feature into at most `max_bins` bins with
+    /// the highest total information value at once, by dynamic programming,
+    /// rather than growing a tree. The values are first grouped into
+    /// `n_atoms` atoms of about the same weight, and the bins are made up
+    /// of whole atoms. Each bin must satisfy the `min_obs` and `min_pos`
+    /// constraints, the bins must follow the trend, and each pair of
+    /// adjacent bins must differ significantly. The total information value
+    /// must be at least `min_iv`. The search grows with the cube of
+    /// `n_atoms`, which can be at most 500. A peak or valley trend, cross
+    /// validation, and `max_bin_pct` can not be used.
+    OptimalDP { n_atoms: usize },
+    /// Only the provided edges may be used as splits, so the fitted
+    /// bins are always made up of adjacent pre-bins, merged to satisfy
+    /// the information value, monotonicity, and max bins constraints.
+    MergeOnly {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_float::vec"))]
+        edges: Vec<f64>,
+    },
+    /// Split the feature into `n_bins` bins, each holding about the same
+    /// weighted share of the records. The splits are found without the
+    /// target, which is only used for the statistics of each bin, so the
+    /// information value, monotonicity, and max bins constraints are not
+    /// applied. Values shared by many records can produce fewer bins.
+    Quantile { n_bins: usize },
+    /// Split the range of the feature into `n_bins` bins of equal width.
+    /// As with `Quantile`, the target is only used for the bin statistics.
+    Uniform { n_bins: usize },
+    /// Use the given splits, such as cut points set by a business rule,
+    /// and hold out the given exception values, along with any passed
+    /// when fitting. As with `Quantile`, the target is only used for the
+    /// bin statistics, so none of the constraints are applied.
+    Manual {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_float::vec"))]
+        splits: Vec<f64>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_float::vec"))]
+        exception_values: Vec<f64>,
+    },
+}
+
+impl Strategy {
+    /// The number of bins of an unsupervised strategy, or `None`
+    /// if the splits are searched for using the target.
+    pub fn unsupervised_bins(&self) -> Option<usize> {
+        match self {
+            Strategy::Quantile { n_bins } | Strategy::Uniform { n_bins } => Some(*n_bins),
+            _ => None,
+        }
+    }
+}
+
+/// The kind of performance a discretizer is fit against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TargetType {
+    /// A performance of ones and zeros, the splits maximize the
+    /// information value, and each bin has a weight of evidence.
+    #[default]
+    Binary,
+    /// A performance of any finite values. The splits maximize the
+    /// reduction in the weighted variance of the performance, and in
+    /// place of the weight of evidence each bin has the weighted mean
+    /// of the performance, which is the value predicted. In place of
+    /// the information value, each bin has the share of the variance
+    /// of the performance it explains, `w * (mean - overall_mean)^2 /
+    /// total_sum_of_squares`, which sums to the R-squared of the bins.
+    /// The `min_pos` and `min_pos_rate` constraints are not applied.
+    Continuous,
+    /// A performance of class labels `0, 1, ..., k - 1`. The splits
+    /// maximize the sum of the one-vs-rest information values of the
+    /// classes, and each bin has a weight of evidence for each class,
+    /// given by `predict_woe_multiclass`, the single weight of evidence
+    /// of the bins is NaN. The `min_pos` and `min_pos_rate` constraints
+    /// apply to the count of every class. A monotonic trend can not be
+    /// required, and an automatic trend is left unconstrained.
+    Multiclass,
+}
+
+/// The trend required of the weight of evidence of the bins, from
+/// the lowest to the highest bin. The trend is required between the
+/// two halves of every split, rather than across all of the bins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MonotonicTrend {
+    /// The weight of evidence increases with the feature.
+    Ascending,
+    /// The weight of evidence decreases with the feature.
+    Descending,
+    /// The trend of the best first split, ascending or descending,
+    /// is required of every later split. The discretizer's trend is
+    /// replaced by the detected trend when it is fit.
+    #[default]
+    Auto,
+    /// The weight of evidence increases up to the first split, and
+    /// decreases after it, such as risk that is highest for middle
+    /// aged customers.
+    Peak,
+    /// The weight of evidence decreases up to the first split, and
+    /// increases after it, such as risk that is lowest at moderate
+    /// levels of utilization.
+    Valley,
+    /// No trend is required.
+    Unconstrained,
+}
+
+impl MonotonicTrend {
+    /// Whether a split, with the weight of evidence of its lower
+    /// and upper halves, follows the trend.
+    pub fn allows(&self, lhs_woe: f64, rhs_woe: f64) -> bool {
+        match self {
+            MonotonicTrend::Ascending => lhs_woe < rhs_woe,
+            MonotonicTrend::Descending => lhs_woe >= rhs_woe,
+            _ => true,
+        }
+    }
+
+    /// The trend required of the lower, and upper, halves of a split.
+    /// A peak or valley changes direction at the split, and an automatic
+    /// trend follows the direction of the split.
+    pub fn children(&self, lhs_woe: f64, rhs_woe: f64) -> (MonotonicTrend, MonotonicTrend) {
+        match self {
+            MonotonicTrend::Auto if lhs_woe < rhs_woe => {
+                (MonotonicTrend::Ascending, MonotonicTrend::Ascending)
+            }
+            MonotonicTrend::Auto => (MonotonicTrend::Descending, MonotonicTrend::Descending),
+            MonotonicTrend::Peak => (MonotonicTrend::Ascending, MonotonicTrend::Descending),
+            MonotonicTrend::Valley => (MonotonicTrend::Descending, MonotonicTrend::Ascending),
+            t => (*t, *t),
+        }
+    }
+}
+
+impl From<Option<i8>> for MonotonicTrend {
+    /// The trend of the integer monotonicity, 1 is ascending, -1
+    /// descending, 0 unconstrained, and None the automatic trend.
+    fn from(mono: Option<i8>) -> Self {
+        match mono {
+            None => MonotonicTrend::Auto,
+            Some(0) => MonotonicTrend::Unconstrained,
+            Some(m) if m < 0 => MonotonicTrend::Descending,
+            Some(_) => MonotonicTrend::Ascending,
+        }
+    }
+}
+
+/// Where a split is placed, relative to the observed
+/// values of the feature.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SplitPoint {
+    /// Splits are observed values, values less than or equal to
+    /// the split fall in the lower bin.
+    #[default]
+    Observed,
+    /// Splits are halfway between two adjacent observed values.
+    /// This only applies to numeric features fit with the tree
+    /// strategy, as otherwise the splits are already meaningful
+    /// boundaries, such as the end of a month, or a pre-bin edge.
+    Midpoint,
+}
+
+/// How a tie, between candidate splits with exactly the same
+/// information value, is broken when searching for the best split.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TieBreak {
+    /// The split with the lowest value.
+    #[default]
+    Lowest,
+    /// The split with the highest value.
+    Highest,
+    /// The split that divides the weight of the node most evenly, so
+    /// is closest to its weighted median, then the lowest of these.
+    Median,
+}
+
+/// The order the nodes of the tree are split in, which decides
+/// which splits are made when `max_bins` is reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Growth {
+    /// Every node of one depth is split before those of the next.
+    #[default]
+    BreadthFirst,
+    /// The lower half of each split is split as far as it can be,
+    /// before the upper half.
+    DepthFirst,
+    /// The node whose best split gains the most information value is
+    /// split next, so the bins are spent on the most informative splits.
+    BestFirst,
+}
+
+/// Which end of a bin is closed, so which bin a value equal
+/// to a split falls in, when fitting and when predicting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Closed {
+    /// Bins are `[a, b)`, a value equal to a split falls in the
+    /// upper bin. The last bin also holds positive infinity.
+    Left,
+    /// Bins are `(a, b]`, a value equal to a split falls in the
+    /// lower bin.
+    #[default]
+    Right,
+}
+
+impl Closed {
+    /// Whether the value `v` falls below the split `split`,
+    /// in the lower of the two bins it separates.
+    pub fn in_lower(&self, v: &f64, split: &f64) -> bool {
+        match self {
+            Closed::Left => (v < split) || (*split == f64::INFINITY),
+            Closed::Right => v <= split,
+        }
+    }
+
+    /// The comparison of a value to the upper bound of its bin,
+    /// as written in the exported binnings.
+    pub fn below(&self) -> &'static str {
+        match self {
+            Closed::Left => "<",
+            Closed::Right => "<=",
+        }
+    }
+
+    /// The number of the sorted `splits` that `v` falls above, which
+    /// is the index of its bin, if the first split is not included.
+    pub fn position(&self, splits: &[f64], v: &f64) -> usize {
+        // The splits are distinct, so only the last split at or below
+        // `v` can hold it in its lower bin, when `v` is equal to a split
+        // of right closed bins, or is positive infinity.
+        match first_greater_than(splits, v) {
+            i if (i > 0) && self.in_lower(v, &splits[i - 1]) => i - 1,
+            i => i,
+        }
+    }
+}
+
+/// How a value is predicted when it is missing, but was not an exception
+/// value when fitting. A value is missing if it is NaN, or one of the
+/// special codes of the discretizer's input spec.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum OnMissing {
+    /// Return an error.
+    Error,
+    /// Predict the lowest bin.
+    #[default]
+    LowestBin,
+    /// Predict the highest bin.
+    HighestBin,
+    /// Predict this weight of evidence, with an index one past the
+    /// exception values, and the label of missing values.
+    WoeValue(#[cfg_attr(feature = "serde", serde(with = "crate::serde_float"))] f64),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_closed() {
+        let splits = [1.0, 2.0, f64::INFINITY];
+        assert_eq!(Closed::Right.position(&splits, &1.0), 0);
+        assert_eq!(Closed::Left.position(&splits, &1.0), 1);
+        assert_eq!(Closed::Left.position(&splits, &0.5), 0);
+        assert_eq!(Closed::Right.position(&splits, &f64::INFINITY), 2);
+        assert_eq!(Closed::Left.position(&splits, &f64::INFINITY), 2);
+        assert_eq!(Closed::Right.position(&splits, &1.5), 1);
+        assert_eq!(Closed::Left.position(&splits, &2.0), 2);
+        assert_eq!(Closed::Right.position(&splits, &2.0), 1);
+        assert_eq!(Closed::Right.position(&splits[..2], &3.0), 2);
+    }
+
+    #[test]
+    fn test_monotonic_trend() {
+        assert_eq!(MonotonicTrend::from(None), MonotonicTrend::Auto);
+        assert_eq!(MonotonicTrend::from(Some(0)), MonotonicTrend::Unconstrained);
+        assert_eq!(MonotonicTrend::from(Some(1)), MonotonicTrend::Ascending);
+        assert_eq!(MonotonicTrend::from(Some(-1)), MonotonicTrend::Descending);
+        assert!(MonotonicTrend::Ascending.allows(-1.0, 1.0));
+        assert!(!MonotonicTrend::Ascending.allows(1.0, -1.0));
+        assert!(!MonotonicTrend::Descending.allows(-1.0, 1.0));
+        assert!(MonotonicTrend::Peak.allows(1.0, -1.0));
+        assert_eq!(
+            MonotonicTrend::Peak.children(1.0, -1.0),
+            (MonotonicTrend::Ascending, MonotonicTrend::Descending)
+        );
+        assert_eq!(
+            MonotonicTrend::Auto.children(1.0, -1.0),
+            (MonotonicTrend::Descending, MonotonicTrend::Descending)
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use crate::discretize::Discretizer;
+use std::fmt;
+
+/// Summary information for a single bin of a
+/// fitted discretizer.
+#[derive(Debug, PartialEq)]
+pub struct BinSummary {
+    /// Lower bound of the bin, values in the bin are
+    /// strictly greater than this.
+    pub lower: f64,
+    /// Upper bound of the bin, values in the bin are
+    /// less than or equal to this.
+    pub upper: f64,
+    pub totals_ct: f64,
+    pub ones_ct: f64,
+    pub zero_ct: f64,
+    /// The Kish effective sample size of the bin,
+    /// `sum(w)^2 / sum(w^2)`.
+    pub effective_ct: f64,
+    /// The weighted share of the bin's records that are ones,
+    /// NaN if the bin is empty.
+    pub event_rate: f64,
+    /// The weighted mean, and standard deviation, of the performance,
+    /// for a binary performance the mean is the event rate.
+    pub mean: f64,
+    pub std: f64,
+    /// The weight of evidence, or for a continuous performance
+    /// the mean used in its place, which is the value predicted.
+    pub woe: f64,
+    /// The information value, or for a continuous performance
+    /// the share of the variance of the performance explained.
+    pub iv: f64,
+    /// The bin rendered according to the `label_format`,
+    /// such as `(6.95, 7.125]`.
+    pub label: String,
+}
+
+/// Summary information for a single exception value
+/// of a fitted discretizer.
+#[derive(Debug, PartialEq)]
+pub struct ExceptionSummary {
+    pub value: f64,
+    pub totals_ct: f64,
+    pub ones_ct: f64,
+    pub zero_ct: f64,
+    /// The Kish effective sample size of the value's records,
+    /// `sum(w)^2 / sum(w^2)`.
+    pub effective_ct: f64,
+    /// The weighted share of the value's records that are
+    /// ones, NaN if the value had no records.
+    pub event_rate: f64,
+    /// The weighted mean, and standard deviation, of the performance.
+    pub mean: f64,
+    pub std: f64,
+    pub woe: f64,
+    pub iv: f64,
+    /// The label set with `set_exception_label`, or otherwise
+    /// the value rendered according to the `label_format`.
+    pub label: String,
+}
+
+/// Conditions encountered while fitting, that did not
+/// prevent the discretizer from being fit.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FitWarning {
+    /// No valid split was found, so the feature has a single bin.
+    NoSplits,
+    /// Fitting stopped early, because the maximum number
+    /// of bins was reached.
+    MaxBinsReached,
+    /// An exception value was provided, that had no records
+    /// in the data the discretizer was fit on.
+    ExceptionValueNotPresent(f64),
+    /// Adjacent bins were merged, so that the weight of evidence of
+    /// the bins follows the monotonic trend, with the number merged.
+    BinsMerged(usize),
+    /// Bins that hold more than `max_bin_pct` of the total weight
+    /// remain, as no valid split of them was found, with their number.
+    MaxBinPctExceeded(usize),
+}
+
+/// The outcome of fitting a discretizer.
+#[derive(Debug, PartialEq)]
+pub struct FitResult {
+    /// The sorted split values, including negative
+    /// and positive infinity.
+    pub splits: Vec<f64>,
+    /// Summary statistics of each bin.
+    pub bins: Vec<BinSummary>,
+    /// Summary statistics of each exception value.
+    pub exceptions: Vec<ExceptionSummary>,
+    /// Summary statistics of the missing bin, if there were
+    /// missing values that were not an exception value.
+    pub missing: Option<ExceptionSummary>,
+    pub warnings: Vec<FitWarning>,
+}
+
+impl fmt::Display for Discretizer {
+    /// The parameters that differ from the defaults, and once fitted,
+    /// a table of the count, event rate, weight of evidence and
+    /// information value of each bin, exception value and missing bin.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let defaults = Discretizer::default().parameters();
+        let parameters: Vec<String> = self
+            .parameters()
+            .into_iter()
+            .filter(|(k, v)| defaults.get(k) != Some(v))
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect();
+        write!(f, "Discretizer({})", parameters.join(", "))?;
+        let (bins, exceptions, missing) = match (
+            self.bin_summary(),
+            self.exception_summary(),
+            self.missing_summary(),
+        ) {
+            (Ok(b), Ok(e), Ok(m)) => (b, e, m),
+            _ => return Ok(()),
+        };
+        // The (label, count, event rate, woe, iv) of each row.
+        let rows: Vec<(&str, f64, f64, f64, f64)> = bins
+            .iter()
+            .map(|b| (b.label.as_str(), b.totals_ct, b.event_rate, b.woe, b.iv))
+            .chain(
+                exceptions
+                    .iter()
+                    .chain(&missing)
+                    .map(|e| (e.label.as_str(), e.totals_ct, e.event_rate, e.woe, e.iv)),
+            )
+            .collect();
+        let label_w = rows
+            .iter()
+            .map(|r| r.0.chars().count())
+            .fold("bin".len(), usize::max);
+        writeln!(f)?;
+        write!(
+            f,
+            "{:<label_w$}  {:>10}  {:>10}  {:>10}  {:>10}",
+            "bin", "count", "event_rate", "woe", "iv"
+        )?;
+        for (label, count, event_rate, woe, iv) in rows {
+            writeln!(f)?;
+            write!(
+                f,
+                "{:<label_w$}  {:>10}  {:>10.4}  {:>10.4}  {:>10.4}",
+                label, count, event_rate, woe, iv
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_display_discretizer() {
+        let x_ = vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, -1.0];
+        let y_ = vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        assert_eq!(disc.to_string(), "Discretizer(min_obs=1, min_pos=1)");
+        disc.fit(&x_, &y_, None, Some(vec![-1.0])).unwrap();
+        let lines: Vec<String> = disc.to_string().lines().map(String::from).collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(
+            lines[1],
+            "bin             count  event_rate         woe          iv"
+        );
+        assert!(lines[2].starts_with("(-inf, 1]           4      0.7500"));
+        assert!(lines[4].starts_with("-1                  2      0.5000      0.0000"));
+    }
+}
@@ -0,0 +1,30 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A transform applied to the feature before searching for
+/// splits. The transform must be monotonically increasing, so
+/// that the fitted splits can be expressed on the original scale
+/// of the feature.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PreTransform {
+    /// The natural log of one plus the value, values must be
+    /// greater than negative one.
+    Log1p,
+    /// The square root of the value, values must be non-negative.
+    Sqrt,
+    /// A user provided monotonically increasing function,
+    /// this can not be serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(fn(f64) -> f64),
+}
+
+impl PreTransform {
+    pub fn apply(&self, v: f64) -> f64 {
+        match self {
+            PreTransform::Log1p => v.ln_1p(),
+            PreTransform::Sqrt => v.sqrt(),
+            PreTransform::Custom(f) => f(v),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use crate::discretize::Discretizer;
+use crate::errors::DiscrustError;
+use crate::feature::Feature;
+use crate::node::Node;
+use crate::report::StopReason;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A node of the tree grown when the discretizer was fit, with the
+/// records of the feature that fall between its `lower` and `upper`
+/// bounds. Exception values, and missing values, are not in any node.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TreeNode {
+    /// The depth of the node, the root has a depth of zero.
+    pub depth: usize,
+    /// Lower bound of the node, values in the node are strictly
+    /// greater than this, or equal to it if bins are closed on the left.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float"))]
+    pub lower: f64,
+    /// Upper bound of the node, values in the node are less than
+    /// or equal to this, or only less if bins are closed on the left.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float"))]
+    pub upper: f64,
+    /// The split of the node, values below the split fall in the
+    /// left node, None if the node was not split.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float::option"))]
+    pub split: Option<f64>,
+    /// Whether the split is one of the discretizer's splits, false
+    /// if it was removed after the tree was grown, such as when bins
+    /// are merged to enforce monotonicity.
+    pub kept: bool,
+    pub totals_ct: f64,
+    pub ones_ct: f64,
+    pub zero_ct: f64,
+    /// The weight of evidence, or for a continuous performance
+    /// the mean used in its place.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float"))]
+    pub woe: f64,
+    /// The information value, or for a continuous performance
+    /// the share of the variance of the performance explained.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_float"))]
+    pub iv: f64,
+    /// Why the node was not split, None if it was split, or
+    /// the strategy does not search for splits.
+    pub stop_reason: Option<StopReason>,
+    pub left: Option<Box<TreeNode>>,
+    pub right: Option<Box<TreeNode>>,
+}
+
+impl TreeNode {
+    fn new(
+        node: &Node,
+        feature: &Feature,
+        splits: &[f64],
+        depth: usize,
+        bounds: (f64, f64),
+    ) -> Self {
+        let (totals_ct, ones_ct, zero_ct) =
+            feature.range_totals_ones_zero_ct(node.start, node.stop);
+        let (iv, woe) = feature.range_iv_woe(node.start, node.stop);
+        let split = node.split_info.split;
+        let child = |child: &Option<Box<Node>>, bounds: (f64, f64)| {
+            child
+                .as_ref()
+                .map(|c| Box::new(TreeNode::new(c, feature, splits, depth + 1, bounds)))
+        };
+        let (left, right) = match split {
+            Some(s) => (
+                child(&node.left_node, (bounds.0, s)),
+                child(&node.right_node, (s, bounds.1)),
+            ),
+            None => (None, None),
+        };
+        TreeNode {
+            depth,
+            lower: bounds.0,
+            upper: bounds.1,
+            split,
+            kept: split.is_some_and(|s| splits.contains(&s)),
+            totals_ct,
+            ones_ct,
+            zero_ct,
+            woe,
+            iv,
+            stop_reason: node.stop_reason,
+            left,
+            right,
+        }
+    }
+
+    /// Whether the node was not split.
+    pub fn is_terminal(&self) -> bool {
+        self.split.is_none()
+    }
+
+    /// The nodes of the tree, in depth first order, with
+    /// each node followed by its left, then right, children.
+    pub fn nodes(&self) -> Vec<&TreeNode> {
+        let mut nodes = Vec::new();
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            nodes.push(node);
+            stack.extend(node.right.as_deref());
+            stack.extend(node.left.as_deref());
+        }
+        nodes
+    }
+}
+
+impl Discretizer {
+    /// The tree grown when the discretizer was fit, showing the order
+    /// the splits were found in, and the statistics of each node. The
+    /// `quantile`, `uniform` and `manual` strategies do not search for splits,
+    /// so their tree is only the root node.
+    pub fn tree(&self) -> Result<TreeNode, DiscrustError> {
+        let feature = self.feature.as_ref().ok_or(DiscrustError::NotFitted)?;
+        let root = self.root_node.as_ref().ok_or(DiscrustError::NotFitted)?;
+        Ok(TreeNode::new(
+            root,
+            feature,
+            &self.splits_,
+            0,
+            (f64::NEG_INFINITY, f64::INFINITY),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tree() {
+        let x_: Vec<f64> = (0..30).map(|i| i as f64).collect();
+        let y_: Vec<f64> = (0..30)
+            .map(|i| ((i % 3 == 0) ^ (i >= 15)) as i64 as f64)
+            .collect();
+        let w_ = vec![1.0; x_.len()];
+        let mut disc = Discretizer::new(Some(3.0), Some(10), None, Some(1.0), Some(1));
+        disc.woe_regularization = 0.5;
+        assert!(disc.tree().is_err());
+        disc.fit(&x_, &y_, Some(&w_), None).unwrap();
+        let tree = disc.tree().unwrap();
+        assert_eq!(tree.depth, 0);
+        assert_eq!(tree.totals_ct, 30.0);
+        assert_eq!(tree.ones_ct, 15.0);
+        assert_eq!(tree.split, Some(15.0));
+        assert!(tree.kept);
+        let left = tree.left.as_ref().unwrap();
+        assert_eq!((left.lower, left.upper), (f64::NEG_INFINITY, 15.0));
+        assert_eq!(left.depth, 1);
+        assert_eq!(left.totals_ct, 16.0);
+        assert!(left.is_terminal() && !left.kept);
+
+        // Every split of the discretizer is a node of the tree.
+        let nodes = tree.nodes();
+        let mut splits: Vec<f64> = nodes.iter().filter_map(|n| n.split).collect();
+        splits.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(splits, disc.splits_[1..(disc.splits_.len() - 1)]);
+        assert!(nodes.iter().all(|n| n.is_terminal() == n.left.is_none()));
+        let leaves: f64 = nodes
+            .iter()
+            .filter(|n| n.is_terminal())
+            .map(|n| n.totals_ct)
+            .sum();
+        assert_eq!(leaves, 30.0);
+    }
+}
@@ -13,10 +13,15 @@ pub fn nan_safe_compare<T: Float>(i: &T, j: &T) -> Ordering {
     }
 }
 
+/// The value as a `f64`, the type all statistics are calculated in.
+/// Values that can't be represented, are treated as missing.
+pub fn to_f64<T: Float>(v: T) -> f64 {
+    v.to_f64().unwrap_or(f64::NAN)
+}
+
 /// Take a sorted array, and find the position
-/// of the first value that is less than some target
-/// value.
-#[allow(dead_code)]
+/// of the first value that is greater than some
+/// target value.
 pub fn first_greater_than<T: std::cmp::PartialOrd>(x: &[T], v: &T) -> usize {
     let mut low = 0;
     let mut high = x.len();
@@ -31,13 +36,209 @@ pub fn first_greater_than<T: std::cmp::PartialOrd>(x: &[T], v: &T) -> usize {
     low
 }
 
+/// The weighted quantile of `x`, this is the smallest value
+/// where the cumulative weight of all values less than or equal
+/// to it is at least `q` of the total weight. NaN values are ignored.
+pub fn weighted_quantile(x: &[f64], w: &[f64], q: f64) -> f64 {
+    let mut pairs: Vec<(f64, f64)> = x
+        .iter()
+        .zip(w)
+        .filter(|(v, _)| !v.is_nan())
+        .map(|(v, w)| (*v, *w))
+        .collect();
+    if pairs.is_empty() {
+        return f64::NAN;
+    }
+    pairs.sort_by(|a, b| nan_safe_compare(&a.0, &b.0));
+    let total: f64 = pairs.iter().map(|p| p.1).sum();
+    let target = q * total;
+    let mut cuml = 0.0;
+    for (v, w) in pairs.iter() {
+        cuml += w;
+        if cuml >= target {
+            return *v;
+        }
+    }
+    pairs[pairs.len() - 1].0
+}
+
+/// The complementary error function, with a fractional
+/// error of less than 1.2e-7 (Numerical Recipes, erfcc).
+pub fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98
+                                + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let ans = t * (-z * z + poly).exp();
+    if x >= 0.0 {
+        ans
+    } else {
+        2.0 - ans
+    }
+}
+
+/// The two sided p-value of a two-proportion z-test, that the share of
+/// ones is the same in two groups, given the weighted (total, ones) counts
+/// of each group. This is equivalent to a chi-square test with one degree
+/// of freedom. If every record is a one, or every record a zero, the
+/// shares can not differ, and the p-value is 1.
+pub fn two_proportion_pvalue(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (a_ct, a_ones) = a;
+    let (b_ct, b_ones) = b;
+    let pooled = (a_ones + b_ones) / (a_ct + b_ct);
+    let se = (pooled * (1.0 - pooled) * (1.0 / a_ct + 1.0 / b_ct)).sqrt();
+    if se.is_nan() || (se <= 0.0) {
+        return 1.0;
+    }
+    let z = (a_ones / a_ct - b_ones / b_ct) / se;
+    erfc(z.abs() / std::f64::consts::SQRT_2).min(1.0)
+}
+
+/// The two sided p-value of a z-test, that the mean is the same in two
+/// groups, given the weighted (count, mean, variance) of each group. The
+/// variances are not assumed to be equal. If neither group varies, the
+/// p-value is 0 when the means differ, and 1 when they are the same.
+pub fn two_mean_pvalue(a: (f64, f64, f64), b: (f64, f64, f64)) -> f64 {
+    let (a_ct, a_mean, a_var) = a;
+    let (b_ct, b_mean, b_var) = b;
+    let se = (a_var / a_ct + b_var / b_ct).sqrt();
+    if se.is_nan() || (se <= 0.0) {
+        return if a_mean == b_mean { 1.0 } else { 0.0 };
+    }
+    let z = (a_mean - b_mean) / se;
+    erfc(z.abs() / std::f64::consts::SQRT_2).min(1.0)
+}
+
+// The entropy, in bits, of a group of records with the weighted
+// count of each class, and the number of classes present.
+fn entropy(class_ct: &[f64]) -> (f64, f64) {
+    let total: f64 = class_ct.iter().sum();
+    class_ct
+        .iter()
+        .filter(|c| **c > 0.0)
+        .fold((0.0, 0.0), |(ent, k), c| {
+            let p = c / total;
+            (ent - p * p.log2(), k + 1.0)
+        })
+}
+
+/// Whether splitting a group of records into two, given the weighted count
+/// of each class in each, passes the minimum description length test of
+/// Fayyad and Irani (1993). The information gain of the split, the fall in
+/// entropy, must be more than the cost of encoding it, `(log2(n - 1) +
+/// delta) / n`, where `delta` grows with the classes present in each half.
+pub fn mdlp_accepts(lhs: &[f64], rhs: &[f64]) -> bool {
+    let parent: Vec<f64> = lhs.iter().zip(rhs).map(|(l, r)| l + r).collect();
+    let (lhs_n, rhs_n): (f64, f64) = (lhs.iter().sum(), rhs.iter().sum());
+    let n = lhs_n + rhs_n;
+    if n <= 1.0 {
+        return false;
+    }
+    let (ent, k) = entropy(&parent);
+    let (lhs_ent, lhs_k) = entropy(lhs);
+    let (rhs_ent, rhs_k) = entropy(rhs);
+    let gain = ent - (lhs_n * lhs_ent + rhs_n * rhs_ent) / n;
+    let delta = (3.0_f64.powf(k) - 2.0).log2() - (k * ent - lhs_k * lhs_ent - rhs_k * rhs_ent);
+    gain > ((n - 1.0).log2() + delta) / n
+}
+
+// A small, seedable, pseudo random number generator,
+// this does not need to be cryptographically secure.
+pub(crate) struct SplitMix64(pub u64);
+
+impl SplitMix64 {
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // A uniform value in the open interval (0, 1).
+    pub fn uniform(&mut self) -> f64 {
+        ((self.next_u64() >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+    }
+
+    // A uniform integer in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        ((self.uniform() * n as f64) as usize).min(n - 1)
+    }
+
+    pub fn laplace(&mut self, scale: f64) -> f64 {
+        let u = self.uniform() - 0.5;
+        -scale * u.signum() * (1.0 - 2.0 * u.abs()).ln()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    #[test]
+    fn test_laplace() {
+        let mut rng = SplitMix64(7);
+        let draws: Vec<f64> = (0..20000).map(|_| rng.laplace(2.0)).collect();
+        let mean = draws.iter().sum::<f64>() / draws.len() as f64;
+        let mean_abs = draws.iter().map(|d| d.abs()).sum::<f64>() / draws.len() as f64;
+        assert!(mean.abs() < 0.1);
+        assert!((mean_abs - 2.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_two_mean_pvalue() {
+        assert_eq!(two_mean_pvalue((10.0, 1.0, 1.0), (10.0, 1.0, 1.0)), 1.0);
+        // A difference of 1.96 standard errors.
+        let p = two_mean_pvalue((2.0, 1.96, 1.0), (2.0, 0.0, 1.0));
+        assert!((p - 0.05).abs() < 1e-3);
+        assert_eq!(two_mean_pvalue((2.0, 1.0, 0.0), (2.0, 0.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn test_mdlp_accepts() {
+        // A perfect split of 50 records of each class gains one bit,
+        // well over the cost of about 0.07 bits.
+        assert!(mdlp_accepts(&[50.0, 0.0], &[0.0, 50.0]));
+        // 30 of 100 against 50 of 100 gains about 0.03 bits, less than
+        // the cost of about 0.06 bits, unless there are ten times as many.
+        assert!(!mdlp_accepts(&[70.0, 30.0], &[50.0, 50.0]));
+        assert!(mdlp_accepts(&[700.0, 300.0], &[500.0, 500.0]));
+        assert!(!mdlp_accepts(&[5.0, 5.0], &[10.0, 10.0]));
+        assert!(!mdlp_accepts(&[1.0, 0.0], &[0.0, 0.0]));
+    }
+
+    #[test]
+    fn test_two_proportion_pvalue() {
+        assert!((erfc(0.0) - 1.0).abs() < 1e-7);
+        assert!((erfc(1.0) - 0.157_299_207).abs() < 1e-7);
+        assert!((erfc(-1.0) - 1.842_700_793).abs() < 1e-7);
+        // 30 of 100 against 50 of 100 has a z of about 2.89.
+        let p = two_proportion_pvalue((100.0, 30.0), (100.0, 50.0));
+        assert!((p - 0.003_892).abs() < 1e-5);
+        assert_eq!(two_proportion_pvalue((10.0, 5.0), (20.0, 10.0)), 1.0);
+        assert_eq!(two_proportion_pvalue((10.0, 0.0), (20.0, 0.0)), 1.0);
+    }
+
+    #[test]
+    fn test_weighted_quantile() {
+        let x = vec![5.0, 1.0, f64::NAN, 3.0, 2.0, 4.0];
+        let w = vec![1.0; x.len()];
+        assert_eq!(weighted_quantile(&x, &w, 0.0), 1.0);
+        assert_eq!(weighted_quantile(&x, &w, 0.5), 3.0);
+        assert_eq!(weighted_quantile(&x, &w, 1.0), 5.0);
+        let w = vec![1.0, 1.0, 1.0, 1.0, 1.0, 10.0];
+        assert_eq!(weighted_quantile(&x, &w, 0.5), 4.0);
+    }
+
     #[test]
     fn test_compare_in_sort() {
-        let mut v = vec![0.0, 100.0, 1.1, f64::NAN, 2.2, f64::NAN];
-        v.sort_by(|i, j| nan_safe_compare(i, j));
+        let mut v = [0.0, 100.0, 1.1, f64::NAN, 2.2, f64::NAN];
+        v.sort_by(nan_safe_compare);
         // Check the first two values are NaN
         assert!(v[0].is_nan());
         assert!(v[1].is_nan());
@@ -51,11 +252,11 @@ mod test {
         assert_eq!(1, first_greater_than(&v, &1));
         assert_eq!(3, first_greater_than(&v, &2));
         assert_eq!(5, first_greater_than(&v, &5));
-        let i = (&v).iter().position(|&v| v > 2).unwrap();
+        let i = v.iter().position(|&v| v > 2).unwrap();
         assert_eq!(3, i);
-        let i = (&v).iter().position(|&v| v > 0).unwrap();
+        let i = v.iter().position(|&v| v > 0).unwrap();
         assert_eq!(1, i);
-        let i = (&v).iter().position(|&v| v > 5).unwrap();
+        let i = v.iter().position(|&v| v > 5).unwrap();
         assert_eq!(5, i);
     }
 }
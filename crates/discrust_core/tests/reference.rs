@@ -0,0 +1,132 @@
+//! Compare the statistics discrust calculates for a binning against
+//! the reference binnings produced by optbinning and scorecardpy, and
+//! calculated from the definitions of the statistics, which are committed
+//! in `tests/reference`, and regenerated by `generate.py`. The bins each tool
+//! found are applied to the same data, and the counts, weight of evidence
+//! and information value of every bin must match within a tolerance.
+#![cfg(feature = "reference-tests")]
+use discrust_core::Discretizer;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const TOLERANCE: f64 = 1e-6;
+
+struct Reference {
+    meta: HashMap<String, String>,
+    // The (lower, upper, count, event, woe, iv) of each bin.
+    bins: Vec<[f64; 6]>,
+}
+
+fn read_reference(path: &Path) -> Reference {
+    let file = fs::read_to_string(path).expect("Something went wrong reading the file");
+    let mut lines = file.lines();
+    let meta = lines
+        .next()
+        .and_then(|l| l.strip_prefix("# "))
+        .expect("The first line must be a comment of key=value pairs")
+        .split_whitespace()
+        .filter_map(|kv| kv.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    // Skip the header.
+    lines.next();
+    let bins = lines
+        .map(|l| {
+            let mut row = [0.0; 6];
+            for (r, v) in row.iter_mut().zip(l.split(',')) {
+                *r = v.trim().parse::<f64>().unwrap();
+            }
+            row
+        })
+        .collect();
+    Reference { meta, bins }
+}
+
+fn read_dataset(name: &str) -> (Vec<f64>, Vec<f64>) {
+    let file = fs::read_to_string(Path::new("resources").join(name))
+        .expect("Something went wrong reading the file");
+    file.lines()
+        .map(|l| {
+            let split: Vec<f64> = l.split(',').map(|x| x.parse::<f64>().unwrap()).collect();
+            (split[0], split[1])
+        })
+        .unzip()
+}
+
+// Discrust bins are closed on the right, (lower, upper], for a tool whose
+// bins are closed on the left, [lower, upper), each split is moved to the
+// largest value below it, which places every record in the same bin.
+fn right_closed_splits(reference: &Reference, x: &[f64]) -> Vec<f64> {
+    let closed = reference.meta.get("closed").map(|c| c.as_str());
+    let mut splits = vec![f64::NEG_INFINITY];
+    for b in &reference.bins[1..] {
+        let s = b[0];
+        let s = match closed {
+            Some("left") => x
+                .iter()
+                .cloned()
+                .filter(|v| *v < s)
+                .fold(f64::NEG_INFINITY, f64::max),
+            _ => s,
+        };
+        splits.push(s);
+    }
+    splits.push(f64::INFINITY);
+    splits
+}
+
+fn assert_close(name: &str, what: &str, bin: usize, expected: f64, actual: f64) {
+    assert!(
+        (expected - actual).abs() <= TOLERANCE * expected.abs().max(1.0),
+        "{}: {} of bin {} is {}, but the reference is {}",
+        name,
+        what,
+        bin,
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn test_matches_reference_binnings() {
+    let dir = Path::new("tests/reference");
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|e| e == "csv"))
+        .collect();
+    paths.sort();
+    assert!(
+        !paths.is_empty(),
+        "No reference binnings found in tests/reference"
+    );
+    for path in paths {
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        let reference = read_reference(&path);
+        let (x, y) = read_dataset(&reference.meta["dataset"]);
+        let w = vec![1.0; x.len()];
+        let woe_sign: f64 = reference
+            .meta
+            .get("woe_sign")
+            .map_or(1.0, |s| s.parse().unwrap());
+
+        let mut disc = Discretizer::new(Some(1.0), Some(2), Some(0.0), Some(0.0), None);
+        disc.fit(&x, &y, Some(&w), None).unwrap();
+        disc.splits_ = right_closed_splits(&reference, &x);
+        let summary = disc.bin_summary().unwrap();
+        assert_eq!(summary.len(), reference.bins.len(), "{}", name);
+        for (i, (bin, expected)) in summary.iter().zip(&reference.bins).enumerate() {
+            assert_close(&name, "count", i, expected[2], bin.totals_ct);
+            assert_close(&name, "event count", i, expected[3], bin.ones_ct);
+            assert_close(
+                &name,
+                "weight of evidence",
+                i,
+                expected[4],
+                woe_sign * bin.woe,
+            );
+            assert_close(&name, "information value", i, expected[5], bin.iv);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+"""Generate the reference binnings compared against by the `reference-tests`
+feature of discrust_core. Each bundled dataset is binned with optbinning
+and scorecardpy, and the bins, with their counts, weight of evidence and
+information value, are written to a csv file in this directory.
+
+    pip install numpy pandas optbinning scorecardpy
+    python crates/discrust_core/tests/reference/generate.py
+    cargo test -p discrust_core --features reference-tests
+
+The generated files are committed, so the tests do not need either tool,
+this script is only run again to regenerate them, such as after upgrading
+a tool. The names of the tools to regenerate may be passed as arguments,
+by default every tool is run. The `definition` reference needs no packages,
+it applies fixed splits, and calculates the statistics of each bin directly
+from their definitions, independently of discrust.
+
+Each file starts with a comment line of `key=value` pairs, the `tool` that
+produced it, the `dataset` in the resources directory, the end each bin is
+`closed` on, and the `woe_sign`, -1 when the tool defines the weight of
+evidence as log(non-event share / event share), the opposite of discrust.
+"""
+import csv
+import math
+import os
+import sys
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+RESOURCES = os.path.join(HERE, "..", "..", "resources")
+# Datasets in the resources directory, with the feature in the first
+# column, and the binary target in the second.
+DATASETS = {"titanic_fare": "data.csv"}
+COLUMNS = ["lower", "upper", "count", "event", "woe", "iv"]
+
+
+def load(dataset: str):
+    import pandas as pd
+
+    return pd.read_csv(os.path.join(RESOURCES, dataset), header=None, names=["x", "y"])
+
+
+def write(name: str, meta: dict, table):
+    with open(os.path.join(HERE, f"{name}.csv"), "w", newline="") as f:
+        f.write("# " + " ".join(f"{k}={v}" for k, v in meta.items()) + "\n")
+        table.to_csv(f, index=False, columns=COLUMNS)
+
+
+def bounds(splits: list) -> tuple:
+    edges = [-math.inf] + [float(s) for s in splits] + [math.inf]
+    return edges[:-1], edges[1:]
+
+
+# Splits applied by the definition reference, for each dataset.
+DEFINITION_SPLITS = {"titanic_fare": [7.8958, 10.5, 26.0, 52.0]}
+
+
+def definition_reference(name: str, dataset: str):
+    with open(os.path.join(RESOURCES, dataset)) as f:
+        records = [(float(r[0]), float(r[1])) for r in csv.reader(f)]
+    total_event = sum(y for _, y in records)
+    total_non_event = len(records) - total_event
+    lower, upper = bounds(DEFINITION_SPLITS[name])
+    rows = []
+    # Bins are closed on the right, (lower, upper].
+    for lo, up in zip(lower, upper):
+        ys = [y for x, y in records if lo < x <= up]
+        count, event = float(len(ys)), float(sum(ys))
+        event_dist = event / total_event
+        non_event_dist = (count - event) / total_non_event
+        woe = math.log(event_dist / non_event_dist)
+        iv = (event_dist - non_event_dist) * woe
+        rows.append([lo, up, count, event, woe, iv])
+    meta = {"tool": "definition", "dataset": dataset, "closed": "right", "woe_sign": 1}
+    with open(os.path.join(HERE, f"definition_{name}.csv"), "w", newline="") as f:
+        f.write("# " + " ".join(f"{k}={v}" for k, v in meta.items()) + "\n")
+        writer = csv.writer(f, lineterminator="\n")
+        writer.writerow(COLUMNS)
+        writer.writerows([[repr(v) for v in row] for row in rows])
+
+
+def optbinning_reference(name: str, dataset: str):
+    import numpy as np
+    import pandas as pd
+    from optbinning import OptimalBinning
+
+    df = load(dataset)
+    optb = OptimalBinning(name="x", dtype="numerical", solver="cp")
+    optb.fit(df["x"].values, df["y"].values)
+    # The binning table has a row for each bin, followed
+    # by rows for special values, missing values, and the totals.
+    n_bins = len(optb.splits) + 1
+    table = optb.binning_table.build().iloc[:n_bins]
+    lower, upper = bounds(optb.splits)
+    out = pd.DataFrame(
+        {
+            "lower": lower,
+            "upper": upper,
+            "count": table["Count"].astype(float).values,
+            "event": table["Event"].astype(float).values,
+            "woe": table["WoE"].astype(float).values,
+            "iv": table["IV"].astype(float).values,
+        }
+    )
+    meta = {"tool": "optbinning", "dataset": dataset, "closed": "left", "woe_sign": -1}
+    write(f"optbinning_{name}", meta, out)
+
+
+def scorecardpy_reference(name: str, dataset: str):
+    import numpy as np
+    import pandas as pd
+    import scorecardpy as sc
+
+    df = load(dataset)
+    table = sc.woebin(df, y="y", x=["x"])["x"]
+    table = table[(table["bin"] != "missing") & ~table["is_special_values"]]
+    upper = [float(b) for b in table["breaks"]]
+    lower = [-np.inf] + upper[:-1]
+    out = pd.DataFrame(
+        {
+            "lower": lower,
+            "upper": upper,
+            "count": table["count"].astype(float).values,
+            # scorecardpy calls the records where y is 1 "bad".
+            "event": table["bad"].astype(float).values,
+            "woe": table["woe"].astype(float).values,
+            "iv": table["bin_iv"].astype(float).values,
+        }
+    )
+    meta = {"tool": "scorecardpy", "dataset": dataset, "closed": "left", "woe_sign": 1}
+    write(f"scorecardpy_{name}", meta, out)
+
+
+TOOLS = {
+    "definition": definition_reference,
+    "optbinning": optbinning_reference,
+    "scorecardpy": scorecardpy_reference,
+}
+
+if __name__ == "__main__":
+    for tool in sys.argv[1:] or TOOLS:
+        for name, dataset in DATASETS.items():
+            TOOLS[tool](name, dataset)
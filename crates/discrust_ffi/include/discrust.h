@@ -0,0 +1,58 @@
+/*
+ * The C ABI of discrust, see crates/discrust_ffi/src/lib.rs for the
+ * documentation of each function. Arrays are passed as a pointer and a
+ * length, and every function that can fail returns a DiscrustStatus, or
+ * a null pointer, with the message of the error on the calling thread
+ * returned by discrust_last_error.
+ */
+#ifndef DISCRUST_H
+#define DISCRUST_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum DiscrustStatus {
+    DISCRUST_OK = 0,
+    DISCRUST_NULL_POINTER = 1,
+    DISCRUST_ERROR = 2,
+    DISCRUST_BUFFER_TOO_SMALL = 3,
+    DISCRUST_PANIC = 4,
+} DiscrustStatus;
+
+typedef struct DiscrustDiscretizer DiscrustDiscretizer;
+
+/* A null mono uses the automatic trend. */
+DiscrustDiscretizer *discrust_discretizer_new(double min_obs, int64_t max_bins,
+                                              double min_iv, double min_pos,
+                                              const int8_t *mono);
+DiscrustDiscretizer *discrust_discretizer_from_json(const char *json);
+/* Free the returned string with discrust_string_free. */
+char *discrust_discretizer_to_json(const DiscrustDiscretizer *disc);
+void discrust_string_free(char *s);
+void discrust_discretizer_free(DiscrustDiscretizer *disc);
+
+/* w, and exception_values, may be null. */
+DiscrustStatus discrust_fit(DiscrustDiscretizer *disc, const double *x,
+                            const double *y, const double *w, size_t n,
+                            const double *exception_values,
+                            size_t n_exceptions);
+/* Call with a null out, and a len of 0, to find n_splits. */
+DiscrustStatus discrust_splits(const DiscrustDiscretizer *disc, double *out,
+                               size_t len, size_t *n_splits);
+DiscrustStatus discrust_predict_woe(const DiscrustDiscretizer *disc,
+                                    const double *x, size_t n, double *out);
+DiscrustStatus discrust_predict_idx(const DiscrustDiscretizer *disc,
+                                    const double *x, size_t n, int64_t *out);
+
+/* Owned by discrust, valid until the next error on the same thread. */
+const char *discrust_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* DISCRUST_H */
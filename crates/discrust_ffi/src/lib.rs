@@ -0,0 +1,394 @@
+//! A C ABI for discrust, so discretizers can be fit, and scored, from
+//! C, C++, Java (JNI) or .NET. A discretizer is created with
+//! `discrust_discretizer_new`, or loaded from JSON, and must be freed
+//! with `discrust_discretizer_free`. Arrays are passed as a pointer and
+//! a length. Every function that can fail returns a `DiscrustStatus`,
+//! or a null pointer, and the message of the last error on the calling
+//! thread is returned by `discrust_last_error`. The declarations are
+//! in `include/discrust.h`.
+use discrust_core::{Discretizer, DiscrustError};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+/// The status returned by every function that can fail.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiscrustStatus {
+    Ok = 0,
+    /// A required pointer was null.
+    NullPointer = 1,
+    /// discrust returned an error, such as an invalid parameter,
+    /// or predicting with a discretizer that has not been fit.
+    Error = 2,
+    /// The output buffer is too small.
+    BufferTooSmall = 3,
+    /// The call panicked, the discretizer should not be used again.
+    Panic = 4,
+}
+
+/// An opaque handle to a discretizer.
+pub struct DiscrustDiscretizer(Discretizer);
+
+type FfiResult<T> = Result<T, (DiscrustStatus, String)>;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(msg: String) {
+    // A C string can't hold a nul byte, so any are dropped.
+    let msg = CString::new(msg.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(msg));
+}
+
+fn discrust_err(e: DiscrustError) -> (DiscrustStatus, String) {
+    (DiscrustStatus::Error, e.to_string())
+}
+
+// Run `f`, recording the message of an error, or a panic, which
+// must not unwind across the C boundary.
+fn run<T, F: FnOnce() -> FfiResult<T>>(f: F) -> Result<T, DiscrustStatus> {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(v)) => Ok(v),
+        Ok(Err((status, msg))) => {
+            set_last_error(msg);
+            Err(status)
+        }
+        Err(_) => {
+            set_last_error(String::from("discrust panicked"));
+            Err(DiscrustStatus::Panic)
+        }
+    }
+}
+
+fn status(res: Result<(), DiscrustStatus>) -> DiscrustStatus {
+    res.err().unwrap_or(DiscrustStatus::Ok)
+}
+
+fn null_pointer(name: &str) -> (DiscrustStatus, String) {
+    (
+        DiscrustStatus::NullPointer,
+        format!("{} is a null pointer", name),
+    )
+}
+
+// The `len` values at `data`, which may only be null if `len` is zero.
+unsafe fn slice_arg<'a, T>(data: *const T, len: usize, name: &str) -> FfiResult<&'a [T]> {
+    if len == 0 {
+        Ok(&[])
+    } else if data.is_null() {
+        Err(null_pointer(name))
+    } else {
+        Ok(slice::from_raw_parts(data, len))
+    }
+}
+
+unsafe fn slice_arg_mut<'a, T>(data: *mut T, len: usize, name: &str) -> FfiResult<&'a mut [T]> {
+    if len == 0 {
+        Ok(&mut [])
+    } else if data.is_null() {
+        Err(null_pointer(name))
+    } else {
+        Ok(slice::from_raw_parts_mut(data, len))
+    }
+}
+
+unsafe fn disc_arg<'a>(disc: *const DiscrustDiscretizer) -> FfiResult<&'a Discretizer> {
+    disc.as_ref()
+        .map(|d| &d.0)
+        .ok_or_else(|| null_pointer("disc"))
+}
+
+/// Create a discretizer, which must be freed with
+/// `discrust_discretizer_free`. A null `mono` uses the automatic trend,
+/// otherwise 1 is ascending, -1 descending, and 0 unconstrained.
+///
+/// # Safety
+///
+/// `mono` must be null, or point to a valid value.
+#[no_mangle]
+pub unsafe extern "C" fn discrust_discretizer_new(
+    min_obs: f64,
+    max_bins: i64,
+    min_iv: f64,
+    min_pos: f64,
+    mono: *const i8,
+) -> *mut DiscrustDiscretizer {
+    let disc = Discretizer::new(
+        Some(min_obs),
+        Some(max_bins),
+        Some(min_iv),
+        Some(min_pos),
+        mono.as_ref().copied(),
+    );
+    Box::into_raw(Box::new(DiscrustDiscretizer(disc)))
+}
+
+/// Load a discretizer from the JSON written by
+/// `discrust_discretizer_to_json`, or null if it can't be read.
+///
+/// # Safety
+///
+/// `json` must be a nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn discrust_discretizer_from_json(
+    json: *const c_char,
+) -> *mut DiscrustDiscretizer {
+    let res = run(|| {
+        if json.is_null() {
+            return Err(null_pointer("json"));
+        }
+        let json = CStr::from_ptr(json)
+            .to_str()
+            .map_err(|e| (DiscrustStatus::Error, e.to_string()))?;
+        serde_json::from_str(json).map_err(|e| (DiscrustStatus::Error, e.to_string()))
+    });
+    match res {
+        Ok(disc) => Box::into_raw(Box::new(DiscrustDiscretizer(disc))),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// The discretizer as JSON, which must be freed with
+/// `discrust_string_free`, or null if it can't be written.
+///
+/// # Safety
+///
+/// `disc` must be a discretizer that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn discrust_discretizer_to_json(
+    disc: *const DiscrustDiscretizer,
+) -> *mut c_char {
+    let res = run(|| {
+        let json = serde_json::to_string(disc_arg(disc)?)
+            .map_err(|e| (DiscrustStatus::Error, e.to_string()))?;
+        CString::new(json).map_err(|e| (DiscrustStatus::Error, e.to_string()))
+    });
+    res.map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Free a string returned by discrust.
+///
+/// # Safety
+///
+/// `s` must be null, or a string returned by discrust
+/// that has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn discrust_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Free a discretizer.
+///
+/// # Safety
+///
+/// `disc` must be null, or a discretizer that has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn discrust_discretizer_free(disc: *mut DiscrustDiscretizer) {
+    if !disc.is_null() {
+        drop(Box::from_raw(disc));
+    }
+}
+
+/// Fit the discretizer on `n` records. The weights `w` may be null,
+/// for a weight of 1 for each record, as may `exception_values` when
+/// `n_exceptions` is zero.
+///
+/// # Safety
+///
+/// `x` and `y`, and `w` unless it is null, must each point to `n`
+/// values, and `exception_values` to `n_exceptions` values.
+#[no_mangle]
+pub unsafe extern "C" fn discrust_fit(
+    disc: *mut DiscrustDiscretizer,
+    x: *const f64,
+    y: *const f64,
+    w: *const f64,
+    n: usize,
+    exception_values: *const f64,
+    n_exceptions: usize,
+) -> DiscrustStatus {
+    status(run(|| {
+        let disc = &mut disc.as_mut().ok_or_else(|| null_pointer("disc"))?.0;
+        let x = slice_arg(x, n, "x")?;
+        let y = slice_arg(y, n, "y")?;
+        let w = if w.is_null() {
+            None
+        } else {
+            Some(slice_arg(w, n, "w")?)
+        };
+        let e = slice_arg(exception_values, n_exceptions, "exception_values")?;
+        disc.fit(x, y, w, Some(e.to_vec()))
+            .map(|_| ())
+            .map_err(discrust_err)
+    }))
+}
+
+/// Write the number of splits to `n_splits`, including the infinite
+/// first and last splits, and the splits to `out`, if it holds `len`
+/// values, which is at least `n_splits`. Call with a null `out`, and a
+/// `len` of zero, to find the size of the buffer needed.
+///
+/// # Safety
+///
+/// `out` must point to `len` values, and `n_splits` to a valid value.
+#[no_mangle]
+pub unsafe extern "C" fn discrust_splits(
+    disc: *const DiscrustDiscretizer,
+    out: *mut f64,
+    len: usize,
+    n_splits: *mut usize,
+) -> DiscrustStatus {
+    status(run(|| {
+        let splits = &disc_arg(disc)?.splits_;
+        *n_splits.as_mut().ok_or_else(|| null_pointer("n_splits"))? = splits.len();
+        if out.is_null() && len == 0 {
+            return Ok(());
+        }
+        if len < splits.len() {
+            return Err((
+                DiscrustStatus::BufferTooSmall,
+                format!("there are {} splits, but out holds {}", splits.len(), len),
+            ));
+        }
+        slice_arg_mut(out, len, "out")?[..splits.len()].copy_from_slice(splits);
+        Ok(())
+    }))
+}
+
+/// Write the weight of evidence of each of the `n` values of `x` to
+/// `out`, which is not allocated, so a discretizer can be shared by
+/// threads scoring into their own buffers.
+///
+/// # Safety
+///
+/// `x` and `out` must each point to `n` values.
+#[no_mangle]
+pub unsafe extern "C" fn discrust_predict_woe(
+    disc: *const DiscrustDiscretizer,
+    x: *const f64,
+    n: usize,
+    out: *mut f64,
+) -> DiscrustStatus {
+    status(run(|| {
+        let disc = disc_arg(disc)?;
+        let x = slice_arg(x, n, "x")?;
+        let out = slice_arg_mut(out, n, "out")?;
+        disc.predict_woe_into(x, out).map_err(discrust_err)
+    }))
+}
+
+/// Write the bin index of each of the `n` values of `x` to `out`,
+/// exception values have negative indexes, starting at -1.
+///
+/// # Safety
+///
+/// `x` and `out` must each point to `n` values.
+#[no_mangle]
+pub unsafe extern "C" fn discrust_predict_idx(
+    disc: *const DiscrustDiscretizer,
+    x: *const f64,
+    n: usize,
+    out: *mut i64,
+) -> DiscrustStatus {
+    status(run(|| {
+        let disc = disc_arg(disc)?;
+        let x = slice_arg(x, n, "x")?;
+        let out = slice_arg_mut(out, n, "out")?;
+        let idx = disc.predict_idx(x).map_err(discrust_err)?;
+        out.copy_from_slice(&idx);
+        Ok(())
+    }))
+}
+
+/// The message of the last error on the calling thread, or null if
+/// there has not been one. It is owned by discrust, and is valid until
+/// the next error on the same thread.
+#[no_mangle]
+pub extern "C" fn discrust_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |m| m.as_ptr()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(discrust_last_error()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_fit_predict() {
+        let x = [1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, -1.0];
+        let y = [1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let e = [-1.0];
+        unsafe {
+            let disc = discrust_discretizer_new(1.0, 10, 0.001, 1.0, ptr::null());
+            let mut woe = [0.0; 3];
+            assert_eq!(
+                discrust_predict_woe(disc, x.as_ptr(), 3, woe.as_mut_ptr()),
+                DiscrustStatus::Error
+            );
+            assert!(last_error().contains("fit"));
+            assert_eq!(
+                discrust_fit(disc, x.as_ptr(), y.as_ptr(), ptr::null(), 10, e.as_ptr(), 1),
+                DiscrustStatus::Ok
+            );
+
+            let mut n_splits = 0;
+            assert_eq!(
+                discrust_splits(disc, ptr::null_mut(), 0, &mut n_splits),
+                DiscrustStatus::Ok
+            );
+            assert_eq!(n_splits, 3);
+            let mut splits = vec![0.0; 2];
+            assert_eq!(
+                discrust_splits(disc, splits.as_mut_ptr(), 2, &mut n_splits),
+                DiscrustStatus::BufferTooSmall
+            );
+            splits.resize(n_splits, 0.0);
+            discrust_splits(disc, splits.as_mut_ptr(), n_splits, &mut n_splits);
+            assert_eq!(splits, vec![f64::NEG_INFINITY, 1.0, f64::INFINITY]);
+
+            let scored = [1.0, 2.0, -1.0];
+            assert_eq!(
+                discrust_predict_woe(disc, scored.as_ptr(), 3, woe.as_mut_ptr()),
+                DiscrustStatus::Ok
+            );
+            assert_eq!(woe.to_vec(), (*disc).0.predict_woe(&scored).unwrap());
+            let mut idx = [0; 3];
+            discrust_predict_idx(disc, scored.as_ptr(), 3, idx.as_mut_ptr());
+            assert_eq!(idx, [0, 1, -1]);
+            assert_eq!(
+                discrust_predict_idx(disc, ptr::null(), 3, idx.as_mut_ptr()),
+                DiscrustStatus::NullPointer
+            );
+
+            // A discretizer is saved, and loaded, as JSON.
+            let json = discrust_discretizer_to_json(disc);
+            let loaded = discrust_discretizer_from_json(json);
+            discrust_string_free(json);
+            let mut loaded_woe = [0.0; 3];
+            discrust_predict_woe(loaded, scored.as_ptr(), 3, loaded_woe.as_mut_ptr());
+            assert_eq!(loaded_woe, woe);
+            discrust_discretizer_free(loaded);
+            discrust_discretizer_free(disc);
+
+            let bad = CString::new("{").unwrap();
+            assert!(discrust_discretizer_from_json(bad.as_ptr()).is_null());
+            assert_eq!(
+                discrust_predict_woe(ptr::null(), x.as_ptr(), 3, woe.as_mut_ptr()),
+                DiscrustStatus::NullPointer
+            );
+            assert_eq!(last_error(), "disc is a null pointer");
+        }
+    }
+}
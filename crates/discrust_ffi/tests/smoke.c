@@ -0,0 +1,75 @@
+/*
+ * Call every function of include/discrust.h, against the static library,
+ * to check the header matches the library. Built and run in CI with:
+ *
+ *   cargo build --manifest-path crates/discrust_ffi/Cargo.toml
+ *   cc -Wall -Werror -I crates/discrust_ffi/include \
+ *       crates/discrust_ffi/tests/smoke.c \
+ *       crates/discrust_ffi/target/debug/libdiscrust_ffi.a \
+ *       -lpthread -ldl -lm -o smoke
+ *   ./smoke
+ */
+#include "discrust.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <string.h>
+
+#define CHECK(cond)                                                        \
+    do {                                                                   \
+        if (!(cond)) {                                                     \
+            fprintf(stderr, "%s:%d: %s failed: %s\n", __FILE__, __LINE__,  \
+                    #cond, discrust_last_error());                         \
+            return 1;                                                      \
+        }                                                                  \
+    } while (0)
+
+int main(void) {
+    double x[] = {1, 1, 1, 1, 2, 2, 2, 2, -1, -1};
+    double y[] = {1, 1, 1, 0, 1, 0, 0, 0, 1, 0};
+    double w[] = {1, 1, 1, 1, 1, 1, 1, 1, 1, 1};
+    double exceptions[] = {-1};
+    size_t n = 10;
+
+    int8_t mono = 0;
+    DiscrustDiscretizer *disc =
+        discrust_discretizer_new(1, 10, 0.001, 1, &mono);
+    CHECK(disc != NULL);
+    CHECK(discrust_fit(disc, x, y, w, n, exceptions, 1) == DISCRUST_OK);
+
+    size_t n_splits = 0;
+    CHECK(discrust_splits(disc, NULL, 0, &n_splits) == DISCRUST_OK);
+    CHECK(n_splits == 3);
+    double splits[3];
+    CHECK(discrust_splits(disc, splits, 2, &n_splits) == DISCRUST_BUFFER_TOO_SMALL);
+    CHECK(discrust_splits(disc, splits, 3, &n_splits) == DISCRUST_OK);
+    CHECK(isinf(splits[0]) && splits[1] == 1 && isinf(splits[2]));
+
+    double woe[10];
+    int64_t idx[10];
+    CHECK(discrust_predict_woe(disc, x, n, woe) == DISCRUST_OK);
+    CHECK(woe[0] > 0 && woe[4] < 0 && woe[0] == woe[3]);
+    CHECK(discrust_predict_idx(disc, x, n, idx) == DISCRUST_OK);
+    CHECK(idx[0] == 0 && idx[4] == 1 && idx[8] == -1);
+
+    /* A saved discretizer predicts the same. */
+    char *json = discrust_discretizer_to_json(disc);
+    CHECK(json != NULL);
+    DiscrustDiscretizer *loaded = discrust_discretizer_from_json(json);
+    discrust_string_free(json);
+    CHECK(loaded != NULL);
+    double loaded_woe[10];
+    CHECK(discrust_predict_woe(loaded, x, n, loaded_woe) == DISCRUST_OK);
+    CHECK(memcmp(woe, loaded_woe, sizeof(woe)) == 0);
+    discrust_discretizer_free(loaded);
+
+    /* Errors are returned with a message. */
+    CHECK(discrust_fit(disc, x, y, w, 0, NULL, 0) == DISCRUST_ERROR);
+    CHECK(strlen(discrust_last_error()) > 0);
+    CHECK(discrust_predict_woe(NULL, x, n, woe) == DISCRUST_NULL_POINTER);
+    CHECK(discrust_discretizer_from_json("{") == NULL);
+
+    discrust_discretizer_free(disc);
+    printf("discrust.h smoke test passed\n");
+    return 0;
+}
@@ -0,0 +1,124 @@
+//! Emit the search for the best split of each node of a discretizer's
+//! tree as `tracing` events, so a binning can be debugged with whichever
+//! subscriber the application already uses, rather than the `fit_trace`
+//! of a `verbose` discretizer. The method is added to `Discretizer` by
+//! the `FitTraced` trait.
+use discrust_core::{Discretizer, DiscrustError, FitEvent, FitResult};
+use tracing::{debug, info, info_span, warn};
+
+/// The target of every event, to filter them from other events.
+pub const TARGET: &str = "discrust";
+
+pub trait FitTraced {
+    /// Fit the discretizer, in the same way as `fit`, within a `fit` span,
+    /// and emit a debug event for each node searched, in the order the
+    /// nodes were searched, an info event with the fitted splits, and a
+    /// warn event for each warning. The events are emitted once the
+    /// search is done. The `fit_trace` of the discretizer is kept, as if
+    /// it had been fit with `verbose` set.
+    fn fit_traced(
+        &mut self,
+        x: &[f64],
+        y: &[f64],
+        w: Option<&[f64]>,
+        exception_values: Option<Vec<f64>>,
+    ) -> Result<FitResult, DiscrustError>;
+}
+
+impl FitTraced for Discretizer {
+    fn fit_traced(
+        &mut self,
+        x: &[f64],
+        y: &[f64],
+        w: Option<&[f64]>,
+        exception_values: Option<Vec<f64>>,
+    ) -> Result<FitResult, DiscrustError> {
+        let span = info_span!(target: TARGET, "fit", n_records = x.len());
+        let _enter = span.enter();
+        let verbose = self.verbose;
+        self.verbose = true;
+        let res = self.fit(x, y, w, exception_values);
+        self.verbose = verbose;
+        self.fit_trace().iter().for_each(emit);
+        let res = res?;
+        info!(target: TARGET, splits = ?res.splits, "fitted {} bins", res.bins.len());
+        for warning in &res.warnings {
+            warn!(target: TARGET, ?warning);
+        }
+        Ok(res)
+    }
+}
+
+// Emit a debug event for the search of a node.
+fn emit(event: &FitEvent) {
+    debug!(
+        target: TARGET,
+        lower = ?event.range.map(|r| r.0),
+        upper = ?event.range.map(|r| r.1),
+        totals_ct = event.totals_ct,
+        candidates = event.candidates,
+        split = ?event.split,
+        gain = ?event.gain,
+        stop_reason = ?event.stop_reason.map(|r| r.to_string()),
+        "{}",
+        event
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Level, Metadata, Subscriber};
+
+    // Count the debug and info events with the discrust target.
+    #[derive(Default)]
+    struct Counter {
+        debug: AtomicUsize,
+        info: AtomicUsize,
+    }
+
+    struct CountSubscriber(Arc<Counter>);
+
+    impl Subscriber for CountSubscriber {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.target() == TARGET
+        }
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, event: &Event<'_>) {
+            match *event.metadata().level() {
+                Level::DEBUG => self.0.debug.fetch_add(1, Ordering::SeqCst),
+                Level::INFO => self.0.info.fetch_add(1, Ordering::SeqCst),
+                _ => 0,
+            };
+        }
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn test_fit_traced() {
+        let x_: Vec<f64> = (0..30).map(|i| i as f64).collect();
+        let y_: Vec<f64> = (0..30)
+            .map(|i| ((i % 3 == 0) ^ (i >= 15)) as i64 as f64)
+            .collect();
+        let counter = Arc::new(Counter::default());
+        let mut disc = Discretizer::new(Some(3.0), Some(10), None, Some(1.0), Some(1));
+        let mut expected = disc.unfitted_copy();
+        let expected = expected.fit(&x_, &y_, None, None).unwrap();
+        let res = tracing::subscriber::with_default(CountSubscriber(counter.clone()), || {
+            disc.fit_traced(&x_, &y_, None, None).unwrap()
+        });
+        assert_eq!(res, expected);
+        assert!(!disc.verbose);
+        assert!(!disc.fit_trace().is_empty());
+        assert_eq!(counter.debug.load(Ordering::SeqCst), disc.fit_trace().len());
+        assert_eq!(counter.info.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,220 @@
+use discrust_core::{BinSummary, Discretizer as CrateDiscretizer, DiscrustError, ExceptionSummary};
+use js_sys::{Array, Float64Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+// The core crate doesn't depend on wasm-bindgen, so its
+// errors are converted to JavaScript errors here.
+fn js_err(e: DiscrustError) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+fn set(obj: &Object, key: &str, value: &JsValue) -> Result<(), JsError> {
+    Reflect::set(obj, &JsValue::from_str(key), value)
+        .map(|_| ())
+        .map_err(|_| JsError::new(&format!("could not set {}", key)))
+}
+
+/// A supervised discretizer, fit on `Float64Array` columns, for binning
+/// and scoring the weight of evidence in the browser, or Node, such as
+/// in an interactive bin tuning tool.
+#[wasm_bindgen]
+pub struct Discretizer {
+    disc: CrateDiscretizer,
+}
+
+#[wasm_bindgen]
+impl Discretizer {
+    /// Create a discretizer, any parameter left undefined takes its
+    /// default. A `mono` of 1 or -1 requires the weight of evidence to
+    /// increase, or decrease, with the values of the feature.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        min_obs: Option<f64>,
+        max_bins: Option<i32>,
+        min_iv: Option<f64>,
+        min_pos: Option<f64>,
+        mono: Option<i8>,
+    ) -> Discretizer {
+        Discretizer {
+            disc: CrateDiscretizer::new(min_obs, max_bins.map(i64::from), min_iv, min_pos, mono),
+        }
+    }
+
+    /// Fit the discretizer, returning the splits. Exception values are
+    /// held out of the bins, each with its own weight of evidence.
+    pub fn fit(
+        &mut self,
+        x: &[f64],
+        y: &[f64],
+        w: Option<Box<[f64]>>,
+        exception_values: Option<Box<[f64]>>,
+    ) -> Result<Float64Array, JsError> {
+        let res = self
+            .disc
+            .fit(x, y, w.as_deref(), exception_values.map(|e| e.into_vec()))
+            .map_err(js_err)?;
+        // There is no system clock, so the time is taken from JavaScript.
+        let fitted_at = (js_sys::Date::now() / 1000.0) as u64;
+        self.disc.set_fitted_at(fitted_at).map_err(js_err)?;
+        Ok(Float64Array::from(res.splits.as_slice()))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn splits(&self) -> Float64Array {
+        Float64Array::from(self.disc.splits_.as_slice())
+    }
+
+    #[wasm_bindgen(js_name = predictWoe)]
+    pub fn predict_woe(&self, x: &[f64]) -> Result<Float64Array, JsError> {
+        let woe = self.disc.predict_woe(x).map_err(js_err)?;
+        Ok(Float64Array::from(woe.as_slice()))
+    }
+
+    /// The bin index of each value, exception values have
+    /// negative indexes, starting at -1.
+    #[wasm_bindgen(js_name = predictIdx)]
+    pub fn predict_idx(&self, x: &[f64]) -> Result<Vec<i32>, JsError> {
+        self.disc
+            .predict_idx(x)
+            .map(|idx| idx.iter().map(|i| *i as i32).collect())
+            .map_err(js_err)
+    }
+
+    /// The label of the bin, or exception value, of each value.
+    #[wasm_bindgen(js_name = predictLabel)]
+    pub fn predict_label(&self, x: &[f64]) -> Result<Array, JsError> {
+        let labels = self.disc.predict_label(x).map_err(js_err)?;
+        Ok(labels.iter().map(|l| JsValue::from_str(l)).collect())
+    }
+
+    /// The statistics of each bin, followed by each exception value, and
+    /// the missing bin, if there is one, as an object with a column for
+    /// each statistic, the same as the `summary` of the Python package.
+    pub fn summary(&self) -> Result<Object, JsError> {
+        let bins = self.disc.bin_summary().map_err(js_err)?;
+        let exceptions = self.disc.exception_summary().map_err(js_err)?;
+        let missing = self.disc.missing_summary().map_err(js_err)?;
+        let column = |b: fn(&BinSummary) -> f64, e: fn(&ExceptionSummary) -> f64| {
+            let values: Vec<f64> = bins
+                .iter()
+                .map(b)
+                .chain(exceptions.iter().chain(&missing).map(e))
+                .collect();
+            JsValue::from(Float64Array::from(values.as_slice()))
+        };
+        let res = Object::new();
+        set(&res, "lower", &column(|b| b.lower, |e| e.value))?;
+        set(&res, "upper", &column(|b| b.upper, |e| e.value))?;
+        let flags = |exception: bool, missing_: bool| -> Array {
+            bins.iter()
+                .map(|_| false)
+                .chain(exceptions.iter().map(|_| exception))
+                .chain(missing.iter().map(|_| missing_))
+                .map(JsValue::from_bool)
+                .collect()
+        };
+        set(&res, "exception", &flags(true, false))?;
+        set(&res, "missing", &flags(false, true))?;
+        set(&res, "totals_ct", &column(|b| b.totals_ct, |e| e.totals_ct))?;
+        set(
+            &res,
+            "effective_ct",
+            &column(|b| b.effective_ct, |e| e.effective_ct),
+        )?;
+        set(&res, "ones_ct", &column(|b| b.ones_ct, |e| e.ones_ct))?;
+        set(&res, "zero_ct", &column(|b| b.zero_ct, |e| e.zero_ct))?;
+        set(
+            &res,
+            "event_rate",
+            &column(|b| b.event_rate, |e| e.event_rate),
+        )?;
+        set(&res, "woe", &column(|b| b.woe, |e| e.woe))?;
+        set(&res, "iv", &column(|b| b.iv, |e| e.iv))?;
+        set(&res, "mean", &column(|b| b.mean, |e| e.mean))?;
+        set(&res, "std", &column(|b| b.std, |e| e.std))?;
+        let labels: Array = bins
+            .iter()
+            .map(|b| b.label.as_str())
+            .chain(exceptions.iter().chain(&missing).map(|e| e.label.as_str()))
+            .map(JsValue::from_str)
+            .collect();
+        set(&res, "label", &labels)?;
+        Ok(res)
+    }
+
+    /// Merge the bins from `i` to `j`, inclusive, into a single bin.
+    #[wasm_bindgen(js_name = mergeBins)]
+    pub fn merge_bins(&mut self, i: usize, j: usize) -> Result<(), JsError> {
+        self.disc.merge_bins(i, j).map_err(js_err)
+    }
+
+    /// Split the bin that `value` falls in, at `value`.
+    #[wasm_bindgen(js_name = splitBinAt)]
+    pub fn split_bin_at(&mut self, value: f64) -> Result<(), JsError> {
+        self.disc.split_bin_at(value).map_err(js_err)
+    }
+
+    /// The discretizer as JSON, which can be loaded with `fromJSON`,
+    /// such as to save a binning once it has been tuned.
+    #[wasm_bindgen(js_name = toJSON)]
+    pub fn to_json(&self) -> Result<String, JsError> {
+        serde_json::to_string(&self.disc).map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = fromJSON)]
+    pub fn from_json(json: &str) -> Result<Discretizer, JsError> {
+        serde_json::from_str(json)
+            .map(|disc| Discretizer { disc })
+            .map_err(|e| JsError::new(&e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn fitted() -> Discretizer {
+        let x = [1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, -1.0, -1.0];
+        let y = [1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let mut disc = Discretizer::new(Some(1.0), Some(10), Some(0.001), Some(1.0), None);
+        disc.fit(&x, &y, None, Some(vec![-1.0].into_boxed_slice()))
+            .unwrap();
+        disc
+    }
+
+    #[wasm_bindgen_test]
+    fn test_fit_predict() {
+        let disc = fitted();
+        assert_eq!(
+            disc.splits().to_vec(),
+            vec![f64::NEG_INFINITY, 1.0, f64::INFINITY]
+        );
+        assert!(disc.disc.metadata().unwrap().fitted_at > 0);
+        let x = [1.0, 2.0, -1.0];
+        assert_eq!(
+            disc.predict_woe(&x).unwrap().to_vec(),
+            disc.disc.predict_woe(&x).unwrap()
+        );
+        assert_eq!(disc.predict_idx(&x).unwrap(), vec![0, 1, -1]);
+        assert_eq!(disc.predict_label(&x).unwrap().length(), 3);
+        assert_eq!(
+            Reflect::get(&disc.summary().unwrap(), &JsValue::from_str("woe"))
+                .unwrap()
+                .dyn_into::<Float64Array>()
+                .unwrap()
+                .length(),
+            3
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn test_json() {
+        let disc = fitted();
+        let loaded = Discretizer::from_json(&disc.to_json().unwrap()).unwrap();
+        assert_eq!(loaded.splits().to_vec(), disc.splits().to_vec());
+        assert!(Discretizer::new(None, None, None, None, None)
+            .predict_woe(&[1.0])
+            .is_err());
+    }
+}
@@ -1,10 +1,20 @@
 from __future__ import annotations
 from .discrust import Discretizer as RustDiscretizer
+from .discrust import DiscretizerSet as RustDiscretizerSet
+from .discrust import EnsembleDiscretizer
+from .discrust import NotFittedError, PredictionError
+from .discrust import rank_features as _rank_features
 import numpy as np
 import pandas as pd
-from typing import List, Optional, Union
+from typing import Dict, List, Optional, Tuple, Union
 
-ArrayLike = Union[pd.Series, np.ndarray]
+ArrayLike = Union[pd.Series, np.ndarray, List[float]]
+
+
+def _is_polars(x) -> bool:
+    # polars is not a dependency, so its Series and DataFrames are
+    # recognized by their module, without importing it.
+    return type(x).__module__.split(".")[0] == "polars"
 
 
 class Discretizer(RustDiscretizer):
@@ -13,8 +23,42 @@ class Discretizer(RustDiscretizer):
         min_obs: Optional[float] = 5,
         max_bins: Optional[int] = 10,
         min_iv: Optional[float] = 0.001,
-        min_pos: Optional[float] = 5,
-        mono: Optional[int] = None,
+        min_pos: Optional[Union[float, str]] = 5,
+        mono: Optional[Union[int, str]] = None,
+        min_effective_obs: Optional[float] = None,
+        pre_transform: Optional[str] = None,
+        clip_quantiles: Optional[Tuple[float, float]] = None,
+        approx_splits: Optional[int] = None,
+        merge_only_edges: Optional[List[float]] = None,
+        split_point: Optional[str] = None,
+        n_threads: Optional[int] = None,
+        tags: Optional[Dict[str, str]] = None,
+        on_missing: Optional[Union[str, float]] = None,
+        strategy: Optional[str] = None,
+        n_bins: Optional[int] = None,
+        woe_regularization: Optional[float] = None,
+        enforce_monotonicity: Optional[bool] = None,
+        max_pvalue: Optional[float] = None,
+        min_obs_rate: Optional[float] = None,
+        min_pos_rate: Optional[float] = None,
+        special_missing: bool = True,
+        cv_folds: Optional[int] = None,
+        cv_agreement: float = 1.0,
+        target_type: str = "binary",
+        merge_exceptions: bool = False,
+        manual_splits: Optional[List[float]] = None,
+        manual_exceptions: Optional[List[float]] = None,
+        closed: Optional[str] = None,
+        max_candidates: Optional[int] = None,
+        tie_break: Optional[str] = None,
+        min_bin_width: Optional[float] = None,
+        max_bin_pct: Optional[float] = None,
+        growth: Optional[str] = None,
+        verbose: bool = False,
+        max_children: Optional[int] = None,
+        n_atoms: Optional[int] = None,
+        mdlp: bool = False,
+        min_iv_gain_ratio: Optional[float] = None,
     ):
         return super().__new__(
             cls,
@@ -23,6 +67,40 @@ class Discretizer(RustDiscretizer):
             min_iv=min_iv,
             min_pos=min_pos,
             mono=mono,
+            min_effective_obs=min_effective_obs,
+            pre_transform=pre_transform,
+            clip_quantiles=clip_quantiles,
+            approx_splits=approx_splits,
+            merge_only_edges=merge_only_edges,
+            split_point=split_point,
+            n_threads=n_threads,
+            tags=tags,
+            on_missing=on_missing,
+            strategy=strategy,
+            n_bins=n_bins,
+            woe_regularization=woe_regularization,
+            enforce_monotonicity=enforce_monotonicity,
+            max_pvalue=max_pvalue,
+            min_obs_rate=min_obs_rate,
+            min_pos_rate=min_pos_rate,
+            special_missing=special_missing,
+            cv_folds=cv_folds,
+            cv_agreement=cv_agreement,
+            target_type=target_type,
+            merge_exceptions=merge_exceptions,
+            manual_splits=manual_splits,
+            manual_exceptions=manual_exceptions,
+            closed=closed,
+            max_candidates=max_candidates,
+            tie_break=tie_break,
+            min_bin_width=min_bin_width,
+            max_bin_pct=max_bin_pct,
+            growth=growth,
+            verbose=verbose,
+            max_children=max_children,
+            n_atoms=n_atoms,
+            mdlp=mdlp,
+            min_iv_gain_ratio=min_iv_gain_ratio,
         )
 
     def __init__(
@@ -30,8 +108,42 @@ class Discretizer(RustDiscretizer):
         min_obs: Optional[float] = 5,
         max_bins: Optional[int] = 10,
         min_iv: Optional[float] = 0.001,
-        min_pos: Optional[float] = 5,
-        mono: Optional[int] = None,
+        min_pos: Optional[Union[float, str]] = 5,
+        mono: Optional[Union[int, str]] = None,
+        min_effective_obs: Optional[float] = None,
+        pre_transform: Optional[str] = None,
+        clip_quantiles: Optional[Tuple[float, float]] = None,
+        approx_splits: Optional[int] = None,
+        merge_only_edges: Optional[List[float]] = None,
+        split_point: Optional[str] = None,
+        n_threads: Optional[int] = None,
+        tags: Optional[Dict[str, str]] = None,
+        on_missing: Optional[Union[str, float]] = None,
+        strategy: Optional[str] = None,
+        n_bins: Optional[int] = None,
+        woe_regularization: Optional[float] = None,
+        enforce_monotonicity: Optional[bool] = None,
+        max_pvalue: Optional[float] = None,
+        min_obs_rate: Optional[float] = None,
+        min_pos_rate: Optional[float] = None,
+        special_missing: bool = True,
+        cv_folds: Optional[int] = None,
+        cv_agreement: float = 1.0,
+        target_type: str = "binary",
+        merge_exceptions: bool = False,
+        manual_splits: Optional[List[float]] = None,
+        manual_exceptions: Optional[List[float]] = None,
+        closed: Optional[str] = None,
+        max_candidates: Optional[int] = None,
+        tie_break: Optional[str] = None,
+        min_bin_width: Optional[float] = None,
+        max_bin_pct: Optional[float] = None,
+        growth: Optional[str] = None,
+        verbose: bool = False,
+        max_children: Optional[int] = None,
+        n_atoms: Optional[int] = None,
+        mdlp: bool = False,
+        min_iv_gain_ratio: Optional[float] = None,
     ):
         """Create a binary discretizer
 
@@ -42,28 +154,237 @@ class Discretizer(RustDiscretizer):
                 into. Defaults to 10.
             min_iv (Optional[float], optional): Minimum information value required to make a split.
                 Defaults to 0.001.
-            min_pos (Optional[float], optional): Minimum number of records with a value of one
-                that should be present in a split. Defaults to 5.
-            mono (Optional[int], optional): The monotonicity required between the binned variable and
-                the binary performance outcome. A value of -1 will result in negative corrlation between
-                the binned x and y variables, while a value of 1 will result in a positive correlation between the
+            min_pos (Optional[Union[float, str]], optional): Minimum number of records with a
+                value of one that should be present in a split. If "auto", the minimum is scaled
+                to the imbalance of a binary target, as the number of ones a bin of `min_obs`
+                records would hold at the overall event rate, and at least one. Defaults to 5.
+            mono (Optional[Union[int, str]], optional): The monotonicity required between the binned variable and
+                the binary performance outcome. A value of -1, or "descending", will result in negative corrlation between
+                the binned x and y variables, while a value of 1, or "ascending", will result in a positive correlation between the
                 binned x variable and the y variable. Specifying a value of 0 will result in binning
-                x, with no monotonicity constraint. If a value of None is specified the monotonicity
-                will be determined the monotonicity of the first split. Defaults to None.
+                x, with no monotonicity constraint. A value of "peak" requires the weight of evidence to rise up to the
+                first split and fall after it, and "valley" the opposite, for features with a U-shaped relationship.
+                If a value of None, or "auto", is specified the monotonicity will be determined the monotonicity of
+                the first split. Defaults to None.
+            min_effective_obs (Optional[float], optional): Minimum Kish effective sample size,
+                `sum(w)^2 / sum(w^2)`, required in a bin. If specified, this is used in place of
+                `min_obs`, which is useful when the sample weights are highly variable. Defaults to None.
+            pre_transform (Optional[str], optional): A transform applied to `x`, excluding exception
+                values, before searching for splits. Must be one of "log1p" or "sqrt". The fitted
+                splits are always reported on the original scale of `x`. Defaults to None.
+            clip_quantiles (Optional[Tuple[float, float]], optional): Lower and upper quantiles
+                of `x` to winsorize at before searching for splits, preventing a few extreme
+                values from distorting the bins. When predicting, values beyond these quantiles
+                fall in the first and last bins. Defaults to None.
+            approx_splits (Optional[int], optional): If specified, only this many candidate splits,
+                placed at the weighted quantiles of the records in each node, are evaluated when
+                searching for the best split. This trades exactness for speed on high cardinality
+                features. Defaults to None.
+            merge_only_edges (Optional[List[float]], optional): Fine grained pre-bin edges. If
+                specified, only these edges are considered as splits, so the fitted bins are always
+                made up of adjacent pre-bins merged to satisfy the other constraints. Defaults to None.
+            split_point (Optional[str], optional): Where splits are placed, must be one of
+                "observed" or "midpoint". With "observed", each split is a value of `x`, while
+                with "midpoint" each split is halfway between two adjacent values of `x`.
+                Midpoints only apply to numeric features without `merge_only_edges`. If None,
+                "observed" is used. Defaults to None.
+            n_threads (Optional[int], optional): The number of threads used to sort and
+                count the records, and search for splits. If None, these run on the calling
+                thread, if 0, one thread per core is used. Defaults to None.
+            tags (Optional[Dict[str, str]], optional): User supplied tags, such as a model name,
+                recorded in the metadata when the discretizer is fit. Defaults to None.
+            on_missing (Optional[Union[str, float]], optional): How values that are missing,
+                either `np.nan` or a special code of the input spec, but were not exception values
+                when fitting, are predicted. Must be one of "error", to raise an error,
+                "lowest_bin", "highest_bin", or a float, the weight of evidence to predict. If
+                None, "lowest_bin" is used. Defaults to None.
+            strategy (Optional[str], optional): How the splits are found, one of "tree",
+                "quantile", "uniform", "manual", which uses `manual_splits`, "chaid", which splits each node into as many as `max_children` bins in one step, or "optimal_dp", which finds the bins with the highest total information value at once, by dynamic programming. With "quantile" or "uniform", `n_bins` bins of equal
+                frequency, or equal width, are made without using the target, which is only used
+                for the bin statistics, and the min_obs, min_iv, min_pos, mono and max_bins
+                constraints are not applied. If None, "tree" is used, or the merge only strategy
+                if `merge_only_edges` are provided. Defaults to None.
+            n_bins (Optional[int], optional): The number of bins of the "quantile" and "uniform"
+                strategies, required when using them. Defaults to None.
+            woe_regularization (Optional[float], optional): A count added to the ones and zeros
+                of every bin, and exception value, when calculating the weight of evidence, so
+                that bins with only ones or only zeros have a finite weight of evidence. 0.5 is
+                a common choice. If None, 0 is used, the unadjusted weight of evidence. Defaults
+                to None.
+            enforce_monotonicity (Optional[bool], optional): If True, adjacent bins are merged
+                after fitting, until the weight of evidence of the bins follows the `mono` trend
+                across all of the bins, rather than only between the two halves of each split.
+                If None, False is used. Defaults to None.
+            max_pvalue (Optional[float], optional): The largest p-value, of a two-proportion
+                z-test between the share of ones in the two halves of a split, for the split to
+                be made. This prevents splits where the difference between the bins is not
+                statistically significant, 0.05 is a common choice. Defaults to None.
+            min_obs_rate (Optional[float], optional): Minimum share of the total weight required
+                in a bin. If `min_obs` is also specified, the larger of the two thresholds is
+                used. Defaults to None.
+            min_pos_rate (Optional[float], optional): Minimum share of the total weight, made up
+                of records with a value of one, required in a bin. If `min_pos` is also
+                specified, the larger of the two thresholds is used. Defaults to None.
+            special_missing (bool, optional): Collect missing values, `np.nan`, in a bin of
+                their own, with its own counts, weight of evidence and information value, unless
+                `np.nan` is passed as an exception value. The missing bin has the index after
+                the last exception value, and is reported apart from the exception values in
+                `bin_summary`. If False, `np.nan` must be passed as an exception value when
+                fitting. Defaults to True.
+            cv_folds (Optional[int], optional): The number of folds the records are split into,
+                to check each candidate split by cross validation. A split is only made if its
+                information value gain, on the records held out in each fold, is positive on at
+                least `cv_agreement` of the folds. If None, splits are not cross validated.
+                Defaults to None.
+            cv_agreement (float, optional): The share of the `cv_folds` a split's information
+                value gain must be positive on. Defaults to 1.0.
+            target_type (str, optional): The type of the performance, either 'binary', where the
+                bins are found using the weight of evidence of a 0/1 performance, 'continuous',
+                where the bins are found using the mean of the performance, or 'multiclass',
+                for class labels 0, 1, 2, ... With a continuous performance, the weight of
+                evidence of a bin is its mean, and the information value the share of the
+                variance of the performance it explains, the `min_pos` parameter is not used.
+                With a multiclass performance, the information value is the sum of the
+                one-vs-rest information values of the classes, `min_pos` applies to every
+                class, and the weight of evidence of each class is returned by
+                `predict_woe_multiclass`. Defaults to "binary".
+            merge_exceptions (bool, optional): Once the bins are found, merge each exception
+                value with records into the bin with the closest weight of evidence, adding its
+                records to the bin. A merged value is then predicted as that bin, and is no
+                longer listed in `exception_values_`. Exception values with no records are kept.
+                Can not be used with a multiclass target. Defaults to False.
+            manual_splits (Optional[List[float]], optional): The splits of the "manual"
+                strategy, such as cut points set by a business rule, used as given. The target
+                is only used for the bin statistics, and none of the constraints are applied.
+                See `from_splits`. Defaults to None.
+            manual_exceptions (Optional[List[float]], optional): Exception values of the
+                "manual" strategy, held out of the bins along with any passed to `fit`. Defaults
+                to None.
+            closed (Optional[str], optional): Which end of each bin is closed, "right", where
+                bins are `(a, b]` and a value equal to a split falls in the lower bin, or
+                "left", where bins are `[a, b)` and it falls in the upper bin. The records on
+                each side of a split are the same either way, only the value of the split
+                changes. "left" can not be used with a snapped datetime feature. If None,
+                "right" is used. Defaults to None.
+            max_candidates (Optional[int], optional): The largest number of candidate splits. If
+                specified, the values of `x` are grouped into this many pre-bins of about the
+                same weight, like histogram binning, before searching for splits, so only the
+                pre-bin boundaries can be split on. This speeds up fitting features with many
+                unique values. Unlike `approx_splits`, the pre-bins are found once, rather than
+                in each node. If None, every unique value is a candidate. Defaults to None.
+            tie_break (Optional[str], optional): How splits with exactly the same information
+                value are broken, either "lowest", where the split at the lowest value is kept,
+                "highest", where the split at the highest value is kept, or "median", where the
+                split dividing the weight of the records most evenly is kept, the lowest if it
+                is also tied. If None, "lowest" is used. Defaults to None.
+            min_bin_width (Optional[float], optional): The narrowest a bin may be, the distance
+                between its lower and upper split, splits that would create a narrower bin are
+                not made. If a pre_transform is set, the width is on the transformed scale. If
+                None, bins may be of any width. Defaults to None.
+            max_bin_pct (Optional[float], optional): The largest share of the total weight a bin
+                may hold. A bin holding more is split further, even if the information value of
+                the split is below min_iv, while a split meeting the other constraints, and
+                max_bins, can be found. If None, bins may hold any share of the weight. Defaults
+                to None.
+            growth (Optional[str], optional): The order the nodes of the tree are split in,
+                which decides which splits are made once max_bins is reached, either
+                "breadth_first", where every bin of one depth is split before the next,
+                "depth_first", where the lower half of each split is split as far as it can be
+                first, or "best_first", where the split gaining the most information value is
+                made next. If None, "breadth_first" is used. Defaults to None.
+            verbose (bool, optional): Print the search for the best split of each node of the
+                tree, the candidates evaluated, the number rejected by each constraint, and the
+                best split and the information value it gains, or why the node was not split,
+                after fitting. Defaults to False.
+            max_children (Optional[int], optional): The most bins each node is split into in one
+                step by the "chaid" strategy, with the cuts chosen jointly to maximize the total
+                information value. If None, 3 is used. Defaults to None.
+            n_atoms (Optional[int], optional): The number of atoms of about the same weight the
+                values are grouped into by the "optimal_dp" strategy, before the partition into
+                at most `max_bins` bins of whole atoms with the highest total information value
+                is found. At most 500, the search grows with the cube of the count. If None, 100
+                is used. Defaults to None.
+            mdlp (bool, optional): Accept a split only if its fall in the entropy of the target
+                passes the minimum description length test of Fayyad and Irani, a stopping rule
+                without a parameter to tune, in place of `min_iv`. Only applies to a binary or
+                multiclass target. Defaults to False.
+            min_iv_gain_ratio (Optional[float], optional): The smallest information value a
+                split must gain over the node it splits, as a share of the information value of
+                the node, so splits that barely improve on their node are not made. The first
+                split only needs to meet `min_iv`. Defaults to None.
         """
         super().__init__()
+        # The constructor arguments, returned by `get_params`.
+        self._params = {
+            k: v for k, v in locals().items() if k not in ("self", "__class__")
+        }
 
-    @staticmethod
-    def _convert_array(x: ArrayLike) -> np.ndarray:
-        # Relevant conversions, need to be a numpy array.
-        if isinstance(x, pd.Series):
-            x = x.to_numpy().astype(np.float64)
+    def __getstate__(self) -> dict:
+        # The state of the Rust discretizer, along with the constructor
+        # arguments, which are not set when unpickling, as `__init__` is not called.
+        return {"discretizer": super().__getstate__(), "params": self._params}
+
+    def __setstate__(self, state: dict):
+        super().__setstate__(state["discretizer"])
+        self._params = state["params"]
+
+    def get_params(self, deep: bool = True) -> dict:
+        """The parameters the discretizer was created with, as with scikit-learn
+        estimators, so it can be cloned, and searched over in a `Pipeline`.
+
+        Args:
+            deep (bool, optional): Unused, as the discretizer has no nested
+                estimators. Defaults to True.
+
+        Returns:
+            dict: The value of each constructor argument, keyed by name.
+        """
+        return dict(self._params)
+
+    def set_params(self, **params) -> Discretizer:
+        """Set constructor parameters, as with scikit-learn estimators. The
+        discretizer is reset, and must be fit again. The label format and input
+        spec are kept.
 
-        # Check dtypes, need to be a 64 bit float.
-        # TODO: Make rust code accept different numerical types.
-        if not x.dtype == np.float64:
-            x = x.astype(np.float64)
+        Returns:
+            Discretizer: The discretizer, with the new parameters.
+
+        Raises:
+            ValueError: If a parameter is not a constructor argument, or its value
+                is not valid.
+        """
+        invalid = [k for k in params if k not in self._params]
+        if invalid:
+            raise ValueError(
+                f"Invalid parameters {invalid} for Discretizer, "
+                + f"valid parameters are {list(self._params)}."
+            )
+        updated = {**self._params, **params}
+        super()._set_parameters(type(self)(**updated))
+        self._params = updated
+        return self
 
+    @staticmethod
+    def _convert_array(x: ArrayLike) -> np.ndarray:
+        # Numbers of any dtype are converted to float64 by the Rust discretizer,
+        # apart from float32 values, which are predicted on as is, rather than
+        # copied. The missing values of nullable pandas dtypes are NaN.
+        if isinstance(x, pd.Series) and pd.api.types.is_extension_array_dtype(x.dtype):
+            x = x.to_numpy(dtype=np.float64, na_value=np.nan)
+        # A polars Series, or DataFrame, is converted without pandas, the nulls of
+        # a numeric column are NaN, and a float column without nulls is not copied.
+        if _is_polars(x):
+            x = x.to_numpy()
+        # A single column of a 2-D array, or DataFrame, such as the
+        # columns passed by a scikit-learn `ColumnTransformer`.
+        x = np.asarray(x)
+        if x.ndim == 2 and x.shape[1] == 1:
+            x = x[:, 0]
+        elif x.ndim != 1:
+            raise ValueError(
+                f"A single column is expected, but an array of shape {x.shape} was passed, "
+                + "use a DiscretizerSet for several columns."
+            )
         return x
 
     def fit(
@@ -77,7 +398,7 @@ class Discretizer(RustDiscretizer):
 
         Args:
             x (ArrayLike): An arraylike numeric field that will be discretized based on
-                the values of `y`.
+                the values of `y`, or a 2-D array, or DataFrame, with a single column.
             y (ArrayLike): An arraylike binary field.
             sample_weight (Optional[ArrayLike], optional): Optional sample weight column to be applied
                 to be used when calculating the optimal breaks. Defaults to None.
@@ -95,8 +416,234 @@ class Discretizer(RustDiscretizer):
             sample_weight = self._convert_array(sample_weight)
 
         super().fit(x, y, sample_weight, exception_values)
+        self._print_trace()
+        return self
+
+    def _print_trace(self):
+        # The search for the split of each node, when verbose.
+        if self._params.get("verbose"):
+            for line in super().fit_trace():
+                print(line)
+
+    @classmethod
+    def from_splits(
+        cls,
+        splits: List[float],
+        exception_values: Optional[List[float]] = None,
+        **kwargs,
+    ) -> Discretizer:
+        """Create a discretizer with the given splits, such as cut points set by a
+        business rule, rather than searching for them. Fit it with `fit_stats`, which
+        only computes the statistics of each bin.
+
+        Args:
+            splits (List[float]): Increasing splits, infinite splits are ignored, as
+                the first and last bins are always unbounded.
+            exception_values (Optional[List[float]], optional): Exception values held
+                out of the bins. Defaults to None.
+            **kwargs: Any other constructor arguments, such as `on_missing`.
+
+        Returns:
+            Discretizer: An unfitted discretizer with the "manual" strategy.
+        """
+        return cls(
+            strategy="manual",
+            manual_splits=splits,
+            manual_exceptions=exception_values,
+            **kwargs,
+        )
+
+    def fit_stats(
+        self,
+        x: ArrayLike,
+        y: ArrayLike,
+        sample_weight: Optional[ArrayLike] = None,
+    ) -> Discretizer:
+        """Compute the statistics of the bins, exception values and missing bin of a
+        discretizer created with `from_splits`, after which it can predict as any
+        fitted discretizer.
+
+        Args:
+            x (ArrayLike): An arraylike numeric field, or a 2-D array, or DataFrame,
+                with a single column.
+            y (ArrayLike): An arraylike binary field.
+            sample_weight (Optional[ArrayLike], optional): Optional sample weight column.
+                Defaults to None.
+
+        Returns:
+            Discretizer: The fitted discretizer.
+        """
+        x = self._convert_array(x)
+        y = self._convert_array(y)
+        if sample_weight is not None:
+            sample_weight = self._convert_array(sample_weight)
+        super().fit_stats(x, y, sample_weight)
+        return self
+
+    def recompute_stats(
+        self,
+        x: ArrayLike,
+        y: ArrayLike,
+        sample_weight: Optional[ArrayLike] = None,
+    ) -> Discretizer:
+        """Recompute the statistics of the bins, exception values and missing bin on
+        new records, such as an out of time sample, keeping the splits, exception
+        values, and labels of the fitted discretizer. Missing values are collected in
+        the missing bin as when fitting, so it is present if the new records have any.
+
+        Args:
+            x (ArrayLike): An arraylike numeric field, or a 2-D array, or DataFrame,
+                with a single column.
+            y (ArrayLike): An arraylike binary field.
+            sample_weight (Optional[ArrayLike], optional): Optional sample weight column.
+                Defaults to None.
+
+        Returns:
+            Discretizer: The discretizer, with the statistics of the new records.
+        """
+        x = self._convert_array(x)
+        y = self._convert_array(y)
+        if sample_weight is not None:
+            sample_weight = self._convert_array(sample_weight)
+        super().recompute_stats(x, y, sample_weight)
+        return self
+
+    def partial_fit(
+        self,
+        x: ArrayLike,
+        y: ArrayLike,
+        sample_weight: Optional[ArrayLike] = None,
+        exception_values: Optional[List[float]] = None,
+    ) -> Discretizer:
+        """Add a batch of records to the data the discretizer is fit on, without
+        fitting it. Once every batch has been added, call `finalize` to fit the
+        discretizer on all of them, which gives the same splits as passing all of the
+        records to `fit`. Only the weighted counts of each unique value are kept, so
+        datasets too large to hold in memory can be fit in batches.
+
+        Args:
+            x (ArrayLike): An arraylike numeric field that will be discretized based on
+                the values of `y`.
+            y (ArrayLike): An arraylike binary field.
+            sample_weight (Optional[ArrayLike], optional): Optional sample weight column to be applied
+                to be used when calculating the optimal breaks. Defaults to None.
+            exception_values (Optional[List[float]], optional): Optional list specifying exception
+                values, which must be the same for every batch. Defaults to None.
+
+        Returns:
+            Discretizer: The discretizer, with the batch added.
+        """
+        x = self._convert_array(x)
+        y = self._convert_array(y)
+        if sample_weight is not None:
+            sample_weight = self._convert_array(sample_weight)
+
+        super().partial_fit(x, y, sample_weight, exception_values)
+        return self
+
+    def finalize(self) -> Discretizer:
+        """Fit the discretizer on all of the batches added with `partial_fit`,
+        the batches are discarded once it is fit.
+
+        Returns:
+            Discretizer: The fitted discretizer.
+        """
+        super().finalize()
+        self._print_trace()
+        return self
+
+    def fit_categorical(
+        self,
+        x: ArrayLike,
+        y: ArrayLike,
+        sample_weight: Optional[ArrayLike] = None,
+    ) -> Discretizer:
+        """Fit the discretizer on a categorical feature. The levels of `x` are
+        ordered by their weight of evidence, and then grouped into bins of levels
+        with similar weights of evidence. The levels in each bin can be found in
+        the `category_bins_` attribute.
+
+        Args:
+            x (ArrayLike): An arraylike field of string levels.
+            y (ArrayLike): An arraylike binary field.
+            sample_weight (Optional[ArrayLike], optional): Optional sample weight array.
+                Defaults to None.
+
+        Returns:
+            Discretizer: The fitted discretizer.
+        """
+        x = [str(v) for v in x]
+        y = self._convert_array(y)
+        if sample_weight is not None:
+            sample_weight = self._convert_array(sample_weight)
+        super().fit_categorical(x, y, sample_weight)
+        self._print_trace()
         return self
 
+    def predict_categorical(self, x: ArrayLike, prediction_type: str = "woe") -> np.ndarray:
+        """Convert a categorical feature to the weight of evidence, or the index,
+        of the bin each level falls in. The discretizer must have been fit with
+        `fit_categorical`, and a ValueError is raised for levels not seen when fitting.
+
+        Args:
+            x (ArrayLike): An arraylike field of string levels.
+            prediction_type (str, optional): Either "woe" or "index". Defaults to "woe".
+
+        Returns:
+            np.ndarray: The weight of evidence, or bin index, of each level.
+        """
+        x = [str(v) for v in x]
+        if prediction_type == "woe":
+            return super().predict_categorical_woe(x)
+        if prediction_type == "index":
+            return super().predict_categorical_idx(x)
+        else:
+            e_msg = (
+                "The parameter `prediction_type` must be one of 'index' or 'woe', "
+                + f"but {prediction_type} was passed."
+            )
+            raise ValueError(e_msg)
+
+    def fit_ensemble(
+        self,
+        x: ArrayLike,
+        y: ArrayLike,
+        sample_weight: Optional[ArrayLike] = None,
+        exception_values: Optional[List[float]] = None,
+        n_models: int = 10,
+        row_subsample: float = 0.8,
+        seed: int = 0,
+    ) -> EnsembleDiscretizer:
+        """Fit several copies of this discretizer, each on a random subsample of
+        the records. This discretizer itself is not fit.
+
+        Args:
+            x (ArrayLike): An arraylike numeric field that will be discretized.
+            y (ArrayLike): An arraylike binary field.
+            sample_weight (Optional[ArrayLike], optional): Optional sample weight array.
+                Defaults to None.
+            exception_values (Optional[List[float]], optional): Optional list specifying
+                exception values. Defaults to None.
+            n_models (int, optional): The number of discretizers to fit. Defaults to 10.
+            row_subsample (float, optional): The share of the records, drawn without
+                replacement, each discretizer is fit on. Defaults to 0.8.
+            seed (int, optional): The seed used to draw the subsamples. Defaults to 0.
+
+        Returns:
+            EnsembleDiscretizer: The fitted discretizers. Its `predict_woe` method returns
+                the weight of evidence averaged over the discretizers, `split_frequencies`
+                returns each split with the share of discretizers that found it, and
+                `consensus_splits(min_share)` returns the splits found by at least
+                `min_share` of the discretizers.
+        """
+        x = self._convert_array(x)
+        y = self._convert_array(y)
+        if sample_weight is not None:
+            sample_weight = self._convert_array(sample_weight)
+        return super().fit_ensemble(
+            x, y, sample_weight, exception_values, n_models, row_subsample, seed
+        )
+
     def predict(self, x: ArrayLike, prediction_type: str = "woe") -> np.ndarray:
         """Convert provided variable to WOE given the predicted discretization
         scheme.
@@ -105,7 +652,7 @@ class Discretizer(RustDiscretizer):
             x (ArrayLike): An arraylike numeric field.
             prediction_type (str, optional): A string specifying which prediction
                 type should be returned. The string specified must be one of
-                "woe" or "index". Defaults to "woe".
+                "woe", "index" or "label". Defaults to "woe".
 
                 * If "woe" is supplied, weight of evidence substitution will
                 be returned for each value. Exception values will use the
@@ -117,20 +664,676 @@ class Discretizer(RustDiscretizer):
                 attribute and will zero indexed. Any exception values will be encoded
                 starting with -1 to -N, where N is the number of exception values present
                 in the `exception_values_` attribute. The order of the exception values
-                will be equivalent to the `vals_` key in this attribute.
+                will be equivalent to the `vals_` key in this attribute. Missing values in
+                the missing bin, or if `on_missing` is a float, missing values that were
+                not exception values, are encoded as -(N+1).
+                * If "label" is specified, each value will be converted to the
+                label of its bin, such as "(6.95, 7.125]", or the label of its
+                exception value, formatted according to `set_label_format`.
         Returns:
             np.ndarray: The x variable where each level is transformed to
                 it's respective weight of evidence given the fitted binning
-                scheme.
+                scheme. If x is a polars Series, a polars Series with the same
+                name is returned.
         """
-        x = self._convert_array(x)
+        x_ = self._convert_array(x)
         if prediction_type == "woe":
-            return super().predict_woe(x)
-        if prediction_type == "index":
-            return super().predict_idx(x)
+            res = super().predict_woe(x_)
+        elif prediction_type == "index":
+            res = super().predict_idx(x_)
+        elif prediction_type == "label":
+            res = np.array(super().predict_label(x_), dtype=object)
         else:
             e_msg = (
-                "The parameter `prediction_type` must be one of 'index' or 'woe', "
+                "The parameter `prediction_type` must be one of 'index', 'label' or 'woe', "
                 + f"but {prediction_type} was passed."
             )
             raise ValueError(e_msg)
+        if _is_polars(x) and hasattr(x, "name"):
+            import polars as pl
+
+            return pl.Series(x.name, res.tolist() if prediction_type == "label" else res)
+        return res
+
+    def predict_bounds(self, x: ArrayLike) -> Tuple[np.ndarray, np.ndarray]:
+        """The lower and upper bounds of the bin each value falls in, for reports that
+        need the numeric range of the bin, rather than its index or weight of evidence.
+
+        Args:
+            x (ArrayLike): An arraylike numeric field.
+
+        Returns:
+            Tuple[np.ndarray, np.ndarray]: The lower, and upper, bound of the bin of each
+                value. An exception value is bounded by the value itself, and missing
+                values in the missing bin, or predicted with the weight of evidence of
+                `on_missing`, have bounds of `np.nan`.
+        """
+        return super().predict_bounds(self._convert_array(x))
+
+    def out_of_range(self, x: ArrayLike) -> np.ndarray:
+        """Flag the values outside the range of the values the discretizer was fit on,
+        which are predicted in the lowest, or highest, bin, for monitoring the quality
+        of the data being scored.
+
+        Args:
+            x (ArrayLike): An arraylike numeric field.
+
+        Returns:
+            np.ndarray: A boolean mask, True for each value below the smallest, or above
+                the largest, value the discretizer was fit on. Exception and missing
+                values are never out of range.
+        """
+        return super().out_of_range(self._convert_array(x))
+
+    def predict_report(self, x: ArrayLike) -> Dict[str, int]:
+        """Count the values by how they are binned, for monitoring the quality of the
+        data being scored.
+
+        Args:
+            x (ArrayLike): An arraylike numeric field.
+
+        Returns:
+            Dict[str, int]: The "n_records", the "exception_ct" of exception values, the
+                "missing_ct" of missing values, and the "below_range_ct",
+                "above_range_ct" and "out_of_range_ct" of values outside the range the
+                discretizer was fit on.
+        """
+        return super().predict_report(self._convert_array(x))
+
+    def transform(self, x: ArrayLike) -> np.ndarray:
+        """Convert the provided variable to its weight of evidence, as a column, for
+        use as a scikit-learn transformer, such as in a `Pipeline` or `ColumnTransformer`.
+
+        Args:
+            x (ArrayLike): An arraylike numeric field, or a 2-D array, or DataFrame,
+                with a single column.
+
+        Returns:
+            np.ndarray: A 2-D array with a single column of the weight of evidence of
+                each value, or for a `target_type` of "multiclass", a column for the
+                weight of evidence of each class.
+        """
+        if self._params["target_type"] == "multiclass":
+            return self.predict_woe_multiclass(x)
+        return self.predict(x).reshape(-1, 1)
+
+    def fit_transform(
+        self,
+        x: ArrayLike,
+        y: ArrayLike,
+        sample_weight: Optional[ArrayLike] = None,
+        exception_values: Optional[List[float]] = None,
+    ) -> np.ndarray:
+        """Fit the discretizer, and convert `x` to its weight of evidence, the
+        same as calling `fit` followed by `transform`.
+
+        Returns:
+            np.ndarray: A 2-D array of the weight of evidence, as from `transform`.
+        """
+        return self.fit(x, y, sample_weight, exception_values).transform(x)
+
+    def predict_woe_multiclass(self, x: ArrayLike) -> np.ndarray:
+        """Convert the provided variable to the one-vs-rest weight of evidence of
+        each class, for a discretizer fit with a `target_type` of "multiclass".
+
+        Args:
+            x (ArrayLike): An arraylike numeric field.
+
+        Returns:
+            np.ndarray: A 2-D array, with a row for each value, and a column for each
+                class, in the order of the class labels.
+        """
+        return super().predict_woe_multiclass(self._convert_array(x))
+
+    def predict_code_u16(self, x: ArrayLike) -> Tuple[np.ndarray, np.ndarray]:
+        """Convert the provided variable to compact bin codes, which take a quarter of
+        the memory of the weight of evidence, along with the table to expand them.
+
+        Args:
+            x (ArrayLike): An arraylike numeric field.
+
+        Returns:
+            Tuple[np.ndarray, np.ndarray]: The `np.uint16` code of each value, and the
+                weight of evidence of each code, so `table[codes]` is the weight of
+                evidence. Code 0 is reserved for `np.nan` values, when `np.nan` is not an
+                exception value, codes 1 to N for the N exception values, in the order
+                of the `vals_` key of `exception_values_`, and the following codes for
+                the bins, in order. Code 0 has the weight of evidence of the missing bin,
+                if there is one, and empty bins have a weight of evidence of 0.
+        """
+        return super().predict_code_u16(self._convert_array(x))
+
+    def transform_onehot(self, x: ArrayLike) -> pd.DataFrame:
+        """Convert the provided variable to 0/1 indicators of the bin each value
+        falls in, for models that use dummy variables rather than the weight of evidence.
+
+        Args:
+            x (ArrayLike): An arraylike numeric field.
+
+        Returns:
+            pd.DataFrame: A `np.uint8` column for each bin, in order, followed by a column
+                for each exception value, in the order of the `vals_` key of
+                `exception_values_`, and one for the missing bin, if there is one, named
+                with the label of the bin or exception value. Missing values predicted with
+                the weight of evidence of `on_missing` are zero in every column.
+        """
+        onehot = super().transform_onehot(self._convert_array(x))
+        return pd.DataFrame(onehot, columns=super().onehot_labels())
+
+    def bin_labels(self, precision: Optional[int] = None) -> List[str]:
+        """A label for each bin, followed by each exception value, and the missing bin,
+        if there is one, in the same positions as `bin_woe_`, such as "(-inf, 6.95]",
+        "-1" and "Missing", for reports. These are the same labels as `predict_label`.
+
+        Args:
+            precision (Optional[int], optional): The number of decimal places to show.
+                If None, the `decimals` of the label format are used. Defaults to None.
+
+        Returns:
+            List[str]: The label of each bin, exception value, and the missing bin.
+                Exception values given a label with `set_exception_label` use it.
+        """
+        return super().bin_labels(precision)
+
+    def bin_summary(self) -> pd.DataFrame:
+        """Summary statistics of the fitted binning, so it can be inspected
+        without recomputing them from the data.
+
+        Returns:
+            pd.DataFrame: A row for each bin, in the order of the bin indexes, followed
+                by a row for each exception value, in the order of the `vals_` key of
+                `exception_values_`, and a row for the missing bin, if there is one. The
+                columns are the `lower` and `upper` bounds of each bin, which are the value
+                itself for an exception value, or the missing bin, whether the row is an
+                `exception` value, or the `missing` bin, the `totals_ct`, the Kish
+                effective sample size `effective_ct`, the `ones_ct` and `zero_ct`, the
+                `event_rate`, the weight of evidence `woe`, the
+                information value `iv`, the `mean` and `std` of the performance, and
+                the `label` of the bin, or exception value.
+        """
+        return pd.DataFrame(super().bin_summary())
+
+    def summary(self) -> Dict[str, list]:
+        """The columns of `bin_summary`, as a dictionary of lists, so a summary can be
+        built without pandas, or passed to `pandas.DataFrame`.
+
+        Returns:
+            Dict[str, list]: The values of each column of `bin_summary`, keyed by the
+                column name, with a row for each bin, exception value, and the missing
+                bin, if there is one.
+        """
+        return dict(super().bin_summary())
+
+    def simulate_shift(self, bin_deltas: List[float]) -> dict:
+        """Simulate a shift in the population across the bins, assuming the event
+        rate of each bin does not change.
+
+        Args:
+            bin_deltas (List[float]): The factor the weighted count of each bin is
+                multiplied by, ordered with the numeric bins first, followed by the
+                exception values, in the order of `exception_values_["vals_"]`, and
+                the missing bin, if there is one.
+
+        Returns:
+            dict: The "bin" index, "woe", "event_rate", "reference_pct", and "shifted_pct"
+                of each bin, along with the "reference_event_rate", the implied
+                "shifted_event_rate", and the "reference_mean_woe" and "shifted_mean_woe".
+        """
+        return super().simulate_shift(list(bin_deltas))
+
+    def score_distribution(
+        self, x: ArrayLike, sample_weight: Optional[ArrayLike] = None
+    ) -> dict:
+        """Count the records of a sample, such as the data being scored, in each bin,
+        for a quick check of drift without a reference sample.
+
+        Args:
+            x (ArrayLike): An arraylike numeric field.
+            sample_weight (Optional[ArrayLike], optional): Optional sample weight column.
+                Defaults to None.
+
+        Returns:
+            dict: The "bin" index, "label", weighted count "ct" and share "pct" of each
+                bin, followed by each exception value and the missing bin, if there is
+                one, along with the "unbinned_ct" and "unbinned_pct" of missing values
+                predicted with the weight of evidence of `on_missing`, the
+                "out_of_range_ct" and "out_of_range_pct" of values outside the range the
+                discretizer was fit on, which are also counted in the edge bins, and the
+                "total_ct".
+        """
+        x = self._convert_array(x)
+        if sample_weight is not None:
+            sample_weight = self._convert_array(sample_weight)
+        return super().score_distribution(x, sample_weight)
+
+    def set_label_format(
+        self,
+        decimals: Optional[int] = None,
+        scientific_threshold: Optional[float] = None,
+        brackets: str = "(]",
+        missing_label: str = "Missing",
+        thousands_separator: bool = False,
+    ):
+        """Set how bins and exception values are rendered as labels, when
+        predicting labels or exporting the binning.
+
+        Args:
+            decimals (Optional[int], optional): The number of decimal places to show.
+                If None, the shortest exact representation is used. Defaults to None.
+            scientific_threshold (Optional[float], optional): Values with an absolute
+                value greater than or equal to this are shown in scientific notation.
+                Defaults to None.
+            brackets (str, optional): Two characters, the bracket used at the open, lower,
+                end of a bin, and the bracket used at the closed, upper, end. Defaults to "(]".
+            missing_label (str, optional): The label used for `np.nan`. Defaults to "Missing".
+            thousands_separator (bool, optional): Group the digits of whole numbers in
+                thousands with a comma. Defaults to False.
+        """
+        super().set_label_format(
+            decimals, scientific_threshold, brackets, missing_label, thousands_separator
+        )
+
+    def set_input_spec(
+        self,
+        name: str,
+        min_value: Optional[float] = None,
+        max_value: Optional[float] = None,
+        special_codes: Optional[Dict[str, float]] = None,
+        min_non_null_share: Optional[float] = None,
+    ):
+        """Attach the spec of the values a source system is expected to provide.
+        Once attached, the values passed to `fit` and `predict` are checked against
+        the spec, and a `ValueError` describing each violation is raised if they do
+        not match, so that upstream changes are caught before they distort the bins.
+
+        Args:
+            name (str): The name of the source system, reported with any violations.
+            min_value (Optional[float], optional): The smallest expected value.
+                Defaults to None.
+            max_value (Optional[float], optional): The largest expected value.
+                Defaults to None.
+            special_codes (Optional[Dict[str, float]], optional): Codes the source
+                system uses for special cases, keyed by name, such as
+                `{"not recorded": -999}`. These are allowed outside of the expected
+                range, and are usually passed as `exception_values`. Defaults to None.
+            min_non_null_share (Optional[float], optional): The smallest share of the
+                values that must not be `np.nan`. Defaults to None.
+        """
+        codes = None if special_codes is None else list(special_codes.items())
+        super().set_input_spec(name, min_value, max_value, codes, min_non_null_share)
+
+    def clear_input_spec(self):
+        """Remove the attached input spec, so values are no longer checked."""
+        super().clear_input_spec()
+
+    def input_spec_violations(self, x: ArrayLike) -> List[dict]:
+        """Check values against the attached input spec, without raising an error.
+
+        Args:
+            x (ArrayLike): An arraylike numeric field to check.
+
+        Returns:
+            List[dict]: A dictionary for each violation, with the kind of
+                `violation`, one of "below_min", "above_max" or "non_null_share",
+                the `limit` from the spec, and the offending `value`, the smallest,
+                largest, or observed non-null share. Range violations also have
+                the `count` of offending values. An empty list means the values match.
+        """
+        return super().input_spec_violations(self._convert_array(x))
+
+    def add_exception_value(self, value: float):
+        """Treat a value as an exception value, after the discretizer has been fit.
+        The records with this value are removed from the bin they fell in, and
+        the bin statistics are recomputed. The `splits_` are not changed.
+
+        Args:
+            value (float): The value to add to the exception values.
+        """
+        super().add_exception_value(value)
+
+    def merge_bins(self, i: int, j: int) -> Discretizer:
+        """Merge the bins from `i` to `j`, inclusive, into a single bin, by removing
+        the splits between them, such as when hand tuning the fitted bins. The
+        statistics of the merged bin are those of all of its records.
+
+        Args:
+            i (int): The index of the lowest bin to merge.
+            j (int): The index of the highest bin to merge.
+
+        Returns:
+            Discretizer: The discretizer, with the merged bins.
+        """
+        super().merge_bins(i, j)
+        return self
+
+    def split_bin_at(self, value: float) -> Discretizer:
+        """Split the bin `value` falls in, so that values less than or equal to
+        `value` fall in the lower bin, and recompute the statistics of both bins.
+
+        Args:
+            value (float): The new split, which can not be an exception value, or
+                an existing split.
+
+        Returns:
+            Discretizer: The discretizer, with the split bin.
+        """
+        super().split_bin_at(value)
+        return self
+
+    def compact(self) -> Discretizer:
+        """Collapse the fitted feature to the counts of each bin, rather than of each
+        unique value, so a discretizer fit on a feature with many unique values is small
+        to keep in memory, and pickle. Predictions and the summaries are unchanged, and
+        bins can still be merged, but a bin can no longer be split, nor a value made an
+        exception value, until the discretizer is refit.
+
+        Returns:
+            Discretizer: The discretizer, with the compacted feature.
+        """
+        super().compact()
+        return self
+
+    def set_exception_label(self, value: float, label: str):
+        """Attach a label to an exception value, such as "No history" for -1, which is
+        used in place of the value by `predict` with a `prediction_type` of "label",
+        `bin_summary`, and the exports. `np.nan` may be labelled, whether it is an
+        exception value, or the missing bin.
+
+        Args:
+            value (float): The exception value to label.
+            label (str): The label of the value.
+        """
+        super().set_exception_label(value, label)
+
+    def set_exception_order(self, order: List[float]):
+        """Set the order of the exception values, which gives the index returned by
+        `predict` with a `prediction_type` of "index", the first value has the index -1,
+        the next -2, and so on. They are listed in the same order in `exception_values_`,
+        `bin_summary`, and the exports. By default the exception values are sorted, with
+        `np.nan` first. The missing bin keeps the index after the last exception value.
+
+        Args:
+            order (List[float]): Each of the exception values, once, in the new order.
+        """
+        super().set_exception_order(order)
+
+    def validate_splits(self):
+        """Check that the fitted splits are a valid binning. The splits must be
+        increasing, start at -inf, end at inf, and every other split must be a value
+        of `x` seen when fitting, or with `split_point="midpoint"`, lie strictly
+        between two values of `x` seen when fitting.
+
+        Raises:
+            ValueError: If the splits are not valid.
+        """
+        super().validate_splits()
+
+    def validate_exceptions(self):
+        """Check that no exception value is also a value aggregated in the bins.
+
+        Raises:
+            ValueError: If any exception value is also present in the bins, listing
+                the conflicting values.
+        """
+        super().validate_exceptions()
+
+    def to_json_logic(
+        self, var: str = "x", output: str = "woe", include_metadata: bool = False
+    ) -> str:
+        """Export the fitted binning as a JSON Logic rule.
+
+        Args:
+            var (str, optional): The name of the variable the rule should
+                be applied to. Defaults to "x".
+            output (str, optional): The value the rule should produce, must be
+                one of "woe", "index" or "label", matching the `prediction_type` of the
+                `predict` method. Defaults to "woe".
+            include_metadata (bool, optional): Return a JSON document, with the
+                `metadata` of the fit, and the rule under the `json_logic` key.
+                Defaults to False.
+
+        Returns:
+            str: A JSON Logic rule. If `np.nan` is an exception value, missing
+                values are matched by `null`.
+        """
+        return super().to_json_logic(var, output, include_metadata)
+
+    def to_sql(self, column: str, dialect: str = "ansi", output: str = "woe") -> str:
+        """Export the fitted binning as a SQL CASE expression.
+
+        Args:
+            column (str): The column the expression should be applied to, written
+                as passed, so it may be a qualified name, or any numeric expression,
+                and must be quoted if required.
+            dialect (str, optional): The SQL dialect, one of "ansi", "postgres" or
+                "spark". Standard SQL has no NaN or infinite values, these are written
+                as NULL. Defaults to "ansi".
+            output (str, optional): The value the expression should produce, must be
+                one of "woe", "index" or "label", matching the `prediction_type` of the
+                `predict` method. Defaults to "woe".
+
+        Returns:
+            str: A SQL CASE expression. Missing values, NULL and NaN, are mapped to the
+                exception value of `np.nan`, or the missing bin, otherwise according to
+                `on_missing`, where "error" is mapped to NULL.
+        """
+        return super().to_sql(column, dialect, output)
+
+    def to_pmml(self, field: str, name: str, output: str = "woe") -> str:
+        """Export the fitted binning as a PMML DerivedField, with a Discretize
+        transformation, to be added to the TransformationDictionary of a PMML
+        document.
+
+        Args:
+            field (str): The name of the field the transformation is applied to.
+            name (str): The name of the derived field.
+            output (str, optional): The value the derived field should hold, must be
+                one of "woe", "index" or "label", matching the `prediction_type` of the
+                `predict` method. Defaults to "woe".
+
+        Returns:
+            str: A PMML DerivedField element. Exception values are intervals of a
+                single value, before the bins, as the first matching bin is used.
+                Missing values are mapped to the exception value of `np.nan`, or the
+                missing bin, otherwise according to `on_missing`, where "error" leaves
+                the derived field missing.
+        """
+        return super().to_pmml(field, name, output)
+
+    def to_onnx(self, input: str, output_name: str, output: str = "woe") -> bytes:
+        """Export the fitted binning as a serialized ONNX model, so the transform
+        can be deployed in ONNX Runtime alongside the downstream model, for example
+        by combining the graphs with `onnx.compose`.
+
+        Args:
+            input (str): The name of the input, a one dimensional double tensor.
+            output_name (str): The name of the output tensor.
+            output (str, optional): The value the output should hold, must be one of
+                "woe", "index" or "label", matching the `prediction_type` of the
+                `predict` method. Defaults to "woe".
+
+        Returns:
+            bytes: An ONNX model, using operators of the default domain at opset 13.
+                Missing values are mapped to the exception value of `np.nan`, or the
+                missing bin, otherwise according to `on_missing`, where "error" is
+                NaN, and can't be exported for an index or label.
+        """
+        return super().to_onnx(input, output_name, output)
+
+    def tree(self) -> dict:
+        """The tree grown when the discretizer was fit, showing the order the
+        splits were found in, and the statistics of each node. The 'quantile' and
+        'uniform' strategies do not search for splits, so their tree is only the
+        root node.
+
+        Returns:
+            dict: The root node, with the `depth` of the node, its `lower` and `upper`
+                bounds, the `split` of the node, or None if it was not split, whether
+                the split was `kept`, or removed when merging bins after fitting, the
+                `totals_ct`, `ones_ct` and `zero_ct` of the records in the node, its
+                weight of evidence `woe` and information value `iv`, the `stop_reason`
+                the node was not split for, and the `left` and `right` child nodes, in
+                the same format, or None.
+        """
+        return super().tree()
+
+    def fit_report(self) -> Dict[str, int]:
+        """Report why the terminal nodes of the tree were not split further, to
+        diagnose which constraint limited the number of bins. When no candidate
+        split of a node was valid, the constraint reported is the one that rejected
+        the candidate meeting the most constraints.
+
+        Returns:
+            Dict[str, int]: The number of terminal nodes stopped by each reason, one
+                of "no_candidates", "min_bin_width", "min_pos", "min_obs", "max_pvalue",
+                "min_iv", "monotonicity", "cross_validation" or "max_bins", where the
+                node was waiting to be split when `max_bins` was reached.
+        """
+        return super().fit_report()
+
+    def fit_trace(self) -> List[str]:
+        """The search for the best split of each node of the tree, in the order the
+        nodes were searched, when the discretizer was last fit with `verbose` set.
+
+        Returns:
+            List[str]: A line for each node, with the range of its values, the number
+                of candidate splits, the number rejected by each constraint, and the best
+                split and the information value it gains, or why it was not split.
+        """
+        return super().fit_trace()
+
+    def metadata(self) -> dict:
+        """The metadata recorded when the discretizer was fit, for tracing a
+        binning back to the code, parameters and data that produced it.
+
+        Returns:
+            dict: The `crate_version` the discretizer was fit with, when it was
+                `fitted_at`, in seconds since the Unix epoch, the `parameters` used,
+                the `n_records`, `total_weight` and `event_rate` of the data, and the
+                user supplied `tags`.
+        """
+        return super().metadata()
+
+
+class DiscretizerSet(RustDiscretizerSet):
+    def __new__(cls, template: Discretizer, names: List[str]):
+        return super().__new__(cls, template, names)
+
+    def __init__(self, template: Discretizer, names: List[str]):
+        """Create a discretizer for each of several columns, each a copy of
+        the `template` discretizer, with the same parameters.
+
+        Args:
+            template (Discretizer): The discretizer copied for each column.
+            names (List[str]): The name of each column. The fitted splits of
+                each column can be found in the `splits_` attribute, keyed by name.
+
+        The columns are predicted on the `n_threads` threads of the template, which
+        can be changed by setting the `n_threads` attribute. If None, they are predicted
+        on the calling thread, if 0, one thread per core is used.
+        """
+        super().__init__()
+
+    @staticmethod
+    def _convert_matrix(x: Union[pd.DataFrame, np.ndarray]) -> np.ndarray:
+        if isinstance(x, pd.DataFrame) or _is_polars(x):
+            x = x.to_numpy()
+        return np.asarray(x, dtype=np.float64)
+
+    def fit(
+        self,
+        x: Union[pd.DataFrame, np.ndarray],
+        y: ArrayLike,
+        sample_weight: Optional[ArrayLike] = None,
+        exception_values: Optional[List[float]] = None,
+    ) -> DiscretizerSet:
+        """Fit a discretizer to each column of `x`.
+
+        Args:
+            x (Union[pd.DataFrame, np.ndarray]): A 2-D array, with a column
+                for each name, in the same order.
+            y (ArrayLike): An arraylike binary field.
+            sample_weight (Optional[ArrayLike], optional): Optional sample weight array.
+                Defaults to None.
+            exception_values (Optional[List[float]], optional): Exception values, used
+                for every column. Defaults to None.
+
+        Returns:
+            DiscretizerSet: The fitted set.
+        """
+        x = self._convert_matrix(x)
+        y = Discretizer._convert_array(y)
+        if sample_weight is not None:
+            sample_weight = Discretizer._convert_array(sample_weight)
+        super().fit(x, y, sample_weight, exception_values)
+        return self
+
+    def predict_woe_matrix(self, x: Union[pd.DataFrame, np.ndarray]) -> np.ndarray:
+        """Convert each column of `x` to its weight of evidence.
+
+        Args:
+            x (Union[pd.DataFrame, np.ndarray]): A 2-D array, with a column
+                for each name, in the same order.
+
+        Returns:
+            np.ndarray: A 2-D array, the same shape as `x`, of weights of evidence.
+        """
+        return super().predict_woe_matrix(self._convert_matrix(x))
+
+    def predict_idx_matrix(self, x: Union[pd.DataFrame, np.ndarray]) -> np.ndarray:
+        """Convert each column of `x` to the index of its bin, as from `predict` with
+        a `prediction_type` of "index".
+
+        Args:
+            x (Union[pd.DataFrame, np.ndarray]): A 2-D array, with a column
+                for each name, in the same order.
+
+        Returns:
+            np.ndarray: A 2-D array, the same shape as `x`, of bin indexes.
+        """
+        return super().predict_idx_matrix(self._convert_matrix(x))
+
+
+def rank_features(
+    x: Union[pd.DataFrame, np.ndarray],
+    y: ArrayLike,
+    sample_weight: Optional[ArrayLike] = None,
+    template: Optional[Discretizer] = None,
+    names: Optional[List[str]] = None,
+) -> pd.DataFrame:
+    """Fit a discretizer to each column of `x`, and rank the columns by their
+    total information value, the first step of selecting the features of a
+    scorecard. The columns are fit in parallel on the `n_threads` threads of
+    the template.
+
+    Args:
+        x (Union[pd.DataFrame, np.ndarray]): A 2-D array of candidate features.
+        y (ArrayLike): An arraylike binary field.
+        sample_weight (Optional[ArrayLike], optional): Optional sample weight array.
+            Defaults to None.
+        template (Optional[Discretizer], optional): The discretizer copied for each
+            column. If None, a discretizer with the default parameters is used.
+            Defaults to None.
+        names (Optional[List[str]], optional): The name of each column. If None,
+            the column names of a DataFrame are used, otherwise the position of
+            each column. Defaults to None.
+
+    Returns:
+        pd.DataFrame: A row for each column, with its name, total information value,
+            number of bins, not including the exception values or missing bin, and
+            the trend of its discretizer, the detected trend if it was "auto". The
+            rows are sorted by the information value, highest first.
+    """
+    if names is None:
+        if isinstance(x, pd.DataFrame) or _is_polars(x):
+            names = [str(c) for c in x.columns]
+        else:
+            names = [str(i) for i in range(np.shape(x)[1])]
+    x = DiscretizerSet._convert_matrix(x)
+    y = Discretizer._convert_array(y)
+    if sample_weight is not None:
+        sample_weight = Discretizer._convert_array(sample_weight)
+    if template is None:
+        template = Discretizer()
+    ranks = _rank_features(names, x, y, sample_weight, template)
+    return pd.DataFrame(ranks, columns=["name", "total_iv", "n_bins", "mono"])
@@ -1,11 +1,92 @@
+// The pymethods macro in this version of pyO3 defines its impl blocks
+// inside of a const item, and the create_exception macro checks a cfg
+// this version of rust does not know of.
+#![allow(non_local_definitions, unexpected_cfgs)]
+use discrust_core::rank_features as crate_rank_features;
+use discrust_core::BinSummary;
+use discrust_core::Closed;
 use discrust_core::Discretizer as CrateDiscretizer;
+use discrust_core::DiscretizerSet as CrateDiscretizerSet;
 use discrust_core::DiscrustError;
+use discrust_core::EnsembleDiscretizer as CrateEnsembleDiscretizer;
+use discrust_core::ExceptionSummary;
+use discrust_core::ExportOutput;
+use discrust_core::Growth;
+use discrust_core::InputSpec;
+use discrust_core::LabelFormat;
+use discrust_core::MonotonicTrend;
+use discrust_core::OnMissing;
+use discrust_core::PreTransform;
+use discrust_core::SpecViolation;
+use discrust_core::SplitPoint;
+use discrust_core::SqlDialect;
+use discrust_core::Strategy;
+use discrust_core::TargetType;
+use discrust_core::TieBreak;
+use discrust_core::TreeNode;
 use numpy::Element;
-use numpy::{IntoPyArray, PyArray1, PyReadonlyArray1};
+use numpy::{IntoPyArray, PyArray1, PyArray2, PyReadonlyArray1, PyReadonlyArray2};
+use pyo3::create_exception;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use pyo3::types::{IntoPyDict, PyBytes, PyDict};
 use pyo3::Python;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+// A 1-D array of float64 values, converted from any sequence, or
+// buffer, of numbers, such as a list, an integer or float32 array, or a
+// pandas Series with NaN values. A contiguous float64 array is used as
+// is, anything else is copied with `numpy.ascontiguousarray`.
+struct F64Array<'a>(PyReadonlyArray1<'a, f64>);
+
+impl<'a> FromPyObject<'a> for F64Array<'a> {
+    fn extract(ob: &'a PyAny) -> PyResult<Self> {
+        if let Ok(a) = ob.extract::<PyReadonlyArray1<f64>>() {
+            if a.is_contiguous() {
+                return Ok(F64Array(a));
+            }
+        }
+        let kwargs = [("dtype", "float64")].into_py_dict(ob.py());
+        let a = ob
+            .py()
+            .import("numpy")?
+            .call_method("ascontiguousarray", (ob,), Some(kwargs))?;
+        Ok(F64Array(a.extract()?))
+    }
+}
+
+impl<'a> std::ops::Deref for F64Array<'a> {
+    type Target = PyReadonlyArray1<'a, f64>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+// A 1-D array of float32 values, or any other values converted to
+// float64, so float32 arrays can be predicted on without first being
+// copied to float64.
+#[derive(FromPyObject)]
+enum FloatArray<'a> {
+    F32(PyReadonlyArray1<'a, f32>),
+    F64(F64Array<'a>),
+}
+
+// Evaluate the expression with the slice of values of a `FloatArray`.
+macro_rules! with_slice {
+    ($x:expr, $s:ident => $e:expr) => {
+        match &$x {
+            FloatArray::F64(a) => {
+                let $s = a.as_slice()?;
+                $e
+            }
+            FloatArray::F32(a) => {
+                let $s = a.as_slice()?;
+                $e
+            }
+        }
+    };
+}
 
 // We need to pass subclass here, so that we
 // can inherit from this class later.
@@ -26,28 +107,401 @@ struct Discretizer {
 #[pymethods]
 impl Discretizer {
     #[new]
+    #[allow(clippy::too_many_arguments)]
     fn new(
         min_obs: Option<f64>,
         max_bins: Option<i64>,
         min_iv: Option<f64>,
-        min_pos: Option<f64>,
-        mono: Option<i8>,
-    ) -> Self {
-        Discretizer {
-            disc: CrateDiscretizer::new(min_obs, max_bins, min_iv, min_pos, mono),
-            splits_: Vec::new(),
+        min_pos: Option<&PyAny>,
+        mono: Option<&PyAny>,
+        min_effective_obs: Option<f64>,
+        pre_transform: Option<&str>,
+        clip_quantiles: Option<(f64, f64)>,
+        approx_splits: Option<usize>,
+        merge_only_edges: Option<Vec<f64>>,
+        split_point: Option<&str>,
+        n_threads: Option<usize>,
+        tags: Option<BTreeMap<String, String>>,
+        on_missing: Option<&PyAny>,
+        strategy: Option<&str>,
+        n_bins: Option<usize>,
+        woe_regularization: Option<f64>,
+        enforce_monotonicity: Option<bool>,
+        max_pvalue: Option<f64>,
+        min_obs_rate: Option<f64>,
+        min_pos_rate: Option<f64>,
+        special_missing: Option<bool>,
+        cv_folds: Option<usize>,
+        cv_agreement: Option<f64>,
+        target_type: Option<&str>,
+        merge_exceptions: Option<bool>,
+        manual_splits: Option<Vec<f64>>,
+        manual_exceptions: Option<Vec<f64>>,
+        closed: Option<&str>,
+        max_candidates: Option<usize>,
+        tie_break: Option<&str>,
+        min_bin_width: Option<f64>,
+        max_bin_pct: Option<f64>,
+        growth: Option<&str>,
+        verbose: Option<bool>,
+        max_children: Option<usize>,
+        n_atoms: Option<usize>,
+        mdlp: Option<bool>,
+        min_iv_gain_ratio: Option<f64>,
+    ) -> PyResult<Self> {
+        let on_missing = match on_missing {
+            None => OnMissing::default(),
+            Some(m) => match (m.extract::<&str>(), m.extract::<f64>()) {
+                (Ok("error"), _) => OnMissing::Error,
+                (Ok("lowest_bin"), _) => OnMissing::LowestBin,
+                (Ok("highest_bin"), _) => OnMissing::HighestBin,
+                (_, Ok(woe)) => OnMissing::WoeValue(woe),
+                _ => {
+                    return Err(PyValueError::new_err(format!(
+                        "The parameter `on_missing` must be one of 'error', 'lowest_bin', 'highest_bin' or a float, but {} was passed.",
+                        m
+                    )))
+                }
+            },
+        };
+        let strategy = match (strategy, merge_only_edges, n_bins) {
+            (Some("manual"), None, None) => match manual_splits {
+                Some(splits) => Strategy::Manual {
+                    splits,
+                    exception_values: manual_exceptions.unwrap_or_default(),
+                },
+                None => {
+                    return Err(PyValueError::new_err(
+                        "The manual strategy requires `manual_splits`.",
+                    ))
+                }
+            },
+            (Some("manual"), _, _) => {
+                return Err(PyValueError::new_err(
+                    "The manual strategy can not be used with `merge_only_edges` or `n_bins`.",
+                ))
+            }
+            _ if manual_splits.is_some() || manual_exceptions.is_some() => {
+                return Err(PyValueError::new_err(
+                    "The parameters `manual_splits` and `manual_exceptions` only apply to the 'manual' strategy.",
+                ))
+            }
+            _ if n_atoms.is_some() && (strategy != Some("optimal_dp")) => {
+                return Err(PyValueError::new_err(
+                    "The parameter `n_atoms` only applies to the 'optimal_dp' strategy.",
+                ))
+            }
+            _ if max_children.is_some() && (strategy != Some("chaid")) => {
+                return Err(PyValueError::new_err(
+                    "The parameter `max_children` only applies to the 'chaid' strategy.",
+                ))
+            }
+            (Some("chaid"), None, None) => Strategy::Chaid {
+                max_children: max_children.unwrap_or(3),
+            },
+            (Some("optimal_dp"), None, None) => Strategy::OptimalDP {
+                n_atoms: n_atoms.unwrap_or(100),
+            },
+            (Some(s @ ("chaid" | "optimal_dp")), _, _) => {
+                return Err(PyValueError::new_err(format!(
+                    "The {} strategy can not be used with `merge_only_edges` or `n_bins`.",
+                    s
+                )))
+            }
+            (None | Some("tree"), None, None) => Strategy::Tree,
+            (None, Some(edges), None) => Strategy::MergeOnly { edges },
+            (Some("quantile"), None, Some(n_bins)) => Strategy::Quantile { n_bins },
+            (Some("uniform"), None, Some(n_bins)) => Strategy::Uniform { n_bins },
+            (Some(s @ ("quantile" | "uniform")), _, _) => {
+                return Err(PyValueError::new_err(format!(
+                    "The {} strategy requires `n_bins`, and can not be used with `merge_only_edges`.",
+                    s
+                )))
+            }
+            (None | Some("tree"), _, Some(_)) => {
+                return Err(PyValueError::new_err(
+                    "The parameter `n_bins` only applies to the 'quantile' and 'uniform' strategies.",
+                ))
+            }
+            (Some("tree"), Some(_), None) => {
+                return Err(PyValueError::new_err(
+                    "The parameter `merge_only_edges` can not be used with the 'tree' strategy.",
+                ))
+            }
+            (Some(s), _, _) => {
+                return Err(PyValueError::new_err(format!(
+                    "The parameter `strategy` must be one of 'tree', 'quantile', 'uniform', 'manual', 'chaid' or 'optimal_dp', but {} was passed.",
+                    s
+                )))
+            }
+        };
+        let pre_transform = match pre_transform {
+            None => None,
+            Some("log1p") => Some(PreTransform::Log1p),
+            Some("sqrt") => Some(PreTransform::Sqrt),
+            Some(t) => {
+                return Err(PyValueError::new_err(format!(
+                    "The parameter `pre_transform` must be one of 'log1p' or 'sqrt', but {} was passed.",
+                    t
+                )))
+            }
+        };
+        let split_point = match split_point {
+            None | Some("observed") => SplitPoint::Observed,
+            Some("midpoint") => SplitPoint::Midpoint,
+            Some(p) => {
+                return Err(PyValueError::new_err(format!(
+                    "The parameter `split_point` must be one of 'observed' or 'midpoint', but {} was passed.",
+                    p
+                )))
+            }
+        };
+        let closed = match closed {
+            None | Some("right") => Closed::Right,
+            Some("left") => Closed::Left,
+            Some(c) => {
+                return Err(PyValueError::new_err(format!(
+                    "The parameter `closed` must be one of 'right' or 'left', but {} was passed.",
+                    c
+                )))
+            }
+        };
+        let tie_break = match tie_break {
+            None | Some("lowest") => TieBreak::Lowest,
+            Some("highest") => TieBreak::Highest,
+            Some("median") => TieBreak::Median,
+            Some(t) => {
+                return Err(PyValueError::new_err(format!(
+                    "The parameter `tie_break` must be one of 'lowest', 'highest' or 'median', but {} was passed.",
+                    t
+                )))
+            }
+        };
+        let growth = match growth {
+            None | Some("breadth_first") => Growth::BreadthFirst,
+            Some("depth_first") => Growth::DepthFirst,
+            Some("best_first") => Growth::BestFirst,
+            Some(g) => {
+                return Err(PyValueError::new_err(format!(
+                    "The parameter `growth` must be one of 'breadth_first', 'depth_first' or 'best_first', but {} was passed.",
+                    g
+                )))
+            }
+        };
+        let target_type = match target_type {
+            None | Some("binary") => TargetType::Binary,
+            Some("continuous") => TargetType::Continuous,
+            Some("multiclass") => TargetType::Multiclass,
+            Some(t) => {
+                return Err(PyValueError::new_err(format!(
+                    "The parameter `target_type` must be one of 'binary', 'continuous' or 'multiclass', but {} was passed.",
+                    t
+                )))
+            }
+        };
+        let mut builder = CrateDiscretizer::builder()
+            .mono(monotonic_trend(mono)?)
+            .min_effective_obs(min_effective_obs)
+            .pre_transform(pre_transform)
+            .clip_quantiles(clip_quantiles)
+            .approx_splits(approx_splits)
+            .max_candidates(max_candidates)
+            .split_point(split_point)
+            .closed(closed)
+            .tie_break(tie_break)
+            .n_threads(n_threads)
+            .tags(tags.unwrap_or_default())
+            .on_missing(on_missing)
+            .strategy(strategy)
+            .max_pvalue(max_pvalue)
+            .min_bin_width(min_bin_width)
+            .max_bin_pct(max_bin_pct)
+            .min_iv_gain_ratio(min_iv_gain_ratio)
+            .growth(growth)
+            .min_obs_rate(min_obs_rate)
+            .min_pos_rate(min_pos_rate)
+            .cv_folds(cv_folds)
+            .target_type(target_type);
+        if let Some(min_obs) = min_obs {
+            builder = builder.min_obs(min_obs);
+        }
+        if let Some(max_bins) = max_bins {
+            builder = builder.max_bins(max_bins);
+        }
+        if let Some(min_iv) = min_iv {
+            builder = builder.min_iv(min_iv);
+        }
+        if let Some(min_pos) = min_pos {
+            builder = match (min_pos.extract::<&str>(), min_pos.extract::<f64>()) {
+                (Ok("auto"), _) => builder.min_pos_auto(true),
+                (_, Ok(min_pos)) => builder.min_pos(min_pos),
+                _ => {
+                    return Err(PyValueError::new_err(format!(
+                        "The parameter `min_pos` must be a number, or 'auto', but {} was passed.",
+                        min_pos
+                    )))
+                }
+            };
+        }
+        if let Some(woe_regularization) = woe_regularization {
+            builder = builder.woe_regularization(woe_regularization);
+        }
+        if let Some(enforce_monotonicity) = enforce_monotonicity {
+            builder = builder.enforce_monotonicity(enforce_monotonicity);
         }
+        if let Some(verbose) = verbose {
+            builder = builder.verbose(verbose);
+        }
+        if let Some(mdlp) = mdlp {
+            builder = builder.mdlp(mdlp);
+        }
+        if let Some(special_missing) = special_missing {
+            builder = builder.special_missing(special_missing);
+        }
+        if let Some(cv_agreement) = cv_agreement {
+            builder = builder.cv_agreement(cv_agreement);
+        }
+        if let Some(merge_exceptions) = merge_exceptions {
+            builder = builder.merge_exceptions(merge_exceptions);
+        }
+        let disc = builder.build().map_err(py_err)?;
+        Ok(Discretizer {
+            disc,
+            splits_: Vec::new(),
+        })
+    }
+
+    // The parameters that differ from the defaults,
+    // and once fitted, a table of the bins.
+    pub fn __repr__(&self) -> String {
+        self.disc.to_string()
+    }
+
+    pub fn __str__(&self) -> String {
+        self.disc.to_string()
+    }
+
+    // The discretizer serialized as JSON, so it can be pickled,
+    // including the fitted feature and tree.
+    pub fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<&'py PyBytes> {
+        let state =
+            serde_json::to_vec(&self.disc).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyBytes::new(py, &state))
+    }
+
+    pub fn __setstate__(&mut self, state: &PyBytes) -> PyResult<()> {
+        self.disc = serde_json::from_slice(state.as_bytes())
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        self.splits_ = self.disc.splits_.to_vec();
+        Ok(())
+    }
+
+    // Replace the fitting parameters with those of `other`, for
+    // `set_params`, keeping the label format and input spec, which are
+    // not constructor parameters. The discretizer must be fit again.
+    pub fn _set_parameters(&mut self, other: PyRef<Discretizer>) {
+        let mut disc = other.disc.unfitted_copy();
+        disc.label_format = self.disc.label_format.clone();
+        disc.input_spec = self.disc.input_spec.clone();
+        self.disc = disc;
+        self.splits_ = Vec::new();
+    }
+
+    #[getter]
+    pub fn exception_values_<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let exceptions = &self
+            .disc
+            .feature
+            .as_ref()
+            .ok_or_else(|| py_err(DiscrustError::NotFitted))?
+            .exception_values_;
+        let res = exceptions.to_hashmap().into_py_dict(py);
+        let labels: Vec<Option<&str>> = (0..exceptions.vals_.len())
+            .map(|i| exceptions.label(i))
+            .collect();
+        res.set_item("labels_", labels)?;
+        Ok(res)
     }
 
     #[getter]
-    pub fn exception_values_(&self) -> PyResult<HashMap<String, Vec<f64>>> {
-        Ok(self
+    pub fn feature_stats_<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let feature = self
             .disc
             .feature
             .as_ref()
-            .unwrap()
-            .exception_values_
-            .to_hashmap())
+            .ok_or_else(|| py_err(DiscrustError::NotFitted))?;
+        let (totals, ones, zero) = feature.cumulative_counts();
+        let (total_ones, total_zero) = feature.class_totals();
+        let stats = PyDict::new(py);
+        stats.set_item("vals_", feature.unique_values().to_vec())?;
+        stats.set_item("cuml_totals_ct_", totals.to_vec())?;
+        stats.set_item("cuml_ones_ct_", ones.to_vec())?;
+        stats.set_item("cuml_zero_ct_", zero.to_vec())?;
+        stats.set_item("total_ones_", total_ones)?;
+        stats.set_item("total_zero_", total_zero)?;
+        Ok(stats)
+    }
+
+    pub fn bin_labels(&self, precision: Option<usize>) -> PyResult<Vec<String>> {
+        self.disc.bin_labels(precision).map_err(py_err)
+    }
+
+    pub fn bin_summary<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let bins = self.disc.bin_summary().map_err(py_err)?;
+        let exceptions = self.disc.exception_summary().map_err(py_err)?;
+        let missing = self.disc.missing_summary().map_err(py_err)?;
+        // The bins, followed by a row for each exception value, and the
+        // missing bin, the bounds of an exception value are the value itself.
+        let column = |b: fn(&BinSummary) -> f64, e: fn(&ExceptionSummary) -> f64| {
+            bins.iter()
+                .map(b)
+                .chain(exceptions.iter().chain(&missing).map(e))
+                .collect::<Vec<f64>>()
+        };
+        let res = PyDict::new(py);
+        res.set_item("lower", column(|b| b.lower, |e| e.value))?;
+        res.set_item("upper", column(|b| b.upper, |e| e.value))?;
+        res.set_item(
+            "exception",
+            bins.iter()
+                .map(|_| false)
+                .chain(exceptions.iter().map(|_| true))
+                .chain(missing.iter().map(|_| false))
+                .collect::<Vec<bool>>(),
+        )?;
+        res.set_item(
+            "missing",
+            bins.iter()
+                .map(|_| false)
+                .chain(exceptions.iter().map(|_| false))
+                .chain(missing.iter().map(|_| true))
+                .collect::<Vec<bool>>(),
+        )?;
+        res.set_item("totals_ct", column(|b| b.totals_ct, |e| e.totals_ct))?;
+        res.set_item(
+            "effective_ct",
+            column(|b| b.effective_ct, |e| e.effective_ct),
+        )?;
+        res.set_item("ones_ct", column(|b| b.ones_ct, |e| e.ones_ct))?;
+        res.set_item("zero_ct", column(|b| b.zero_ct, |e| e.zero_ct))?;
+        res.set_item("event_rate", column(|b| b.event_rate, |e| e.event_rate))?;
+        res.set_item("woe", column(|b| b.woe, |e| e.woe))?;
+        res.set_item("iv", column(|b| b.iv, |e| e.iv))?;
+        res.set_item("mean", column(|b| b.mean, |e| e.mean))?;
+        res.set_item("std", column(|b| b.std, |e| e.std))?;
+        res.set_item(
+            "label",
+            bins.iter()
+                .map(|b| b.label.to_owned())
+                .chain(
+                    exceptions
+                        .iter()
+                        .chain(&missing)
+                        .map(|e| e.label.to_owned()),
+                )
+                .collect::<Vec<String>>(),
+        )?;
+        Ok(res)
     }
 
     #[getter]
@@ -55,64 +509,836 @@ impl Discretizer {
         Ok(self.splits_.to_vec())
     }
 
+    #[getter]
+    pub fn bin_woe_(&self) -> PyResult<Vec<f64>> {
+        self.disc.bin_woe_().map_err(py_err)
+    }
+
+    #[getter]
+    pub fn bin_iv_(&self) -> PyResult<Vec<f64>> {
+        self.disc.bin_iv_().map_err(py_err)
+    }
+
+    #[getter]
+    pub fn counts_<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        self.bin_column(py, |b| b.totals_ct)
+    }
+
+    #[getter]
+    pub fn ones_ct_<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        self.bin_column(py, |b| b.ones_ct)
+    }
+
+    #[getter]
+    pub fn event_rate_<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        self.bin_column(py, |b| b.event_rate)
+    }
+
+    #[getter]
+    pub fn woe_<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        self.bin_column(py, |b| b.woe)
+    }
+
+    #[getter]
+    pub fn iv_<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        self.bin_column(py, |b| b.iv)
+    }
+
+    #[getter]
+    pub fn total_iv_(&self) -> PyResult<f64> {
+        self.disc.total_iv_().map_err(py_err)
+    }
+
+    #[getter]
+    pub fn gini_(&self) -> PyResult<f64> {
+        self.disc.gini_().map_err(py_err)
+    }
+
+    #[getter]
+    pub fn ks_(&self) -> PyResult<f64> {
+        self.disc.ks_().map_err(py_err)
+    }
+
     #[setter]
-    pub fn set_mono(&mut self, value: Option<i8>) -> PyResult<()> {
-        self.disc.mono = value;
+    pub fn set_mono(&mut self, value: Option<&PyAny>) -> PyResult<()> {
+        self.disc.mono = monotonic_trend(value)?;
         Ok(())
     }
 
     pub fn fit(
         &mut self,
-        x: PyReadonlyArray1<f64>,
-        y: PyReadonlyArray1<f64>,
-        w: Option<PyReadonlyArray1<f64>>,
+        py: Python,
+        x: F64Array,
+        y: F64Array,
+        w: Option<F64Array>,
         exception_values: Option<Vec<f64>>,
     ) -> PyResult<Vec<f64>> {
         let x = x.as_slice()?;
+        let y = y.as_slice()?;
+        let w = w.as_ref().map(|w| w.as_slice()).transpose()?;
+        // The GIL is released while fitting, so other Python threads can
+        // run, such as fitting other columns.
+        let disc = &mut self.disc;
+        let res = py.allow_threads(|| disc.fit(x, y, w, exception_values));
+        match res {
+            Ok(r) => self.splits_ = r.splits,
+            Err(e) => return Err(py_err(e)),
+        }
+        Ok(self.splits_.to_vec())
+    }
+
+    pub fn fit_stats(
+        &mut self,
+        py: Python,
+        x: F64Array,
+        y: F64Array,
+        w: Option<F64Array>,
+    ) -> PyResult<Vec<f64>> {
+        let x = x.as_slice()?;
+        let y = y.as_slice()?;
+        let w = w.as_ref().map(|w| w.as_slice()).transpose()?;
+        let disc = &mut self.disc;
+        let res = py
+            .allow_threads(|| disc.fit_stats(x, y, w))
+            .map_err(py_err)?;
+        self.splits_ = res.splits;
+        Ok(self.splits_.to_vec())
+    }
+
+    pub fn recompute_stats(
+        &mut self,
+        py: Python,
+        x: F64Array,
+        y: F64Array,
+        w: Option<F64Array>,
+    ) -> PyResult<()> {
+        let x = x.as_slice()?;
+        let y = y.as_slice()?;
+        let w = w.as_ref().map(|w| w.as_slice()).transpose()?;
+        let disc = &mut self.disc;
+        py.allow_threads(|| disc.recompute_stats(x, y, w))
+            .map(|_| ())
+            .map_err(py_err)
+    }
+
+    pub fn partial_fit(
+        &mut self,
+        py: Python,
+        x: F64Array,
+        y: F64Array,
+        w: Option<F64Array>,
+        exception_values: Option<Vec<f64>>,
+    ) -> PyResult<()> {
+        let x = x.as_slice()?;
+        let y = y.as_slice()?;
+        let w = w.as_ref().map(|w| w.as_slice()).transpose()?;
+        let disc = &mut self.disc;
+        py.allow_threads(|| disc.partial_fit(x, y, w, exception_values))
+            .map_err(py_err)
+    }
+
+    pub fn finalize(&mut self, py: Python) -> PyResult<Vec<f64>> {
+        let disc = &mut self.disc;
+        let res = py.allow_threads(|| disc.finalize()).map_err(py_err)?;
+        self.splits_ = res.splits;
+        Ok(self.splits_.to_vec())
+    }
+
+    pub fn fit_categorical(
+        &mut self,
+        py: Python,
+        x: Vec<String>,
+        y: F64Array,
+        w: Option<F64Array>,
+    ) -> PyResult<Vec<f64>> {
         let y = y.as_slice()?;
         let w_ = match w {
-            Some(v) => v.to_vec(),
-            // If a weight is provided this means we are always coping it...
-            // Probably should change this so that it's accepting a value,
-            // and just init with np.ones
-            None => {
-                let v = vec![1.0; y.len()];
-                Ok(v)
-            }
-        }?;
-        let splits = self.disc.fit(x, y, &w_, exception_values);
-        match splits {
-            Ok(s) => self.splits_ = s,
-            Err(e) => return Err(PyValueError::new_err(e.to_string())),
+            Some(v) => v.to_vec()?,
+            None => vec![1.0; y.len()],
+        };
+        let disc = &mut self.disc;
+        match py.allow_threads(|| disc.fit_categorical(&x, y, &w_)) {
+            Ok(r) => self.splits_ = r.splits,
+            Err(e) => return Err(py_err(e)),
         }
         Ok(self.splits_.to_vec())
     }
 
-    pub fn predict_woe<'py>(
+    #[getter]
+    pub fn category_bins_(&self) -> PyResult<Vec<Vec<String>>> {
+        self.disc.category_bins().map_err(py_err)
+    }
+
+    pub fn predict_categorical_woe<'py>(
         &self,
         py: Python<'py>,
-        x: PyReadonlyArray1<f64>,
+        x: Vec<String>,
     ) -> PyResult<&'py PyArray1<f64>> {
-        let x = x.as_slice()?;
-        pyarray_or_value_error(py, self.disc.predict_woe(x))
+        pyarray_or_value_error(py, self.disc.predict_categorical_woe(&x))
     }
 
-    pub fn predict_idx<'py>(
+    pub fn predict_categorical_idx<'py>(
         &self,
         py: Python<'py>,
-        x: PyReadonlyArray1<f64>,
+        x: Vec<String>,
     ) -> PyResult<&'py PyArray1<i64>> {
+        pyarray_or_value_error(py, self.disc.predict_categorical_idx(&x))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn fit_ensemble(
+        &self,
+        py: Python,
+        x: F64Array,
+        y: F64Array,
+        w: Option<F64Array>,
+        exception_values: Option<Vec<f64>>,
+        n_models: usize,
+        row_subsample: f64,
+        seed: u64,
+    ) -> PyResult<EnsembleDiscretizer> {
         let x = x.as_slice()?;
-        pyarray_or_value_error(py, self.disc.predict_idx(x))
+        let y = y.as_slice()?;
+        let w_ = match w {
+            Some(v) => v.to_vec()?,
+            None => vec![1.0; y.len()],
+        };
+        let disc = &self.disc;
+        py.allow_threads(|| {
+            disc.fit_ensemble(x, y, &w_, exception_values, n_models, row_subsample, seed)
+        })
+        .map(|ensemble| EnsembleDiscretizer { ensemble })
+        .map_err(py_err)
+    }
+
+    pub fn simulate_shift<'py>(
+        &self,
+        py: Python<'py>,
+        bin_deltas: Vec<f64>,
+    ) -> PyResult<&'py PyDict> {
+        let sim = self.disc.simulate_shift(&bin_deltas).map_err(py_err)?;
+        let res = PyDict::new(py);
+        res.set_item("bin", sim.bins.iter().map(|b| b.bin).collect::<Vec<i64>>())?;
+        res.set_item("woe", sim.bins.iter().map(|b| b.woe).collect::<Vec<f64>>())?;
+        res.set_item(
+            "event_rate",
+            sim.bins.iter().map(|b| b.event_rate).collect::<Vec<f64>>(),
+        )?;
+        res.set_item(
+            "reference_pct",
+            sim.bins
+                .iter()
+                .map(|b| b.reference_pct)
+                .collect::<Vec<f64>>(),
+        )?;
+        res.set_item(
+            "shifted_pct",
+            sim.bins.iter().map(|b| b.shifted_pct).collect::<Vec<f64>>(),
+        )?;
+        res.set_item("reference_event_rate", sim.reference_event_rate)?;
+        res.set_item("shifted_event_rate", sim.shifted_event_rate)?;
+        res.set_item("reference_mean_woe", sim.reference_mean_woe)?;
+        res.set_item("shifted_mean_woe", sim.shifted_mean_woe)?;
+        Ok(res)
+    }
+
+    pub fn score_distribution<'py>(
+        &self,
+        py: Python<'py>,
+        x: FloatArray,
+        w: Option<F64Array>,
+    ) -> PyResult<&'py PyDict> {
+        let w = w.as_ref().map(|w| w.as_slice()).transpose()?;
+        let dist = with_slice!(x, x => self.disc.score_distribution(x, w)).map_err(py_err)?;
+        let res = PyDict::new(py);
+        res.set_item("bin", dist.bins.iter().map(|b| b.bin).collect::<Vec<i64>>())?;
+        res.set_item(
+            "label",
+            dist.bins
+                .iter()
+                .map(|b| b.label.as_str())
+                .collect::<Vec<&str>>(),
+        )?;
+        res.set_item("ct", dist.bins.iter().map(|b| b.ct).collect::<Vec<f64>>())?;
+        res.set_item("pct", dist.bins.iter().map(|b| b.pct).collect::<Vec<f64>>())?;
+        res.set_item("unbinned_ct", dist.unbinned_ct)?;
+        res.set_item("unbinned_pct", dist.unbinned_pct)?;
+        res.set_item("out_of_range_ct", dist.out_of_range_ct)?;
+        res.set_item("out_of_range_pct", dist.out_of_range_pct)?;
+        res.set_item("total_ct", dist.total_ct)?;
+        Ok(res)
+    }
+
+    pub fn set_label_format(
+        &mut self,
+        decimals: Option<usize>,
+        scientific_threshold: Option<f64>,
+        brackets: &str,
+        missing_label: &str,
+        thousands_separator: bool,
+    ) -> PyResult<()> {
+        let (open_bracket, closed_bracket) = match brackets.chars().collect::<Vec<char>>()[..] {
+            [o, c] => (o, c),
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "The parameter `brackets` must be two characters, but {} was passed.",
+                    brackets
+                )))
+            }
+        };
+        self.disc.label_format = LabelFormat {
+            decimals,
+            scientific_threshold,
+            open_bracket,
+            closed_bracket,
+            missing_label: missing_label.to_string(),
+            thousands_separator,
+        };
+        Ok(())
+    }
+
+    pub fn set_input_spec(
+        &mut self,
+        name: &str,
+        min_value: Option<f64>,
+        max_value: Option<f64>,
+        special_codes: Option<Vec<(String, f64)>>,
+        min_non_null_share: Option<f64>,
+    ) {
+        self.disc.input_spec = Some(InputSpec {
+            name: name.to_string(),
+            min_value,
+            max_value,
+            special_codes: special_codes.unwrap_or_default(),
+            min_non_null_share,
+        });
+    }
+
+    pub fn clear_input_spec(&mut self) {
+        self.disc.input_spec = None;
+    }
+
+    pub fn input_spec_violations<'py>(
+        &self,
+        py: Python<'py>,
+        x: F64Array,
+    ) -> PyResult<Vec<&'py PyDict>> {
+        let x = x.as_slice()?;
+        let violations = match &self.disc.input_spec {
+            Some(spec) => spec.violations(x),
+            None => Vec::new(),
+        };
+        violations
+            .iter()
+            .map(|v| {
+                let res = PyDict::new(py);
+                match v {
+                    SpecViolation::BelowMin {
+                        min_value,
+                        count,
+                        smallest,
+                    } => {
+                        res.set_item("violation", "below_min")?;
+                        res.set_item("limit", min_value)?;
+                        res.set_item("count", count)?;
+                        res.set_item("value", smallest)?;
+                    }
+                    SpecViolation::AboveMax {
+                        max_value,
+                        count,
+                        largest,
+                    } => {
+                        res.set_item("violation", "above_max")?;
+                        res.set_item("limit", max_value)?;
+                        res.set_item("count", count)?;
+                        res.set_item("value", largest)?;
+                    }
+                    SpecViolation::NonNullShare { required, observed } => {
+                        res.set_item("violation", "non_null_share")?;
+                        res.set_item("limit", required)?;
+                        res.set_item("value", observed)?;
+                    }
+                }
+                Ok(res)
+            })
+            .collect()
+    }
+
+    pub fn predict_label(&self, py: Python, x: FloatArray) -> PyResult<Vec<String>> {
+        let disc = &self.disc;
+        with_slice!(x, x => py.allow_threads(|| disc.predict_label(x))).map_err(py_err)
+    }
+
+    pub fn validate_splits(&self) -> PyResult<()> {
+        self.disc.validate_splits().map_err(py_err)
+    }
+
+    pub fn validate_exceptions(&self) -> PyResult<()> {
+        self.disc.validate_exceptions().map_err(py_err)
+    }
+
+    pub fn add_exception_value(&mut self, value: f64) -> PyResult<()> {
+        self.disc.add_exception_value(value).map_err(py_err)
+    }
+
+    pub fn merge_bins(&mut self, i: usize, j: usize) -> PyResult<()> {
+        self.disc.merge_bins(i, j).map_err(py_err)?;
+        self.splits_ = self.disc.splits_.to_vec();
+        Ok(())
+    }
+
+    pub fn compact(&mut self) -> PyResult<()> {
+        self.disc.compact().map_err(py_err)
+    }
+
+    pub fn split_bin_at(&mut self, value: f64) -> PyResult<()> {
+        self.disc.split_bin_at(value).map_err(py_err)?;
+        self.splits_ = self.disc.splits_.to_vec();
+        Ok(())
+    }
+
+    pub fn set_exception_label(&mut self, value: f64, label: &str) -> PyResult<()> {
+        self.disc.set_exception_label(value, label).map_err(py_err)
+    }
+
+    pub fn set_exception_order(&mut self, order: Vec<f64>) -> PyResult<()> {
+        self.disc.set_exception_order(&order).map_err(py_err)
+    }
+
+    pub fn to_json_logic(
+        &self,
+        var: &str,
+        output: &str,
+        include_metadata: bool,
+    ) -> PyResult<String> {
+        let output = export_output(output)?;
+        let res = if include_metadata {
+            self.disc.to_json_logic_document(var, output)
+        } else {
+            self.disc.to_json_logic(var, output)
+        };
+        res.map_err(py_err)
+    }
+
+    pub fn metadata<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let metadata = self.disc.metadata().map_err(py_err)?;
+        let res = PyDict::new(py);
+        res.set_item("crate_version", &metadata.crate_version)?;
+        res.set_item("fitted_at", metadata.fitted_at)?;
+        res.set_item("parameters", metadata.parameters.clone())?;
+        res.set_item("n_records", metadata.n_records)?;
+        res.set_item("total_weight", metadata.total_weight)?;
+        res.set_item("event_rate", metadata.event_rate)?;
+        res.set_item("tags", metadata.tags.clone())?;
+        Ok(res)
+    }
+
+    pub fn to_sql(&self, column: &str, dialect: &str, output: &str) -> PyResult<String> {
+        let dialect = match dialect {
+            "ansi" => SqlDialect::Ansi,
+            "postgres" => SqlDialect::Postgres,
+            "spark" => SqlDialect::Spark,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "The parameter `dialect` must be one of 'ansi', 'postgres' or 'spark', but {} was passed.",
+                    dialect
+                )))
+            }
+        };
+        self.disc
+            .to_sql(column, dialect, export_output(output)?)
+            .map_err(py_err)
+    }
+
+    pub fn to_pmml(&self, field: &str, name: &str, output: &str) -> PyResult<String> {
+        self.disc
+            .to_pmml(field, name, export_output(output)?)
+            .map_err(py_err)
+    }
+
+    pub fn to_onnx<'py>(
+        &self,
+        py: Python<'py>,
+        input: &str,
+        output_name: &str,
+        output: &str,
+    ) -> PyResult<&'py PyBytes> {
+        let model = self
+            .disc
+            .to_onnx(input, output_name, export_output(output)?)
+            .map_err(py_err)?;
+        Ok(PyBytes::new(py, &model))
+    }
+
+    pub fn tree<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let tree = self.disc.tree().map_err(py_err)?;
+        tree_dict(py, &tree)
+    }
+
+    pub fn fit_trace(&self) -> Vec<String> {
+        self.disc
+            .fit_trace()
+            .iter()
+            .map(|e| e.to_string())
+            .collect()
+    }
+
+    pub fn fit_report<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let report = self.disc.fit_report().map_err(py_err)?;
+        let res = PyDict::new(py);
+        for (reason, count) in report.stop_reasons {
+            res.set_item(reason.to_string(), count)?;
+        }
+        Ok(res)
+    }
+
+    pub fn out_of_range<'py>(
+        &self,
+        py: Python<'py>,
+        x: FloatArray,
+    ) -> PyResult<&'py PyArray1<bool>> {
+        with_slice!(x, x => pyarray_or_value_error(py, self.disc.out_of_range(x)))
+    }
+
+    pub fn predict_report<'py>(&self, py: Python<'py>, x: FloatArray) -> PyResult<&'py PyDict> {
+        let report = with_slice!(x, x => self.disc.predict_report(x)).map_err(py_err)?;
+        let res = PyDict::new(py);
+        res.set_item("n_records", report.n_records)?;
+        res.set_item("exception_ct", report.exception_ct)?;
+        res.set_item("missing_ct", report.missing_ct)?;
+        res.set_item("below_range_ct", report.below_range_ct)?;
+        res.set_item("above_range_ct", report.above_range_ct)?;
+        res.set_item("out_of_range_ct", report.out_of_range_ct())?;
+        Ok(res)
+    }
+
+    pub fn predict_woe<'py>(&self, py: Python<'py>, x: FloatArray) -> PyResult<&'py PyArray1<f64>> {
+        let disc = &self.disc;
+        with_slice!(x, x => pyarray_or_value_error(py, py.allow_threads(|| disc.predict_woe(x))))
+    }
+
+    pub fn predict_woe_multiclass<'py>(
+        &self,
+        py: Python<'py>,
+        x: FloatArray,
+    ) -> PyResult<&'py PyArray2<f64>> {
+        let disc = &self.disc;
+        let classes = with_slice!(x, x => py.allow_threads(|| disc.predict_woe_multiclass(x)))
+            .map_err(py_err)?;
+        // A row for each record, with a column for each class.
+        let n_records = classes.first().map_or(0, |c| c.len());
+        let rows: Vec<Vec<f64>> = (0..n_records)
+            .map(|i| classes.iter().map(|c| c[i]).collect())
+            .collect();
+        if rows.is_empty() {
+            return Ok(PyArray2::zeros(py, [0, classes.len()], false));
+        }
+        PyArray2::from_vec2(py, &rows).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    pub fn predict_idx<'py>(&self, py: Python<'py>, x: FloatArray) -> PyResult<&'py PyArray1<i64>> {
+        let disc = &self.disc;
+        with_slice!(x, x => pyarray_or_value_error(py, py.allow_threads(|| disc.predict_idx(x))))
+    }
+
+    pub fn predict_bounds<'py>(
+        &self,
+        py: Python<'py>,
+        x: FloatArray,
+    ) -> PyResult<(&'py PyArray1<f64>, &'py PyArray1<f64>)> {
+        let disc = &self.disc;
+        let (lower, upper) =
+            with_slice!(x, x => py.allow_threads(|| disc.predict_bounds(x))).map_err(py_err)?;
+        Ok((lower.into_pyarray(py), upper.into_pyarray(py)))
+    }
+
+    pub fn predict_code_u16<'py>(
+        &self,
+        py: Python<'py>,
+        x: FloatArray,
+    ) -> PyResult<(&'py PyArray1<u16>, &'py PyArray1<f64>)> {
+        let disc = &self.disc;
+        let coded =
+            with_slice!(x, x => py.allow_threads(|| disc.predict_code_u16(x))).map_err(py_err)?;
+        Ok((coded.codes.into_pyarray(py), coded.woe.into_pyarray(py)))
+    }
+
+    pub fn transform_onehot<'py>(
+        &self,
+        py: Python<'py>,
+        x: FloatArray,
+    ) -> PyResult<&'py PyArray2<u8>> {
+        let rows = with_slice!(x, x => self.disc.transform_onehot(x)).map_err(py_err)?;
+        let n_columns = self.disc.onehot_labels().map_err(py_err)?.len();
+        // An empty input has no rows to infer the columns from.
+        if rows.is_empty() {
+            return Ok(PyArray2::zeros(py, [0, n_columns], false));
+        }
+        PyArray2::from_vec2(py, &rows).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    pub fn onehot_labels(&self) -> PyResult<Vec<String>> {
+        self.disc.onehot_labels().map_err(py_err)
     }
 }
 
+impl Discretizer {
+    // A statistic of each bin, aligned with the bins of `splits_`,
+    // without the exception values or missing bin.
+    fn bin_column<'py>(
+        &self,
+        py: Python<'py>,
+        column: fn(&BinSummary) -> f64,
+    ) -> PyResult<&'py PyArray1<f64>> {
+        let bins = self.disc.bin_summary().map_err(py_err)?;
+        Ok(bins
+            .iter()
+            .map(column)
+            .collect::<Vec<f64>>()
+            .into_pyarray(py))
+    }
+}
+
+// Several discretizers fit on subsamples of the records,
+// created by the `fit_ensemble` method of the discretizer.
+#[pyclass]
+struct EnsembleDiscretizer {
+    ensemble: CrateEnsembleDiscretizer,
+}
+
+#[pymethods]
+impl EnsembleDiscretizer {
+    pub fn predict_woe<'py>(&self, py: Python<'py>, x: F64Array) -> PyResult<&'py PyArray1<f64>> {
+        let x = x.as_slice()?;
+        let ensemble = &self.ensemble;
+        pyarray_or_value_error(py, py.allow_threads(|| ensemble.predict_woe(x)))
+    }
+
+    pub fn split_frequencies(&self) -> Vec<(f64, f64)> {
+        self.ensemble.split_frequencies()
+    }
+
+    pub fn consensus_splits(&self, min_share: f64) -> Vec<f64> {
+        self.ensemble.consensus_splits(min_share)
+    }
+}
+
+// A discretizer for each column of a 2-D array, fit
+// against the same binary performance.
+#[pyclass(subclass)]
+struct DiscretizerSet {
+    set: CrateDiscretizerSet,
+}
+
+#[pymethods]
+impl DiscretizerSet {
+    #[new]
+    fn new(template: PyRef<Discretizer>, names: Vec<String>) -> Self {
+        DiscretizerSet {
+            set: CrateDiscretizerSet::new(&template.disc, names),
+        }
+    }
+
+    #[getter]
+    pub fn names_(&self) -> Vec<String> {
+        self.set.names.to_vec()
+    }
+
+    #[getter]
+    pub fn splits_(&self) -> HashMap<String, Vec<f64>> {
+        self.set
+            .names
+            .iter()
+            .cloned()
+            .zip(self.set.discretizers.iter().map(|d| d.splits_.to_vec()))
+            .collect()
+    }
+
+    pub fn fit(
+        &mut self,
+        py: Python,
+        x: PyReadonlyArray2<f64>,
+        y: F64Array,
+        w: Option<F64Array>,
+        exception_values: Option<Vec<f64>>,
+    ) -> PyResult<()> {
+        let columns = array_columns(&x);
+        let columns: Vec<&[f64]> = columns.iter().map(|c| c.as_slice()).collect();
+        let y = y.as_slice()?;
+        let w_ = match w {
+            Some(v) => v.to_vec()?,
+            None => vec![1.0; y.len()],
+        };
+        let set = &mut self.set;
+        py.allow_threads(|| set.fit(&columns, y, &w_, exception_values))
+            .map(|_| ())
+            .map_err(py_err)
+    }
+
+    pub fn predict_woe_matrix<'py>(
+        &self,
+        py: Python<'py>,
+        x: PyReadonlyArray2<f64>,
+    ) -> PyResult<&'py PyArray2<f64>> {
+        let columns = array_columns(&x);
+        let columns: Vec<&[f64]> = columns.iter().map(|c| c.as_slice()).collect();
+        let set = &self.set;
+        let rows = py
+            .allow_threads(|| set.predict_woe_matrix(&columns))
+            .map_err(py_err)?;
+        if rows.is_empty() {
+            return Ok(PyArray2::zeros(py, [0, columns.len()], false));
+        }
+        PyArray2::from_vec2(py, &rows).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    pub fn predict_idx_matrix<'py>(
+        &self,
+        py: Python<'py>,
+        x: PyReadonlyArray2<f64>,
+    ) -> PyResult<&'py PyArray2<i64>> {
+        let columns = array_columns(&x);
+        let columns: Vec<&[f64]> = columns.iter().map(|c| c.as_slice()).collect();
+        let set = &self.set;
+        let rows = py
+            .allow_threads(|| set.predict_idx_matrix(&columns))
+            .map_err(py_err)?;
+        if rows.is_empty() {
+            return Ok(PyArray2::zeros(py, [0, columns.len()], false));
+        }
+        PyArray2::from_vec2(py, &rows).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    #[getter]
+    pub fn n_threads(&self) -> Option<usize> {
+        self.set.n_threads
+    }
+
+    #[setter]
+    pub fn set_n_threads(&mut self, value: Option<usize>) {
+        self.set.n_threads = value;
+    }
+}
+
+// The name, total information value, number of bins, and trend
+// of each column, ranked by the information value.
+#[pyfunction]
+fn rank_features(
+    py: Python,
+    names: Vec<String>,
+    x: PyReadonlyArray2<f64>,
+    y: F64Array,
+    w: Option<F64Array>,
+    template: PyRef<Discretizer>,
+) -> PyResult<Vec<(String, f64, usize, String)>> {
+    let columns = array_columns(&x);
+    if names.len() != columns.len() {
+        return Err(PyValueError::new_err(format!(
+            "{} names were provided for {} columns",
+            names.len(),
+            columns.len()
+        )));
+    }
+    let columns: Vec<(&str, &[f64])> = names
+        .iter()
+        .map(|n| n.as_str())
+        .zip(columns.iter().map(|c| c.as_slice()))
+        .collect();
+    let y = y.as_slice()?;
+    let w_ = w.map(|v| v.to_vec()).transpose()?;
+    let config = &template.disc;
+    let ranks = py
+        .allow_threads(|| crate_rank_features(&columns, y, w_.as_deref(), config))
+        .map_err(py_err)?;
+    Ok(ranks
+        .into_iter()
+        .map(|r| {
+            let mono = format!("{:?}", r.mono).to_lowercase();
+            (r.name, r.total_iv, r.n_bins, mono)
+        })
+        .collect())
+}
+
 #[pymodule]
-fn discrust(_py: Python, m: &PyModule) -> PyResult<()> {
+fn discrust(py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<Discretizer>()?;
+    m.add_class::<EnsembleDiscretizer>()?;
+    m.add_class::<DiscretizerSet>()?;
+    m.add_function(wrap_pyfunction!(rank_features, m)?)?;
+    m.add("NotFittedError", py.get_type::<NotFittedError>())?;
+    m.add("PredictionError", py.get_type::<PredictionError>())?;
     Ok(())
 }
 
+create_exception!(
+    discrust,
+    NotFittedError,
+    PyValueError,
+    "Raised when a method that requires a fitted discretizer is called before it is fit."
+);
+create_exception!(
+    discrust,
+    PredictionError,
+    PyValueError,
+    "Raised when a value can not be assigned to a bin, such as a missing value, or category, not seen when fitting."
+);
+
+// The Python exception raised for an error of the core crate. The
+// exceptions are subclasses of ValueError, which every error was
+// raised as before, so existing handlers still catch them.
+fn py_err(e: DiscrustError) -> PyErr {
+    match e {
+        DiscrustError::NotFitted | DiscrustError::NoPartialFit => {
+            NotFittedError::new_err(e.to_string())
+        }
+        DiscrustError::Prediction
+        | DiscrustError::UnseenMissing(_)
+        | DiscrustError::UnknownCategory(_)
+        | DiscrustError::UnknownSegment(_) => PredictionError::new_err(e.to_string()),
+        _ => PyValueError::new_err(e.to_string()),
+    }
+}
+
+fn export_output(output: &str) -> PyResult<ExportOutput> {
+    match output {
+        "woe" => Ok(ExportOutput::Woe),
+        "index" => Ok(ExportOutput::Index),
+        "label" => Ok(ExportOutput::Label),
+        _ => Err(PyValueError::new_err(format!(
+            "The parameter `output` must be one of 'index', 'label' or 'woe', but {} was passed.",
+            output
+        ))),
+    }
+}
+
+fn monotonic_trend(mono: Option<&PyAny>) -> PyResult<MonotonicTrend> {
+    let mono = match mono {
+        None => return Ok(MonotonicTrend::Auto),
+        Some(m) => m,
+    };
+    match (mono.extract::<&str>(), mono.extract::<i8>()) {
+        (Ok("ascending"), _) => Ok(MonotonicTrend::Ascending),
+        (Ok("descending"), _) => Ok(MonotonicTrend::Descending),
+        (Ok("auto"), _) => Ok(MonotonicTrend::Auto),
+        (Ok("peak"), _) => Ok(MonotonicTrend::Peak),
+        (Ok("valley"), _) => Ok(MonotonicTrend::Valley),
+        (_, Ok(m)) => Ok(MonotonicTrend::from(Some(m))),
+        _ => Err(PyValueError::new_err(format!(
+            "The parameter `mono` must be one of -1, 0, 1, 'ascending', 'descending', 'auto', 'peak' or 'valley', but {} was passed.",
+            mono
+        ))),
+    }
+}
+
+// Each column of a 2-D array, copied into its own vector.
+fn array_columns(x: &PyReadonlyArray2<f64>) -> Vec<Vec<f64>> {
+    x.as_array()
+        .columns()
+        .into_iter()
+        .map(|c| c.to_vec())
+        .collect()
+}
+
 fn pyarray_or_value_error<'py, T: Element>(
     py: Python<'py>,
     preds: Result<Vec<T>, DiscrustError>,
@@ -120,10 +1346,31 @@ fn pyarray_or_value_error<'py, T: Element>(
     // I didn't want the underlying discrust_core crate to depend on
     // pyO3, so have to deal with the error custom here.
     match preds {
-        Ok(v) => {
-            let arr = v.into_pyarray(py);
-            return Ok(arr);
+        Ok(v) => Ok(v.into_pyarray(py)),
+        Err(e) => Err(py_err(e)),
+    }
+}
+
+// A node of the fitted tree as a dictionary, with its
+// children nested under the `left` and `right` keys.
+fn tree_dict<'py>(py: Python<'py>, node: &TreeNode) -> PyResult<&'py PyDict> {
+    let res = PyDict::new(py);
+    res.set_item("depth", node.depth)?;
+    res.set_item("lower", node.lower)?;
+    res.set_item("upper", node.upper)?;
+    res.set_item("split", node.split)?;
+    res.set_item("kept", node.kept)?;
+    res.set_item("totals_ct", node.totals_ct)?;
+    res.set_item("ones_ct", node.ones_ct)?;
+    res.set_item("zero_ct", node.zero_ct)?;
+    res.set_item("woe", node.woe)?;
+    res.set_item("iv", node.iv)?;
+    res.set_item("stop_reason", node.stop_reason.map(|r| r.to_string()))?;
+    for (key, child) in [("left", &node.left), ("right", &node.right)] {
+        match child {
+            Some(c) => res.set_item(key, tree_dict(py, c)?)?,
+            None => res.set_item(key, py.None())?,
         }
-        Err(e) => return Err(PyValueError::new_err(e.to_string())),
-    };
+    }
+    Ok(res)
 }
@@ -1,5 +1,7 @@
-from discrust import Discretizer
+from discrust import Discretizer, NotFittedError, PredictionError
+import pickle
 import numpy as np
+import pytest
 
 
 def test_Discretizer_fit(titanic):
@@ -38,3 +40,814 @@ def test_Discretizer_fit_mono_positive(titanic):
     ).to_list()
 
     assert all([i <= j for i, j in zip(avg_bad, avg_bad[1:])])
+
+
+def test_Discretizer_min_effective_obs_unit_weights(titanic):
+    # With unit weights the effective sample size is the record count.
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    ds.fit(titanic["fare"], titanic["survived"])
+    ds_eff = Discretizer(min_obs=0, min_pos=1.0, min_effective_obs=5)
+    ds_eff.fit(titanic["fare"], titanic["survived"])
+    assert ds.splits_ == ds_eff.splits_
+
+
+def test_Discretizer_min_effective_obs_variable_weights(titanic):
+    # With variable weights the effective sample size of a bin is
+    # smaller than its weighted count.
+    w = np.where(titanic["survived"] == 1, 5.0, 1.0)
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    ds.fit(titanic["fare"], titanic["survived"], sample_weight=w)
+    summary = ds.bin_summary()
+    assert (summary["effective_ct"] < summary["totals_ct"]).all()
+    ds_eff = Discretizer(min_obs=5, min_pos=1.0, min_effective_obs=100)
+    ds_eff.fit(titanic["fare"], titanic["survived"], sample_weight=w)
+    assert ds.splits_ != ds_eff.splits_
+    assert (ds_eff.bin_summary()["effective_ct"] >= 100).all()
+
+
+def test_Discretizer_feature_stats(titanic):
+    ds = Discretizer(min_obs=5, max_bins=10, min_iv=0.001, min_pos=1.0, mono=None)
+    ds.fit(titanic["fare"], titanic["survived"])
+    stats = ds.feature_stats_
+    assert stats["vals_"] == sorted(titanic["fare"].unique())
+    assert stats["cuml_totals_ct_"][-1] == titanic.shape[0]
+    assert stats["total_ones_"] == titanic["survived"].sum()
+
+
+def test_Discretizer_add_exception_value(titanic):
+    ds = Discretizer(min_obs=5, max_bins=10, min_iv=0.001, min_pos=1.0, mono=None)
+    splits = ds.fit(titanic["fare"], titanic["survived"], exception_values=[-1])
+    ds.add_exception_value(0.0)
+    assert ds.splits_ == splits
+    assert ds.exception_values_["vals_"] == [-1.0, 0.0]
+    assert ds.exception_values_["totals_ct_"][1] == (titanic["fare"] == 0).sum()
+    assert ds.predict(np.array([0.0]), prediction_type="index")[0] == -2
+
+
+def test_Discretizer_predict_label(titanic):
+    ds = Discretizer(min_obs=5, max_bins=10, min_iv=0.001, min_pos=1.0, mono=None)
+    ds.fit(titanic["fare"], titanic["survived"], exception_values=[np.nan])
+    ds.set_label_format(decimals=1, brackets="]]", missing_label="None")
+    labels = ds.predict(np.array([0.0, np.nan]), prediction_type="label")
+    assert list(labels) == [f"]-inf, {ds.splits_[1]:.1f}]", "None"]
+
+
+def test_Discretizer_midpoint_splits(titanic):
+    ds = Discretizer(min_obs=5, max_bins=10, min_iv=0.001, min_pos=1.0, mono=None)
+    ds.fit(titanic["fare"], titanic["survived"])
+    ds_mid = Discretizer(
+        min_obs=5, max_bins=10, min_iv=0.001, min_pos=1.0, mono=None, split_point="midpoint"
+    )
+    ds_mid.fit(titanic["fare"], titanic["survived"])
+    ds_mid.validate_splits()
+    assert all(o < m for o, m in zip(ds.splits_[1:-1], ds_mid.splits_[1:-1]))
+    idx = ds.predict(titanic["fare"], prediction_type="index")
+    idx_mid = ds_mid.predict(titanic["fare"], prediction_type="index")
+    assert (idx == idx_mid).all()
+
+
+def test_Discretizer_fit_ensemble(titanic):
+    ds = Discretizer(min_obs=5, max_bins=10, min_iv=0.001, min_pos=1.0, mono=None)
+    ensemble = ds.fit_ensemble(titanic["fare"], titanic["survived"], n_models=4, seed=1)
+    consensus = ensemble.consensus_splits(0.5)
+    assert consensus[0] == -np.inf
+    assert consensus[-1] == np.inf
+    assert all(0 < s <= 1 for _, s in ensemble.split_frequencies())
+    woe = ensemble.predict_woe(titanic["fare"].to_numpy())
+    assert woe.shape[0] == titanic.shape[0]
+
+
+def test_Discretizer_fit_categorical():
+    x = ["a", "a", "a", "b", "b", "b", "c", "c", "c", "d", "d", "d"]
+    y = np.array([1.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0])
+    ds = Discretizer(min_obs=1, min_pos=1)
+    ds.fit_categorical(x, y)
+    assert ds.category_bins_ == [["b", "d"], ["a", "c"]]
+    idx = ds.predict_categorical(["a", "b"], prediction_type="index")
+    assert list(idx) == [1, 0]
+
+
+def test_DiscretizerSet(titanic):
+    from discrust import DiscretizerSet
+
+    template = Discretizer(min_obs=5, max_bins=10, min_iv=0.001, min_pos=1.0, mono=None)
+    x = titanic[["fare", "fare"]].to_numpy()
+    ds_set = DiscretizerSet(template, ["a", "b"])
+    ds_set.fit(x, titanic["survived"])
+    assert ds_set.splits_["a"] == ds_set.splits_["b"]
+    woe = ds_set.predict_woe_matrix(x)
+    assert woe.shape == x.shape
+    assert (woe[:, 0] == woe[:, 1]).all()
+    assert ds_set.n_threads is None
+    idx = ds_set.predict_idx_matrix(x)
+    assert idx.shape == x.shape
+    template.fit(titanic["fare"], titanic["survived"])
+    assert (idx[:, 0] == template.predict(titanic["fare"], "index")).all()
+    ds_set.n_threads = 2
+    assert (ds_set.predict_idx_matrix(x) == idx).all()
+    assert (ds_set.predict_woe_matrix(x) == woe).all()
+
+
+def test_Discretizer_simulate_shift(titanic):
+    ds = Discretizer(min_obs=5, max_bins=10, min_iv=0.001, min_pos=1.0, mono=None)
+    ds.fit(titanic["fare"], titanic["survived"])
+    sim = ds.simulate_shift([1.0] * (len(ds.splits_) - 1))
+    assert abs(sim["reference_event_rate"] - titanic["survived"].mean()) < 1e-10
+    assert sim["shifted_event_rate"] == sim["reference_event_rate"]
+
+
+def test_Discretizer_input_spec(titanic):
+    ds = Discretizer(min_obs=5, max_bins=10, min_iv=0.001, min_pos=1.0, mono=None)
+    ds.set_input_spec("bookings", min_value=0, special_codes={"not recorded": -1})
+    ds.fit(titanic["fare"], titanic["survived"])
+    assert ds.input_spec_violations(np.array([0.0, -1.0])) == []
+    violations = ds.input_spec_violations(np.array([-5.0, -2.0, 10.0]))
+    assert violations == [{"violation": "below_min", "limit": 0.0, "count": 2, "value": -5.0}]
+    with pytest.raises(ValueError):
+        ds.predict(np.array([-5.0]))
+    ds.clear_input_spec()
+    ds.predict(np.array([-5.0]))
+
+
+def test_Discretizer_predict_code_u16(titanic):
+    ds = Discretizer(min_obs=5, max_bins=10, min_iv=0.001, min_pos=1.0, mono=None)
+    ds.fit(titanic["fare"], titanic["survived"])
+    codes, table = ds.predict_code_u16(titanic["fare"])
+    assert codes.dtype == np.uint16
+    assert len(table) == len(ds.splits_)
+    assert np.allclose(table[codes], ds.predict(titanic["fare"]))
+
+
+def test_Discretizer_bin_summary(titanic):
+    ds = Discretizer(min_obs=5, max_bins=10, min_iv=0.001, min_pos=1.0, mono=None)
+    ds.fit(titanic["age"], titanic["survived"], exception_values=[np.nan])
+    summary = ds.bin_summary()
+    assert len(summary) == len(ds.splits_)
+    assert summary["exception"].sum() == 1
+    assert summary["totals_ct"].sum() == len(titanic)
+    assert np.allclose(summary["event_rate"], summary["ones_ct"] / summary["totals_ct"])
+
+
+def test_Discretizer_special_missing(titanic):
+    ds = Discretizer(min_obs=5, max_bins=10, min_iv=0.001, min_pos=1.0, mono=None)
+    ds.fit(titanic["age"], titanic["survived"])
+    summary = ds.bin_summary()
+    assert summary["missing"].sum() == 1
+    assert summary["exception"].sum() == 0
+    assert summary["totals_ct"].sum() == len(titanic)
+    missing = summary.query("missing")
+    assert missing["totals_ct"].iloc[0] == titanic["age"].isna().sum()
+    assert ds.predict(np.array([np.nan]), prediction_type="index")[0] == -1
+    assert ds.predict(np.array([np.nan]))[0] == missing["woe"].iloc[0]
+    ds = Discretizer(min_obs=5, min_pos=1.0, special_missing=False)
+    n_missing = titanic["age"].isna().sum()
+    first = int(np.flatnonzero(titanic["age"].isna())[0])
+    with pytest.raises(
+        ValueError, match=f"contains {n_missing} NaN values, the first at index {first}"
+    ):
+        ds.fit(titanic["age"], titanic["survived"])
+
+
+def test_Discretizer_predict_float32(titanic):
+    ds = Discretizer(min_obs=5, max_bins=10, min_iv=0.001, min_pos=1.0, mono=None)
+    ds.fit(titanic["fare"], titanic["survived"])
+    fare32 = titanic["fare"].to_numpy().astype(np.float32)
+    expected = ds.predict(fare32.astype(np.float64), prediction_type="index")
+    assert (ds.predict(fare32, prediction_type="index") == expected).all()
+    assert ds.predict(fare32).dtype == np.float64
+    fare_int = titanic["fare"].astype(np.int32)
+    assert (ds.predict(fare_int) == ds.predict(fare_int.astype(np.float64))).all()
+
+
+def test_Discretizer_partial_fit(titanic):
+    ds = Discretizer(min_obs=5, max_bins=10, min_iv=0.001, min_pos=1.0, mono=None)
+    ds.fit(titanic["fare"], titanic["survived"])
+    ds_batched = Discretizer(min_obs=5, max_bins=10, min_iv=0.001, min_pos=1.0, mono=None)
+    for start in range(0, len(titanic), 200):
+        batch = titanic.iloc[start : start + 200]
+        ds_batched.partial_fit(batch["fare"], batch["survived"])
+    ds_batched.finalize()
+    assert ds_batched.splits_ == ds.splits_
+    with pytest.raises(ValueError):
+        ds_batched.finalize()
+
+
+def test_Discretizer_metadata(titanic):
+    import json
+
+    ds = Discretizer(min_obs=5, min_pos=1.0, tags={"model": "survival"})
+    ds.fit(titanic["fare"], titanic["survived"])
+    metadata = ds.metadata()
+    assert metadata["n_records"] == len(titanic)
+    assert abs(metadata["event_rate"] - titanic["survived"].mean()) < 1e-10
+    assert metadata["tags"] == {"model": "survival"}
+    document = json.loads(ds.to_json_logic("fare", include_metadata=True))
+    assert document["metadata"]["parameters"]["min_obs"] == "5"
+    assert "if" in document["json_logic"]
+
+
+def test_Discretizer_on_missing(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0, on_missing=0.5)
+    ds.fit(titanic["fare"], titanic["survived"])
+    assert ds.predict(np.array([np.nan]))[0] == 0.5
+    assert ds.predict(np.array([np.nan]), prediction_type="index")[0] == -1
+    ds = Discretizer(min_obs=5, min_pos=1.0, on_missing="error")
+    ds.fit(titanic["fare"], titanic["survived"])
+    with pytest.raises(PredictionError):
+        ds.predict(np.array([np.nan]))
+
+
+def test_Discretizer_exception_types(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    with pytest.raises(NotFittedError):
+        ds.predict(titanic["fare"])
+    with pytest.raises(NotFittedError):
+        ds.exception_values_
+    # The exceptions are still caught as a ValueError.
+    assert issubclass(NotFittedError, ValueError)
+    assert issubclass(PredictionError, ValueError)
+    with pytest.raises(ValueError):
+        ds.bin_summary()
+
+
+def test_Discretizer_unsupervised_strategies(titanic):
+    ds = Discretizer(strategy="quantile", n_bins=4)
+    ds.fit(titanic["fare"], titanic["survived"])
+    assert len(ds.splits_) <= 5
+    counts = ds.bin_summary()["totals_ct"]
+    assert counts.max() - counts.min() < len(titanic) / 4
+    ds = Discretizer(strategy="uniform", n_bins=5)
+    ds.fit(titanic["fare"], titanic["survived"])
+    assert len(ds.splits_) == 6
+    with pytest.raises(ValueError):
+        Discretizer(strategy="quantile")
+    with pytest.raises(ValueError):
+        Discretizer(n_bins=4)
+
+
+def test_Discretizer_woe_regularization(titanic):
+    ds = Discretizer(min_obs=1, min_pos=0.0, woe_regularization=0.5)
+    ds.fit(titanic["fare"], titanic["survived"])
+    assert np.isfinite(ds.predict(titanic["fare"])).all()
+    assert np.isfinite(ds.bin_summary()["woe"]).all()
+
+
+def test_Discretizer_mono_peak():
+    x = np.repeat(np.arange(60, dtype=float), 10)
+    rate = 0.1 + 0.8 * (1 - np.abs(x - 30) / 30)
+    y = ((np.arange(len(x)) % 10) < 10 * rate).astype(float)
+    ds = Discretizer(min_obs=20, min_pos=1.0, mono="peak")
+    ds.fit(x, y)
+    woe = ds.bin_summary()["woe"].values
+    rising = np.diff(woe) > 0
+    assert (np.diff(rising.astype(int)) != 0).sum() == 1
+    with pytest.raises(ValueError):
+        Discretizer(mono="sideways")
+
+
+def test_Discretizer_enforce_monotonicity(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0, mono=1, enforce_monotonicity=True)
+    ds.fit(titanic["fare"], titanic["survived"])
+    woe = ds.bin_summary().query("~exception")["woe"].values
+    assert (np.diff(woe) > 0).all()
+
+
+def test_Discretizer_max_pvalue(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    ds.fit(titanic["fare"], titanic["survived"])
+    ds_p = Discretizer(min_obs=5, min_pos=1.0, max_pvalue=0.01)
+    ds_p.fit(titanic["fare"], titanic["survived"])
+    assert len(ds_p.splits_) < len(ds.splits_)
+
+
+def test_Discretizer_cv_folds(titanic):
+    ds = Discretizer(min_obs=1, max_bins=50, min_iv=0, min_pos=0)
+    ds.fit(titanic["fare"], titanic["survived"])
+    ds_cv = Discretizer(min_obs=1, max_bins=50, min_iv=0, min_pos=0, cv_folds=5)
+    ds_cv.fit(titanic["fare"], titanic["survived"])
+    assert len(ds_cv.splits_) < len(ds.splits_)
+    with pytest.raises(ValueError):
+        Discretizer(cv_folds=5, cv_agreement=2.0).fit(titanic["fare"], titanic["survived"])
+
+
+def test_Discretizer_min_rates(titanic):
+    ds = Discretizer(min_obs=1, min_pos=1.0, min_obs_rate=0.1, min_pos_rate=0.02)
+    ds.fit(titanic["fare"], titanic["survived"])
+    summary = ds.bin_summary()
+    assert (summary["totals_ct"] >= 0.1 * len(titanic)).all()
+    assert (summary["ones_ct"] >= 0.02 * len(titanic)).all()
+
+
+def test_Discretizer_min_pos_auto(titanic):
+    ds = Discretizer(min_obs=50, min_pos="auto")
+    ds.fit(titanic["fare"], titanic["survived"])
+    min_pos = 50 * titanic["survived"].mean()
+    assert (ds.bin_summary()["ones_ct"] >= min_pos).all()
+    with pytest.raises(ValueError):
+        Discretizer(min_pos="all")
+
+
+def test_Discretizer_transform_onehot(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    ds.fit(titanic["fare"], titanic["survived"], exception_values=[-1])
+    onehot = ds.transform_onehot(titanic["fare"])
+    assert onehot.shape == (len(titanic), len(ds.splits_))
+    assert (onehot.sum(axis=1) == 1).all()
+    idx = ds.predict(titanic["fare"], prediction_type="index")
+    assert (onehot.values.argmax(axis=1) == idx).all()
+
+
+def test_Discretizer_bin_woe_(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    ds.fit(titanic["fare"], titanic["survived"], exception_values=[-1])
+    idx = ds.predict(titanic["fare"], prediction_type="index")
+    woe = np.array(ds.bin_woe_)[np.where(idx < 0, len(ds.splits_) - 2 - idx, idx)]
+    assert np.allclose(woe, ds.predict(titanic["fare"]))
+    assert len(ds.bin_iv_) == len(ds.bin_woe_) == len(ds.splits_)
+    assert np.isclose(ds.total_iv_, sum(ds.bin_iv_))
+    assert 0 < ds.ks_ <= 1
+    assert 0 < ds.gini_ <= 1
+
+
+def test_Discretizer_continuous_target(titanic):
+    df = titanic[titanic["age"].notna()]
+    ds = Discretizer(min_obs=5, min_iv=0.001, target_type="continuous")
+    ds.fit(df["age"], df["fare"])
+    summary = ds.bin_summary()
+    assert np.allclose(summary["woe"], summary["mean"])
+    assert np.isclose(ds.predict(df["age"]).mean(), df["fare"].mean())
+    assert 0 < ds.total_iv_ < 1
+    with pytest.raises(ValueError, match="binary"):
+        ds.ks_
+    with pytest.raises(ValueError, match="target_type"):
+        Discretizer(target_type="count")
+
+
+def test_Discretizer_multiclass_target(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0, target_type="multiclass")
+    ds.fit(titanic["fare"], titanic["pclass"] - 1)
+    woe = ds.predict_woe_multiclass(titanic["fare"])
+    assert woe.shape == (len(titanic), 3)
+    assert ds.total_iv_ > 0
+    with pytest.raises(ValueError, match="predict_woe_multiclass"):
+        ds.predict(titanic["fare"])
+
+
+def test_Discretizer_exception_labels_and_order(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    ds.fit(titanic["age"], titanic["survived"], exception_values=[np.nan, 1.0])
+    ds.set_exception_label(np.nan, "Missing age")
+    ds.set_exception_order([1.0, np.nan])
+    assert ds.exception_values_["vals_"][0] == 1.0
+    assert ds.exception_values_["labels_"] == [None, "Missing age"]
+    assert list(ds.predict(np.array([1.0, np.nan]), prediction_type="index")) == [-1, -2]
+    summary = ds.bin_summary()
+    assert summary["label"].iloc[-1] == "Missing age"
+    with pytest.raises(ValueError):
+        ds.set_exception_order([1.0])
+
+
+def test_Discretizer_merge_exceptions(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0, merge_exceptions=True)
+    ds.fit(titanic["age"], titanic["survived"], exception_values=[np.nan, -1.0])
+    # Missing ages are merged into a bin, -1.0 has no records, so it is kept.
+    assert list(ds.exception_values_["vals_"]) == [-1.0]
+    idx = ds.predict(np.array([np.nan]), prediction_type="index")
+    assert idx[0] >= 0
+    assert ds.bin_summary()["totals_ct"].sum() == len(titanic)
+
+
+def test_Discretizer_sklearn_api(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    assert ds.get_params()["min_obs"] == 5
+    ds.set_params(max_bins=3, mono=1)
+    assert ds.get_params()["max_bins"] == 3
+    woe = ds.fit_transform(titanic[["fare"]], titanic["survived"])
+    assert woe.shape == (len(titanic), 1)
+    assert len(ds.splits_) <= 4
+    assert np.allclose(woe[:, 0], ds.predict(titanic["fare"]))
+    with pytest.raises(ValueError):
+        ds.set_params(not_a_parameter=1)
+    with pytest.raises(ValueError, match="single column"):
+        ds.fit(titanic[["fare", "age"]], titanic["survived"])
+
+
+def test_Discretizer_sklearn_pipeline(titanic):
+    pytest.importorskip("sklearn")
+    from sklearn.base import clone
+    from sklearn.compose import ColumnTransformer
+    from sklearn.linear_model import LogisticRegression
+    from sklearn.pipeline import Pipeline
+
+    ds = clone(Discretizer(min_obs=5, min_pos=1.0, max_bins=4))
+    assert ds.get_params()["max_bins"] == 4
+    df = titanic[["fare", "age"]].fillna(-1)
+    pipe = Pipeline(
+        [
+            (
+                "woe",
+                ColumnTransformer(
+                    [("fare", ds, ["fare"]), ("age", clone(ds), ["age"])]
+                ),
+            ),
+            ("model", LogisticRegression()),
+        ]
+    )
+    pipe.fit(df, titanic["survived"])
+    assert pipe.predict_proba(df).shape == (len(df), 2)
+
+
+def test_Discretizer_pickle(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0, max_bins=5)
+    ds.fit(titanic["age"], titanic["survived"], exception_values=[np.nan])
+    ds.set_exception_label(np.nan, "Missing age")
+    loaded = pickle.loads(pickle.dumps(ds))
+    assert isinstance(loaded, Discretizer)
+    assert loaded.splits_ == ds.splits_
+    assert loaded.get_params() == ds.get_params()
+    assert np.allclose(loaded.predict(titanic["age"]), ds.predict(titanic["age"]))
+    assert list(loaded.predict(np.array([np.nan]), prediction_type="label")) == ["Missing age"]
+    # An unfitted discretizer keeps its parameters.
+    assert pickle.loads(pickle.dumps(Discretizer(max_bins=3))).get_params()["max_bins"] == 3
+
+
+def test_Discretizer_input_types(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    ds.fit(titanic["fare"], titanic["survived"])
+    splits = ds.splits_
+    # Integer performance, lists, and nullable Series give the same splits.
+    ds.fit(list(titanic["fare"]), titanic["survived"].astype(np.int64))
+    assert ds.splits_ == splits
+    ds.fit(titanic["fare"].astype("Float64"), list(titanic["survived"]))
+    assert ds.splits_ == splits
+    assert np.allclose(ds.predict([1, 2, 3]), ds.predict(np.array([1.0, 2.0, 3.0])))
+    age = titanic["age"].astype("Float64")
+    ds.fit(age, titanic["survived"], exception_values=[np.nan])
+    assert ds.exception_values_["totals_ct_"][0] == titanic["age"].isna().sum()
+
+
+def test_Discretizer_invalid_records(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    with pytest.raises(ValueError, match="No records"):
+        ds.fit(np.array([]), np.array([]))
+    with pytest.raises(ValueError, match="y column"):
+        ds.fit(titanic["fare"], titanic["survived"][1:])
+    with pytest.raises(ValueError, match="Invalid weight"):
+        ds.fit(titanic["fare"], titanic["survived"], sample_weight=-np.ones(len(titanic)))
+    with pytest.raises(ValueError, match="not 0 or 1"):
+        ds.fit(titanic["fare"], titanic["pclass"])
+
+
+def test_Discretizer_tree(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    ds.fit(titanic["fare"], titanic["survived"])
+    tree = ds.tree()
+    assert tree["depth"] == 0
+    assert tree["totals_ct"] == titanic["fare"].notna().sum()
+
+    def splits(node):
+        if node is None or node["split"] is None:
+            return []
+        return [node["split"]] + splits(node["left"]) + splits(node["right"])
+
+    assert sorted(splits(tree)) == ds.splits_[1:-1]
+
+
+def test_Discretizer_to_sql(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    ds.fit(titanic["fare"], titanic["survived"])
+    sql = ds.to_sql("fare", dialect="spark", output="index")
+    assert sql.startswith("CASE WHEN (fare IS NULL OR isnan(fare)) THEN")
+    assert sql.endswith(f"ELSE {len(ds.splits_) - 2} END")
+    with pytest.raises(ValueError, match="dialect"):
+        ds.to_sql("fare", dialect="oracle")
+
+
+def test_Discretizer_to_pmml(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    ds.fit(titanic["fare"], titanic["survived"])
+    pmml = ds.to_pmml("fare", "fare_idx", output="index")
+    assert pmml.startswith('<DerivedField name="fare_idx" optype="categorical"')
+    assert pmml.count("<DiscretizeBin") == len(ds.splits_) - 1
+
+
+def test_Discretizer_to_onnx(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    ds.fit(titanic["fare"], titanic["survived"])
+    model = ds.to_onnx("fare", "fare_woe")
+    assert isinstance(model, bytes)
+    assert b"LessOrEqual" in model
+    ort = pytest.importorskip("onnxruntime")
+    session = ort.InferenceSession(model)
+    x = titanic["fare"].to_numpy(dtype=np.float64)
+    (woe,) = session.run(None, {"fare": x})
+    np.testing.assert_allclose(woe, ds.predict(titanic["fare"]))
+
+
+def test_rank_features(titanic):
+    from discrust import rank_features
+
+    template = Discretizer(min_obs=5, min_pos=1.0)
+    x = titanic[["fare", "age", "pclass"]]
+    ranks = rank_features(x, titanic["survived"], template=template)
+    assert sorted(ranks["name"]) == ["age", "fare", "pclass"]
+    assert ranks["total_iv"].is_monotonic_decreasing
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    ds.fit(titanic["fare"], titanic["survived"])
+    fare = ranks.set_index("name").loc["fare"]
+    assert fare["total_iv"] == pytest.approx(ds.total_iv_)
+    assert fare["n_bins"] == len(ds.splits_) - 1
+
+
+def test_Discretizer_recompute_stats(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    ds.fit(titanic["fare"], titanic["survived"])
+    splits = ds.splits_
+    half = titanic.iloc[: len(titanic) // 2]
+    summary = ds.recompute_stats(half["fare"], half["survived"]).bin_summary()
+    assert ds.splits_ == splits
+    assert summary["totals_ct"].sum() == len(half)
+
+
+def test_Discretizer_from_splits(titanic):
+    ds = Discretizer.from_splits([10.0, 25.0, 50.0], exception_values=[-1.0])
+    assert ds.get_params()["manual_splits"] == [10.0, 25.0, 50.0]
+    ds.fit_stats(titanic["fare"], titanic["survived"])
+    assert ds.splits_ == [-np.inf, 10.0, 25.0, 50.0, np.inf]
+    assert list(ds.predict_idx(np.array([5.0, 30.0, -1.0]))) == [0, 2, -1]
+    with pytest.raises(ValueError):
+        Discretizer(manual_splits=[1.0])
+
+
+def test_Discretizer_edit_bins(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    ds.fit(titanic["fare"], titanic["survived"])
+    splits = ds.splits_
+    ds.merge_bins(0, 1)
+    assert ds.splits_ == [splits[0]] + splits[2:]
+    ds.split_bin_at(splits[1])
+    assert ds.splits_ == splits
+    assert ds.bin_summary()["totals_ct"].sum() == len(titanic)
+    with pytest.raises(ValueError):
+        ds.split_bin_at(splits[1])
+
+
+def test_Discretizer_compact(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    ds.fit(titanic["fare"], titanic["survived"], exception_values=[-1.0])
+    woe = ds.predict(titanic["fare"])
+    size = len(pickle.dumps(ds))
+    assert ds.compact() is ds
+    assert np.allclose(ds.predict(titanic["fare"]), woe)
+    assert len(pickle.dumps(ds)) < size
+    assert ds.bin_summary()["totals_ct"].sum() == len(titanic)
+    with pytest.raises(ValueError):
+        ds.split_bin_at(20.0)
+
+
+def test_Discretizer_repr_summary(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    assert repr(ds) == "Discretizer(min_pos=1)"
+    ds.fit(titanic["fare"], titanic["survived"])
+    lines = str(ds).splitlines()
+    assert lines[1].split() == ["bin", "count", "event_rate", "woe", "iv"]
+    assert len(lines) == len(ds.splits_) + 1
+    summary = ds.summary()
+    assert isinstance(summary["woe"], list)
+    assert summary["label"] == list(ds.bin_summary()["label"])
+
+
+def test_Discretizer_bin_arrays(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    with pytest.raises(ValueError):
+        ds.woe_
+    ds.fit(titanic["fare"], titanic["survived"], exception_values=[-1.0])
+    summary = ds.bin_summary()
+    bins = summary[~(summary["exception"] | summary["missing"])]
+    assert isinstance(ds.woe_, np.ndarray)
+    assert len(ds.counts_) == len(ds.splits_) - 1
+    assert np.allclose(ds.counts_, bins["totals_ct"])
+    assert np.allclose(ds.ones_ct_, bins["ones_ct"])
+    assert np.allclose(ds.event_rate_, bins["event_rate"])
+    assert np.allclose(ds.woe_, ds.bin_woe_[: len(ds.woe_)])
+    assert np.isclose(ds.iv_.sum(), np.sum(ds.bin_iv_[: len(ds.iv_)]))
+
+
+def test_Discretizer_tie_break():
+    # The values of 1 and 4 mirror each other, so splitting
+    # after 1, or after 3, has the same information value.
+    x = [1, 1, 1, 1, 2, 2, 3, 3, 4, 4, 4, 4]
+    y = [1, 1, 1, 0, 1, 0, 0, 1, 0, 0, 0, 1]
+    splits = {}
+    for tie_break in ["lowest", "highest", "median"]:
+        ds = Discretizer(min_obs=1, max_bins=2, min_iv=0, min_pos=1, tie_break=tie_break)
+        splits[tie_break] = ds.fit(x, y)
+    assert splits["lowest"] == [-np.inf, 1.0, np.inf]
+    assert splits["highest"] == [-np.inf, 3.0, np.inf]
+    assert splits["median"] == splits["lowest"]
+    with pytest.raises(ValueError):
+        Discretizer(tie_break="middle")
+
+
+def test_Discretizer_min_bin_width(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0, min_bin_width=2.0)
+    splits = ds.fit(titanic["fare"], titanic["survived"])
+    assert len(splits) > 2
+    assert np.min(np.diff(splits)) >= 2.0
+    with pytest.raises(ValueError):
+        Discretizer(min_bin_width=-1.0).fit(titanic["fare"], titanic["survived"])
+
+
+def test_Discretizer_max_bin_pct(titanic):
+    ds = Discretizer(min_obs=5, max_bins=20, min_iv=0.2, min_pos=1.0, max_bin_pct=0.3)
+    ds.fit(titanic["fare"], titanic["survived"])
+    bins = ds.bin_summary()
+    bins = bins[~(bins["exception"] | bins["missing"])]
+    assert (bins["totals_ct"] / len(titanic)).max() <= 0.3
+    with pytest.raises(ValueError):
+        Discretizer(max_bin_pct=1.5).fit(titanic["fare"], titanic["survived"])
+
+
+def test_Discretizer_growth(titanic):
+    ivs = {}
+    for growth in ["breadth_first", "depth_first", "best_first"]:
+        ds = Discretizer(
+            min_obs=5, max_bins=5, min_pos=1.0, woe_regularization=0.5, growth=growth
+        )
+        ds.fit(titanic["fare"], titanic["survived"])
+        assert len(ds.splits_) == 6
+        ivs[growth] = ds.iv_.sum()
+    assert ivs["best_first"] > ivs["breadth_first"]
+    with pytest.raises(ValueError):
+        Discretizer(growth="random")
+
+
+def test_Discretizer_fit_report(titanic):
+    ds = Discretizer(min_obs=5, max_bins=5, min_pos=1.0)
+    with pytest.raises(NotFittedError):
+        ds.fit_report()
+    ds.fit(titanic["fare"], titanic["survived"])
+    report = ds.fit_report()
+    assert report["max_bins"] > 0
+    assert sum(report.values()) == len(ds.splits_) - 1
+    root = ds.tree()
+    assert root["stop_reason"] is None
+
+
+def test_Discretizer_verbose(titanic, capsys):
+    ds = Discretizer(min_obs=5, max_bins=5, min_pos=1.0)
+    ds.fit(titanic["fare"], titanic["survived"])
+    assert capsys.readouterr().out == ""
+    assert ds.fit_trace() == []
+    ds = Discretizer(min_obs=5, max_bins=5, min_pos=1.0, verbose=True)
+    ds.fit(titanic["fare"], titanic["survived"])
+    lines = capsys.readouterr().out.splitlines()
+    assert lines == ds.fit_trace()
+    assert "candidates" in lines[0]
+    assert "best split" in lines[0]
+
+
+def test_Discretizer_chaid(titanic):
+    tree = Discretizer(min_obs=5, max_bins=3, min_pos=1.0)
+    tree.fit(titanic["fare"], titanic["survived"])
+    ds = Discretizer(min_obs=5, max_bins=3, min_pos=1.0, strategy="chaid")
+    ds.fit(titanic["fare"], titanic["survived"])
+    assert len(ds.splits_) == 4
+    assert ds.iv_.sum() >= tree.iv_.sum()
+    with pytest.raises(ValueError):
+        Discretizer(max_children=3)
+    with pytest.raises(ValueError):
+        Discretizer(strategy="chaid", n_bins=3)
+
+
+def test_Discretizer_optimal_dp(titanic):
+    tree = Discretizer(min_obs=5, max_bins=5, min_pos=1.0, mono=0, growth="best_first")
+    tree.fit(titanic["fare"], titanic["survived"])
+    ds = Discretizer(
+        min_obs=5, max_bins=5, min_pos=1.0, mono=0, strategy="optimal_dp", n_atoms=500
+    )
+    ds.fit(titanic["fare"], titanic["survived"])
+    assert len(ds.splits_) == 6
+    assert ds.iv_.sum() >= tree.iv_.sum()
+    with pytest.raises(ValueError):
+        Discretizer(n_atoms=100)
+    with pytest.raises(ValueError):
+        Discretizer(strategy="chaid", n_atoms=100)
+
+
+def test_Discretizer_mdlp(titanic):
+    all_bins = Discretizer(min_obs=5, max_bins=100, min_iv=0.0, min_pos=1.0, mono=0)
+    all_bins.fit(titanic["fare"], titanic["survived"])
+    ds = Discretizer(min_obs=5, max_bins=100, min_iv=0.0, min_pos=1.0, mono=0, mdlp=True)
+    ds.fit(titanic["fare"], titanic["survived"])
+    assert 2 < len(ds.splits_) < len(all_bins.splits_)
+    assert ds.fit_report()["mdlp"] > 0
+
+
+def test_Discretizer_min_iv_gain_ratio(titanic):
+    all_bins = Discretizer(min_obs=5, max_bins=100, min_pos=1.0, mono=0)
+    all_bins.fit(titanic["fare"], titanic["survived"])
+    ds = Discretizer(min_obs=5, max_bins=100, min_pos=1.0, mono=0, min_iv_gain_ratio=0.5)
+    ds.fit(titanic["fare"], titanic["survived"])
+    assert 2 < len(ds.splits_) < len(all_bins.splits_)
+    assert ds.fit_report()["min_iv_gain_ratio"] > 0
+    with pytest.raises(ValueError):
+        Discretizer(min_iv_gain_ratio=-1.0).fit(titanic["fare"], titanic["survived"])
+
+
+def test_Discretizer_score_distribution(titanic):
+    d = Discretizer(min_obs=5, max_bins=10, min_iv=0.001, min_pos=1)
+    d.fit(titanic["fare"], titanic["survived"])
+    dist = d.score_distribution(titanic["fare"])
+    assert dist["total_ct"] == titanic.shape[0]
+    assert len(dist["ct"]) == len(d.splits_) - 1
+    assert sum(dist["ct"]) == titanic.shape[0]
+    assert set(d.predict(titanic["fare"], "label")) <= set(dist["label"])
+    assert abs(sum(dist["pct"]) - 1) < 1e-12
+    assert dist["unbinned_ct"] == 0
+
+
+def test_Discretizer_out_of_range(titanic):
+    d = Discretizer(min_obs=5, max_bins=10, min_iv=0.001, min_pos=1)
+    d.fit(titanic["fare"], titanic["survived"])
+    x = np.array([-1.0, titanic["fare"].max(), titanic["fare"].max() + 1])
+    assert d.out_of_range(x).tolist() == [True, False, True]
+    report = d.predict_report(x)
+    assert report["below_range_ct"] == 1
+    assert report["above_range_ct"] == 1
+    assert report["out_of_range_ct"] == 2
+    assert d.score_distribution(titanic["fare"])["out_of_range_ct"] == 0
+
+
+def test_Discretizer_fit_threads(titanic):
+    from concurrent.futures import ThreadPoolExecutor
+
+    def fit(min_obs):
+        d = Discretizer(min_obs=min_obs, max_bins=10, min_iv=0.001, min_pos=1)
+        d.fit(titanic["fare"], titanic["survived"])
+        return d.splits_, d.predict(titanic["fare"]).tolist()
+
+    # The GIL is released while fitting and predicting, so the
+    # discretizers can be fit in threads with the same result.
+    min_obs = [1, 5, 10, 20]
+    with ThreadPoolExecutor(max_workers=4) as pool:
+        threaded = list(pool.map(fit, min_obs))
+    assert threaded == [fit(m) for m in min_obs]
+
+
+def test_Discretizer_polars(titanic):
+    pl = pytest.importorskip("polars")
+    fare = pl.Series("fare", titanic["fare"].to_numpy())
+    survived = pl.Series("survived", titanic["survived"].to_numpy())
+    d = Discretizer(min_obs=5, max_bins=10, min_iv=0.001, min_pos=1)
+    d.fit(fare, survived)
+    woe = d.predict(fare)
+    assert isinstance(woe, pl.Series)
+    assert woe.name == "fare"
+    assert woe.to_list() == d.predict(titanic["fare"]).tolist()
+    labels = d.predict(fare, "label")
+    assert labels.to_list() == d.predict(titanic["fare"], "label").tolist()
+    # Nulls are missing values.
+    idx = d.predict(pl.Series("fare", [1.0, None]), "index")
+    assert idx.to_list() == d.predict(np.array([1.0, np.nan]), "index").tolist()
+
+
+def test_Discretizer_closed_left(titanic):
+    right = Discretizer(min_obs=5, min_pos=1.0)
+    left = Discretizer(min_obs=5, min_pos=1.0, closed="left")
+    right.fit(titanic["fare"], titanic["survived"])
+    left.fit(titanic["fare"], titanic["survived"])
+    assert len(left.splits_) == len(right.splits_)
+    assert left.splits_[1] > right.splits_[1]
+    assert list(left.predict_idx(np.array([left.splits_[1]]))) == [1]
+    assert left.bin_summary()["label"].iloc[0].endswith(")")
+    with pytest.raises(ValueError):
+        Discretizer(closed="both")
+
+
+def test_Discretizer_bin_labels(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    ds.fit(titanic["fare"], titanic["survived"], exception_values=[-1.0])
+    labels = ds.bin_labels()
+    assert len(labels) == len(ds.bin_woe_)
+    assert labels[0].startswith("(-inf, ")
+    assert labels[len(ds.splits_) - 1] == "-1"
+    assert labels[len(ds.splits_) - 1] == ds.predict(np.array([-1.0]), prediction_type="label")[0]
+    assert ds.bin_labels(precision=3)[0] == f"(-inf, {ds.splits_[1]:.3f}]"
+
+
+def test_Discretizer_predict_bounds(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0)
+    ds.fit(titanic["fare"], titanic["survived"], exception_values=[-1.0])
+    lower, upper = ds.predict_bounds(np.array([ds.splits_[1], -1.0]))
+    assert lower[0] == -np.inf
+    assert upper[0] == ds.splits_[1]
+    assert lower[1] == upper[1] == -1.0
+
+
+def test_Discretizer_max_candidates(titanic):
+    ds = Discretizer(min_obs=5, min_pos=1.0, max_candidates=20)
+    ds.fit(titanic["fare"], titanic["survived"])
+    assert len(ds.splits_) > 2
+    ds.validate_splits()
+    assert ds.bin_summary()["totals_ct"].sum() == len(titanic)